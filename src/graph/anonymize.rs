@@ -0,0 +1,201 @@
+use crate::graph::rng::Rng;
+use crate::graph::{Edge, Graph, GraphError, Vertex};
+use std::collections::{BTreeSet, HashMap};
+
+/// Returns whether every degree that occurs is shared by at least `k` vertices.
+fn is_k_anonymous(degrees: &[usize], k: usize) -> bool {
+    let mut counts: HashMap<usize, usize> = HashMap::new();
+    for &d in degrees {
+        *counts.entry(d).or_insert(0) += 1;
+    }
+    counts.values().all(|&count| count >= k)
+}
+
+/// Raises `degrees` as little as possible, in total, so that every value occurs at least `k`
+/// times (the dynamic program of Liu and Terzi).
+///
+/// The vertices are sorted by decreasing degree and split into consecutive groups of `k` to
+/// `2k - 1` vertices, each raised to the largest degree in its group.
+fn anonymized_degrees(degrees: &[usize], k: usize) -> Vec<usize> {
+    let n: usize = degrees.len();
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_by(|&a, &b| degrees[b].cmp(&degrees[a]).then(a.cmp(&b)));
+    let sorted: Vec<usize> = order.iter().map(|&v| degrees[v]).collect();
+    let mut prefix: Vec<usize> = vec![0; n + 1];
+    for i in 0..n {
+        prefix[i + 1] = prefix[i] + sorted[i];
+    }
+    // The cost of raising `sorted[start..end]` to `sorted[start]`.
+    let cost = |start: usize, end: usize| -> usize { sorted[start] * (end - start) - (prefix[end] - prefix[start]) };
+
+    // best[end] is the cheapest grouping of the first `end` vertices and the start of its last group.
+    let mut best: Vec<Option<(usize, usize)>> = vec![None; n + 1];
+    best[0] = Some((0, 0));
+    for end in k..=n {
+        for start in end.saturating_sub(2 * k - 1)..=(end - k) {
+            if let Some((total, _)) = best[start] {
+                let candidate: usize = total + cost(start, end);
+                if best[end].is_none_or(|(current, _)| candidate < current) {
+                    best[end] = Some((candidate, start));
+                }
+            }
+        }
+    }
+
+    let mut targets: Vec<usize> = vec![0; n];
+    let mut end: usize = n;
+    while end > 0 {
+        let (_, start) = best[end].unwrap();
+        for &v in &order[start..end] {
+            targets[v] = sorted[start];
+        }
+        end = start;
+    }
+    targets
+}
+
+impl Graph {
+    /// Replaces every vertex key with a random opaque label, for publishing a graph derived
+    /// from private data.
+    ///
+    /// Labels are `v0`, `v1`, ... zero-padded to a common width, so key order carries no
+    /// information about the original keys. The structure, direction, edge weights, `GraphMode`
+    /// and weight semantics are kept; vertex and edge attributes are dropped, since they may
+    /// identify the vertex, and vertices are inserted in label order.
+    ///
+    /// # Arguments
+    ///
+    /// * `seed` - The random seed; the same seed reproduces the same labels.
+    ///
+    /// # Returns
+    ///
+    /// * A `(Graph, HashMap<String, String>)` with the relabeled graph and the label given to
+    ///   every original key, which should be kept private.
+    pub fn scramble_labels(&self, seed: u64) -> (Graph, HashMap<String, String>) {
+        let mut keys: Vec<&String> = self.vertices.keys().collect();
+        let mut rng: Rng = Rng::new(seed);
+        for i in (1..keys.len()).rev() {
+            keys.swap(i, rng.below(i + 1));
+        }
+        let width: usize = keys.len().saturating_sub(1).to_string().len();
+        let labels: HashMap<String, String> = keys.into_iter()
+            .enumerate()
+            .map(|(i, key)| (key.clone(), format!("v{:0width$}", i)))
+            .collect();
+
+        let mut graph: Graph = self.empty_like();
+        let mut sorted: Vec<&String> = labels.values().collect();
+        sorted.sort();
+        for label in sorted {
+            graph.add_vertex(Vertex::new(label.clone()));
+        }
+        for edge in self.stored_edges() {
+            graph.add_edge(Edge {
+                vertex1: labels[&edge.vertex1].clone(),
+                vertex2: labels[&edge.vertex2].clone(),
+                weight: edge.weight,
+                reverse_weight: edge.reverse_weight,
+                attributes: HashMap::new(),
+            }).unwrap();
+        }
+        (graph, labels)
+    }
+
+    /// Adds edges until every vertex shares its degree with at least `k - 1` others, so an
+    /// attacker who knows a vertex's degree cannot narrow it down to fewer than `k` candidates.
+    ///
+    /// The target degrees are the cheapest k-anonymous raise of the current ones (Liu and
+    /// Terzi). Edges of weight `1` are then added between vertices that still need degree,
+    /// largest need first; when that leaves a vertex short, it is joined to a low-degree
+    /// vertex and the targets are recomputed. No edge is removed, so the result contains the
+    /// original graph. Combine with `scramble_labels` before publishing.
+    ///
+    /// # Arguments
+    ///
+    /// * `k` - The smallest number of vertices allowed to share a degree.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Graph, GraphError>` - The anonymized graph, or an error if the graph is directed
+    ///   or `k` is zero or larger than the number of vertices.
+    pub fn k_degree_anonymize(&self, k: usize) -> Result<Graph, GraphError> {
+        if self.directed {
+            return Err(GraphError::NeedsUndirected("K-degree anonymization".to_string()));
+        }
+        let n: usize = self.vertices.len();
+        if k == 0 || k > n.max(1) {
+            return Err(GraphError::InvalidArgument(format!("k must be between 1 and the number of vertices, got {}", k)));
+        }
+        let keys: Vec<String> = self.vertices.keys().cloned().collect();
+        let mut degrees: Vec<usize> = keys.iter().map(|key| self.degree(key.as_str()).unwrap()).collect();
+        let mut adjacent: Vec<BTreeSet<usize>> = keys.iter()
+            .map(|key| self.neighbors(key.as_str()).unwrap().map(|v| keys.binary_search(&v.value).unwrap()).collect())
+            .collect();
+        let mut added: Vec<(usize, usize)> = Vec::new();
+
+        // Every round adds at least one edge, so the complete graph ends the loop at the latest.
+        while !is_k_anonymous(&degrees, k) {
+            let targets: Vec<usize> = anonymized_degrees(&degrees, k);
+            let mut need: Vec<usize> = (0..n).map(|v| targets[v] - degrees[v]).collect();
+            let mut order: Vec<usize> = (0..n).filter(|&v| need[v] > 0).collect();
+            order.sort_by(|&a, &b| need[b].cmp(&need[a]).then(a.cmp(&b)));
+
+            let mut progressed: bool = false;
+            for &u in &order {
+                let mut partners: Vec<usize> = (0..n)
+                    .filter(|&v| v != u && need[v] > 0 && !adjacent[u].contains(&v))
+                    .collect();
+                partners.sort_by(|&a, &b| need[b].cmp(&need[a]).then(a.cmp(&b)));
+                if need[u] > 0 && partners.is_empty() {
+                    // Raising a low-degree outsider lets the next round regroup around it.
+                    partners = (0..n).filter(|&v| v != u && !adjacent[u].contains(&v)).collect();
+                    partners.sort_by_key(|&v| (degrees[v], v));
+                    partners.truncate(1);
+                }
+                for v in partners {
+                    if need[u] == 0 {
+                        break;
+                    }
+                    adjacent[u].insert(v);
+                    adjacent[v].insert(u);
+                    degrees[u] += 1;
+                    degrees[v] += 1;
+                    need[u] -= 1;
+                    need[v] = need[v].saturating_sub(1);
+                    added.push((u, v));
+                    progressed = true;
+                }
+            }
+            if !progressed {
+                return Err(GraphError::NoSolution("No edge can be added to anonymize the degrees".to_string()));
+            }
+        }
+
+        let mut graph: Graph = self.clone();
+        for (u, v) in added {
+            graph.add_edge(Edge::new(keys[u].clone(), keys[v].clone(), 1.0))?;
+        }
+        Ok(graph)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::graph::graph::{GraphMode, WeightSemantics};
+    use crate::graph::testing::graph_from;
+    use crate::graph::Graph;
+    use std::collections::HashMap;
+
+    #[test]
+    fn scrambled_graph_keeps_structure_and_settings() {
+        let mut graph: Graph = graph_from(true, &[("alice", "bob", 2.0), ("bob", "carol", 3.0)]);
+        graph.set_mode(GraphMode::Simple).unwrap();
+        graph.set_weight_semantics(WeightSemantics::Distance);
+        let (scrambled, labels): (Graph, HashMap<String, String>) = graph.scramble_labels(11);
+        assert!(scrambled.has_edge(&labels["alice"], &labels["bob"]));
+        assert!(scrambled.has_edge(&labels["bob"], &labels["carol"]));
+        assert_eq!(scrambled.edge_count, 2);
+        assert_eq!(scrambled.mode(), GraphMode::Simple);
+        assert_eq!(scrambled.weight_semantics(), WeightSemantics::Distance);
+    }
+}
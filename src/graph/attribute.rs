@@ -0,0 +1,96 @@
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+
+/// A value stored under a named attribute of a vertex or edge, such as a label, color or timestamp.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AttrValue {
+    /// A true or false flag.
+    Bool(bool),
+    /// A whole number, such as a count or an id.
+    Int(i64),
+    /// A real number, such as a score or a coordinate.
+    Float(f64),
+    /// Free text, such as a label or a color name.
+    Text(String),
+    /// A list of real numbers, such as the coordinates of a point.
+    Vector(Vec<f64>),
+}
+
+impl fmt::Display for AttrValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AttrValue::Bool(value) => write!(f, "{}", value),
+            AttrValue::Int(value) => write!(f, "{}", value),
+            AttrValue::Float(value) => write!(f, "{}", value),
+            AttrValue::Text(value) => write!(f, "{}", value),
+            AttrValue::Vector(values) => {
+                let values: Vec<String> = values.iter().map(f64::to_string).collect();
+                write!(f, "{}", values.join(","))
+            }
+        }
+    }
+}
+
+/// Which vertex and edge attributes an export such as `Graph::write_dot_with` or
+/// `Graph::write_graphml_with` writes.
+///
+/// The default writes every attribute.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ExportAttributes {
+    /// The vertex attribute names to write, or `None` for all of them.
+    vertex: Option<BTreeSet<String>>,
+    /// The edge attribute names to write, or `None` for all of them.
+    edge: Option<BTreeSet<String>>,
+}
+
+impl ExportAttributes {
+    /// Writes every vertex and edge attribute.
+    pub fn all() -> ExportAttributes {
+        ExportAttributes::default()
+    }
+
+    /// Writes no attributes.
+    pub fn none() -> ExportAttributes {
+        ExportAttributes { vertex: Some(BTreeSet::new()), edge: Some(BTreeSet::new()) }
+    }
+
+    /// Writes only the named vertex attributes.
+    ///
+    /// # Arguments
+    ///
+    /// * `names` - The attribute names to keep.
+    pub fn vertex_attrs(mut self, names: &[&str]) -> ExportAttributes {
+        self.vertex = Some(names.iter().map(|name| name.to_string()).collect());
+        self
+    }
+
+    /// Writes only the named edge attributes.
+    ///
+    /// # Arguments
+    ///
+    /// * `names` - The attribute names to keep.
+    pub fn edge_attrs(mut self, names: &[&str]) -> ExportAttributes {
+        self.edge = Some(names.iter().map(|name| name.to_string()).collect());
+        self
+    }
+
+    /// Returns the selected attributes of a vertex, sorted by name.
+    pub(crate) fn vertex_selection<'a>(&self, attributes: &'a HashMap<String, AttrValue>) -> Vec<(&'a String, &'a AttrValue)> {
+        select(&self.vertex, attributes)
+    }
+
+    /// Returns the selected attributes of an edge, sorted by name.
+    pub(crate) fn edge_selection<'a>(&self, attributes: &'a HashMap<String, AttrValue>) -> Vec<(&'a String, &'a AttrValue)> {
+        select(&self.edge, attributes)
+    }
+}
+
+/// Returns the attributes whose names are in `names`, or all of them, sorted by name.
+fn select<'a>(names: &Option<BTreeSet<String>>, attributes: &'a HashMap<String, AttrValue>) -> Vec<(&'a String, &'a AttrValue)> {
+    let mut selected: Vec<(&String, &AttrValue)> = attributes.iter()
+        .filter(|(name, _)| names.as_ref().is_none_or(|names| names.contains(*name)))
+        .collect();
+    selected.sort_by(|a, b| a.0.cmp(b.0));
+    selected
+}
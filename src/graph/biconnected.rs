@@ -0,0 +1,147 @@
+use crate::graph::cycles::EdgeList;
+use crate::graph::index::GraphIndex;
+use crate::graph::{Graph, GraphError};
+use std::collections::BTreeSet;
+
+/// The weak points of an undirected graph, as found by one low-link search.
+struct Biconnectivity {
+    /// Whether every vertex is an articulation point.
+    articulation: Vec<bool>,
+    /// The ids of the bridge edges.
+    bridges: Vec<usize>,
+    /// The edge ids of every biconnected component.
+    components: Vec<Vec<usize>>,
+}
+
+/// Runs Hopcroft and Tarjan's depth-first search, which compares every vertex's discovery
+/// time with the earliest one reachable from its subtree through a single back edge (its
+/// low-link). Edges are told apart by id, so parallel edges are never bridges, and
+/// self-loops are skipped.
+fn low_links(list: &EdgeList) -> Biconnectivity {
+    let n: usize = list.incident.len();
+    let mut result: Biconnectivity = Biconnectivity { articulation: vec![false; n], bridges: Vec::new(), components: Vec::new() };
+    let mut discovery: Vec<usize> = vec![usize::MAX; n];
+    let mut low: Vec<usize> = vec![0; n];
+    let mut time: usize = 0;
+    let mut edge_stack: Vec<usize> = Vec::new();
+
+    for root in 0..n {
+        if discovery[root] != usize::MAX {
+            continue;
+        }
+        discovery[root] = time;
+        low[root] = time;
+        time += 1;
+        let mut root_children: usize = 0;
+        // Every frame is `(vertex, edge it was entered by, next incident edge to look at)`.
+        let mut stack: Vec<(usize, Option<usize>, usize)> = vec![(root, None, 0)];
+        while let Some(&mut (u, entered_by, ref mut cursor)) = stack.last_mut() {
+            if let Some(&(v, e)) = list.incident[u].get(*cursor) {
+                *cursor += 1;
+                if v == u || Some(e) == entered_by {
+                    continue;
+                }
+                if discovery[v] == usize::MAX {
+                    discovery[v] = time;
+                    low[v] = time;
+                    time += 1;
+                    edge_stack.push(e);
+                    stack.push((v, Some(e), 0));
+                } else if discovery[v] < discovery[u] {
+                    edge_stack.push(e);
+                    low[u] = low[u].min(discovery[v]);
+                }
+                continue;
+            }
+
+            stack.pop();
+            let (Some(&(parent, _, _)), Some(e)) = (stack.last(), entered_by) else {
+                continue;
+            };
+            low[parent] = low[parent].min(low[u]);
+            if low[u] > discovery[parent] {
+                result.bridges.push(e);
+            }
+            if low[u] >= discovery[parent] {
+                if parent == root {
+                    root_children += 1;
+                } else {
+                    result.articulation[parent] = true;
+                }
+                let start: usize = edge_stack.iter().rposition(|&id| id == e).unwrap();
+                result.components.push(edge_stack.split_off(start));
+            }
+        }
+        result.articulation[root] = root_children > 1;
+    }
+    result
+}
+
+impl Graph {
+    /// Numbers the edges of an undirected graph and runs the low-link search over them.
+    fn biconnectivity(&self, operation: &str) -> Result<(GraphIndex, EdgeList, Biconnectivity), GraphError> {
+        if self.directed {
+            return Err(GraphError::NeedsUndirected(operation.to_string()));
+        }
+        let index: GraphIndex = self.index();
+        let list: EdgeList = EdgeList::new(&index);
+        let found: Biconnectivity = low_links(&list);
+        Ok((index, list, found))
+    }
+
+    /// Finds the articulation points (cut vertices), whose removal disconnects the part of
+    /// the graph they are in, such as routers that are single points of failure.
+    ///
+    /// Runs one depth-first search with low-links (Hopcroft and Tarjan), in linear time.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<String>, GraphError>` - The keys of the articulation points in sorted
+    ///   order, or an error if the graph is directed.
+    pub fn articulation_points(&self) -> Result<Vec<String>, GraphError> {
+        let (index, _, found) = self.biconnectivity("Articulation points")?;
+        Ok(index.keys.into_iter().zip(found.articulation).filter(|&(_, cut)| cut).map(|(key, _)| key).collect())
+    }
+
+    /// Finds the bridges (cut edges), whose removal disconnects the part of the graph they
+    /// are in, such as links that are single points of failure.
+    ///
+    /// Runs one depth-first search with low-links, in linear time. An edge with a parallel
+    /// twin is never a bridge, and self-loops are never bridges.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<(String, String)>, GraphError>` - The endpoints of every bridge, the smaller
+    ///   key first, in sorted order, or an error if the graph is directed.
+    pub fn bridges(&self) -> Result<Vec<(String, String)>, GraphError> {
+        let (index, list, found) = self.biconnectivity("Bridges")?;
+        let mut bridges: Vec<(String, String)> = found.bridges.into_iter()
+            .map(|e| (index.keys[list.edges[e].0].clone(), index.keys[list.edges[e].1].clone()))
+            .collect();
+        bridges.sort();
+        Ok(bridges)
+    }
+
+    /// Splits the edges into biconnected components, the maximal parts that stay connected
+    /// when any one vertex is removed.
+    ///
+    /// Components share only articulation points, and a bridge forms a component of its
+    /// two endpoints. Vertices without edges belong to no component, and self-loops are
+    /// ignored. Runs one depth-first search with low-links, in linear time.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<Vec<String>>, GraphError>` - The vertex keys of every component, each
+    ///   sorted, ordered by their keys, or an error if the graph is directed.
+    pub fn biconnected_components(&self) -> Result<Vec<Vec<String>>, GraphError> {
+        let (index, list, found) = self.biconnectivity("Biconnected components")?;
+        let mut components: Vec<Vec<String>> = found.components.into_iter()
+            .map(|edges| {
+                let vertices: BTreeSet<usize> = edges.iter().flat_map(|&e| [list.edges[e].0, list.edges[e].1]).collect();
+                vertices.into_iter().map(|v| index.keys[v].clone()).collect()
+            })
+            .collect();
+        components.sort();
+        Ok(components)
+    }
+}
@@ -0,0 +1,201 @@
+use crate::graph::edgelist::{is_data_line, parse_edge_line, Separator};
+use crate::graph::{Graph, GraphError};
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+/// Hashes an ordered pair of vertex keys into 64 well-mixed bits, the same on every platform
+/// and release (FNV-1a over the keys, finished with a SplitMix64 step).
+pub(crate) fn hash_pair(from: &str, to: &str, seed: u64) -> u64 {
+    let mut h: u64 = 0xCBF2_9CE4_8422_2325 ^ seed;
+    // The separator keeps ("ab", "c") and ("a", "bc") apart.
+    for byte in from.bytes().chain([0xFF]).chain(to.bytes()) {
+        h = (h ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01B3);
+    }
+    h = (h ^ (h >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    h = (h ^ (h >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    h ^ (h >> 31)
+}
+
+/// An approximate set of edges (a Bloom filter), for pre-filtering edge lookups in graphs
+/// too large to index exactly.
+///
+/// `maybe_has_edge` never answers `false` for an inserted edge, and answers `true` for a
+/// missing one with about the false-positive rate the filter was sized for. It takes a
+/// fixed number of bits per expected edge, whatever the length of the keys, and cannot list
+/// or remove edges.
+#[derive(Clone, Debug)]
+pub struct EdgeFilter {
+    bits: Vec<u64>,
+    bit_count: u64,
+    hashes: u32,
+    directed: bool,
+    inserted: usize,
+}
+
+impl EdgeFilter {
+    /// Creates an empty filter sized for `expected_edges` edges at the given false-positive
+    /// rate.
+    ///
+    /// The filter has `-n ln p / ln² 2` bits and `(m / n) ln 2` hash functions, the optimum
+    /// for `n` edges; inserting more still works, but the rate rises.
+    ///
+    /// # Arguments
+    ///
+    /// * `expected_edges` - The number of edges that will be inserted.
+    /// * `false_positive_rate` - The chance, in `(0, 1)`, of reporting a missing edge.
+    /// * `directed` - Whether `(from, to)` and `(to, from)` are different edges.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<EdgeFilter, GraphError>` - The filter, or an error if the rate is outside
+    ///   `(0, 1)`.
+    pub fn new(expected_edges: usize, false_positive_rate: f64, directed: bool) -> Result<EdgeFilter, GraphError> {
+        if !(false_positive_rate > 0.0 && false_positive_rate < 1.0) {
+            return Err(GraphError::InvalidArgument(format!("False-positive rate {} is outside (0, 1)", false_positive_rate)));
+        }
+        let n: f64 = expected_edges.max(1) as f64;
+        let ln2: f64 = std::f64::consts::LN_2;
+        let bit_count: u64 = ((-n * false_positive_rate.ln() / (ln2 * ln2)).ceil() as u64).max(64);
+        let hashes: u32 = ((bit_count as f64 / n * ln2).round() as u32).max(1);
+        Ok(EdgeFilter { bits: vec![0; bit_count.div_ceil(64) as usize], bit_count, hashes, directed, inserted: 0 })
+    }
+
+    /// Returns the bit positions of an edge, by double hashing.
+    fn positions(&self, from: &str, to: &str) -> impl Iterator<Item = u64> {
+        let (from, to) = if !self.directed && to < from { (to, from) } else { (from, to) };
+        let first: u64 = hash_pair(from, to, 0);
+        // An odd step visits distinct positions for as long as possible.
+        let step: u64 = hash_pair(from, to, 0x5851_F42D_4C95_7F2D) | 1;
+        let bit_count: u64 = self.bit_count;
+        (0..u64::from(self.hashes)).map(move |i| first.wrapping_add(i.wrapping_mul(step)) % bit_count)
+    }
+
+    /// Records an edge.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - The key of the first endpoint.
+    /// * `to` - The key of the second endpoint.
+    pub fn insert(&mut self, from: &str, to: &str) {
+        let positions: Vec<u64> = self.positions(from, to).collect();
+        for position in positions {
+            self.bits[(position / 64) as usize] |= 1 << (position % 64);
+        }
+        self.inserted += 1;
+    }
+
+    /// Checks whether an edge may have been inserted. In an undirected filter the order of
+    /// the endpoints does not matter.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - The key of the first endpoint.
+    /// * `to` - The key of the second endpoint.
+    ///
+    /// # Returns
+    ///
+    /// * A `bool` that is `false` if the edge was certainly never inserted, and `true` if it
+    ///   probably was.
+    pub fn maybe_has_edge(&self, from: &str, to: &str) -> bool {
+        self.positions(from, to).all(|position| self.bits[(position / 64) as usize] & (1 << (position % 64)) != 0)
+    }
+
+    /// Returns the number of edges inserted, counting repeats.
+    pub fn len(&self) -> usize {
+        self.inserted
+    }
+
+    /// Returns whether no edge has been inserted.
+    pub fn is_empty(&self) -> bool {
+        self.inserted == 0
+    }
+
+    /// Returns whether the filter tells `(from, to)` and `(to, from)` apart.
+    pub fn is_directed(&self) -> bool {
+        self.directed
+    }
+
+    /// Returns the size of the filter in bits.
+    pub fn bit_count(&self) -> u64 {
+        self.bit_count
+    }
+
+    /// Estimates the current false-positive rate from the share of bits set, which rises
+    /// above the configured rate once more edges than expected are inserted.
+    pub fn estimated_false_positive_rate(&self) -> f64 {
+        let set: u64 = self.bits.iter().map(|word| u64::from(word.count_ones())).sum();
+        (set as f64 / self.bit_count as f64).powi(self.hashes as i32)
+    }
+
+    /// Builds a filter from an edge list as it is read, without building the graph.
+    ///
+    /// The lines are read one at a time in the format of `Graph::from_edge_list`, so memory
+    /// stays at the size of the filter however long the list is. Weights are checked but
+    /// not stored.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - The source of the edge list.
+    /// * `directed` - Whether the edges are directed.
+    /// * `expected_edges` - The number of edges the list is expected to hold.
+    /// * `false_positive_rate` - The chance, in `(0, 1)`, of reporting a missing edge.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<EdgeFilter, GraphError>` - The filter, or an error if the rate is outside
+    ///   `(0, 1)`, the reader fails or a line is malformed.
+    pub fn from_edge_list_reader<R: BufRead>(reader: R, directed: bool, expected_edges: usize, false_positive_rate: f64) -> Result<EdgeFilter, GraphError> {
+        let mut filter: EdgeFilter = EdgeFilter::new(expected_edges, false_positive_rate, directed)?;
+        for (index, text) in reader.lines().enumerate() {
+            let text: String = text.map_err(|e| GraphError::Io(format!("Line {}: {}", index + 1, e)))?;
+            let content: &str = text.trim();
+            if !is_data_line(content) {
+                continue;
+            }
+            let (from, to, _) = parse_edge_line(content, index + 1, Separator::Whitespace)?;
+            filter.insert(from, to);
+        }
+        Ok(filter)
+    }
+
+    /// Builds a filter from an edge list file as it is read, as
+    /// `EdgeFilter::from_edge_list_reader`.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The file to read.
+    /// * `directed` - Whether the edges are directed.
+    /// * `expected_edges` - The number of edges the file is expected to hold.
+    /// * `false_positive_rate` - The chance, in `(0, 1)`, of reporting a missing edge.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<EdgeFilter, GraphError>` - The filter, or an error if the rate is outside
+    ///   `(0, 1)`, the file cannot be read or a line is malformed.
+    pub fn from_edge_list_file<P: AsRef<Path>>(path: P, directed: bool, expected_edges: usize, false_positive_rate: f64) -> Result<EdgeFilter, GraphError> {
+        let path: &Path = path.as_ref();
+        let file: File = File::open(path).map_err(|e| GraphError::Io(format!("Could not read {}: {}", path.display(), e)))?;
+        EdgeFilter::from_edge_list_reader(BufReader::new(file), directed, expected_edges, false_positive_rate)
+    }
+}
+
+impl Graph {
+    /// Builds an `EdgeFilter` holding every edge of the graph, sized for its edge count.
+    ///
+    /// # Arguments
+    ///
+    /// * `false_positive_rate` - The chance, in `(0, 1)`, of reporting a missing edge.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<EdgeFilter, GraphError>` - The filter, or an error if the rate is outside
+    ///   `(0, 1)`.
+    pub fn edge_filter(&self, false_positive_rate: f64) -> Result<EdgeFilter, GraphError> {
+        let mut filter: EdgeFilter = EdgeFilter::new(self.edge_count, false_positive_rate, self.directed)?;
+        for edge in self.stored_edges() {
+            filter.insert(&edge.vertex1, &edge.vertex2);
+        }
+        Ok(filter)
+    }
+}
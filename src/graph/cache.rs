@@ -0,0 +1,85 @@
+use crate::graph::Graph;
+use std::any::Any;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// A remembered query result and the graph generation it was computed at.
+type Entry = (u64, Box<dyn Any + Send>);
+
+/// The results of pure queries on a graph, kept while the graph does not change.
+///
+/// Cloning gives an empty cache that is enabled if this one is, so a cloned graph never
+/// serves results computed on another graph.
+#[derive(Default)]
+pub(crate) struct QueryCache {
+    /// Whether results are kept at all.
+    enabled: bool,
+    /// The latest result of every query, by query name and any arguments that change it.
+    entries: Mutex<HashMap<String, Entry>>,
+}
+
+impl Clone for QueryCache {
+    fn clone(&self) -> QueryCache {
+        QueryCache { enabled: self.enabled, entries: Mutex::default() }
+    }
+}
+
+impl Graph {
+    /// Starts remembering the results of pure queries such as `connected_components`,
+    /// `distance_extremes` and `pagerank`, so asking again before the graph changes returns a
+    /// copy of the earlier result instead of recomputing it. Queries with arguments remember
+    /// one result per set of arguments.
+    ///
+    /// Every change made through the graph's methods advances its generation and so retires
+    /// the remembered results. Changes made by editing the public fields directly cannot be
+    /// seen; call `mark_changed` after them.
+    pub fn enable_cache(&mut self) {
+        self.cache.enabled = true;
+    }
+
+    /// Stops remembering query results and drops the ones already kept.
+    pub fn disable_cache(&mut self) {
+        self.cache.enabled = false;
+        self.cache.entries.get_mut().unwrap().clear();
+    }
+
+    /// Returns the generation of the graph, which advances with every change made through
+    /// its methods.
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Records a change to the graph, so results remembered before it are not reused.
+    ///
+    /// The graph's own methods do this themselves; it is only needed after editing the public
+    /// fields, for example a vertex reached through `vertices.get_mut`.
+    pub fn mark_changed(&mut self) {
+        self.generation += 1;
+    }
+
+    /// Returns the remembered result of `query` if it was computed at the current generation,
+    /// and otherwise computes it with `compute`, remembering it when the cache is enabled.
+    /// A query taking arguments must spell them out in `query`.
+    pub(crate) fn cached<T, F>(&self, query: &str, compute: F) -> T
+    where
+        T: Clone + Send + 'static,
+        F: FnOnce() -> T,
+    {
+        if !self.cache.enabled {
+            return compute();
+        }
+        let remembered: Option<T> = self.cache.entries.lock().unwrap()
+            .get(query)
+            .filter(|(generation, _)| *generation == self.generation)
+            .and_then(|(_, result)| result.downcast_ref::<T>())
+            .cloned();
+        if let Some(result) = remembered {
+            return result;
+        }
+
+        // The lock is released while computing, since the query may ask for other cached results.
+        let result: T = compute();
+        self.cache.entries.lock().unwrap().insert(query.to_string(), (self.generation, Box::new(result.clone())));
+        result
+    }
+}
@@ -0,0 +1,225 @@
+use crate::graph::control::ExecutionControl;
+use crate::graph::cycles::EdgeList;
+use crate::graph::index::GraphIndex;
+use crate::graph::trace::{trace_counts, trace_span};
+use crate::graph::{Graph, GraphError};
+use std::collections::{BTreeMap, HashMap};
+
+/// An edge in canonical numbering, with the bit pattern of its weight.
+type CanonicalEdge = (usize, usize, u32);
+
+/// A `(neighbor, weight bits, outgoing)` incidence of a vertex.
+type Incidence = (usize, u32, bool);
+
+/// A vertex color together with the sorted incidences it sees.
+type Signature = (usize, Vec<Incidence>);
+
+/// The structure the canonical search works on.
+struct Labeled {
+    /// Every edge once (every arc, in directed graphs), with the bit pattern of its weight.
+    edges: Vec<CanonicalEdge>,
+    /// For every vertex, its incidences.
+    incidences: Vec<Vec<Incidence>>,
+    directed: bool,
+}
+
+impl Labeled {
+    fn new(index: &GraphIndex) -> Labeled {
+        let edges: Vec<CanonicalEdge> = if index.directed {
+            index.adjacency.iter()
+                .enumerate()
+                .flat_map(|(u, neighbors)| neighbors.iter().map(move |&(v, weight)| (u, v, weight.to_bits())))
+                .collect()
+        } else {
+            EdgeList::new(index).edges.into_iter().map(|(u, v, weight)| (u, v, weight.to_bits())).collect()
+        };
+        let mut incidences: Vec<Vec<Incidence>> = vec![Vec::new(); index.len()];
+        for &(u, v, bits) in &edges {
+            incidences[u].push((v, bits, true));
+            incidences[v].push((u, bits, !index.directed));
+        }
+        Labeled { edges, incidences, directed: index.directed }
+    }
+
+    /// Refines `colors` until stable: vertices keep a color only if they see the same multiset
+    /// of `(neighbor color, weight, direction)`. Colors are ranks of the signatures, so the
+    /// result does not depend on the vertex numbering.
+    fn refine(&self, colors: &mut Vec<usize>) {
+        loop {
+            let signatures: Vec<Signature> = (0..colors.len())
+                .map(|v| {
+                    let mut seen: Vec<Incidence> = self.incidences[v].iter()
+                        .map(|&(w, bits, outgoing)| (colors[w], bits, outgoing))
+                        .collect();
+                    seen.sort_unstable();
+                    (colors[v], seen)
+                })
+                .collect();
+            let ranks: BTreeMap<&Signature, usize> = signatures.iter()
+                .map(|signature| (signature, 0))
+                .collect::<BTreeMap<_, _>>()
+                .into_keys()
+                .enumerate()
+                .map(|(rank, signature)| (signature, rank))
+                .collect();
+            let mut distinct: Vec<usize> = colors.clone();
+            distinct.sort_unstable();
+            distinct.dedup();
+            let refined: Vec<usize> = signatures.iter().map(|signature| ranks[signature]).collect();
+            *colors = refined;
+            if ranks.len() == distinct.len() {
+                return;
+            }
+        }
+    }
+
+    /// Returns the sorted edge list under the numbering given by a discrete coloring.
+    fn certificate(&self, colors: &[usize]) -> Vec<CanonicalEdge> {
+        let mut relabeled: Vec<CanonicalEdge> = self.edges.iter()
+            .map(|&(u, v, bits)| {
+                let (a, b) = (colors[u], colors[v]);
+                if self.directed || a <= b { (a, b, bits) } else { (b, a, bits) }
+            })
+            .collect();
+        relabeled.sort_unstable();
+        relabeled
+    }
+
+    /// Returns whether swapping `u` and `v` maps the graph onto itself.
+    fn twins(&self, u: usize, v: usize) -> bool {
+        let view = |x: usize| -> Vec<Incidence> {
+            let mut seen: Vec<Incidence> = self.incidences[x].iter()
+                .map(|&(w, bits, outgoing)| (if w == u || w == v { usize::MAX - usize::from(w == x) } else { w }, bits, outgoing))
+                .collect();
+            seen.sort_unstable();
+            seen
+        };
+        view(u) == view(v)
+    }
+
+    /// Explores the individualization-refinement tree, keeping the smallest certificate.
+    /// `expanded` counts the tree nodes visited, which are reported to `control`.
+    fn search(
+        &self,
+        mut colors: Vec<usize>,
+        best: &mut Option<(Vec<CanonicalEdge>, Vec<usize>)>,
+        control: &ExecutionControl,
+        expanded: &mut usize,
+    ) -> Result<(), GraphError> {
+        self.refine(&mut colors);
+        *expanded += 1;
+        control.step(*expanded, None)?;
+        let n: usize = colors.len();
+        let mut sizes: Vec<usize> = vec![0; n];
+        for &c in &colors {
+            sizes[c] += 1;
+        }
+        let target: Option<usize> = (0..n).find(|&c| sizes[c] > 1);
+        let target: usize = match target {
+            Some(c) => c,
+            None => {
+                let certificate: Vec<CanonicalEdge> = self.certificate(&colors);
+                if best.as_ref().is_none_or(|(current, _)| certificate < *current) {
+                    *best = Some((certificate, colors));
+                }
+                return Ok(());
+            }
+        };
+
+        // Individualize each vertex of the first non-trivial cell, skipping twins of earlier choices.
+        let cell: Vec<usize> = (0..n).filter(|&v| colors[v] == target).collect();
+        let mut tried: Vec<usize> = Vec::new();
+        for &v in &cell {
+            if tried.iter().any(|&u| self.twins(u, v)) {
+                continue;
+            }
+            tried.push(v);
+            let individualized: Vec<usize> = colors.iter()
+                .enumerate()
+                .map(|(w, &c)| 2 * c + usize::from(c == target && w != v))
+                .collect();
+            self.search(individualized, best, control, expanded)?;
+        }
+        Ok(())
+    }
+}
+
+/// Computes the canonical numbering of the vertices and the matching certificate.
+fn canonical_labeling(graph: &Graph, control: &ExecutionControl) -> Result<(GraphIndex, Vec<usize>, Vec<CanonicalEdge>), GraphError> {
+    let index: GraphIndex = graph.index();
+    trace_span!("canonical_labeling", vertices = index.len());
+    let labeled: Labeled = Labeled::new(&index);
+    let mut best: Option<(Vec<CanonicalEdge>, Vec<usize>)> = None;
+    let mut expanded: usize = 0;
+    labeled.search(vec![0; index.len()], &mut best, control, &mut expanded)?;
+    trace_counts!("canonical search finished", expanded = expanded);
+    let (certificate, colors) = best.unwrap_or_default();
+    Ok((index, colors, certificate))
+}
+
+impl Graph {
+    /// Produces a canonical text form of the graph's structure for deduplication.
+    ///
+    /// Two graphs get the same form exactly when they are isomorphic, taking edge weights and
+    /// direction into account but ignoring vertex keys. The vertices are renumbered by color
+    /// refinement with individualization and branching, keeping the numbering whose sorted edge
+    /// list is smallest; interchangeable twin vertices are branched on only once. This is exact
+    /// but exponential in the worst case, so it is meant for small graphs such as molecules.
+    ///
+    /// # Returns
+    ///
+    /// * A `String` of the form `u5|0-1:1;1-2:2` (or `d5|...` for directed graphs) listing the
+    ///   vertex count and every edge as `from-to:weight` in canonical numbering.
+    pub fn canonical_form(&self) -> String {
+        // Without limits the run is never stopped.
+        self.canonical_form_with(&ExecutionControl::new()).unwrap()
+    }
+
+    /// Produces the canonical form as `canonical_form` does, under a deadline, cancellation
+    /// token or progress callback.
+    ///
+    /// Progress counts the nodes of the search tree visited; their total is not known in advance.
+    ///
+    /// # Arguments
+    ///
+    /// * `control` - The limits and hooks for the run.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<String, GraphError>` - The canonical form, or an error if the run was stopped.
+    pub fn canonical_form_with(&self, control: &ExecutionControl) -> Result<String, GraphError> {
+        let (index, _, certificate) = canonical_labeling(self, control)?;
+        let edges: Vec<String> = certificate.iter()
+            .map(|&(u, v, bits)| format!("{}-{}:{}", u, v, f32::from_bits(bits)))
+            .collect();
+        Ok(format!("{}{}|{}", if self.directed { "d" } else { "u" }, index.len(), edges.join(";")))
+    }
+
+    /// Returns the canonical number of every vertex used by `canonical_form`.
+    ///
+    /// Mapping two isomorphic graphs through their canonical numberings yields an isomorphism.
+    ///
+    /// # Returns
+    ///
+    /// * A `HashMap<String, usize>` from vertex key to canonical number in `0..n`.
+    pub fn canonical_labeling(&self) -> HashMap<String, usize> {
+        // Without limits the run is never stopped.
+        self.canonical_labeling_with(&ExecutionControl::new()).unwrap()
+    }
+
+    /// Returns the canonical numbering as `canonical_labeling` does, under a deadline,
+    /// cancellation token or progress callback.
+    ///
+    /// # Arguments
+    ///
+    /// * `control` - The limits and hooks for the run.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<HashMap<String, usize>, GraphError>` - The canonical number of every vertex,
+    ///   or an error if the run was stopped.
+    pub fn canonical_labeling_with(&self, control: &ExecutionControl) -> Result<HashMap<String, usize>, GraphError> {
+        let (index, colors, _) = canonical_labeling(self, control)?;
+        Ok(index.keys.into_iter().zip(colors).collect())
+    }
+}
@@ -0,0 +1,561 @@
+use crate::graph::dag::cycle_error;
+use crate::graph::index::GraphIndex;
+use crate::graph::linalg::{grounded_inverse, Matrix};
+use crate::graph::path::{dijkstra, reject_negative_weights, State};
+use crate::graph::rng::Rng;
+use crate::graph::trace::{trace_counts, trace_span};
+use crate::graph::{Graph, GraphError};
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+
+/// The shortest-path DAG rooted at one source, as used by Brandes-style algorithms.
+pub(crate) struct ShortestPathDag {
+    /// The distance from the source (`f32::INFINITY` if unreachable).
+    pub distance: Vec<f32>,
+    /// The number of shortest paths from the source.
+    pub sigma: Vec<f64>,
+    /// The predecessors of every vertex on its shortest paths, once per parallel edge.
+    pub predecessors: Vec<Vec<usize>>,
+    /// The reached vertices in non-decreasing distance order, each after its predecessors.
+    pub order: Vec<usize>,
+}
+
+/// Returns an error naming a cycle of zero-weight edges, if the graph has one.
+///
+/// Such a cycle can be walked any number of times without getting longer, so the vertices
+/// on it have no finite set of shortest paths to count. In undirected graphs every
+/// zero-weight edge is such a cycle.
+pub(crate) fn reject_zero_weight_cycles(index: &GraphIndex) -> Result<(), GraphError> {
+    let mut zero: GraphIndex = index.clone();
+    for (u, neighbors) in zero.adjacency.iter_mut().enumerate() {
+        neighbors.retain(|&(v, weight)| weight == 0.0 && v != u);
+    }
+    match cycle_error(&zero) {
+        Some(error) => Err(error.into()),
+        None => Ok(()),
+    }
+}
+
+/// Builds the shortest-path DAG from `source` with Dijkstra, counting shortest paths.
+///
+/// An edge is kept when it leads to a farther vertex at exactly its weight, or weighs nothing
+/// between two vertices at the same distance. The vertices are then ordered by distance and,
+/// within a distance, topologically over the zero-weight edges, which needs the graph to pass
+/// `reject_zero_weight_cycles`.
+pub(crate) fn shortest_path_dag(index: &GraphIndex, source: usize) -> ShortestPathDag {
+    let n: usize = index.len();
+    let (distance, _) = dijkstra(index, source, |_| true, |_, _, _| true);
+    let mut predecessors: Vec<Vec<usize>> = vec![Vec::new(); n];
+    let mut successors: Vec<Vec<usize>> = vec![Vec::new(); n];
+    for (u, neighbors) in index.adjacency.iter().enumerate() {
+        if distance[u].is_infinite() {
+            continue;
+        }
+        for &(v, weight) in neighbors {
+            let tight: bool = if distance[u] == distance[v] { weight == 0.0 && u != v } else { distance[u] + weight == distance[v] };
+            if tight {
+                predecessors[v].push(u);
+                successors[u].push(v);
+            }
+        }
+    }
+
+    // Kahn's algorithm, always placing the nearest ready vertex, so distances never decrease.
+    let mut waiting: Vec<usize> = predecessors.iter().map(Vec::len).collect();
+    let mut sigma: Vec<f64> = vec![0.0; n];
+    let mut order: Vec<usize> = Vec::with_capacity(n);
+    sigma[source] = 1.0;
+    let mut heap: BinaryHeap<State> = BinaryHeap::from([State { cost: 0.0, vertex: source }]);
+    while let Some(State { vertex, .. }) = heap.pop() {
+        order.push(vertex);
+        for &next in &successors[vertex] {
+            sigma[next] += sigma[vertex];
+            waiting[next] -= 1;
+            if waiting[next] == 0 {
+                heap.push(State { cost: distance[next], vertex: next });
+            }
+        }
+    }
+    ShortestPathDag { distance, sigma, predecessors, order }
+}
+
+/// Bounds the number of vertices on any shortest path, for sizing a betweenness sample.
+///
+/// In an undirected graph whose edges all weigh the same positive amount, a shortest path
+/// within a component has at most twice the hop eccentricity of any of its vertices plus
+/// one vertices. Otherwise only the size of the component bounds it, and in a directed
+/// graph the number of vertices.
+fn vertex_diameter_bound(index: &GraphIndex) -> usize {
+    let n: usize = index.len();
+    if index.directed {
+        return n;
+    }
+    let mut weights = index.adjacency.iter().flatten().map(|&(_, w)| w);
+    let first: Option<f32> = weights.next();
+    let unit: bool = first.is_some_and(|w| w > 0.0) && weights.all(|w| Some(w) == first);
+
+    let mut hops: Vec<usize> = vec![usize::MAX; n];
+    let mut bound: usize = 1;
+    for start in 0..n {
+        if hops[start] != usize::MAX {
+            continue;
+        }
+        // Breadth-first search over the component, which also measures the start's eccentricity.
+        hops[start] = 0;
+        let mut queue: VecDeque<usize> = VecDeque::from([start]);
+        let (mut size, mut eccentricity): (usize, usize) = (0, 0);
+        while let Some(u) = queue.pop_front() {
+            size += 1;
+            eccentricity = eccentricity.max(hops[u]);
+            for &(v, _) in &index.adjacency[u] {
+                if hops[v] == usize::MAX {
+                    hops[v] = hops[u] + 1;
+                    queue.push_back(v);
+                }
+            }
+        }
+        bound = bound.max(if unit { (2 * eccentricity + 1).min(size) } else { size });
+    }
+    bound
+}
+
+/// Settings for `Graph::pagerank`.
+///
+/// Built with chained calls, for example `PageRankOptions::new().damping(0.9).tolerance(1e-9)`.
+/// The defaults are a damping factor of `0.85`, at most `100` iterations and a tolerance of
+/// `1e-6`.
+#[derive(Clone, Copy, Debug)]
+pub struct PageRankOptions {
+    pub(crate) damping: f64,
+    pub(crate) max_iterations: usize,
+    pub(crate) tolerance: f64,
+}
+
+impl Default for PageRankOptions {
+    fn default() -> PageRankOptions {
+        PageRankOptions { damping: 0.85, max_iterations: 100, tolerance: 1e-6 }
+    }
+}
+
+impl PageRankOptions {
+    /// Creates the default settings.
+    pub fn new() -> PageRankOptions {
+        PageRankOptions::default()
+    }
+
+    /// Sets the probability, in `[0, 1]`, that the random surfer follows an edge rather than
+    /// jumping to a uniformly random vertex.
+    pub fn damping(mut self, damping: f64) -> PageRankOptions {
+        self.damping = damping;
+        self
+    }
+
+    /// Sets the largest number of iterations to run before returning the current scores.
+    pub fn max_iterations(mut self, max_iterations: usize) -> PageRankOptions {
+        self.max_iterations = max_iterations;
+        self
+    }
+
+    /// Stops iterating once the scores change by less than `tolerance` in total.
+    pub fn tolerance(mut self, tolerance: f64) -> PageRankOptions {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Names a PageRank query with these settings for the query cache, by their exact bits.
+    pub(crate) fn cache_key(&self, query: &str) -> String {
+        format!("{}({:x}, {}, {:x})", query, self.damping.to_bits(), self.max_iterations, self.tolerance.to_bits())
+    }
+
+    /// Returns an error if the damping factor is outside `[0, 1]` or the tolerance is negative.
+    pub(crate) fn check(&self) -> Result<(), GraphError> {
+        if !(0.0..=1.0).contains(&self.damping) {
+            return Err(GraphError::InvalidArgument(format!("Damping factor {} is outside [0, 1]", self.damping)));
+        }
+        if self.tolerance.is_nan() || self.tolerance < 0.0 {
+            return Err(GraphError::InvalidArgument(format!("Tolerance {} is negative", self.tolerance)));
+        }
+        Ok(())
+    }
+}
+
+/// Runs the PageRank power iteration of `Graph::pagerank` over `index`, whose weights must
+/// be non-negative.
+pub(crate) fn pagerank_scores(index: &GraphIndex, options: &PageRankOptions) -> Vec<f64> {
+    let n: usize = index.len();
+    if n == 0 {
+        return Vec::new();
+    }
+    trace_span!("pagerank", vertices = n);
+
+    let out_weight: Vec<f64> = index.adjacency.iter()
+        .map(|neighbors| neighbors.iter().map(|&(_, weight)| f64::from(weight)).sum())
+        .collect();
+    let mut scores: Vec<f64> = vec![1.0 / n as f64; n];
+    let mut iterations: usize = 0;
+    while iterations < options.max_iterations {
+        iterations += 1;
+        let dangling: f64 = (0..n).filter(|&u| out_weight[u] <= 0.0).map(|u| scores[u]).sum();
+        let base: f64 = (1.0 - options.damping + options.damping * dangling) / n as f64;
+        let mut next: Vec<f64> = vec![base; n];
+        for (u, neighbors) in index.adjacency.iter().enumerate() {
+            if out_weight[u] <= 0.0 {
+                continue;
+            }
+            for &(v, weight) in neighbors {
+                next[v] += options.damping * scores[u] * f64::from(weight) / out_weight[u];
+            }
+        }
+        let change: f64 = scores.iter().zip(&next).map(|(a, b)| (a - b).abs()).sum();
+        scores = next;
+        if change < options.tolerance {
+            break;
+        }
+    }
+    trace_counts!("pagerank finished", iterations = iterations);
+    scores
+}
+
+/// Returns the closeness of vertex `v` in the Wasserman-Faust form of
+/// `Graph::closeness_centrality`, from one Dijkstra run over non-negative weights.
+pub(crate) fn closeness_score(index: &GraphIndex, v: usize) -> f64 {
+    let (distances, _) = dijkstra(index, v, |_| true, |_, _, _| true);
+    let reached: Vec<f64> = distances.iter().filter(|d| d.is_finite()).map(|&d| f64::from(d)).collect();
+    let others: f64 = (reached.len() - 1) as f64;
+    let total: f64 = reached.iter().sum();
+    if total > 0.0 { (others / total) * (others / (index.len() - 1) as f64) } else { 0.0 }
+}
+
+/// Adds the Brandes dependencies of `source` on every other vertex to `scores`.
+pub(crate) fn add_dependencies(index: &GraphIndex, source: usize, scores: &mut [f64]) {
+    let dag: ShortestPathDag = shortest_path_dag(index, source);
+    // Dependencies accumulate from the farthest vertices back towards the source.
+    let mut dependency: Vec<f64> = vec![0.0; index.len()];
+    for &w in dag.order.iter().rev() {
+        for &p in &dag.predecessors[w] {
+            dependency[p] += dag.sigma[p] / dag.sigma[w] * (1.0 + dependency[w]);
+        }
+        if w != source {
+            scores[w] += dependency[w];
+        }
+    }
+}
+
+/// Prepares the grounded Laplacian inverse for the current-flow measures.
+fn current_flow_setup(graph: &Graph) -> Result<(GraphIndex, Matrix), GraphError> {
+    if graph.directed {
+        return Err(GraphError::NeedsUndirected("Current-flow centrality".to_string()));
+    }
+    let index: GraphIndex = graph.index();
+    if index.len() == 0 {
+        return Ok((index, Vec::new()));
+    }
+    let inverse: Matrix = grounded_inverse(&index).ok_or(GraphError::Disconnected)?;
+    Ok((index, inverse))
+}
+
+impl Graph {
+    /// Computes degree centrality: the degree of every vertex divided by `n - 1`.
+    ///
+    /// In directed graphs incoming and outgoing edges both count, as in `degree`, so a vertex
+    /// linked both ways with every other vertex scores `2`.
+    ///
+    /// # Returns
+    ///
+    /// * A `HashMap<String, f64>` with the score of every vertex; a lone vertex scores `0`.
+    pub fn degree_centrality(&self) -> HashMap<String, f64> {
+        let n: usize = self.vertices.len();
+        let scale: f64 = if n > 1 { 1.0 / (n - 1) as f64 } else { 0.0 };
+        self.vertices.keys()
+            .map(|key| (key.clone(), self.degree(key.as_str()).unwrap() as f64 * scale))
+            .collect()
+    }
+
+    /// Computes closeness centrality from weighted shortest-path distances.
+    ///
+    /// A vertex that reaches `r - 1` others at total distance `d` scores
+    /// `((r - 1) / d) * ((r - 1) / (n - 1))`, the Wasserman-Faust form, so vertices in small
+    /// components are not ranked above well-connected ones. In directed graphs distances are
+    /// measured along outgoing edges.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<HashMap<String, f64>, GraphError>` - The score of every vertex, `0` for one that
+    ///   reaches no other, or an error if an edge weight is negative.
+    pub fn closeness_centrality(&self) -> Result<HashMap<String, f64>, GraphError> {
+        self.require_distances("Closeness centrality")?;
+        let index: GraphIndex = self.index();
+        reject_negative_weights(&index)?;
+        let scores: Vec<f64> = (0..index.len()).map(|v| closeness_score(&index, v)).collect();
+        Ok(index.keys.into_iter().zip(scores).collect())
+    }
+
+    /// Computes exact betweenness centrality with Brandes' algorithm.
+    ///
+    /// A vertex scores the fraction of shortest paths through it, summed over every ordered
+    /// pair of other vertices, with edge weights as lengths. Scores are normalized by the
+    /// `n (n - 1)` ordered pairs, as in `approximate_betweenness`, so the two are comparable.
+    /// This takes `O(n m log n)` time; `approximate_betweenness` scales to larger graphs.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<HashMap<String, f64>, GraphError>` - The normalized score of every vertex, or an
+    ///   error if an edge weight is negative or zero-weight edges form a cycle.
+    pub fn betweenness_centrality(&self) -> Result<HashMap<String, f64>, GraphError> {
+        self.require_distances("Betweenness centrality")?;
+        let index: GraphIndex = self.index();
+        reject_negative_weights(&index)?;
+        reject_zero_weight_cycles(&index)?;
+        let n: usize = index.len();
+        let mut scores: Vec<f64> = vec![0.0; n];
+        for source in 0..n {
+            add_dependencies(&index, source, &mut scores);
+        }
+
+        let pairs: f64 = if n > 1 { (n * (n - 1)) as f64 } else { 1.0 };
+        Ok(index.keys.into_iter().zip(scores).map(|(key, score)| (key, score / pairs)).collect())
+    }
+
+    /// Computes PageRank by power iteration.
+    ///
+    /// A random surfer follows an outgoing edge with probability `damping`, choosing edges in
+    /// proportion to their weights, and otherwise jumps to a uniformly random vertex; a vertex
+    /// scores the long-run share of time spent on it. Vertices without outgoing weight jump
+    /// uniformly. Undirected edges are followed both ways. Iteration stops once the total change
+    /// of the scores drops below the tolerance, or after the iteration limit, whichever is first.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - The damping factor and stopping limits.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<HashMap<String, f64>, GraphError>` - The score of every vertex, summing to `1`, or
+    ///   an error if the damping factor is outside `[0, 1]`, the tolerance is negative or an edge
+    ///   weight is negative.
+    pub fn pagerank(&self, options: &PageRankOptions) -> Result<HashMap<String, f64>, GraphError> {
+        options.check()?;
+        self.cached(&options.cache_key("pagerank"), || {
+            let index: GraphIndex = self.index();
+            reject_negative_weights(&index)?;
+            let scores: Vec<f64> = pagerank_scores(&index, options);
+            Ok(index.keys.into_iter().zip(scores).collect())
+        })
+    }
+
+    /// Estimates normalized betweenness centrality by sampling shortest paths (Riondato-Kornaropoulos).
+    ///
+    /// Random vertex pairs are drawn and one of their shortest paths is picked uniformly;
+    /// every interior vertex on it earns `1 / r`. The sample size `r` depends only on the
+    /// accuracy parameters and an estimate of the vertex diameter, not on the graph size,
+    /// so every estimate is within `epsilon` of the exact value with probability `1 - delta`.
+    /// Exact values are normalized by `n (n - 1)` ordered pairs.
+    ///
+    /// # Arguments
+    ///
+    /// * `epsilon` - The additive error bound, in `(0, 1)`.
+    /// * `delta` - The failure probability, in `(0, 1)`.
+    /// * `seed` - The random seed; the same seed reproduces the same estimate.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<HashMap<String, f64>, GraphError>` - The estimated betweenness of every vertex,
+    ///   or an error if `epsilon` or `delta` is outside `(0, 1)`, an edge weight is negative or
+    ///   zero-weight edges form a cycle.
+    pub fn approximate_betweenness(&self, epsilon: f64, delta: f64, seed: u64) -> Result<HashMap<String, f64>, GraphError> {
+        self.require_distances("Betweenness centrality")?;
+        if !(epsilon > 0.0 && epsilon < 1.0 && delta > 0.0 && delta < 1.0) {
+            return Err(GraphError::InvalidArgument(format!("Epsilon {} and delta {} must both be in (0, 1)", epsilon, delta)));
+        }
+        let index: GraphIndex = self.index();
+        reject_negative_weights(&index)?;
+        reject_zero_weight_cycles(&index)?;
+        let n: usize = index.len();
+        let mut scores: Vec<f64> = vec![0.0; n];
+        if n < 3 {
+            return Ok(index.keys.into_iter().map(|key| (key, 0.0)).collect());
+        }
+        let mut rng: Rng = Rng::new(seed);
+
+        let vertex_diameter: f64 = vertex_diameter_bound(&index) as f64;
+        let bound: f64 = (vertex_diameter - 2.0).max(1.0).log2().floor() + 1.0;
+        let samples: usize = ((0.5 / (epsilon * epsilon)) * (bound + (1.0 / delta).ln())).ceil() as usize;
+
+        for _ in 0..samples {
+            let source: usize = rng.below(n);
+            let mut target: usize = rng.below(n - 1);
+            if target >= source {
+                target += 1;
+            }
+            let dag: ShortestPathDag = shortest_path_dag(&index, source);
+            if dag.distance[target].is_infinite() {
+                continue;
+            }
+            // Walk back from the target, choosing each predecessor in proportion to its path count.
+            let mut current: usize = target;
+            while current != source {
+                let total: f64 = dag.sigma[current];
+                let mut pick: f64 = rng.next_f64() * total;
+                let mut chosen: usize = dag.predecessors[current][0];
+                for &p in &dag.predecessors[current] {
+                    if pick < dag.sigma[p] {
+                        chosen = p;
+                        break;
+                    }
+                    pick -= dag.sigma[p];
+                }
+                if chosen != source {
+                    scores[chosen] += 1.0 / samples as f64;
+                }
+                current = chosen;
+            }
+        }
+
+        Ok(index.keys.into_iter().zip(scores).collect())
+    }
+
+    /// Computes current-flow (random-walk) betweenness centrality.
+    ///
+    /// Every pair `(s, t)` injects a unit current at `s` and extracts it at `t`, with edge
+    /// weights acting as conductances; a vertex scores the current passing through it,
+    /// averaged over all `(n - 1)(n - 2) / 2` pairs it is not an endpoint of. Node potentials
+    /// come from the inverse of the Laplacian, which costs `O(n^3)` once plus `O(n^2 m)`.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<HashMap<String, f32>, GraphError>` - The normalized score of every vertex, or an
+    ///   error if the graph is directed or not connected.
+    pub fn current_flow_betweenness(&self) -> Result<HashMap<String, f32>, GraphError> {
+        let (index, inverse) = current_flow_setup(self)?;
+        let n: usize = index.len();
+        let mut scores: Vec<f64> = vec![0.0; n];
+
+        for s in 0..n {
+            for t in (s + 1)..n {
+                let potential: Vec<f64> = (0..n).map(|v| inverse[v][s] - inverse[v][t]).collect();
+                for v in 0..n {
+                    if v == s || v == t {
+                        continue;
+                    }
+                    let through: f64 = index.adjacency[v].iter()
+                        .filter(|&&(w, _)| w != v)
+                        .map(|&(w, weight)| (f64::from(weight) * (potential[v] - potential[w])).abs())
+                        .sum();
+                    scores[v] += through / 2.0;
+                }
+            }
+        }
+
+        let pairs: f64 = if n > 2 { ((n - 1) * (n - 2)) as f64 / 2.0 } else { 1.0 };
+        Ok(index.keys.into_iter().zip(scores).map(|(key, score)| (key, (score / pairs) as f32)).collect())
+    }
+
+    /// Computes current-flow closeness (information) centrality.
+    ///
+    /// A vertex scores `(n - 1)` divided by the sum of its effective resistances to all
+    /// other vertices, treating edge weights as conductances.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<HashMap<String, f32>, GraphError>` - The score of every vertex, or an error if the
+    ///   graph is directed or not connected.
+    pub fn current_flow_closeness(&self) -> Result<HashMap<String, f32>, GraphError> {
+        let (index, inverse) = current_flow_setup(self)?;
+        let n: usize = index.len();
+        let scores: Vec<f64> = (0..n)
+            .map(|v| {
+                let resistance: f64 = (0..n)
+                    .filter(|&w| w != v)
+                    .map(|w| inverse[v][v] + inverse[w][w] - 2.0 * inverse[v][w])
+                    .sum();
+                if resistance > 0.0 { (n - 1) as f64 / resistance } else { 0.0 }
+            })
+            .collect();
+        Ok(index.keys.into_iter().zip(scores).map(|(key, score)| (key, score as f32)).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PageRankOptions;
+    use crate::graph::testing::graph_from;
+    use crate::graph::{Graph, GraphError};
+    use std::collections::HashMap;
+
+    #[test]
+    fn approximate_betweenness_rejects_bad_accuracy() {
+        let graph: Graph = graph_from(false, &[("A", "B", 1.0), ("B", "C", 1.0)]);
+        for (epsilon, delta) in [(0.0, 0.1), (1.0, 0.1), (0.1, 0.0), (0.1, 1.5), (f64::NAN, 0.1)] {
+            assert!(matches!(graph.approximate_betweenness(epsilon, delta, 1), Err(GraphError::InvalidArgument(_))));
+        }
+    }
+
+    #[test]
+    fn approximate_betweenness_rejects_negative_weights() {
+        let graph: Graph = graph_from(false, &[("A", "B", -1.0), ("B", "C", 1.0)]);
+        assert!(matches!(graph.approximate_betweenness(0.1, 0.1, 1), Err(GraphError::NegativeWeight(_, _))));
+    }
+
+    #[test]
+    fn approximate_betweenness_bounds_the_largest_component() {
+        // A lone edge next to a long path: a probe in the small component must not shrink the sample.
+        let mut edges: Vec<(String, String)> = vec![("X".to_string(), "Y".to_string())];
+        edges.extend((0..9).map(|i| (format!("P{}", i), format!("P{}", i + 1))));
+        let edges: Vec<(&str, &str, f32)> = edges.iter().map(|(a, b)| (a.as_str(), b.as_str(), 1.0)).collect();
+        let graph: Graph = graph_from(false, &edges);
+        let n: f64 = graph.vertices.len() as f64;
+        // P4 lies between the 4 path vertices before it and the 5 after it, in both directions.
+        let exact: f64 = 40.0 / (n * (n - 1.0));
+        assert!((graph.betweenness_centrality().unwrap()["P4"] - exact).abs() < 1e-12);
+        for seed in 0..5 {
+            let estimate: f64 = graph.approximate_betweenness(0.05, 0.01, seed).unwrap()["P4"];
+            assert!((estimate - exact).abs() <= 0.05, "seed {}: {} vs {}", seed, estimate, exact);
+        }
+    }
+
+    #[test]
+    fn shortest_path_dag_counts_ties_through_zero_weight_edges() {
+        let graph: Graph = graph_from(true, &[("S", "Z", 1.0), ("S", "B", 1.0), ("Z", "B", 0.0)]);
+        let dag = graph.shortest_path_dag("S").unwrap();
+        assert_eq!(dag.count("B").unwrap(), 2.0);
+        assert_eq!(dag.predecessors("B").unwrap(), vec!["S", "Z"]);
+        assert_eq!(dag.paths("B").unwrap().len(), 2);
+        // Z is on one of the two shortest S-B paths, and every other pair has a single path.
+        let scores: HashMap<String, f64> = graph.betweenness_centrality().unwrap();
+        assert_eq!(scores["Z"], 0.5 / 6.0);
+    }
+
+    #[test]
+    fn shortest_path_dag_orders_zero_weight_chains() {
+        // The chain C -> B -> A weighs nothing, against key order.
+        let graph: Graph = graph_from(true, &[("S", "C", 1.0), ("S", "B", 1.0), ("S", "A", 1.0), ("C", "B", 0.0), ("B", "A", 0.0)]);
+        let dag = graph.shortest_path_dag("S").unwrap();
+        assert_eq!((dag.count("B").unwrap(), dag.count("A").unwrap()), (2.0, 3.0));
+    }
+
+    #[test]
+    fn shortest_path_dag_rejects_zero_weight_cycles() {
+        let graph: Graph = graph_from(true, &[("S", "A", 1.0), ("A", "B", 0.0), ("B", "A", 0.0)]);
+        assert!(matches!(graph.shortest_path_dag("S"), Err(GraphError::CycleDetected(_))));
+        assert!(matches!(graph.betweenness_centrality(), Err(GraphError::CycleDetected(_))));
+        let graph: Graph = graph_from(false, &[("S", "A", 1.0), ("A", "B", 0.0)]);
+        assert!(matches!(graph.shortest_path_dag("S"), Err(GraphError::CycleDetected(_))));
+    }
+
+    #[test]
+    fn pagerank_is_cached_per_options() {
+        let mut graph: Graph = graph_from(true, &[("A", "B", 1.0), ("A", "C", 1.0), ("B", "C", 1.0)]);
+        graph.enable_cache();
+        let options: PageRankOptions = PageRankOptions::new();
+        let first: HashMap<String, f64> = graph.pagerank(&options).unwrap();
+
+        // An edit behind the graph's back is only seen by queries not remembered yet.
+        graph.vertices.get_mut("A").unwrap().edges[1].weight = 5.0;
+        assert_eq!(graph.pagerank(&options).unwrap(), first);
+        let damped: HashMap<String, f64> = graph.pagerank(&PageRankOptions::new().damping(0.5)).unwrap();
+        assert_ne!(damped, first);
+
+        graph.mark_changed();
+        let second: HashMap<String, f64> = graph.pagerank(&options).unwrap();
+        assert!(second["C"] > first["C"]);
+    }
+}
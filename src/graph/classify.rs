@@ -0,0 +1,157 @@
+use crate::graph::index::GraphIndex;
+use crate::graph::path::reject_negative_weights;
+use crate::graph::trace::{trace_counts, trace_span};
+use crate::graph::{Graph, GraphError};
+use std::collections::{BTreeSet, HashMap};
+
+/// Settings for `Graph::spread_labels`.
+///
+/// Built with chained calls, for example `LabelSpreadingOptions::new().alpha(0.9)`.
+/// The defaults are an alpha of `0.8`, at most `100` iterations and a tolerance of `1e-6`.
+#[derive(Clone, Copy, Debug)]
+pub struct LabelSpreadingOptions {
+    alpha: f64,
+    max_iterations: usize,
+    tolerance: f64,
+}
+
+impl Default for LabelSpreadingOptions {
+    fn default() -> LabelSpreadingOptions {
+        LabelSpreadingOptions { alpha: 0.8, max_iterations: 100, tolerance: 1e-6 }
+    }
+}
+
+impl LabelSpreadingOptions {
+    /// Creates the default settings.
+    pub fn new() -> LabelSpreadingOptions {
+        LabelSpreadingOptions::default()
+    }
+
+    /// Sets the share, in `[0, 1)`, of every round's scores that comes from the neighbors
+    /// rather than from the given labels. Higher values trust the graph more than the seeds.
+    pub fn alpha(mut self, alpha: f64) -> LabelSpreadingOptions {
+        self.alpha = alpha;
+        self
+    }
+
+    /// Sets the largest number of iterations to run before returning the current labels.
+    pub fn max_iterations(mut self, max_iterations: usize) -> LabelSpreadingOptions {
+        self.max_iterations = max_iterations;
+        self
+    }
+
+    /// Stops iterating once the scores change by less than `tolerance` in total.
+    pub fn tolerance(mut self, tolerance: f64) -> LabelSpreadingOptions {
+        self.tolerance = tolerance;
+        self
+    }
+}
+
+/// The label predicted for a vertex by `Graph::spread_labels`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LabelPrediction {
+    /// The most likely label.
+    pub label: String,
+    /// The share, in `(0, 1]`, of the vertex's score that went to `label`.
+    pub confidence: f64,
+}
+
+/// Returns the weighted neighbors of every vertex with direction ignored and self-loops
+/// dropped.
+fn undirected_weights(index: &GraphIndex) -> Vec<Vec<(usize, f64)>> {
+    let mut weights: Vec<Vec<(usize, f64)>> = vec![Vec::new(); index.len()];
+    for (u, neighbors) in index.adjacency.iter().enumerate() {
+        for &(v, weight) in neighbors.iter().filter(|&&(v, _)| v != u) {
+            weights[u].push((v, f64::from(weight)));
+            // Undirected edges are already listed under both endpoints.
+            if index.directed {
+                weights[v].push((u, f64::from(weight)));
+            }
+        }
+    }
+    weights
+}
+
+impl Graph {
+    /// Predicts labels for every vertex from labels known on a few, by spreading them over
+    /// the weighted edges (the label spreading of Zhou et al.).
+    ///
+    /// Every round, each vertex takes `alpha` of its score for each label from its
+    /// neighbors, through the symmetrically normalized weights `D^-1/2 W D^-1/2`, and the
+    /// rest from its own given label, until the scores settle. Seeds are predicted too and
+    /// may be outvoted by their neighborhood, which helps with noisy labels. Direction is
+    /// ignored and self-loops are dropped. Ties go to the label that sorts first.
+    ///
+    /// # Arguments
+    ///
+    /// * `seeds` - The known label of each labeled vertex.
+    /// * `options` - The `LabelSpreadingOptions` to use.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<HashMap<String, LabelPrediction>, GraphError>` - The prediction for every
+    ///   vertex connected to a seed, or an error if there are no seeds, a seed is missing,
+    ///   `alpha` is outside `[0, 1)` or a weight is negative.
+    pub fn spread_labels(&self, seeds: &HashMap<String, String>, options: &LabelSpreadingOptions) -> Result<HashMap<String, LabelPrediction>, GraphError> {
+        if !(0.0..1.0).contains(&options.alpha) {
+            return Err(GraphError::InvalidArgument(format!("Alpha {} is outside [0, 1)", options.alpha)));
+        }
+        if seeds.is_empty() {
+            return Err(GraphError::InvalidArgument("Label spreading needs at least one labeled vertex".to_string()));
+        }
+        let index: GraphIndex = self.index();
+        reject_negative_weights(&index)?;
+        let n: usize = index.len();
+        trace_span!("spread_labels", vertices = n);
+
+        let labels: Vec<&String> = seeds.values().collect::<BTreeSet<&String>>().into_iter().collect();
+        let label_positions: HashMap<&String, usize> = labels.iter().enumerate().map(|(c, &label)| (label, c)).collect();
+        let mut given: Vec<Vec<f64>> = vec![vec![0.0; labels.len()]; n];
+        for (key, label) in seeds {
+            given[index.position(key)?][label_positions[label]] = 1.0;
+        }
+
+        let weights: Vec<Vec<(usize, f64)>> = undirected_weights(&index);
+        let scale: Vec<f64> = weights.iter()
+            .map(|neighbors| {
+                let degree: f64 = neighbors.iter().map(|&(_, weight)| weight).sum();
+                if degree > 0.0 { 1.0 / degree.sqrt() } else { 0.0 }
+            })
+            .collect();
+
+        let mut scores: Vec<Vec<f64>> = given.clone();
+        let mut iterations: usize = 0;
+        while iterations < options.max_iterations {
+            iterations += 1;
+            let mut next: Vec<Vec<f64>> = given.iter()
+                .map(|row| row.iter().map(|&value| (1.0 - options.alpha) * value).collect())
+                .collect();
+            for (u, neighbors) in weights.iter().enumerate() {
+                for &(v, weight) in neighbors {
+                    let share: f64 = options.alpha * scale[u] * weight * scale[v];
+                    for (target, &source) in next[u].iter_mut().zip(&scores[v]) {
+                        *target += share * source;
+                    }
+                }
+            }
+            let change: f64 = scores.iter().flatten().zip(next.iter().flatten()).map(|(a, b)| (a - b).abs()).sum();
+            scores = next;
+            if change < options.tolerance {
+                break;
+            }
+        }
+        trace_counts!("spread_labels finished", iterations = iterations);
+
+        let mut predictions: HashMap<String, LabelPrediction> = HashMap::new();
+        for (u, row) in scores.iter().enumerate() {
+            let total: f64 = row.iter().sum();
+            if total <= 0.0 {
+                continue;
+            }
+            // The first of the highest scores, so ties go to the label that sorts first.
+            let best: usize = (0..row.len()).fold(0, |best, c| if row[c] > row[best] { c } else { best });
+            predictions.insert(index.keys[u].clone(), LabelPrediction { label: labels[best].clone(), confidence: row[best] / total });
+        }
+        Ok(predictions)
+    }
+}
@@ -0,0 +1,402 @@
+use crate::graph::cycles::EdgeList;
+use crate::graph::index::GraphIndex;
+use crate::graph::{Graph, GraphError};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// Returns the distinct neighbors of every vertex, ignoring direction and self-loops.
+pub(crate) fn symmetric_neighbors(index: &GraphIndex) -> Vec<HashSet<usize>> {
+    let mut neighbors: Vec<HashSet<usize>> = vec![HashSet::new(); index.len()];
+    for (u, list) in index.adjacency.iter().enumerate() {
+        for &(v, _) in list {
+            if u != v {
+                neighbors[u].insert(v);
+                neighbors[v].insert(u);
+            }
+        }
+    }
+    neighbors
+}
+
+/// Colors the vertices with the DSatur heuristic and returns the color of every vertex.
+///
+/// The next vertex colored is always the one with the most distinct neighbor colors
+/// (ties broken by degree, then position), and it takes the smallest free color.
+pub(crate) fn dsatur(neighbors: &[HashSet<usize>]) -> Vec<usize> {
+    let n: usize = neighbors.len();
+    let mut colors: Vec<Option<usize>> = vec![None; n];
+    let mut saturation: Vec<HashSet<usize>> = vec![HashSet::new(); n];
+
+    for _ in 0..n {
+        let v: usize = (0..n)
+            .filter(|&v| colors[v].is_none())
+            .max_by(|&a, &b| {
+                saturation[a].len().cmp(&saturation[b].len())
+                    .then_with(|| neighbors[a].len().cmp(&neighbors[b].len()))
+                    .then_with(|| b.cmp(&a))
+            })
+            .unwrap();
+        let color: usize = (0..).find(|c| !saturation[v].contains(c)).unwrap();
+        colors[v] = Some(color);
+        for &u in &neighbors[v] {
+            saturation[u].insert(color);
+        }
+    }
+    colors.into_iter().map(Option::unwrap).collect()
+}
+
+/// Colors the vertices greedily in Welsh-Powell order and returns the color of every vertex.
+///
+/// Vertices are taken by decreasing degree (ties broken by position), and each takes the
+/// smallest color none of its neighbors has.
+fn welsh_powell(neighbors: &[HashSet<usize>]) -> Vec<usize> {
+    let n: usize = neighbors.len();
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_by(|&a, &b| neighbors[b].len().cmp(&neighbors[a].len()).then(a.cmp(&b)));
+    let mut colors: Vec<Option<usize>> = vec![None; n];
+    for v in order {
+        let taken: HashSet<usize> = neighbors[v].iter().filter_map(|&u| colors[u]).collect();
+        colors[v] = (0..).find(|c| !taken.contains(c));
+    }
+    colors.into_iter().map(Option::unwrap).collect()
+}
+
+/// Splits the vertices into two sides if the graph is bipartite, ignoring edge direction.
+///
+/// Returns the side of every vertex (`false` or `true`), or `None` if an odd cycle exists.
+pub(crate) fn bipartition(neighbors: &[Vec<usize>]) -> Option<Vec<bool>> {
+    let n: usize = neighbors.len();
+    let mut side: Vec<Option<bool>> = vec![None; n];
+    for root in 0..n {
+        if side[root].is_some() {
+            continue;
+        }
+        side[root] = Some(false);
+        let mut queue: VecDeque<usize> = VecDeque::from([root]);
+        while let Some(u) = queue.pop_front() {
+            let current: bool = side[u].unwrap();
+            for &v in &neighbors[u] {
+                match side[v] {
+                    None => {
+                        side[v] = Some(!current);
+                        queue.push_back(v);
+                    }
+                    Some(other) if other == current => return None,
+                    Some(_) => {}
+                }
+            }
+        }
+    }
+    Some(side.into_iter().map(Option::unwrap).collect())
+}
+
+/// Per-vertex bookkeeping of which incident edge holds which color.
+struct EdgeColors {
+    /// The color of every edge.
+    color: Vec<Option<usize>>,
+    /// For every vertex, the incident edge using each color.
+    at: Vec<HashMap<usize, usize>>,
+}
+
+impl EdgeColors {
+    fn new(n: usize, m: usize) -> EdgeColors {
+        EdgeColors { color: vec![None; m], at: vec![HashMap::new(); n] }
+    }
+
+    fn set(&mut self, edges: &[(usize, usize, f32)], e: usize, color: Option<usize>) {
+        let (u, v, _) = edges[e];
+        if let Some(old) = self.color[e] {
+            self.at[u].remove(&old);
+            self.at[v].remove(&old);
+        }
+        self.color[e] = color;
+        if let Some(new) = color {
+            self.at[u].insert(new, e);
+            self.at[v].insert(new, e);
+        }
+    }
+
+    fn free(&self, v: usize) -> usize {
+        (0..).find(|c| !self.at[v].contains_key(c)).unwrap()
+    }
+
+    fn is_free(&self, v: usize, color: usize) -> bool {
+        !self.at[v].contains_key(&color)
+    }
+
+    /// Swaps colors `a` and `b` along the alternating path that starts at `start` with color `a`.
+    fn flip_path(&mut self, edges: &[(usize, usize, f32)], start: usize, a: usize, b: usize) {
+        let mut path: Vec<usize> = Vec::new();
+        let (mut vertex, mut color) = (start, a);
+        while let Some(&e) = self.at[vertex].get(&color) {
+            if path.contains(&e) {
+                break;
+            }
+            path.push(e);
+            vertex = if edges[e].0 == vertex { edges[e].1 } else { edges[e].0 };
+            color = if color == a { b } else { a };
+        }
+        for &e in &path {
+            self.set(edges, e, None);
+        }
+        for (i, &e) in path.iter().enumerate() {
+            self.set(edges, e, Some(if i % 2 == 0 { b } else { a }));
+        }
+    }
+}
+
+/// Colors the edges of a bipartite multigraph with exactly `max degree` colors (König).
+fn bipartite_edge_colors(n: usize, edges: &[(usize, usize, f32)]) -> Vec<usize> {
+    let mut colors: EdgeColors = EdgeColors::new(n, edges.len());
+    for e in 0..edges.len() {
+        let (u, v, _) = edges[e];
+        let a: usize = colors.free(u);
+        let b: usize = colors.free(v);
+        if a != b && !colors.is_free(v, a) {
+            // In a bipartite graph the a/b path from v can never reach u.
+            colors.flip_path(edges, v, a, b);
+        }
+        colors.set(edges, e, Some(a));
+    }
+    colors.color.into_iter().map(Option::unwrap).collect()
+}
+
+/// Colors the edges of a simple graph with at most `max degree + 1` colors (Misra-Gries).
+fn vizing_edge_colors(n: usize, edges: &[(usize, usize, f32)], incident: &[Vec<(usize, usize)>]) -> Vec<usize> {
+    let mut colors: EdgeColors = EdgeColors::new(n, edges.len());
+    let edge_between = |x: usize, y: usize| incident[x].iter().find(|&&(w, _)| w == y).unwrap().1;
+
+    for e in 0..edges.len() {
+        let (x, first, _) = edges[e];
+
+        // Build a maximal fan of x starting at `first`.
+        let mut fan: Vec<usize> = vec![first];
+        loop {
+            let last: usize = *fan.last().unwrap();
+            let next = incident[x].iter().find(|&&(w, f)| {
+                !fan.contains(&w) && colors.color[f].is_some_and(|c| colors.is_free(last, c))
+            });
+            match next {
+                Some(&(w, _)) => fan.push(w),
+                None => break,
+            }
+        }
+
+        let c: usize = colors.free(x);
+        let d: usize = colors.free(*fan.last().unwrap());
+        if c != d {
+            colors.flip_path(edges, x, d, c);
+        }
+
+        // The longest prefix of the fan that is still a fan and ends at a vertex where d is free.
+        let mut end: usize = 0;
+        for (i, &w) in fan.iter().enumerate() {
+            if i > 0 {
+                let coloring = colors.color[edge_between(x, w)];
+                if !coloring.is_some_and(|col| colors.is_free(fan[i - 1], col)) {
+                    break;
+                }
+            }
+            if colors.is_free(w, d) {
+                end = i;
+                break;
+            }
+        }
+
+        // Rotate the fan prefix and give the freed edge color d.
+        for i in 0..end {
+            let next_color: Option<usize> = colors.color[edge_between(x, fan[i + 1])];
+            colors.set(edges, edge_between(x, fan[i + 1]), None);
+            colors.set(edges, edge_between(x, fan[i]), next_color);
+        }
+        colors.set(edges, edge_between(x, fan[end]), Some(d));
+    }
+    colors.color.into_iter().map(Option::unwrap).collect()
+}
+
+/// Numbers the edges of an undirected graph and rejects self-loops.
+fn colorable_edges(graph: &Graph) -> Result<(GraphIndex, EdgeList), GraphError> {
+    if graph.directed {
+        return Err(GraphError::NeedsUndirected("Edge coloring".to_string()));
+    }
+    let index: GraphIndex = graph.index();
+    let list: EdgeList = EdgeList::new(&index);
+    if let Some(&(u, _, _)) = list.edges.iter().find(|&&(u, v, _)| u == v) {
+        return Err(GraphError::SelfLoop(index.keys[u].clone()));
+    }
+    Ok((index, list))
+}
+
+/// Pairs every numbered edge with its color.
+fn colored_edges(index: &GraphIndex, list: &EdgeList, colors: Vec<usize>) -> Vec<(String, String, usize)> {
+    list.edges.iter()
+        .zip(colors)
+        .map(|(&(u, v, _), color)| (index.keys[u].clone(), index.keys[v].clone(), color))
+        .collect()
+}
+
+/// A conflict-free schedule built from a coloring.
+#[derive(Clone, Debug)]
+pub struct Schedule {
+    /// The vertices running together in every time slot, in execution order.
+    pub slots: Vec<Vec<String>>,
+    /// The start time of every vertex.
+    pub start: HashMap<String, f32>,
+    /// The time at which the last slot finishes.
+    pub makespan: f32,
+}
+
+/// A vertex coloring in which adjacent vertices have different colors.
+#[derive(Clone, Debug)]
+pub struct Coloring {
+    /// The color of every vertex, numbered from `0`.
+    pub colors: HashMap<String, usize>,
+    /// The number of colors used, an upper bound on the chromatic number.
+    pub color_count: usize,
+}
+
+impl Graph {
+    /// Colors the vertices so that adjacent vertices get different colors, with the greedy
+    /// Welsh-Powell heuristic.
+    ///
+    /// Vertices are colored by decreasing degree, each with the smallest color its neighbors
+    /// do not have, so at most `max degree + 1` colors are used. Edge direction and
+    /// self-loops are ignored, which suits interference graphs for register allocation.
+    ///
+    /// # Returns
+    ///
+    /// * A `Coloring` with the color of every vertex and the number of colors used.
+    pub fn greedy_coloring(&self) -> Coloring {
+        let index: GraphIndex = self.index();
+        let colors: Vec<usize> = welsh_powell(&symmetric_neighbors(&index));
+        Coloring {
+            color_count: colors.iter().max().map_or(0, |c| c + 1),
+            colors: index.keys.iter().cloned().zip(colors).collect(),
+        }
+    }
+
+    /// Checks that a vertex coloring is proper, meaning no edge joins two vertices of the
+    /// same color. Edge direction and self-loops are ignored, as in `greedy_coloring`.
+    ///
+    /// # Arguments
+    ///
+    /// * `colors` - The color of every vertex.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<bool, GraphError>` - Whether the coloring is proper, or an error if a vertex
+    ///   has no color or a colored vertex does not exist.
+    pub fn is_proper_coloring(&self, colors: &HashMap<String, usize>) -> Result<bool, GraphError> {
+        if let Some(key) = colors.keys().find(|key| !self.vertices.contains_key(*key)) {
+            return Err(GraphError::VertexNotFound(key.clone()));
+        }
+        if let Some(key) = self.vertices.keys().find(|key| !colors.contains_key(*key)) {
+            return Err(GraphError::InvalidArgument(format!("Vertex {} has no color", key)));
+        }
+        Ok(self.stored_edges().iter().all(|edge| edge.vertex1 == edge.vertex2 || colors[&edge.vertex1] != colors[&edge.vertex2]))
+    }
+
+    /// Builds a conflict-free schedule from a conflict graph and per-vertex durations.
+    ///
+    /// Adjacent vertices conflict and must not run at the same time. The graph is colored
+    /// with DSatur, every color class becomes one time slot that lasts as long as its longest
+    /// task, and slots run back to back. Edge direction and self-loops are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `duration` - Returns how long the task for a vertex takes.
+    ///
+    /// # Returns
+    ///
+    /// * A `Schedule` with the slots, start times and makespan.
+    pub fn conflict_schedule<D>(&self, duration: D) -> Schedule
+    where
+        D: Fn(&str) -> f32,
+    {
+        let index: GraphIndex = self.index();
+        let colors: Vec<usize> = dsatur(&symmetric_neighbors(&index));
+        let durations: Vec<f32> = index.keys.iter().map(|key| duration(key)).collect();
+
+        let mut slots: Vec<Vec<usize>> = vec![Vec::new(); colors.iter().max().map_or(0, |c| c + 1)];
+        for (v, &color) in colors.iter().enumerate() {
+            slots[color].push(v);
+        }
+        let length = |slot: &Vec<usize>| slot.iter().map(|&v| durations[v]).fold(0.0, f32::max);
+        slots.sort_by(|a, b| length(b).total_cmp(&length(a)));
+
+        let mut start: HashMap<String, f32> = HashMap::new();
+        let mut time: f32 = 0.0;
+        for slot in &slots {
+            for &v in slot {
+                start.insert(index.keys[v].clone(), time);
+            }
+            time += length(slot);
+        }
+
+        Schedule {
+            slots: slots.into_iter()
+                .map(|slot| slot.into_iter().map(|v| index.keys[v].clone()).collect())
+                .collect(),
+            start,
+            makespan: time,
+        }
+    }
+
+    /// Colors the edges so that edges sharing a vertex get different colors.
+    ///
+    /// Bipartite graphs use `bipartite_edge_coloring`, which is optimal; all other graphs use
+    /// `vizing_edge_coloring`, which needs at most one color more than the maximum degree.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<(String, String, usize)>, GraphError>` - Every edge with its color, or an error
+    ///   if the graph is directed, has a self-loop, or is a non-bipartite multigraph.
+    pub fn edge_coloring(&self) -> Result<Vec<(String, String, usize)>, GraphError> {
+        let (index, list) = colorable_edges(self)?;
+        let neighbors: Vec<Vec<usize>> = list.incident.iter().map(|l| l.iter().map(|&(v, _)| v).collect()).collect();
+        if bipartition(&neighbors).is_some() {
+            let colors: Vec<usize> = bipartite_edge_colors(index.len(), &list.edges);
+            Ok(colored_edges(&index, &list, colors))
+        } else {
+            self.vizing_edge_coloring()
+        }
+    }
+
+    /// Colors the edges of a bipartite graph with exactly as many colors as its maximum degree.
+    ///
+    /// Edges are colored one at a time; when the endpoints have no common free color, an
+    /// alternating two-colored path is flipped to make one (König's theorem). Parallel edges
+    /// are allowed.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<(String, String, usize)>, GraphError>` - Every edge with its color, or an error
+    ///   if the graph is directed, has a self-loop, or is not bipartite.
+    pub fn bipartite_edge_coloring(&self) -> Result<Vec<(String, String, usize)>, GraphError> {
+        let (index, list) = colorable_edges(self)?;
+        let neighbors: Vec<Vec<usize>> = list.incident.iter().map(|l| l.iter().map(|&(v, _)| v).collect()).collect();
+        if bipartition(&neighbors).is_none() {
+            return Err(GraphError::NoSolution("Graph is not bipartite".to_string()));
+        }
+        let colors: Vec<usize> = bipartite_edge_colors(index.len(), &list.edges);
+        Ok(colored_edges(&index, &list, colors))
+    }
+
+    /// Colors the edges of a simple graph with at most `max degree + 1` colors.
+    ///
+    /// Uses the Misra-Gries constructive proof of Vizing's theorem: each edge is colored by
+    /// building a fan around one endpoint, flipping one alternating path and rotating the fan.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<(String, String, usize)>, GraphError>` - Every edge with its color, or an error
+    ///   if the graph is directed or has self-loops or parallel edges.
+    pub fn vizing_edge_coloring(&self) -> Result<Vec<(String, String, usize)>, GraphError> {
+        let (index, list) = colorable_edges(self)?;
+        let mut seen: HashSet<(usize, usize)> = HashSet::new();
+        if let Some(&(u, v, _)) = list.edges.iter().find(|&&(u, v, _)| !seen.insert((u, v))) {
+            return Err(GraphError::DuplicateEdge(index.keys[u].clone(), index.keys[v].clone()));
+        }
+        let colors: Vec<usize> = vizing_edge_colors(index.len(), &list.edges, &list.incident);
+        Ok(colored_edges(&index, &list, colors))
+    }
+}
@@ -0,0 +1,240 @@
+use crate::graph::{Edge, Graph, GraphError, Vertex};
+use std::collections::BTreeMap;
+
+/// The weights of the edges between a pair of vertices, for each direction of travel.
+///
+/// Undirected pairs are keyed with the smaller key first; the first weight is the cost of
+/// travelling from the first key.
+type PairWeights = BTreeMap<(String, String), (f32, f32)>;
+
+/// How `Graph::union` and `Graph::intersection` settle an edge whose weights differ, whether
+/// between the two graphs or between parallel edges of one graph.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WeightConflict {
+    /// Keeps the smaller weight.
+    Min,
+    /// Keeps the larger weight.
+    Max,
+    /// Keeps the weight from the graph the method is called on, or the first of parallel
+    /// edges.
+    Left,
+    /// Keeps the weight from the other graph, or the last of parallel edges.
+    Right,
+    /// Fails with `GraphError::InvalidArgument` naming the edge.
+    Error,
+}
+
+impl WeightConflict {
+    /// Settles the weights `left` and `right` of the edge `(from, to)`.
+    fn resolve(self, from: &str, to: &str, left: f32, right: f32) -> Result<f32, GraphError> {
+        if left == right {
+            return Ok(left);
+        }
+        match self {
+            WeightConflict::Min => Ok(left.min(right)),
+            WeightConflict::Max => Ok(left.max(right)),
+            WeightConflict::Left => Ok(left),
+            WeightConflict::Right => Ok(right),
+            WeightConflict::Error => Err(GraphError::InvalidArgument(format!(
+                "Edge ({}, {}) has conflicting weights {} and {}", from, to, left, right
+            ))),
+        }
+    }
+
+    /// Settles both directions of the pair `(a, b)`.
+    fn resolve_pair(self, (a, b): &(String, String), left: (f32, f32), right: (f32, f32)) -> Result<(f32, f32), GraphError> {
+        Ok((self.resolve(a, b, left.0, right.0)?, self.resolve(b, a, left.1, right.1)?))
+    }
+}
+
+/// Collects the edges of `graph` by vertex pair, merging parallel edges with `conflict`.
+fn pair_weights(graph: &Graph, conflict: WeightConflict) -> Result<PairWeights, GraphError> {
+    let mut pairs: PairWeights = BTreeMap::new();
+    for edge in graph.stored_edges() {
+        let (a, b) = (&edge.vertex1, &edge.vertex2);
+        let pair: (String, String) = if graph.directed || a <= b { (a.clone(), b.clone()) } else { (b.clone(), a.clone()) };
+        let weights: (f32, f32) = (edge.weight_from(pair.0.as_str()), edge.weight_from(pair.1.as_str()));
+        let merged: (f32, f32) = match pairs.get(&pair) {
+            Some(&existing) => conflict.resolve_pair(&pair, existing, weights)?,
+            None => weights,
+        };
+        pairs.insert(pair, merged);
+    }
+    Ok(pairs)
+}
+
+/// Returns an error unless both graphs are directed or both are undirected.
+fn same_direction(a: &Graph, b: &Graph) -> Result<(), GraphError> {
+    if a.directed != b.directed {
+        return Err(GraphError::InvalidArgument("Cannot combine a directed and an undirected graph".to_string()));
+    }
+    Ok(())
+}
+
+impl Graph {
+    /// Builds a graph with the direction and settings of this one and the given vertices,
+    /// copying the attributes each has in this graph and then in `other`, and one edge per pair.
+    ///
+    /// Fails only when this graph is `GraphMode::Simple` and a pair is a self-loop.
+    fn assemble<'a, K>(&self, other: &Graph, keys: K, pairs: PairWeights) -> Result<Graph, GraphError>
+    where
+        K: IntoIterator<Item = &'a String>,
+    {
+        let mut graph: Graph = self.empty_like();
+        for key in keys {
+            let mut vertex: Vertex = Vertex::new(key.clone());
+            for source in [other, self] {
+                if let Some(existing) = source.vertices.get(key) {
+                    vertex.attributes.extend(existing.attributes.clone());
+                }
+            }
+            graph.add_vertex(vertex);
+        }
+        for ((a, b), (forward, backward)) in pairs {
+            let edge: Edge = if self.directed || forward == backward {
+                Edge::new(a, b, forward)
+            } else {
+                Edge::asymmetric(a, b, forward, backward)
+            };
+            graph.add_edge(edge)?;
+        }
+        Ok(graph)
+    }
+
+    /// Merges two graphs by vertex key and edge endpoints.
+    ///
+    /// The result has every vertex and every edge of either graph. Edges are identified by
+    /// their endpoints, as ordered pairs in directed graphs, so parallel edges become one edge
+    /// and an edge in both graphs appears once, its weight settled by `conflict`. A vertex in
+    /// both graphs takes its attributes from `other`, overridden by those set in this graph.
+    /// The result keeps the `GraphMode`, `VertexOrder` and weight semantics of this graph.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The graph to merge in.
+    /// * `conflict` - How to settle an edge whose weights differ.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Graph, GraphError>` - The union, or an error if only one graph is directed,
+    ///   `conflict` is `WeightConflict::Error` and a weight differs, or this graph is
+    ///   `GraphMode::Simple` and `other` has a self-loop.
+    pub fn union(&self, other: &Graph, conflict: WeightConflict) -> Result<Graph, GraphError> {
+        same_direction(self, other)?;
+        let mut pairs: PairWeights = pair_weights(self, conflict)?;
+        for (pair, weights) in pair_weights(other, conflict)? {
+            let merged: (f32, f32) = match pairs.get(&pair) {
+                Some(&existing) => conflict.resolve_pair(&pair, existing, weights)?,
+                None => weights,
+            };
+            pairs.insert(pair, merged);
+        }
+        let mut keys: Vec<String> = self.vertex_keys();
+        keys.extend(other.vertex_keys().into_iter().filter(|key| !self.vertices.contains_key(key)));
+        self.assemble(other, &keys, pairs)
+    }
+
+    /// Keeps what two graphs have in common.
+    ///
+    /// The result has the vertices in both graphs and the edges, identified by their
+    /// endpoints as in `union`, in both graphs, with weights settled by `conflict`.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The graph to compare with.
+    /// * `conflict` - How to settle an edge whose weights differ.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Graph, GraphError>` - The intersection, or an error if only one graph is
+    ///   directed or `conflict` is `WeightConflict::Error` and a weight differs.
+    pub fn intersection(&self, other: &Graph, conflict: WeightConflict) -> Result<Graph, GraphError> {
+        same_direction(self, other)?;
+        let theirs: PairWeights = pair_weights(other, conflict)?;
+        let mut pairs: PairWeights = BTreeMap::new();
+        for (pair, weights) in pair_weights(self, conflict)? {
+            if let Some(&other_weights) = theirs.get(&pair) {
+                let merged: (f32, f32) = conflict.resolve_pair(&pair, weights, other_weights)?;
+                pairs.insert(pair, merged);
+            }
+        }
+        let keys: Vec<String> = self.vertex_keys().into_iter().filter(|key| other.vertices.contains_key(key)).collect();
+        self.assemble(other, &keys, pairs)
+    }
+
+    /// Keeps the edges of this graph that `other` does not have, for example to see which
+    /// dependencies a new snapshot added.
+    ///
+    /// Every vertex of this graph is kept, so an edge's endpoints are always present; compare
+    /// the `vertices` maps to find added or removed vertices. Edges are identified by their
+    /// endpoints as in `union`, ignoring weights, and parallel edges become one edge with the
+    /// first edge's weight.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The graph whose edges to remove.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Graph, GraphError>` - The difference, or an error if only one graph is directed.
+    pub fn difference(&self, other: &Graph) -> Result<Graph, GraphError> {
+        same_direction(self, other)?;
+        let theirs: PairWeights = pair_weights(other, WeightConflict::Left)?;
+        let mut pairs: PairWeights = pair_weights(self, WeightConflict::Left)?;
+        pairs.retain(|pair, _| !theirs.contains_key(pair));
+        self.assemble(self, &self.vertex_keys(), pairs)
+    }
+
+    /// Builds the complement: the same vertices, joined exactly where this graph has no edge.
+    ///
+    /// Every new edge has weight `1`, and no self-loops are added. In directed graphs each
+    /// ordered pair is considered separately.
+    ///
+    /// # Returns
+    ///
+    /// * A `Graph` with the same vertices, their attributes and the missing edges.
+    pub fn complement(&self) -> Graph {
+        // Parallel edges never conflict here, since only the pairs are looked at.
+        let present: PairWeights = pair_weights(self, WeightConflict::Left).unwrap();
+        let keys: Vec<&String> = self.vertices.keys().collect();
+        let mut pairs: PairWeights = BTreeMap::new();
+        for (i, a) in keys.iter().enumerate() {
+            for (j, b) in keys.iter().enumerate() {
+                let pair: (String, String) = ((*a).clone(), (*b).clone());
+                if i != j && (self.directed || i < j) && !present.contains_key(&pair) {
+                    pairs.insert(pair, (1.0, 1.0));
+                }
+            }
+        }
+        // The complement has no self-loops, so even a simple graph takes every pair.
+        self.assemble(self, &self.vertex_keys(), pairs).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::WeightConflict;
+    use crate::graph::graph::{GraphMode, WeightSemantics};
+    use crate::graph::testing::graph_from;
+    use crate::graph::{Graph, GraphError};
+
+    #[test]
+    fn union_keeps_settings_of_this_graph() {
+        let mut left: Graph = graph_from(false, &[("a", "b", 1.0)]);
+        left.set_weight_semantics(WeightSemantics::Similarity);
+        let right: Graph = graph_from(false, &[("b", "c", 2.0), ("a", "b", 3.0)]);
+        let union: Graph = left.union(&right, WeightConflict::Max).unwrap();
+        assert_eq!(union.edge_count, 2);
+        assert_eq!(union.weight_semantics(), WeightSemantics::Similarity);
+        assert!(union.has_edge("a", "b") && union.has_edge("b", "c"));
+    }
+
+    #[test]
+    fn simple_union_rejects_self_loops() {
+        let mut left: Graph = graph_from(false, &[("a", "b", 1.0)]);
+        left.set_mode(GraphMode::Simple).unwrap();
+        let right: Graph = graph_from(false, &[("a", "a", 1.0)]);
+        assert!(matches!(left.union(&right, WeightConflict::Left), Err(GraphError::SelfLoop(_))));
+        assert_eq!(left.complement().mode(), GraphMode::Simple);
+    }
+}
@@ -0,0 +1,563 @@
+use crate::graph::centrality::{pagerank_scores, PageRankOptions};
+use crate::graph::cycles::EdgeList;
+use crate::graph::index::GraphIndex;
+use crate::graph::path::reject_negative_weights;
+use crate::graph::rng::Rng;
+use crate::graph::spanning::UnionFind;
+use crate::graph::{Graph, GraphError};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BinaryHeap};
+
+/// The probability that the random walker behind `Graph::infomap` teleports at each step.
+const TELEPORTATION: f64 = 0.15;
+
+/// Moves that shorten the description length by less than this are not made.
+const MIN_IMPROVEMENT: f64 = 1e-10;
+
+/// One step of an agglomerative clustering: two clusters joined into a new one.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Merge {
+    /// The id of the first cluster joined.
+    pub first: usize,
+    /// The id of the second cluster joined.
+    pub second: usize,
+    /// The modularity of the partition right after this merge.
+    pub modularity: f64,
+}
+
+/// The full merge history of an agglomerative clustering, cut at any level with `cut`.
+///
+/// Clusters are numbered as in SciPy's linkage matrices: leaf `i` is the vertex
+/// `leaves[i]`, and merge `k` creates cluster `leaves.len() + k`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Dendrogram {
+    /// The vertex keys, in leaf order.
+    pub leaves: Vec<String>,
+    /// The merges, in the order they were made.
+    pub merges: Vec<Merge>,
+    /// The modularity of the partition into single vertices.
+    pub initial_modularity: f64,
+}
+
+impl Dendrogram {
+    /// Returns the communities after the first `merges` merges.
+    ///
+    /// # Arguments
+    ///
+    /// * `merges` - The number of merges to apply; larger values apply them all.
+    ///
+    /// # Returns
+    ///
+    /// * A `Vec<Vec<String>>` with the keys of every community, each sorted, ordered by their
+    ///   smallest key.
+    pub fn cut(&self, merges: usize) -> Vec<Vec<String>> {
+        let n: usize = self.leaves.len();
+        let mut sets: UnionFind = UnionFind::new(n);
+        // The leaf standing for every cluster created so far.
+        let mut representative: Vec<usize> = (0..n).collect();
+        for merge in self.merges.iter().take(merges) {
+            let (a, b) = (representative[merge.first], representative[merge.second]);
+            sets.union(a, b);
+            representative.push(a);
+        }
+        let mut groups: BTreeMap<usize, Vec<String>> = BTreeMap::new();
+        for v in 0..n {
+            groups.entry(sets.find(v)).or_default().push(self.leaves[v].clone());
+        }
+        let mut communities: Vec<Vec<String>> = groups.into_values().collect();
+        for community in &mut communities {
+            community.sort();
+        }
+        communities.sort();
+        communities
+    }
+
+    /// Returns the communities at the level of highest modularity, the earliest if several
+    /// tie.
+    pub fn best_cut(&self) -> Vec<Vec<String>> {
+        let mut best: (usize, f64) = (0, self.initial_modularity);
+        for (k, merge) in self.merges.iter().enumerate() {
+            if merge.modularity > best.1 {
+                best = (k + 1, merge.modularity);
+            }
+        }
+        self.cut(best.0)
+    }
+}
+
+/// A partition of the vertices found by `Graph::infomap`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FlowCommunities {
+    /// The keys of every community, each sorted, ordered by their smallest key.
+    pub communities: Vec<Vec<String>>,
+    /// The bits per step needed to describe the random walk with this partition.
+    pub codelength: f64,
+    /// The bits per step without any partition; the saving over it measures how much flow
+    /// the communities trap.
+    pub one_level_codelength: f64,
+}
+
+/// Returns `p log2 p`, taken as `0` at `p = 0`.
+fn plogp(p: f64) -> f64 {
+    if p > 0.0 { p * p.log2() } else { 0.0 }
+}
+
+/// The flow network one level of the Infomap search works on, where every node is a module
+/// of the level below.
+struct FlowLevel {
+    /// The visit rate of every node.
+    visits: Vec<f64>,
+    /// The flow leaving every node along links to other nodes.
+    outflow: Vec<f64>,
+    /// For every node, the other nodes it exchanges flow with, as `(node, flow to it, flow
+    /// from it)`.
+    links: Vec<Vec<(usize, f64, f64)>>,
+}
+
+impl FlowLevel {
+    /// Builds the level from the visit rates and the flow along every link.
+    fn new(visits: Vec<f64>, flows: &BTreeMap<(usize, usize), f64>) -> FlowLevel {
+        let n: usize = visits.len();
+        let mut outflow: Vec<f64> = vec![0.0; n];
+        let mut exchanged: Vec<BTreeMap<usize, (f64, f64)>> = vec![BTreeMap::new(); n];
+        for (&(u, v), &flow) in flows {
+            if u != v {
+                outflow[u] += flow;
+                exchanged[u].entry(v).or_insert((0.0, 0.0)).0 += flow;
+                exchanged[v].entry(u).or_insert((0.0, 0.0)).1 += flow;
+            }
+        }
+        let links: Vec<Vec<(usize, f64, f64)>> = exchanged.into_iter()
+            .map(|neighbors| neighbors.into_iter().map(|(v, (to, from))| (v, to, from)).collect())
+            .collect();
+        FlowLevel { visits, outflow, links }
+    }
+
+    /// Moves single nodes between neighboring modules, visiting them in a random order, while
+    /// that shortens the description. `entropy` is the fixed visit-rate entropy of the original
+    /// vertices. Returns the module of every node, numbered from zero, and whether any moved.
+    fn local_moves(&self, entropy: f64, rng: &mut Rng) -> (Vec<usize>, bool) {
+        let n: usize = self.visits.len();
+        let mut module: Vec<usize> = (0..n).collect();
+        let mut visits: Vec<f64> = self.visits.clone();
+        let mut exit: Vec<f64> = self.outflow.clone();
+        let mut total_exit: f64 = exit.iter().sum();
+        let mut exit_terms: f64 = exit.iter().map(|&q| plogp(q)).sum();
+        let mut module_terms: f64 = (0..n).map(|m| plogp(exit[m] + visits[m])).sum();
+        let codelength = |total_exit: f64, exit_terms: f64, module_terms: f64| -> f64 {
+            plogp(total_exit) - 2.0 * exit_terms + entropy + module_terms
+        };
+
+        let mut order: Vec<usize> = (0..n).collect();
+        let mut moved: bool = false;
+        loop {
+            for i in (1..n).rev() {
+                order.swap(i, rng.below(i + 1));
+            }
+            let mut improved: bool = false;
+            for &u in &order {
+                let old: usize = module[u];
+                // The flow from `u` to, and to `u` from, every neighboring module.
+                let mut exchange: BTreeMap<usize, (f64, f64)> = BTreeMap::new();
+                for &(v, to, from) in &self.links[u] {
+                    let entry: &mut (f64, f64) = exchange.entry(module[v]).or_insert((0.0, 0.0));
+                    entry.0 += to;
+                    entry.1 += from;
+                }
+                let (to_old, from_old) = exchange.get(&old).copied().unwrap_or((0.0, 0.0));
+                let old_exit: f64 = exit[old] - (self.outflow[u] - to_old) + from_old;
+                let old_visits: f64 = visits[old] - self.visits[u];
+                let current: f64 = codelength(total_exit, exit_terms, module_terms);
+
+                let mut best: Option<(f64, usize, f64)> = None;
+                for (&target, &(to_new, from_new)) in &exchange {
+                    if target == old {
+                        continue;
+                    }
+                    let new_exit: f64 = exit[target] + (self.outflow[u] - to_new) - from_new;
+                    let new_visits: f64 = visits[target] + self.visits[u];
+                    let length: f64 = codelength(
+                        total_exit - exit[old] - exit[target] + old_exit + new_exit,
+                        exit_terms - plogp(exit[old]) - plogp(exit[target]) + plogp(old_exit) + plogp(new_exit),
+                        module_terms - plogp(exit[old] + visits[old]) - plogp(exit[target] + visits[target])
+                            + plogp(old_exit + old_visits) + plogp(new_exit + new_visits),
+                    );
+                    if length < current - MIN_IMPROVEMENT && best.is_none_or(|(shortest, _, _)| length < shortest) {
+                        best = Some((length, target, new_exit));
+                    }
+                }
+
+                if let Some((_, target, new_exit)) = best {
+                    total_exit += old_exit + new_exit - exit[old] - exit[target];
+                    exit_terms += plogp(old_exit) + plogp(new_exit) - plogp(exit[old]) - plogp(exit[target]);
+                    module_terms += plogp(old_exit + old_visits) + plogp(new_exit + visits[target] + self.visits[u])
+                        - plogp(exit[old] + visits[old]) - plogp(exit[target] + visits[target]);
+                    exit[old] = old_exit;
+                    exit[target] = new_exit;
+                    visits[old] = old_visits;
+                    visits[target] += self.visits[u];
+                    module[u] = target;
+                    improved = true;
+                    moved = true;
+                }
+            }
+            if !improved {
+                break;
+            }
+        }
+
+        let mut numbering: BTreeMap<usize, usize> = BTreeMap::new();
+        for m in &mut module {
+            let next: usize = numbering.len();
+            *m = *numbering.entry(*m).or_insert(next);
+        }
+        (module, moved)
+    }
+}
+
+/// A candidate merge, ordered so that the largest modularity gain pops first from a
+/// `BinaryHeap`, and among equal gains the pair with the smallest ids.
+#[derive(Clone, Copy, PartialEq)]
+struct Candidate {
+    gain: f64,
+    first: usize,
+    second: usize,
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.gain.total_cmp(&other.gain)
+            .then_with(|| other.first.cmp(&self.first))
+            .then_with(|| other.second.cmp(&self.second))
+    }
+}
+
+impl Graph {
+    /// Clusters the vertices by greedy modularity agglomeration (Clauset, Newman and Moore).
+    ///
+    /// Starting from single vertices, the two adjacent communities whose merge raises the
+    /// modularity the most are merged, until no two communities are adjacent; the remaining
+    /// ones, such as separate components, are then merged lightest first so the dendrogram
+    /// ends in one cluster. Unlike Louvain the result is deterministic: ties go to the
+    /// smallest cluster ids. Edge weights are the connection strengths. Candidate merges are
+    /// kept in a heap and only those of a newly merged community are recomputed.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Dendrogram, GraphError>` - The merge history, whose `best_cut` is the greedy
+    ///   partition, or an error if the graph is directed or an edge weight is negative.
+    pub fn greedy_modularity(&self) -> Result<Dendrogram, GraphError> {
+        if self.directed {
+            return Err(GraphError::NeedsUndirected("Greedy modularity".to_string()));
+        }
+        let index: GraphIndex = self.index();
+        let n: usize = index.len();
+        let edges: Vec<(usize, usize, f32)> = EdgeList::new(&index).edges;
+        if let Some(&(u, v, _)) = edges.iter().find(|&&(_, _, weight)| weight < 0.0) {
+            return Err(GraphError::NegativeWeight(index.keys[u].clone(), index.keys[v].clone()));
+        }
+        let total: f64 = edges.iter().map(|&(_, _, weight)| f64::from(weight)).sum();
+        // Without edges every fraction is zero rather than undefined.
+        let scale: f64 = if total > 0.0 { 1.0 / total } else { 0.0 };
+
+        // For every live cluster: the fraction of edge ends it holds, the fraction of edge
+        // weight inside it, and the fraction of weight to each adjacent cluster.
+        let mut ends: Vec<f64> = vec![0.0; n];
+        let mut inside: Vec<f64> = vec![0.0; n];
+        let mut between: Vec<BTreeMap<usize, f64>> = vec![BTreeMap::new(); n];
+        for &(u, v, weight) in &edges {
+            let w: f64 = f64::from(weight) * scale;
+            ends[u] += w / 2.0;
+            ends[v] += w / 2.0;
+            if u == v {
+                inside[u] += w;
+            } else {
+                *between[u].entry(v).or_insert(0.0) += w;
+                *between[v].entry(u).or_insert(0.0) += w;
+            }
+        }
+        let gain = |ends: &[f64], weight: f64, a: usize, b: usize| -> f64 { weight - 2.0 * ends[a] * ends[b] };
+
+        // A merge keeps the slot of the cluster with more neighbors and moves the other
+        // cluster's weights into it, so every slot has the dendrogram id of the cluster in it.
+        let mut live: Vec<bool> = vec![true; n];
+        let mut label: Vec<usize> = (0..n).collect();
+        let mut slot: Vec<usize> = (0..n).collect();
+        let mut heap: BinaryHeap<Candidate> = BinaryHeap::new();
+        for (a, neighbors) in between.iter().enumerate() {
+            for (&b, &weight) in neighbors.range((a + 1)..) {
+                heap.push(Candidate { gain: gain(&ends, weight, a, b), first: a, second: b });
+            }
+        }
+        let mut modularity: f64 = (0..n).map(|c| inside[c] - ends[c] * ends[c]).sum();
+        let initial_modularity: f64 = modularity;
+        let mut merges: Vec<Merge> = Vec::with_capacity(n.saturating_sub(1));
+
+        // A candidate for a cluster that has changed id since it was pushed is stale. The
+        // slot of a cluster that absorbed another one keeps its edges but only gains edge
+        // ends, so the stored gain bounds the current one from above: stale candidates whose
+        // slots are still apart are pushed again with their current gain rather than
+        // updated at every merge.
+        while let Some(Candidate { gain: delta, first, second }) = heap.pop() {
+            let (a, b) = (slot[first], slot[second]);
+            if !live[a] || !live[b] || a == b {
+                continue;
+            }
+            if label[a] != first || label[b] != second {
+                let (first, second) = (label[a].min(label[b]), label[a].max(label[b]));
+                heap.push(Candidate { gain: gain(&ends, between[a][&b], a, b), first, second });
+                continue;
+            }
+            let (keep, gone) = if between[a].len() >= between[b].len() { (a, b) } else { (b, a) };
+            let merged: usize = n + merges.len();
+            live[gone] = false;
+            label[keep] = merged;
+            slot.push(keep);
+            ends[keep] += ends[gone];
+            inside[keep] += inside[gone] + between[keep].remove(&gone).unwrap_or(0.0);
+            for (c, weight) in std::mem::take(&mut between[gone]) {
+                if c == keep {
+                    continue;
+                }
+                between[c].remove(&gone);
+                let total: f64 = *between[keep].entry(c).and_modify(|w| *w += weight).or_insert(weight);
+                between[c].insert(keep, total);
+                heap.push(Candidate { gain: gain(&ends, total, c, keep), first: label[c], second: merged });
+            }
+            modularity += delta;
+            merges.push(Merge { first, second, modularity });
+        }
+
+        // No two clusters are adjacent any more; joining the two with the fewest edge ends
+        // costs the least modularity.
+        let mut rest: Vec<(f64, usize)> = (0..n).filter(|&c| live[c]).map(|c| (ends[c], label[c])).collect();
+        while rest.len() > 1 {
+            rest.sort_by(|a, b| b.0.total_cmp(&a.0).then(b.1.cmp(&a.1)));
+            let ((x, first), (y, second)) = (rest.pop().unwrap(), rest.pop().unwrap());
+            modularity -= 2.0 * x * y;
+            rest.push((x + y, n + merges.len()));
+            merges.push(Merge { first: first.min(second), second: first.max(second), modularity });
+        }
+
+        Ok(Dendrogram { leaves: index.keys, merges, initial_modularity })
+    }
+
+    /// Finds communities that trap the flow of a random walk, by minimizing the map equation
+    /// in the style of Infomap (Rosvall and Bergstrom).
+    ///
+    /// The walker follows edges in proportion to their weights and, in directed graphs,
+    /// teleports to a random vertex with probability `0.15`; only steps along edges are
+    /// described. A partition is good when the walk rarely leaves a community, so describing
+    /// it with one codebook per community is short. Single vertices are moved between
+    /// neighboring communities while that shortens the description, the communities are then
+    /// merged into single nodes and the moves repeated, until nothing moves.
+    ///
+    /// # Arguments
+    ///
+    /// * `seed` - The random seed for the order of the moves; the same seed reproduces the
+    ///   same partition.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<FlowCommunities, GraphError>` - The communities and the description lengths,
+    ///   or an error if an edge weight is negative.
+    pub fn infomap(&self, seed: u64) -> Result<FlowCommunities, GraphError> {
+        let index: GraphIndex = self.index();
+        reject_negative_weights(&index)?;
+        let n: usize = index.len();
+
+        // Undirected walks need no teleportation: every vertex is visited in proportion to
+        // its weighted degree.
+        let out_weight: Vec<f64> = index.adjacency.iter()
+            .map(|neighbors| neighbors.iter().map(|&(_, weight)| f64::from(weight)).sum())
+            .collect();
+        let total: f64 = out_weight.iter().sum();
+        let (visits, damping): (Vec<f64>, f64) = if self.directed {
+            let options: PageRankOptions = PageRankOptions::new()
+                .damping(1.0 - TELEPORTATION)
+                .max_iterations(1000)
+                .tolerance(1e-15);
+            (pagerank_scores(&index, &options), 1.0 - TELEPORTATION)
+        } else if total > 0.0 {
+            (out_weight.iter().map(|w| w / total).collect(), 1.0)
+        } else {
+            (vec![1.0 / n.max(1) as f64; n], 1.0)
+        };
+        let mut flows: BTreeMap<(usize, usize), f64> = BTreeMap::new();
+        for (u, neighbors) in index.adjacency.iter().enumerate() {
+            for &(v, weight) in neighbors {
+                if out_weight[u] > 0.0 {
+                    *flows.entry((u, v)).or_insert(0.0) += damping * visits[u] * f64::from(weight) / out_weight[u];
+                }
+            }
+        }
+        let entropy: f64 = -visits.iter().map(|&p| plogp(p)).sum::<f64>();
+
+        let mut rng: Rng = Rng::new(seed);
+        let mut assignment: Vec<usize> = (0..n).collect();
+        let mut level: FlowLevel = FlowLevel::new(visits.clone(), &flows);
+        loop {
+            let (module, moved) = level.local_moves(entropy, &mut rng);
+            if !moved {
+                break;
+            }
+            for a in &mut assignment {
+                *a = module[*a];
+            }
+            let modules: usize = module.iter().max().map_or(0, |&m| m + 1);
+            let mut module_visits: Vec<f64> = vec![0.0; modules];
+            for (v, &m) in module.iter().enumerate() {
+                module_visits[m] += level.visits[v];
+            }
+            let mut module_flows: BTreeMap<(usize, usize), f64> = BTreeMap::new();
+            for (u, links) in level.links.iter().enumerate() {
+                for &(v, to, _) in links {
+                    *module_flows.entry((module[u], module[v])).or_insert(0.0) += to;
+                }
+            }
+            level = FlowLevel::new(module_visits, &module_flows);
+        }
+
+        let modules: usize = assignment.iter().max().map_or(0, |&m| m + 1);
+        let mut exit: Vec<f64> = vec![0.0; modules];
+        let mut module_visits: Vec<f64> = vec![0.0; modules];
+        for (&(u, v), &flow) in &flows {
+            if assignment[u] != assignment[v] {
+                exit[assignment[u]] += flow;
+            }
+        }
+        for (v, &m) in assignment.iter().enumerate() {
+            module_visits[m] += visits[v];
+        }
+        let codelength: f64 = plogp(exit.iter().sum()) - 2.0 * exit.iter().map(|&q| plogp(q)).sum::<f64>() + entropy
+            + exit.iter().zip(&module_visits).map(|(&q, &p)| plogp(q + p)).sum::<f64>();
+
+        let mut groups: Vec<Vec<String>> = vec![Vec::new(); modules];
+        for (v, &m) in assignment.iter().enumerate() {
+            groups[m].push(index.keys[v].clone());
+        }
+        groups.sort();
+        Ok(FlowCommunities { communities: groups, codelength, one_level_codelength: entropy })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::graph::testing::graph_from;
+    use crate::graph::{Edge, Graph, GraphError, Vertex};
+    use std::collections::HashMap;
+
+    /// Computes the modularity of a partition from its definition.
+    fn modularity(graph: &Graph, communities: &[Vec<String>]) -> f64 {
+        let community: HashMap<&str, usize> = communities.iter()
+            .enumerate()
+            .flat_map(|(c, keys)| keys.iter().map(move |key| (key.as_str(), c)))
+            .collect();
+        let edges = graph.stored_edges();
+        let total: f64 = edges.iter().map(|edge| f64::from(edge.weight)).sum();
+        let mut inside: Vec<f64> = vec![0.0; communities.len()];
+        let mut ends: Vec<f64> = vec![0.0; communities.len()];
+        for edge in edges {
+            let (a, b) = (community[edge.vertex1.as_str()], community[edge.vertex2.as_str()]);
+            ends[a] += f64::from(edge.weight);
+            ends[b] += f64::from(edge.weight);
+            if a == b {
+                inside[a] += f64::from(edge.weight);
+            }
+        }
+        (0..communities.len()).map(|c| inside[c] / total - (ends[c] / (2.0 * total)).powi(2)).sum()
+    }
+
+    #[test]
+    fn greedy_modularity_splits_bridged_triangles() {
+        let graph: Graph = graph_from(false, &[("a", "b", 1.0), ("b", "c", 1.0), ("c", "a", 1.0), ("c", "d", 1.0), ("d", "e", 1.0), ("e", "f", 1.0), ("f", "d", 1.0)]);
+        let dendrogram = graph.greedy_modularity().unwrap();
+        assert_eq!(dendrogram.merges.len(), 5);
+        let best: Vec<Vec<String>> = dendrogram.best_cut();
+        assert_eq!(best, vec![vec!["a", "b", "c"], vec!["d", "e", "f"]]);
+        assert!((modularity(&graph, &best) - (6.0 / 7.0 - 0.5)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn greedy_modularity_records_the_modularity_of_every_cut() {
+        // Two components, so the last merges join clusters without edges between them.
+        let mut graph: Graph = Graph::erdos_renyi(40, 0.1, false, 3).unwrap();
+        let other: Graph = Graph::erdos_renyi(15, 0.3, false, 4).unwrap();
+        for edge in other.stored_edges() {
+            for key in [&edge.vertex1, &edge.vertex2] {
+                if !graph.has_vertex(&format!("x{}", key)) {
+                    graph.add_vertex(Vertex::new(format!("x{}", key)));
+                }
+            }
+            graph.add_edge(Edge::new(format!("x{}", edge.vertex1), format!("x{}", edge.vertex2), edge.weight)).unwrap();
+        }
+        let dendrogram = graph.greedy_modularity().unwrap();
+        assert_eq!(dendrogram.merges.len(), graph.vertices.len() - 1);
+        assert!((dendrogram.initial_modularity - modularity(&graph, &dendrogram.cut(0))).abs() < 1e-9);
+        for (k, merge) in dendrogram.merges.iter().enumerate() {
+            assert!((merge.modularity - modularity(&graph, &dendrogram.cut(k + 1))).abs() < 1e-9, "merge {}", k);
+        }
+        assert_eq!(dendrogram.cut(usize::MAX).len(), 1);
+    }
+
+    #[test]
+    fn greedy_modularity_rejects_directed_graphs_and_negative_weights() {
+        assert!(matches!(graph_from(true, &[("a", "b", 1.0)]).greedy_modularity(), Err(GraphError::NeedsUndirected(_))));
+        assert!(matches!(graph_from(false, &[("a", "b", -1.0)]).greedy_modularity(), Err(GraphError::NegativeWeight(_, _))));
+    }
+
+    /// Builds two cliques of `size` vertices, `a0..` and `b0..`, joined by one edge.
+    fn bridged_cliques(directed: bool, size: usize) -> Graph {
+        let mut edges: Vec<(String, String)> = Vec::new();
+        for side in ["a", "b"] {
+            for i in 0..size {
+                for j in 0..size {
+                    if i != j && (directed || i < j) {
+                        edges.push((format!("{}{}", side, i), format!("{}{}", side, j)));
+                    }
+                }
+            }
+        }
+        edges.push(("a0".to_string(), "b0".to_string()));
+        if directed {
+            edges.push(("b0".to_string(), "a0".to_string()));
+        }
+        let edges: Vec<(&str, &str, f32)> = edges.iter().map(|(a, b)| (a.as_str(), b.as_str(), 1.0)).collect();
+        graph_from(directed, &edges)
+    }
+
+    #[test]
+    fn infomap_finds_bridged_cliques() {
+        for directed in [false, true] {
+            let found = bridged_cliques(directed, 5).infomap(7).unwrap();
+            let expected: Vec<Vec<String>> = ["a", "b"].iter()
+                .map(|side| (0..5).map(|i| format!("{}{}", side, i)).collect())
+                .collect();
+            assert_eq!(found.communities, expected, "directed: {}", directed);
+            assert!(found.codelength < found.one_level_codelength);
+        }
+    }
+
+    #[test]
+    fn infomap_is_reproducible_and_keeps_a_clique_whole() {
+        let graph: Graph = bridged_cliques(false, 4);
+        assert_eq!(graph.infomap(3).unwrap(), graph.infomap(3).unwrap());
+        let clique: Graph = graph_from(false, &[("a", "b", 1.0), ("b", "c", 1.0), ("c", "a", 1.0)]);
+        let found = clique.infomap(1).unwrap();
+        assert_eq!(found.communities, vec![vec!["a", "b", "c"]]);
+        assert!((found.codelength - found.one_level_codelength).abs() < 1e-12);
+    }
+
+    #[test]
+    fn infomap_rejects_negative_weights() {
+        assert!(matches!(graph_from(false, &[("a", "b", -1.0)]).infomap(1), Err(GraphError::NegativeWeight(_, _))));
+    }
+}
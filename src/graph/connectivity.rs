@@ -0,0 +1,371 @@
+use crate::graph::flow::FlowNetwork;
+use crate::graph::index::GraphIndex;
+use crate::graph::{AttrValue, Graph, GraphError};
+use std::collections::{HashMap, HashSet};
+
+/// Labels every vertex with its weakly connected component, ignoring edge direction.
+///
+/// Components are numbered from `0` in order of their smallest vertex.
+pub(crate) fn component_labels(index: &GraphIndex) -> Vec<usize> {
+    let n: usize = index.len();
+    let reversed: Vec<Vec<(usize, f32)>> = if index.directed { index.reversed() } else { Vec::new() };
+    let mut label: Vec<usize> = vec![usize::MAX; n];
+    let mut count: usize = 0;
+    for root in 0..n {
+        if label[root] != usize::MAX {
+            continue;
+        }
+        label[root] = count;
+        let mut stack: Vec<usize> = vec![root];
+        while let Some(u) = stack.pop() {
+            let incoming: &[(usize, f32)] = reversed.get(u).map_or(&[], Vec::as_slice);
+            for &(v, _) in index.adjacency[u].iter().chain(incoming) {
+                if label[v] == usize::MAX {
+                    label[v] = count;
+                    stack.push(v);
+                }
+            }
+        }
+        count += 1;
+    }
+    label
+}
+
+/// Labels every vertex with its strongly connected component using an iterative version of
+/// Tarjan's algorithm.
+///
+/// Components are numbered from `0` in order of their smallest vertex.
+pub(crate) fn strong_component_labels(index: &GraphIndex) -> Vec<usize> {
+    let n: usize = index.len();
+    let mut order: Vec<usize> = vec![usize::MAX; n];
+    let mut low: Vec<usize> = vec![0; n];
+    let mut on_stack: Vec<bool> = vec![false; n];
+    let mut stack: Vec<usize> = Vec::new();
+    let mut label: Vec<usize> = vec![usize::MAX; n];
+    let mut visited: usize = 0;
+    let mut found: usize = 0;
+    for root in 0..n {
+        if order[root] != usize::MAX {
+            continue;
+        }
+        // The DFS path, each vertex with the position of its next neighbor to try.
+        let mut path: Vec<(usize, usize)> = vec![(root, 0)];
+        order[root] = visited;
+        low[root] = visited;
+        visited += 1;
+        stack.push(root);
+        on_stack[root] = true;
+        while let Some(&mut (u, ref mut next)) = path.last_mut() {
+            if let Some(&(v, _)) = index.adjacency[u].get(*next) {
+                *next += 1;
+                if order[v] == usize::MAX {
+                    order[v] = visited;
+                    low[v] = visited;
+                    visited += 1;
+                    stack.push(v);
+                    on_stack[v] = true;
+                    path.push((v, 0));
+                } else if on_stack[v] {
+                    low[u] = low[u].min(order[v]);
+                }
+                continue;
+            }
+            path.pop();
+            if let Some(&(parent, _)) = path.last() {
+                low[parent] = low[parent].min(low[u]);
+            }
+            if low[u] == order[u] {
+                while let Some(w) = stack.pop() {
+                    on_stack[w] = false;
+                    label[w] = found;
+                    if w == u {
+                        break;
+                    }
+                }
+                found += 1;
+            }
+        }
+    }
+    renumber(&label)
+}
+
+/// Renumbers component labels from `0` in order of each component's smallest vertex.
+fn renumber(label: &[usize]) -> Vec<usize> {
+    let mut renamed: HashMap<usize, usize> = HashMap::new();
+    label.iter()
+        .map(|&l| {
+            let next: usize = renamed.len();
+            *renamed.entry(l).or_insert(next)
+        })
+        .collect()
+}
+
+/// Groups the vertex keys by component label.
+pub(crate) fn group(index: &GraphIndex, label: &[usize]) -> Vec<Vec<String>> {
+    let mut groups: Vec<Vec<String>> = vec![Vec::new(); label.iter().max().map_or(0, |&l| l + 1)];
+    for (v, &l) in label.iter().enumerate() {
+        groups[l].push(index.keys[v].clone());
+    }
+    groups
+}
+
+/// Builds the split network where vertex `i` becomes `2i -> 2i + 1` with unit capacity
+/// (unbounded for `source` and `sink`), and every distinct edge becomes a unit arc.
+fn split_network(index: &GraphIndex, source: usize, sink: usize) -> FlowNetwork {
+    let n: usize = index.len();
+    let mut network: FlowNetwork = FlowNetwork::new(2 * n);
+    for v in 0..n {
+        let capacity: f64 = if v == source || v == sink { f64::INFINITY } else { 1.0 };
+        network.add_arc(2 * v, 2 * v + 1, capacity, 0.0);
+    }
+    let mut seen: HashSet<(usize, usize)> = HashSet::new();
+    for (u, neighbors) in index.adjacency.iter().enumerate() {
+        for &(v, _) in neighbors {
+            if u != v && seen.insert((u, v)) {
+                network.add_arc(2 * u + 1, 2 * v, 1.0, 0.0);
+            }
+        }
+    }
+    network
+}
+
+/// Builds a network with one arc per adjacency entry, using `capacity` for each edge weight.
+fn edge_network<C>(index: &GraphIndex, capacity: C) -> FlowNetwork
+where
+    C: Fn(f32) -> f64,
+{
+    let mut network: FlowNetwork = FlowNetwork::new(index.len());
+    for (u, neighbors) in index.adjacency.iter().enumerate() {
+        for &(v, weight) in neighbors {
+            if u != v {
+                network.add_arc(u, v, capacity(weight), 0.0);
+            }
+        }
+    }
+    network
+}
+
+/// A Gomory-Hu (equivalent flow) tree of an undirected graph.
+///
+/// The tree has the same vertices as the graph, and the minimum cut between any two
+/// vertices equals the smallest edge weight on the tree path between them.
+#[derive(Clone, Debug)]
+pub struct GomoryHuTree {
+    /// The tree edges as `(vertex, parent, cut value)`.
+    pub edges: Vec<(String, String, f32)>,
+}
+
+impl GomoryHuTree {
+    /// Returns the minimum cut value between two vertices, or `None` if either is not in the tree.
+    ///
+    /// Vertices in different connected components have a cut value of `0`.
+    pub fn min_cut(&self, from: &str, to: &str) -> Option<f32> {
+        let mut neighbors: HashMap<&str, Vec<(&str, f32)>> = HashMap::new();
+        for (vertex, parent, value) in &self.edges {
+            neighbors.entry(vertex).or_default().push((parent, *value));
+            neighbors.entry(parent).or_default().push((vertex, *value));
+        }
+        if !neighbors.contains_key(from) || !neighbors.contains_key(to) {
+            return None;
+        }
+        if from == to {
+            return Some(f32::INFINITY);
+        }
+
+        let mut best: HashMap<&str, f32> = HashMap::from([(from, f32::INFINITY)]);
+        let mut stack: Vec<&str> = vec![from];
+        while let Some(vertex) = stack.pop() {
+            let bottleneck: f32 = best[vertex];
+            for &(next, value) in &neighbors[vertex] {
+                if !best.contains_key(next) {
+                    best.insert(next, bottleneck.min(value));
+                    stack.push(next);
+                }
+            }
+        }
+        best.get(to).copied()
+    }
+}
+
+/// Counts internally vertex-disjoint paths from `source` to `sink`.
+fn local_vertex_connectivity(index: &GraphIndex, source: usize, sink: usize) -> usize {
+    let mut network: FlowNetwork = split_network(index, source, sink);
+    network.max_flow(2 * source + 1, 2 * sink).round() as usize
+}
+
+impl Graph {
+    /// Counts the maximum number of internally vertex-disjoint paths between two vertices.
+    ///
+    /// Computed as a maximum flow after splitting every vertex into an entry and an exit
+    /// node joined by a unit-capacity arc. A direct edge between the endpoints counts as one path.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - The key of the start vertex.
+    /// * `to` - The key of the target vertex.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<usize, GraphError>` - The number of paths, or an error if a vertex does not exist
+    ///   or both keys name the same vertex.
+    pub fn max_vertex_disjoint_paths(&self, from: &str, to: &str) -> Result<usize, GraphError> {
+        let index: GraphIndex = self.index();
+        let source: usize = index.position(from)?;
+        let sink: usize = index.position(to)?;
+        if source == sink {
+            return Err(GraphError::InvalidArgument(format!("Vertex {} cannot be both endpoints of disjoint paths", from)));
+        }
+        Ok(local_vertex_connectivity(&index, source, sink))
+    }
+
+    /// Computes the vertex connectivity: the fewest vertices whose removal disconnects the graph.
+    ///
+    /// This is the minimum of `max_vertex_disjoint_paths` over all non-adjacent pairs (ordered
+    /// pairs in directed graphs). A complete graph on `n` vertices has connectivity `n - 1`.
+    ///
+    /// # Returns
+    ///
+    /// * A `usize` with the vertex connectivity, `0` for graphs with fewer than two vertices.
+    pub fn vertex_connectivity(&self) -> usize {
+        let index: GraphIndex = self.index();
+        let n: usize = index.len();
+        if n < 2 {
+            return 0;
+        }
+
+        let adjacent: HashSet<(usize, usize)> = index.adjacency.iter()
+            .enumerate()
+            .flat_map(|(u, neighbors)| neighbors.iter().map(move |&(v, _)| (u, v)))
+            .collect();
+
+        let mut best: usize = n - 1;
+        for u in 0..n {
+            for v in 0..n {
+                if u == v || (!index.directed && v < u) || adjacent.contains(&(u, v)) {
+                    continue;
+                }
+                best = best.min(local_vertex_connectivity(&index, u, v));
+                if best == 0 {
+                    return 0;
+                }
+            }
+        }
+        best
+    }
+
+    /// Computes the edge connectivity: the fewest edges whose removal disconnects the graph.
+    ///
+    /// Every edge counts once regardless of its weight. The value is the smallest unit-capacity
+    /// maximum flow between a fixed vertex and every other vertex (in both directions for
+    /// directed graphs).
+    ///
+    /// # Returns
+    ///
+    /// * A `usize` with the edge connectivity, `0` for graphs with fewer than two vertices.
+    pub fn edge_connectivity(&self) -> usize {
+        let index: GraphIndex = self.index();
+        let n: usize = index.len();
+        if n < 2 {
+            return 0;
+        }
+
+        let mut best: f64 = f64::INFINITY;
+        for v in 1..n {
+            best = best.min(edge_network(&index, |_| 1.0).max_flow(0, v));
+            if index.directed {
+                best = best.min(edge_network(&index, |_| 1.0).max_flow(v, 0));
+            }
+        }
+        best.round() as usize
+    }
+
+    /// Builds a Gomory-Hu tree of an undirected graph using Gusfield's algorithm.
+    ///
+    /// Edge weights are used as capacities, and only `n - 1` maximum-flow computations are
+    /// needed to answer the minimum cut between every pair of vertices afterwards.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<GomoryHuTree, GraphError>` - The tree, or an error if the graph is directed.
+    pub fn gomory_hu_tree(&self) -> Result<GomoryHuTree, GraphError> {
+        if self.directed {
+            return Err(GraphError::NeedsUndirected("Gomory-Hu trees".to_string()));
+        }
+        let index: GraphIndex = self.index();
+        let n: usize = index.len();
+        let mut parent: Vec<usize> = vec![0; n];
+        let mut value: Vec<f64> = vec![0.0; n];
+
+        for i in 1..n {
+            let mut network: FlowNetwork = edge_network(&index, f64::from);
+            value[i] = network.max_flow(i, parent[i]);
+            let side: Vec<bool> = network.source_side(i);
+            for j in (i + 1)..n {
+                if side[j] && parent[j] == parent[i] {
+                    parent[j] = i;
+                }
+            }
+        }
+
+        Ok(GomoryHuTree {
+            edges: (1..n)
+                .map(|i| (index.keys[i].clone(), index.keys[parent[i]].clone(), value[i] as f32))
+                .collect(),
+        })
+    }
+
+    /// Splits the graph into connected components.
+    ///
+    /// In directed graphs edge direction is ignored, which gives the weakly connected
+    /// components; see `strongly_connected_components` for the directed notion.
+    ///
+    /// # Returns
+    ///
+    /// * A `Vec<Vec<String>>` with the vertex keys of every component, each sorted, ordered by
+    ///   their first key.
+    pub fn connected_components(&self) -> Vec<Vec<String>> {
+        self.cached("connected_components", || {
+            let index: GraphIndex = self.index();
+            group(&index, &component_labels(&index))
+        })
+    }
+
+    /// Splits the graph into strongly connected components with Tarjan's algorithm.
+    ///
+    /// Two vertices share a component when each can reach the other. In undirected graphs
+    /// this is the same as `connected_components`.
+    ///
+    /// # Returns
+    ///
+    /// * A `Vec<Vec<String>>` with the vertex keys of every component, each sorted, ordered by
+    ///   their first key.
+    pub fn strongly_connected_components(&self) -> Vec<Vec<String>> {
+        self.cached("strongly_connected_components", || {
+            let index: GraphIndex = self.index();
+            group(&index, &strong_component_labels(&index))
+        })
+    }
+
+    /// Stores the connected component of every vertex in its `component` attribute, so
+    /// exports can color vertices by component.
+    ///
+    /// Components are the same as in `connected_components`, weak in directed graphs, and
+    /// are numbered from `0` in order of their smallest vertex key.
+    ///
+    /// # Returns
+    ///
+    /// * A `HashMap<String, usize>` with the component id of every vertex key.
+    pub fn label_components(&mut self) -> HashMap<String, usize> {
+        let index: GraphIndex = self.index();
+        let labels: Vec<usize> = component_labels(&index);
+        let mut mapping: HashMap<String, usize> = HashMap::with_capacity(index.len());
+        for (v, key) in index.keys.into_iter().enumerate() {
+            if let Some(vertex) = self.vertices.get_mut(&key) {
+                vertex.set_attr("component", AttrValue::Int(labels[v] as i64));
+            }
+            mapping.insert(key, labels[v]);
+        }
+        self.mark_changed();
+        mapping
+    }
+}
@@ -0,0 +1,95 @@
+use crate::graph::GraphError;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// A callback told how much work a run has done, and the total when it is known in advance.
+pub type ProgressCallback = Box<dyn Fn(usize, Option<usize>)>;
+
+/// A shared flag that asks a running algorithm to stop.
+///
+/// Clones share the flag, so one clone can be handed to the algorithm and another kept by
+/// whoever decides to cancel it, possibly on another thread.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    /// Creates a token that has not been cancelled.
+    pub fn new() -> CancellationToken {
+        CancellationToken::default()
+    }
+
+    /// Asks every run watching this token to stop.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns whether `cancel` has been called on this token or one of its clones.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+/// Limits and hooks for a long-running algorithm.
+///
+/// Built with chained calls, for example
+/// `ExecutionControl::new().timeout(Duration::from_secs(5)).cancellation(token.clone())`.
+/// The algorithm checks the limits between steps of its work, so it stops soon after, rather
+/// than exactly at, the deadline or cancellation.
+#[derive(Default)]
+pub struct ExecutionControl {
+    deadline: Option<Instant>,
+    token: Option<CancellationToken>,
+    progress: Option<ProgressCallback>,
+}
+
+impl ExecutionControl {
+    /// Creates a control with no limits, under which a run always finishes.
+    pub fn new() -> ExecutionControl {
+        ExecutionControl::default()
+    }
+
+    /// Stops the run once `deadline` has passed.
+    pub fn deadline(mut self, deadline: Instant) -> ExecutionControl {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Stops the run once `limit` has passed from now.
+    pub fn timeout(self, limit: Duration) -> ExecutionControl {
+        self.deadline(Instant::now() + limit)
+    }
+
+    /// Stops the run once `token` is cancelled.
+    pub fn cancellation(mut self, token: CancellationToken) -> ExecutionControl {
+        self.token = Some(token);
+        self
+    }
+
+    /// Calls `progress` after every step of the run with the number of steps done and, when
+    /// the algorithm knows it in advance, the total number of steps.
+    pub fn progress<F>(mut self, progress: F) -> ExecutionControl
+    where
+        F: Fn(usize, Option<usize>) + 'static,
+    {
+        self.progress = Some(Box::new(progress));
+        self
+    }
+
+    /// Reports that `done` steps of the run have finished, then returns an error if the run
+    /// should stop.
+    pub(crate) fn step(&self, done: usize, total: Option<usize>) -> Result<(), GraphError> {
+        if let Some(progress) = &self.progress {
+            progress(done, total);
+        }
+        if self.token.as_ref().is_some_and(CancellationToken::is_cancelled) {
+            return Err(GraphError::Cancelled);
+        }
+        if self.deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            return Err(GraphError::TimedOut);
+        }
+        Ok(())
+    }
+}
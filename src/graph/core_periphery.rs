@@ -0,0 +1,128 @@
+use crate::graph::coloring::symmetric_neighbors;
+use crate::graph::index::GraphIndex;
+use crate::graph::Graph;
+use std::collections::HashSet;
+
+/// A split of the vertices into a densely connected core and a sparse periphery.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CorePeriphery {
+    /// The keys of the core vertices, sorted.
+    pub core: Vec<String>,
+    /// The keys of the periphery vertices, sorted.
+    pub periphery: Vec<String>,
+    /// The correlation, in `[-1, 1]`, between the graph and the ideal core-periphery pattern.
+    pub fit: f64,
+}
+
+/// The pair and edge counts that determine how well a split fits the ideal pattern.
+#[derive(Clone, Copy)]
+struct Counts {
+    n: usize,
+    core: usize,
+    /// Edges with both ends in the core.
+    core_edges: usize,
+    /// Edges with both ends in the periphery.
+    periphery_edges: usize,
+}
+
+impl Counts {
+    /// Returns the Pearson correlation between adjacency and the ideal pattern over the
+    /// core-core and periphery-periphery pairs, or `0` when either is constant.
+    fn fit(self) -> f64 {
+        let core_pairs: f64 = (self.core * self.core.saturating_sub(1) / 2) as f64;
+        let periphery: usize = self.n - self.core;
+        let periphery_pairs: f64 = (periphery * periphery.saturating_sub(1) / 2) as f64;
+        let pairs: f64 = core_pairs + periphery_pairs;
+        let edges: f64 = (self.core_edges + self.periphery_edges) as f64;
+        let spread: f64 = core_pairs * periphery_pairs * edges * (pairs - edges);
+        if spread <= 0.0 {
+            return 0.0;
+        }
+        (pairs * self.core_edges as f64 - core_pairs * edges) / spread.sqrt()
+    }
+}
+
+impl Graph {
+    /// Splits the vertices into a core and a periphery with the discrete model of Borgatti
+    /// and Everett.
+    ///
+    /// The ideal pattern links every pair of core vertices and no pair of periphery vertices;
+    /// pairs with one end in each are not counted. The fit is the correlation between that
+    /// pattern and the graph. The search starts from the best split of the vertices by
+    /// degree, then moves single vertices between core and periphery while the fit improves,
+    /// so it is deterministic but may stop at a local optimum. Direction, weights, parallel
+    /// edges and self-loops are ignored.
+    ///
+    /// # Returns
+    ///
+    /// * A `CorePeriphery` with the core, the periphery and the fit, which is `0` when the
+    ///   graph has no edges or every possible edge.
+    pub fn core_periphery(&self) -> CorePeriphery {
+        let index: GraphIndex = self.index();
+        let n: usize = index.len();
+        let neighbors: Vec<HashSet<usize>> = symmetric_neighbors(&index);
+        let edges: usize = neighbors.iter().map(HashSet::len).sum::<usize>() / 2;
+
+        // Start from the highest-degree prefix that fits best.
+        let mut order: Vec<usize> = (0..n).collect();
+        order.sort_by(|&a, &b| neighbors[b].len().cmp(&neighbors[a].len()).then(a.cmp(&b)));
+        let mut in_core: Vec<bool> = vec![false; n];
+        let mut core_neighbors: Vec<usize> = vec![0; n];
+        let mut counts: Counts = Counts { n, core: 0, core_edges: 0, periphery_edges: edges };
+        let mut best: (f64, usize) = (counts.fit(), 0);
+        for (k, &v) in order.iter().enumerate() {
+            counts.core += 1;
+            counts.core_edges += core_neighbors[v];
+            counts.periphery_edges -= neighbors[v].len() - core_neighbors[v];
+            for &w in &neighbors[v] {
+                core_neighbors[w] += 1;
+            }
+            if counts.fit() > best.0 {
+                best = (counts.fit(), k + 1);
+            }
+        }
+        core_neighbors = vec![0; n];
+        counts = Counts { n, core: 0, core_edges: 0, periphery_edges: edges };
+        for &v in &order[..best.1] {
+            in_core[v] = true;
+            counts.core += 1;
+            counts.core_edges += core_neighbors[v];
+            counts.periphery_edges -= neighbors[v].len() - core_neighbors[v];
+            for &w in &neighbors[v] {
+                core_neighbors[w] += 1;
+            }
+        }
+
+        let mut improved: bool = true;
+        while improved {
+            improved = false;
+            for v in 0..n {
+                let outside: usize = neighbors[v].len() - core_neighbors[v];
+                let flipped: Counts = if in_core[v] {
+                    Counts { core: counts.core - 1, core_edges: counts.core_edges - core_neighbors[v], periphery_edges: counts.periphery_edges + outside, ..counts }
+                } else {
+                    Counts { core: counts.core + 1, core_edges: counts.core_edges + core_neighbors[v], periphery_edges: counts.periphery_edges - outside, ..counts }
+                };
+                if flipped.fit() > counts.fit() + 1e-12 {
+                    in_core[v] = !in_core[v];
+                    counts = flipped;
+                    for &w in &neighbors[v] {
+                        if in_core[v] {
+                            core_neighbors[w] += 1;
+                        } else {
+                            core_neighbors[w] -= 1;
+                        }
+                    }
+                    improved = true;
+                }
+            }
+        }
+
+        let (core, periphery): (Vec<usize>, Vec<usize>) = (0..n).partition(|&v| in_core[v]);
+        CorePeriphery {
+            core: core.into_iter().map(|v| index.keys[v].clone()).collect(),
+            periphery: periphery.into_iter().map(|v| index.keys[v].clone()).collect(),
+            fit: counts.fit(),
+        }
+    }
+}
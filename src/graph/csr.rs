@@ -0,0 +1,209 @@
+use crate::graph::graph::VertexOrder;
+use crate::graph::{Edge, Graph, GraphError, Vertex};
+use std::collections::{BTreeMap, HashSet};
+
+/// A graph in Compressed Sparse Row form, as used by numerical libraries.
+///
+/// Row `i` describes the vertex `keys[i]`: its entries are
+/// `column_indices[row_offsets[i]..row_offsets[i + 1]]`, the positions of the vertices it
+/// reaches, with the matching `weights`. An undirected edge appears in the rows of both
+/// endpoints, each with the cost of travelling from that row's vertex, and an undirected
+/// self-loop twice in its vertex's row.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Csr {
+    /// The vertex keys; position `i` is the key of row and column `i`.
+    pub keys: Vec<String>,
+    /// Where each row starts in `column_indices`, followed by the total number of entries.
+    pub row_offsets: Vec<usize>,
+    /// The column of every entry, row by row.
+    pub column_indices: Vec<usize>,
+    /// The weight of every entry.
+    pub weights: Vec<f32>,
+    /// Whether the graph is directed.
+    pub directed: bool,
+}
+
+impl Csr {
+    /// Returns the row and column of a vertex.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key of the vertex.
+    ///
+    /// # Returns
+    ///
+    /// * An `Option<usize>` with the position, or `None` if the vertex is not in the graph.
+    pub fn position(&self, key: &str) -> Option<usize> {
+        self.keys.iter().position(|probe| probe == key)
+    }
+
+    /// Returns the entries of row `i` as `(column, weight)` pairs.
+    fn row(&self, i: usize) -> impl Iterator<Item = (usize, f32)> + '_ {
+        let range = self.row_offsets[i]..self.row_offsets[i + 1];
+        self.column_indices[range.clone()].iter().copied().zip(self.weights[range].iter().copied())
+    }
+
+    /// Returns an error describing the first way the arrays are inconsistent.
+    fn validate(&self) -> Result<(), GraphError> {
+        let n: usize = self.keys.len();
+        let mut seen: HashSet<&String> = HashSet::new();
+        if let Some(key) = self.keys.iter().find(|&key| !seen.insert(key)) {
+            return Err(GraphError::InvalidArgument(format!("CSR lists the key {} more than once", key)));
+        }
+        if self.row_offsets.len() != n + 1 || self.row_offsets[0] != 0 || self.row_offsets.windows(2).any(|pair| pair[0] > pair[1]) {
+            return Err(GraphError::InvalidArgument(format!("CSR row offsets must rise from 0 in {} steps", n)));
+        }
+        let entries: usize = self.row_offsets[n];
+        if self.column_indices.len() != entries || self.weights.len() != entries {
+            return Err(GraphError::InvalidArgument(format!(
+                "CSR has {} entries but {} column indices and {} weights", entries, self.column_indices.len(), self.weights.len()
+            )));
+        }
+        if let Some(&column) = self.column_indices.iter().find(|&&column| column >= n) {
+            return Err(GraphError::InvalidArgument(format!("CSR column index {} is out of range", column)));
+        }
+        Ok(())
+    }
+}
+
+impl Graph {
+    /// Rebuilds a graph from its Compressed Sparse Row form, as produced by
+    /// `csr_representation`.
+    ///
+    /// In undirected graphs the `k`-th entry for `v` in row `u` is paired with the `k`-th
+    /// entry for `u` in row `v`; an edge whose two weights differ becomes direction-dependent.
+    /// The graph uses `VertexOrder::Insertion`, so its own matrices keep the order of `keys`.
+    ///
+    /// # Arguments
+    ///
+    /// * `csr` - The rows of the graph.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Graph, GraphError>` - The graph, or an error if the arrays are inconsistent or,
+    ///   in an undirected graph, the rows are not symmetric.
+    pub fn from_csr(csr: &Csr) -> Result<Graph, GraphError> {
+        csr.validate()?;
+        let mut graph: Graph = Graph::new(csr.directed);
+        graph.set_vertex_order(VertexOrder::Insertion);
+        for key in &csr.keys {
+            graph.add_vertex(Vertex::new(key.clone()));
+        }
+        // The weights of every row's entries, grouped by column.
+        let rows: Vec<BTreeMap<usize, Vec<f32>>> = (0..csr.keys.len())
+            .map(|u| {
+                let mut columns: BTreeMap<usize, Vec<f32>> = BTreeMap::new();
+                for (v, weight) in csr.row(u) {
+                    columns.entry(v).or_default().push(weight);
+                }
+                columns
+            })
+            .collect();
+        for (u, columns) in rows.iter().enumerate() {
+            for (&v, forward) in columns {
+                if csr.directed {
+                    for &weight in forward {
+                        graph.add_edge(Edge::new(csr.keys[u].clone(), csr.keys[v].clone(), weight))?;
+                    }
+                } else if u == v {
+                    // An undirected self-loop is listed once per endpoint.
+                    if forward.len() % 2 != 0 {
+                        return Err(GraphError::InvalidArgument(format!("CSR lists the self-loop at {} an odd number of times", csr.keys[u])));
+                    }
+                    for &weight in forward.iter().step_by(2) {
+                        graph.add_edge(Edge::new(csr.keys[u].clone(), csr.keys[v].clone(), weight))?;
+                    }
+                } else {
+                    let backward: &[f32] = rows[v].get(&u).map_or(&[], Vec::as_slice);
+                    if forward.len() != backward.len() {
+                        return Err(GraphError::InvalidArgument(format!(
+                            "CSR rows {} and {} disagree on the edges between them", csr.keys[u], csr.keys[v]
+                        )));
+                    }
+                    if v < u {
+                        continue;
+                    }
+                    for (&weight, &reverse) in forward.iter().zip(backward) {
+                        let edge: Edge = if weight == reverse {
+                            Edge::new(csr.keys[u].clone(), csr.keys[v].clone(), weight)
+                        } else {
+                            Edge::asymmetric(csr.keys[u].clone(), csr.keys[v].clone(), weight, reverse)
+                        };
+                        graph.add_edge(edge)?;
+                    }
+                }
+            }
+        }
+        Ok(graph)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Csr;
+    use crate::graph::testing::graph_from;
+    use crate::graph::{Edge, Graph};
+
+    /// Returns every row's entries sorted, since rebuilding may reorder a row.
+    fn sorted_rows(csr: &Csr) -> Vec<Vec<(usize, f32)>> {
+        (0..csr.keys.len())
+            .map(|i| {
+                let mut row: Vec<(usize, f32)> = csr.row(i).collect();
+                row.sort_by(|a, b| a.0.cmp(&b.0).then(a.1.total_cmp(&b.1)));
+                row
+            })
+            .collect()
+    }
+
+    #[test]
+    fn round_trip_through_csr() {
+        let mut undirected: Graph = graph_from(false, &[("a", "b", 1.0), ("a", "b", 2.0), ("b", "c", 3.0), ("c", "c", 4.0)]);
+        undirected.add_edge(Edge::asymmetric("c".to_string(), "a".to_string(), 5.0, 6.0)).unwrap();
+        let directed: Graph = graph_from(true, &[("a", "b", 1.0), ("b", "a", 2.0), ("b", "b", 3.0), ("c", "a", 4.0)]);
+        for graph in [undirected, directed] {
+            let csr: Csr = graph.csr_representation();
+            let rebuilt: Graph = Graph::from_csr(&csr).unwrap();
+            assert_eq!(rebuilt.edge_count, graph.edge_count);
+            let again: Csr = rebuilt.csr_representation();
+            assert_eq!((&again.keys, again.directed), (&csr.keys, csr.directed));
+            assert_eq!(sorted_rows(&again), sorted_rows(&csr));
+        }
+    }
+
+    #[test]
+    fn rows_list_both_endpoints_of_undirected_edges() {
+        let csr: Csr = graph_from(false, &[("a", "b", 1.0), ("b", "b", 2.0)]).csr_representation();
+        let b: usize = csr.position("b").unwrap();
+        assert_eq!(csr.row_offsets[b + 1] - csr.row_offsets[b], 3);
+        assert_eq!(csr.position("z"), None);
+    }
+
+    #[test]
+    fn inconsistent_arrays_are_rejected() {
+        let valid: Csr = graph_from(false, &[("a", "b", 1.0), ("b", "b", 2.0)]).csr_representation();
+        assert!(Graph::from_csr(&valid).is_ok());
+        let broken: [fn(&mut Csr); 6] = [
+            |csr| csr.keys[1] = csr.keys[0].clone(),
+            |csr| csr.row_offsets[0] = 1,
+            |csr| {
+                csr.row_offsets.pop();
+            },
+            |csr| {
+                csr.weights.pop();
+            },
+            |csr| csr.column_indices[0] = 7,
+            // Row `a` keeps its edge to `b` but row `b` loses it.
+            |csr| {
+                let b: usize = csr.position("b").unwrap();
+                let a: usize = csr.position("a").unwrap();
+                let entry: usize = (csr.row_offsets[b]..csr.row_offsets[b + 1]).find(|&i| csr.column_indices[i] == a).unwrap();
+                csr.column_indices[entry] = b;
+            },
+        ];
+        for (case, damage) in broken.iter().enumerate() {
+            let mut csr: Csr = valid.clone();
+            damage(&mut csr);
+            assert!(Graph::from_csr(&csr).is_err(), "case {}", case);
+        }
+    }
+}
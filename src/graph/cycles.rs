@@ -0,0 +1,328 @@
+use crate::graph::index::GraphIndex;
+use crate::graph::path::{reject_negative_weights, State};
+use crate::graph::{Graph, GraphError};
+use std::collections::{BinaryHeap, HashSet, VecDeque};
+
+/// The edges of an undirected graph, numbered, with per-vertex incidence lists.
+pub(crate) struct EdgeList {
+    /// Every edge once, as `(u, v, weight)` with `u <= v`.
+    pub edges: Vec<(usize, usize, f32)>,
+    /// For every vertex, the incident edges as `(neighbor, edge id)`.
+    pub incident: Vec<Vec<(usize, usize)>>,
+}
+
+impl EdgeList {
+    /// Numbers the edges of an undirected index, keeping parallel edges and self-loops.
+    pub fn new(index: &GraphIndex) -> EdgeList {
+        let mut edges: Vec<(usize, usize, f32)> = Vec::new();
+        let mut incident: Vec<Vec<(usize, usize)>> = vec![Vec::new(); index.len()];
+        for (u, neighbors) in index.adjacency.iter().enumerate() {
+            // A self-loop appears twice in its vertex's list, once per endpoint.
+            let mut loop_seen: bool = false;
+            for &(v, weight) in neighbors {
+                if u < v || (u == v && !loop_seen) {
+                    incident[u].push((v, edges.len()));
+                    if u != v {
+                        incident[v].push((u, edges.len()));
+                    }
+                    edges.push((u, v, weight));
+                }
+                if u == v {
+                    loop_seen = !loop_seen;
+                }
+            }
+        }
+        EdgeList { edges, incident }
+    }
+}
+
+/// Orders the edges of a simple cycle into the sequence of vertices it visits.
+fn cycle_vertices(edges: &[(usize, usize, f32)], cycle: &[usize]) -> Vec<usize> {
+    let (start, mut current) = (edges[cycle[0]].0, edges[cycle[0]].1);
+    let mut order: Vec<usize> = vec![start];
+    let mut used: HashSet<usize> = HashSet::from([cycle[0]]);
+    while current != start {
+        order.push(current);
+        let &next = cycle.iter()
+            .find(|&&e| !used.contains(&e) && (edges[e].0 == current || edges[e].1 == current))
+            .unwrap();
+        used.insert(next);
+        current = if edges[next].0 == current { edges[next].1 } else { edges[next].0 };
+    }
+    order
+}
+
+/// Converts a list of edge-id cycles into vertex-key cycles.
+fn cycle_keys(index: &GraphIndex, list: &EdgeList, cycles: Vec<Vec<usize>>) -> Vec<Vec<String>> {
+    cycles.into_iter()
+        .map(|cycle| {
+            cycle_vertices(&list.edges, &cycle)
+                .into_iter()
+                .map(|v| index.keys[v].clone())
+                .collect()
+        })
+        .collect()
+}
+
+/// Reduces `vector` against the GF(2) basis; returns `true` and stores it if it is independent.
+fn insert_independent(basis: &mut Vec<Vec<u64>>, mut vector: Vec<u64>) -> bool {
+    for row in basis.iter() {
+        let pivot: usize = leading_bit(row).unwrap();
+        if vector[pivot / 64] >> (pivot % 64) & 1 == 1 {
+            for (word, &bits) in vector.iter_mut().zip(row) {
+                *word ^= bits;
+            }
+        }
+    }
+    if leading_bit(&vector).is_none() {
+        return false;
+    }
+    basis.push(vector);
+    // Keep every row free of the other rows' pivots so the reduction above stays valid.
+    let last: usize = basis.len() - 1;
+    let pivot: usize = leading_bit(&basis[last]).unwrap();
+    for i in 0..last {
+        if basis[i][pivot / 64] >> (pivot % 64) & 1 == 1 {
+            let row: Vec<u64> = basis[last].clone();
+            for (word, bits) in basis[i].iter_mut().zip(row) {
+                *word ^= bits;
+            }
+        }
+    }
+    true
+}
+
+/// Returns the lowest set bit of a bitset.
+fn leading_bit(vector: &[u64]) -> Option<usize> {
+    vector.iter()
+        .enumerate()
+        .find(|(_, &word)| word != 0)
+        .map(|(i, &word)| i * 64 + word.trailing_zeros() as usize)
+}
+
+/// Finds a minimum-weight cycle basis of `n` vertices with Horton's candidate cycles, using
+/// `weights[e]` as the weight of edge `e`, and returns its cycles as edge ids.
+fn horton_basis(n: usize, list: &EdgeList, weights: &[f32]) -> Vec<Vec<usize>> {
+    let m: usize = list.edges.len();
+    let words: usize = m.div_ceil(64);
+
+    let mut seen: HashSet<Vec<u64>> = HashSet::new();
+    let mut candidates: Vec<(f32, Vec<u64>, Vec<usize>)> = Vec::new();
+    let mut add_candidate = |cycle: Vec<usize>, weight: f32| {
+        let mut bits: Vec<u64> = vec![0; words];
+        for &e in &cycle {
+            bits[e / 64] ^= 1 << (e % 64);
+        }
+        if seen.insert(bits.clone()) {
+            candidates.push((weight, bits, cycle));
+        }
+    };
+
+    for (e, &(u, v, _)) in list.edges.iter().enumerate() {
+        if u == v {
+            add_candidate(vec![e], weights[e]);
+        }
+    }
+
+    for root in 0..n {
+        let mut distance: Vec<f32> = vec![f32::INFINITY; n];
+        let mut parent: Vec<Option<(usize, usize)>> = vec![None; n];
+        let mut heap: BinaryHeap<State> = BinaryHeap::from([State { cost: 0.0, vertex: root }]);
+        distance[root] = 0.0;
+        while let Some(State { cost, vertex }) = heap.pop() {
+            if cost > distance[vertex] {
+                continue;
+            }
+            for &(next, e) in &list.incident[vertex] {
+                let candidate: f32 = cost + weights[e];
+                if candidate < distance[next] {
+                    distance[next] = candidate;
+                    parent[next] = Some((vertex, e));
+                    heap.push(State { cost: candidate, vertex: next });
+                }
+            }
+        }
+
+        let path_to_root = |mut v: usize| {
+            let (mut vertices, mut edges) = (vec![v], Vec::new());
+            while let Some((up, e)) = parent[v] {
+                edges.push(e);
+                vertices.push(up);
+                v = up;
+            }
+            (vertices, edges)
+        };
+        for (e, &(x, y, _)) in list.edges.iter().enumerate() {
+            if x == y || distance[x].is_infinite() || distance[y].is_infinite() {
+                continue;
+            }
+            if parent[x].is_some_and(|(_, p)| p == e) || parent[y].is_some_and(|(_, p)| p == e) {
+                continue;
+            }
+            let (x_vertices, x_edges) = path_to_root(x);
+            let (y_vertices, y_edges) = path_to_root(y);
+            let x_set: HashSet<usize> = x_vertices.into_iter().collect();
+            if y_vertices.iter().any(|v| *v != root && x_set.contains(v)) {
+                continue;
+            }
+            let mut cycle: Vec<usize> = x_edges;
+            cycle.extend(y_edges);
+            cycle.push(e);
+            add_candidate(cycle, distance[x] + distance[y] + weights[e]);
+        }
+    }
+
+    candidates.sort_by(|a, b| a.0.total_cmp(&b.0).then_with(|| a.2.len().cmp(&b.2.len())));
+    let mut basis: Vec<Vec<u64>> = Vec::new();
+    let mut cycles: Vec<Vec<usize>> = Vec::new();
+    for (_, bits, cycle) in candidates {
+        if insert_independent(&mut basis, bits) {
+            cycles.push(cycle);
+        }
+    }
+    cycles
+}
+
+impl Graph {
+    /// Computes a fundamental cycle basis of an undirected graph.
+    ///
+    /// A spanning forest is grown by breadth-first search, and every edge outside it closes
+    /// exactly one cycle with the tree paths. The basis has `m - n + c` cycles, where `c` is
+    /// the number of connected components.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<Vec<String>>, GraphError>` - Every cycle as the keys of the vertices it visits
+    ///   in order, or an error if the graph is directed.
+    pub fn fundamental_cycle_basis(&self) -> Result<Vec<Vec<String>>, GraphError> {
+        if self.directed {
+            return Err(GraphError::NeedsUndirected("Cycle bases".to_string()));
+        }
+        let index: GraphIndex = self.index();
+        let list: EdgeList = EdgeList::new(&index);
+        let n: usize = index.len();
+
+        let mut parent: Vec<Option<(usize, usize)>> = vec![None; n];
+        let mut depth: Vec<usize> = vec![0; n];
+        let mut visited: Vec<bool> = vec![false; n];
+        let mut tree_edge: Vec<bool> = vec![false; list.edges.len()];
+        for root in 0..n {
+            if visited[root] {
+                continue;
+            }
+            visited[root] = true;
+            let mut queue: VecDeque<usize> = VecDeque::from([root]);
+            while let Some(u) = queue.pop_front() {
+                for &(v, e) in &list.incident[u] {
+                    if !visited[v] {
+                        visited[v] = true;
+                        parent[v] = Some((u, e));
+                        depth[v] = depth[u] + 1;
+                        tree_edge[e] = true;
+                        queue.push_back(v);
+                    }
+                }
+            }
+        }
+
+        let mut cycles: Vec<Vec<usize>> = Vec::new();
+        for (e, &(u, v, _)) in list.edges.iter().enumerate() {
+            if tree_edge[e] {
+                continue;
+            }
+            let mut cycle: Vec<usize> = vec![e];
+            let (mut a, mut b) = (u, v);
+            while a != b {
+                if depth[a] >= depth[b] {
+                    let (up, edge) = parent[a].unwrap();
+                    cycle.push(edge);
+                    a = up;
+                } else {
+                    let (up, edge) = parent[b].unwrap();
+                    cycle.push(edge);
+                    b = up;
+                }
+            }
+            cycles.push(cycle);
+        }
+        Ok(cycle_keys(&index, &list, cycles))
+    }
+
+    /// Computes a minimum-weight cycle basis of an undirected graph (Horton's algorithm).
+    ///
+    /// Candidate cycles are formed from every shortest-path tree plus one extra edge, sorted
+    /// by weight, and kept greedily while they are linearly independent over GF(2). The
+    /// result has `m - n + c` cycles whose total weight is minimal. Edge weights must be
+    /// non-negative.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<Vec<String>>, GraphError>` - Every cycle as the keys of the vertices it visits
+    ///   in order, lightest first, or an error if the graph is directed or an edge weight is
+    ///   negative.
+    pub fn minimum_cycle_basis(&self) -> Result<Vec<Vec<String>>, GraphError> {
+        self.require_distances("Minimum cycle bases")?;
+        if self.directed {
+            return Err(GraphError::NeedsUndirected("Cycle bases".to_string()));
+        }
+        let index: GraphIndex = self.index();
+        reject_negative_weights(&index)?;
+        let list: EdgeList = EdgeList::new(&index);
+        let weights: Vec<f32> = list.edges.iter().map(|&(_, _, weight)| weight).collect();
+        let cycles: Vec<Vec<usize>> = horton_basis(index.len(), &list, &weights);
+        Ok(cycle_keys(&index, &list, cycles))
+    }
+
+    /// Perceives the smallest set of smallest rings (SSSR) of a molecule-like graph.
+    ///
+    /// The SSSR is a minimum cycle basis in which every bond counts as one, so it holds
+    /// `m - n + c` rings of the fewest possible atoms. Ring perception looks only at which atoms
+    /// are bonded: self-loops are ignored and parallel edges (e.g. a double bond stored twice)
+    /// count as a single bond. The result is not unique for some ring systems, such as cubane,
+    /// where several equally small rings are interchangeable.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<Vec<String>>, GraphError>` - Every ring as the keys of its atoms in order,
+    ///   smallest first, or an error if the graph is directed.
+    pub fn smallest_set_of_smallest_rings(&self) -> Result<Vec<Vec<String>>, GraphError> {
+        if self.directed {
+            return Err(GraphError::NeedsUndirected("Ring perception".to_string()));
+        }
+        let index: GraphIndex = self.index();
+        let mut bonds: HashSet<(usize, usize)> = HashSet::new();
+        let mut list: EdgeList = EdgeList { edges: Vec::new(), incident: vec![Vec::new(); index.len()] };
+        for (u, neighbors) in index.adjacency.iter().enumerate() {
+            for &(v, _) in neighbors {
+                if u < v && bonds.insert((u, v)) {
+                    list.incident[u].push((v, list.edges.len()));
+                    list.incident[v].push((u, list.edges.len()));
+                    list.edges.push((u, v, 1.0));
+                }
+            }
+        }
+        let weights: Vec<f32> = vec![1.0; list.edges.len()];
+        let cycles: Vec<Vec<usize>> = horton_basis(index.len(), &list, &weights);
+        Ok(cycle_keys(&index, &list, cycles))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::graph::testing::graph_from;
+    use crate::graph::{Graph, GraphError};
+
+    #[test]
+    fn minimum_cycle_basis_rejects_negative_weights() {
+        let graph: Graph = graph_from(false, &[("A", "B", -1.0), ("B", "C", 1.0), ("C", "A", 1.0)]);
+        assert_eq!(graph.minimum_cycle_basis(), Err(GraphError::NegativeWeight("A".to_string(), "B".to_string())));
+    }
+
+    #[test]
+    fn minimum_cycle_basis_keeps_the_lighter_triangles() {
+        // A square with a light diagonal: the basis is the two triangles, not the outer square.
+        let graph: Graph = graph_from(false, &[("A", "B", 1.0), ("B", "C", 1.0), ("C", "D", 1.0), ("D", "A", 1.0), ("A", "C", 1.0)]);
+        let basis: Vec<Vec<String>> = graph.minimum_cycle_basis().unwrap();
+        assert_eq!(basis, vec![vec!["A", "B", "C"], vec!["A", "C", "D"]]);
+    }
+}
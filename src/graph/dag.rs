@@ -0,0 +1,122 @@
+use crate::graph::connectivity::{component_labels, strong_component_labels};
+use crate::graph::index::GraphIndex;
+use crate::graph::Graph;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, VecDeque};
+use std::fmt;
+
+/// The error returned when a topological order is requested for a graph with a cycle.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CycleError {
+    /// One cycle, as vertex keys starting and ending at the same vertex.
+    pub cycle: Vec<String>,
+    /// Every vertex that lies on some cycle, sorted.
+    pub vertices: Vec<String>,
+}
+
+impl fmt::Display for CycleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Cycle {}", self.cycle.join(" -> "))
+    }
+}
+
+impl std::error::Error for CycleError {}
+
+/// Describes a cycle of the graph, if it has one, using its strongly connected components.
+pub(crate) fn cycle_error(index: &GraphIndex) -> Option<CycleError> {
+    let label: Vec<usize> = strong_component_labels(index);
+    let mut size: Vec<usize> = vec![0; index.len()];
+    for &l in &label {
+        size[l] += 1;
+    }
+    // A vertex is on a cycle if its component has other vertices or it has a self-loop.
+    let cyclic: Vec<usize> = (0..index.len())
+        .filter(|&v| size[label[v]] > 1 || index.adjacency[v].iter().any(|&(w, _)| w == v))
+        .collect();
+    let start: usize = *cyclic.first()?;
+
+    // The shortest way back to `start` within its component.
+    let mut parent: Vec<Option<usize>> = vec![None; index.len()];
+    let mut queue: VecDeque<usize> = VecDeque::from([start]);
+    let mut last: usize = start;
+    'search: while let Some(u) = queue.pop_front() {
+        for &(v, _) in &index.adjacency[u] {
+            if v == start {
+                last = u;
+                break 'search;
+            }
+            if label[v] == label[start] && parent[v].is_none() {
+                parent[v] = Some(u);
+                queue.push_back(v);
+            }
+        }
+    }
+    let mut cycle: Vec<usize> = vec![start, last];
+    while let Some(previous) = parent[cycle[cycle.len() - 1]] {
+        cycle.push(previous);
+    }
+    cycle.reverse();
+
+    Some(CycleError {
+        cycle: cycle.into_iter().map(|v| index.keys[v].clone()).collect(),
+        vertices: cyclic.into_iter().map(|v| index.keys[v].clone()).collect(),
+    })
+}
+
+impl Graph {
+    /// Orders the vertices of a directed acyclic graph so every edge points forward.
+    ///
+    /// Uses Kahn's algorithm, always taking the smallest key among the vertices whose
+    /// predecessors are all placed, so the order is deterministic. For a dependency graph
+    /// with edges from a task to the tasks that need it, this is a valid build order.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<String>, CycleError>` - The vertex keys in topological order, or an error
+    ///   describing a cycle if the graph has one. Undirected graphs with at least one edge
+    ///   always have a cycle, since an edge can be walked back and forth.
+    pub fn topological_sort(&self) -> Result<Vec<String>, CycleError> {
+        let index: GraphIndex = self.index();
+        let n: usize = index.len();
+        let mut in_degree: Vec<usize> = vec![0; n];
+        for neighbors in &index.adjacency {
+            for &(v, _) in neighbors {
+                in_degree[v] += 1;
+            }
+        }
+        let mut ready: BinaryHeap<Reverse<usize>> = (0..n).filter(|&v| in_degree[v] == 0).map(Reverse).collect();
+        let mut order: Vec<String> = Vec::with_capacity(n);
+        while let Some(Reverse(u)) = ready.pop() {
+            order.push(index.keys[u].clone());
+            for &(v, _) in &index.adjacency[u] {
+                in_degree[v] -= 1;
+                if in_degree[v] == 0 {
+                    ready.push(Reverse(v));
+                }
+            }
+        }
+        if order.len() < n {
+            return Err(cycle_error(&index).unwrap());
+        }
+        Ok(order)
+    }
+
+    /// Checks whether the graph has a cycle.
+    ///
+    /// In directed graphs a cycle must follow edge directions. In undirected graphs a cycle
+    /// must not reuse an edge, so a single edge is not a cycle but two parallel edges are;
+    /// self-loops count in both kinds of graph.
+    ///
+    /// # Returns
+    ///
+    /// * A `bool` that is `true` if the graph has a cycle.
+    pub fn has_cycle(&self) -> bool {
+        let index: GraphIndex = self.index();
+        if index.directed {
+            return cycle_error(&index).is_some();
+        }
+        // A forest has exactly `n - c` edges; any more close a cycle.
+        let components: usize = component_labels(&index).into_iter().max().map_or(0, |l| l + 1);
+        index.edges().len() + components > index.len()
+    }
+}
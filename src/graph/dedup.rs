@@ -0,0 +1,125 @@
+use crate::graph::index::GraphIndex;
+use crate::graph::{Edge, Graph, Vertex};
+use std::collections::{HashMap, HashSet};
+
+/// Returns the keys of the vertices `vertex` has an edge to, excluding itself.
+fn neighbor_keys(vertex: &Vertex) -> HashSet<&str> {
+    vertex.edges.iter()
+        .map(|edge| if edge.vertex1 == vertex.value { edge.vertex2.as_str() } else { edge.vertex1.as_str() })
+        .filter(|&key| key != vertex.value)
+        .collect()
+}
+
+/// Measures how alike the neighborhoods of two vertices are, for use with
+/// `Graph::merge_similar_vertices`.
+///
+/// # Arguments
+///
+/// * `a` - The first vertex.
+/// * `b` - The second vertex.
+///
+/// # Returns
+///
+/// * An `f32` in `[0, 1]`: the Jaccard index of the two neighbor sets, ignoring the two
+///   vertices themselves, or `0` if both have no other neighbors.
+pub fn neighborhood_similarity(a: &Vertex, b: &Vertex) -> f32 {
+    let mut left: HashSet<&str> = neighbor_keys(a);
+    let mut right: HashSet<&str> = neighbor_keys(b);
+    left.remove(b.value.as_str());
+    right.remove(a.value.as_str());
+    let union: usize = left.union(&right).count();
+    if union == 0 {
+        return 0.0;
+    }
+    left.intersection(&right).count() as f32 / union as f32
+}
+
+impl Graph {
+    /// Merges near-duplicate vertices, for cleaning entity graphs built from messy data.
+    ///
+    /// Every pair of vertices is scored with `similarity`, and pairs scoring at least
+    /// `threshold` are candidates. A greedy maximum-weight matching picks the most similar
+    /// candidates first so each vertex takes part in at most one merge per round; the merged
+    /// vertex keeps the smaller key and inherits the edges of both, and edges between the two
+    /// are dropped. Rounds repeat on the merged graph until no candidate pair is left, so a
+    /// whole group of duplicates collapses only while the merged vertices stay similar. This
+    /// avoids chaining unrelated vertices through a series of pairwise matches. Every round
+    /// scores all pairs, so it takes quadratic time in the number of vertices.
+    ///
+    /// # Arguments
+    ///
+    /// * `similarity` - Scores a pair of vertices; higher means more alike.
+    ///   `neighborhood_similarity` compares their neighbors.
+    /// * `threshold` - The lowest score at which two vertices are merged.
+    ///
+    /// # Returns
+    ///
+    /// * `(Graph, HashMap<String, String>)` - The deduplicated graph, and the key every removed
+    ///   vertex was merged into.
+    pub fn merge_similar_vertices<S>(&self, similarity: S, threshold: f32) -> (Graph, HashMap<String, String>)
+    where
+        S: Fn(&Vertex, &Vertex) -> f32,
+    {
+        // The graph after the latest round, once anything has been merged.
+        let mut current: Option<Graph> = None;
+        let mut merged: HashMap<String, String> = HashMap::new();
+        loop {
+            let graph: &Graph = current.as_ref().unwrap_or(self);
+            let index: GraphIndex = graph.index();
+            let n: usize = index.len();
+            let mut candidates: Vec<(f32, usize, usize)> = Vec::new();
+            for u in 0..n {
+                for v in u + 1..n {
+                    let score: f32 = similarity(&graph.vertices[&index.keys[u]], &graph.vertices[&index.keys[v]]);
+                    if score >= threshold {
+                        candidates.push((score, u, v));
+                    }
+                }
+            }
+            if candidates.is_empty() {
+                break;
+            }
+            candidates.sort_by(|a, b| b.0.total_cmp(&a.0).then_with(|| (a.1, a.2).cmp(&(b.1, b.2))));
+
+            // `keep[v]` is the vertex that `v` survives as.
+            let mut keep: Vec<usize> = (0..n).collect();
+            let mut matched: Vec<bool> = vec![false; n];
+            for (_, u, v) in candidates {
+                if !matched[u] && !matched[v] {
+                    matched[u] = true;
+                    matched[v] = true;
+                    keep[v] = u;
+                }
+            }
+
+            let mut next: Graph = Graph::new(graph.directed);
+            for v in (0..n).filter(|&v| keep[v] == v) {
+                next.add_vertex(Vertex::new(index.keys[v].clone()));
+            }
+            for (u, v, weight) in index.edges() {
+                let (a, b) = (keep[u], keep[v]);
+                if a == b && u != v {
+                    continue;
+                }
+                let edge: Edge = Edge::new(index.keys[a].clone(), index.keys[b].clone(), weight);
+                next.add_edge(edge).unwrap();
+            }
+
+            for v in (0..n).filter(|&v| keep[v] != v) {
+                let survivor: &String = &index.keys[keep[v]];
+                for target in merged.values_mut().filter(|target| *target == &index.keys[v]) {
+                    *target = survivor.clone();
+                }
+                merged.insert(index.keys[v].clone(), survivor.clone());
+            }
+            current = Some(next);
+        }
+        match current {
+            Some(graph) => (graph, merged),
+            None => {
+                let index: GraphIndex = self.index();
+                (index.with_edges(&index.edges()), merged)
+            }
+        }
+    }
+}
@@ -0,0 +1,97 @@
+use crate::graph::attribute::ExportAttributes;
+use crate::graph::{AttrValue, Edge, Graph};
+use std::fmt::Display;
+use std::io::{self, Write};
+
+/// Quotes a value as a Graphviz ID, escaping quotes and backslashes.
+fn quote<T: Display>(value: T) -> String {
+    format!("\"{}\"", value.to_string().replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Formats attributes as the `name=value` list inside a Graphviz attribute statement.
+fn attribute_list(attributes: Vec<(&String, &AttrValue)>) -> String {
+    let pairs: Vec<String> = attributes.into_iter()
+        .map(|(name, value)| {
+            // Plain identifiers such as `color` are left bare, as Graphviz users write them.
+            let plain: bool = name.chars().next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+                && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+            let name: String = if plain { name.clone() } else { quote(name) };
+            format!("{}={}", name, quote(value))
+        })
+        .collect();
+    pairs.join(", ")
+}
+
+impl Graph {
+    /// Writes the graph in the Graphviz DOT language.
+    ///
+    /// Vertices are listed in key order, followed by the edges, each labeled with its
+    /// weight. An undirected edge with direction-dependent weights is labeled
+    /// `forward / backward`. No attributes are written; see `write_dot_with`.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - Where to write the DOT source.
+    ///
+    /// # Returns
+    ///
+    /// * `io::Result<()>` - An error if writing fails.
+    pub fn write_dot<T: Write>(&self, writer: &mut T) -> io::Result<()> {
+        self.write_dot_with(writer, &ExportAttributes::none())
+    }
+
+    /// Writes the graph in the Graphviz DOT language with the selected vertex and edge
+    /// attributes, such as `color` or `shape`, as Graphviz attributes.
+    ///
+    /// Output is as for `write_dot`, with each element's selected attributes added in name
+    /// order. An edge attribute named `label` replaces the weight label.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - Where to write the DOT source.
+    /// * `attributes` - Which attributes to write.
+    ///
+    /// # Returns
+    ///
+    /// * `io::Result<()>` - An error if writing fails.
+    pub fn write_dot_with<T: Write>(&self, writer: &mut T, attributes: &ExportAttributes) -> io::Result<()> {
+        let (kind, connector): (&str, &str) = if self.directed { ("digraph", "->") } else { ("graph", "--") };
+        writeln!(writer, "{} {{", kind)?;
+
+        for (key, vertex) in &self.vertices {
+            let selected: Vec<(&String, &AttrValue)> = attributes.vertex_selection(&vertex.attributes);
+            if selected.is_empty() {
+                writeln!(writer, "    {};", quote(key))?;
+            } else {
+                writeln!(writer, "    {} [{}];", quote(key), attribute_list(selected))?;
+            }
+        }
+
+        let edges: Vec<&Edge> = self.stored_edges();
+        for edge in edges {
+            let mut selected: Vec<(&String, &AttrValue)> = attributes.edge_selection(&edge.attributes);
+            let label: String = match edge.reverse_weight {
+                Some(backward) => format!("{} / {}", edge.weight, backward),
+                None => edge.weight.to_string(),
+            };
+            let label: AttrValue = AttrValue::Text(label);
+            let label_name: String = "label".to_string();
+            if !selected.iter().any(|(name, _)| name.as_str() == "label") {
+                selected.insert(0, (&label_name, &label));
+            }
+            writeln!(writer, "    {} {} {} [{}];", quote(&edge.vertex1), connector, quote(&edge.vertex2), attribute_list(selected))?;
+        }
+        writeln!(writer, "}}")
+    }
+
+    /// Renders the graph in the Graphviz DOT language, for example to draw it with `dot -Tpng`.
+    ///
+    /// # Returns
+    ///
+    /// * A `String` with the DOT source, as written by `write_dot`.
+    pub fn to_dot(&self) -> String {
+        let mut buffer: Vec<u8> = Vec::new();
+        self.write_dot(&mut buffer).expect("Writing to memory cannot fail");
+        String::from_utf8(buffer).expect("DOT output is built from strings")
+    }
+}
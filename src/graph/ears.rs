@@ -0,0 +1,209 @@
+use crate::graph::cycles::EdgeList;
+use crate::graph::index::GraphIndex;
+use crate::graph::{Graph, GraphError};
+use std::collections::HashMap;
+
+/// A depth-first search tree over the edges of an undirected graph.
+struct DfsTree {
+    /// The vertices in the order they were discovered.
+    preorder: Vec<usize>,
+    /// The discovery number of every vertex (`usize::MAX` if unreached).
+    number: Vec<usize>,
+    /// The parent vertex and tree edge of every vertex.
+    parent: Vec<Option<(usize, usize)>>,
+}
+
+/// Runs an iterative depth-first search from `root`, taking `first` as the first edge when given.
+fn dfs(list: &EdgeList, root: usize, first: Option<usize>) -> DfsTree {
+    let n: usize = list.incident.len();
+    let mut tree: DfsTree = DfsTree {
+        preorder: vec![root],
+        number: vec![usize::MAX; n],
+        parent: vec![None; n],
+    };
+    tree.number[root] = 0;
+
+    let mut order: Vec<Vec<(usize, usize)>> = list.incident.clone();
+    if let Some(edge) = first {
+        order[root].sort_by_key(|&(_, e)| e != edge);
+    }
+    let mut cursor: Vec<usize> = vec![0; n];
+    let mut stack: Vec<usize> = vec![root];
+    while let Some(&u) = stack.last() {
+        if cursor[u] == order[u].len() {
+            stack.pop();
+            continue;
+        }
+        let (v, e) = order[u][cursor[u]];
+        cursor[u] += 1;
+        if tree.number[v] == usize::MAX {
+            tree.number[v] = tree.preorder.len();
+            tree.preorder.push(v);
+            tree.parent[v] = Some((u, e));
+            stack.push(v);
+        }
+    }
+    tree
+}
+
+/// Computes Schmidt's chain decomposition and checks that the graph is 2-vertex-connected.
+///
+/// Returns the chains as vertex sequences; the first chain is a cycle and every other
+/// chain is a path whose endpoints already lie on earlier chains.
+fn chains(index: &GraphIndex, list: &EdgeList) -> Result<Vec<Vec<usize>>, GraphError> {
+    let n: usize = index.len();
+    if n < 3 {
+        return Err(GraphError::NoSolution("Graph needs at least 3 vertices to be 2-connected".to_string()));
+    }
+    let tree: DfsTree = dfs(list, 0, None);
+    if tree.preorder.len() < n {
+        return Err(GraphError::Disconnected);
+    }
+
+    let mut visited: Vec<bool> = vec![false; n];
+    let mut covered: Vec<bool> = vec![false; list.edges.len()];
+    let mut result: Vec<Vec<usize>> = Vec::new();
+    for &v in &tree.preorder {
+        visited[v] = true;
+        for &(w, e) in &list.incident[v] {
+            let is_tree_edge: bool = tree.parent[w].is_some_and(|(_, p)| p == e) || tree.parent[v].is_some_and(|(_, p)| p == e);
+            if w == v || is_tree_edge || tree.number[w] < tree.number[v] {
+                continue;
+            }
+            covered[e] = true;
+            let mut chain: Vec<usize> = vec![v, w];
+            let mut current: usize = w;
+            while !visited[current] {
+                visited[current] = true;
+                let (up, edge) = tree.parent[current].unwrap();
+                covered[edge] = true;
+                chain.push(up);
+                current = up;
+            }
+            if !result.is_empty() && chain.first() == chain.last() {
+                return Err(GraphError::NoSolution(format!("Vertex {} is an articulation point", index.keys[v])));
+            }
+            result.push(chain);
+        }
+    }
+
+    if let Some(e) = (0..list.edges.len()).find(|&e| !covered[e] && list.edges[e].0 != list.edges[e].1) {
+        let (u, v, _) = list.edges[e];
+        return Err(GraphError::NoSolution(format!("Edge ({}, {}) is a bridge", index.keys[u], index.keys[v])));
+    }
+    Ok(result)
+}
+
+impl Graph {
+    /// Computes an open ear decomposition of a 2-connected undirected graph.
+    ///
+    /// The first ear is a cycle (its first vertex is repeated at the end) and every later
+    /// ear is a path whose two distinct endpoints lie on earlier ears. The decomposition is
+    /// derived from Schmidt's DFS chain decomposition, which also certifies 2-connectivity.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<Vec<String>>, GraphError>` - The ears as vertex-key sequences, or an error if the
+    ///   graph is directed or not 2-connected.
+    pub fn ear_decomposition(&self) -> Result<Vec<Vec<String>>, GraphError> {
+        if self.directed {
+            return Err(GraphError::NeedsUndirected("Ear decompositions".to_string()));
+        }
+        let index: GraphIndex = self.index();
+        let list: EdgeList = EdgeList::new(&index);
+        Ok(chains(&index, &list)?
+            .into_iter()
+            .map(|chain| chain.into_iter().map(|v| index.keys[v].clone()).collect())
+            .collect())
+    }
+
+    /// Computes an st-numbering of a 2-connected undirected graph.
+    ///
+    /// Vertices are numbered `1..=n` so that `s` gets `1`, `t` gets `n`, and every other vertex
+    /// has both a lower- and a higher-numbered neighbor. Uses Tarjan's DFS-based construction,
+    /// which requires `s` and `t` to be adjacent.
+    ///
+    /// # Arguments
+    ///
+    /// * `s` - The key of the vertex numbered first.
+    /// * `t` - The key of the vertex numbered last.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<HashMap<String, usize>, GraphError>` - The number of every vertex, or an error if the
+    ///   graph is directed or not 2-connected, a vertex does not exist, or `s` and `t` are not adjacent.
+    pub fn st_numbering(&self, s: &str, t: &str) -> Result<HashMap<String, usize>, GraphError> {
+        if self.directed {
+            return Err(GraphError::NeedsUndirected("st-numberings".to_string()));
+        }
+        let index: GraphIndex = self.index();
+        let source: usize = index.position(s)?;
+        let sink: usize = index.position(t)?;
+        let list: EdgeList = EdgeList::new(&index);
+        let Some(&(_, edge)) = list.incident[source].iter().find(|&&(v, _)| v == sink && v != source) else {
+            return Err(GraphError::EdgeNotFound(s.to_string(), t.to_string()));
+        };
+        chains(&index, &list)?;
+
+        let n: usize = index.len();
+        let tree: DfsTree = dfs(&list, source, Some(edge));
+
+        // low[v]: the vertex with the smallest number reachable from v's subtree by one back edge.
+        let mut low: Vec<usize> = (0..n).collect();
+        for &v in tree.preorder.iter().rev() {
+            for &(w, e) in &list.incident[v] {
+                if tree.parent[v].is_some_and(|(_, p)| p == e) {
+                    continue;
+                }
+                if tree.number[w] < tree.number[low[v]] {
+                    low[v] = w;
+                }
+            }
+            if let Some((p, _)) = tree.parent[v] {
+                if tree.number[low[v]] < tree.number[low[p]] {
+                    low[p] = low[v];
+                }
+            }
+        }
+
+        // A doubly linked list of vertices, with a sign per vertex telling on which side
+        // of it the next child should be inserted.
+        let mut next: Vec<Option<usize>> = vec![None; n];
+        let mut previous: Vec<Option<usize>> = vec![None; n];
+        let mut minus: Vec<bool> = vec![false; n];
+        next[source] = Some(sink);
+        previous[sink] = Some(source);
+        minus[source] = true;
+
+        for &v in tree.preorder.iter().skip(2) {
+            let (p, _) = tree.parent[v].unwrap();
+            if minus[low[v]] {
+                let before: Option<usize> = previous[p];
+                previous[v] = before;
+                next[v] = Some(p);
+                previous[p] = Some(v);
+                if let Some(b) = before {
+                    next[b] = Some(v);
+                }
+                minus[p] = false;
+            } else {
+                let after: Option<usize> = next[p];
+                next[v] = after;
+                previous[v] = Some(p);
+                next[p] = Some(v);
+                if let Some(a) = after {
+                    previous[a] = Some(v);
+                }
+                minus[p] = true;
+            }
+        }
+
+        let mut numbering: HashMap<String, usize> = HashMap::new();
+        let mut current: Option<usize> = Some(source);
+        while let Some(v) = current {
+            numbering.insert(index.keys[v].clone(), numbering.len() + 1);
+            current = next[v];
+        }
+        Ok(numbering)
+    }
+}
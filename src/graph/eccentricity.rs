@@ -0,0 +1,139 @@
+use crate::graph::index::GraphIndex;
+use crate::graph::path::{dijkstra, reject_negative_weights};
+use crate::graph::{Graph, GraphError};
+
+/// Distances closer than this are considered equal when comparing eccentricities.
+const TOLERANCE: f32 = 1e-5;
+
+/// The distance extremes of a connected undirected graph.
+#[derive(Clone, Debug)]
+pub struct DistanceExtremes {
+    /// The largest eccentricity.
+    pub diameter: f32,
+    /// The smallest eccentricity.
+    pub radius: f32,
+    /// The vertices whose eccentricity equals the radius, sorted.
+    pub center: Vec<String>,
+    /// The vertices whose eccentricity equals the diameter, sorted.
+    pub periphery: Vec<String>,
+    /// The number of single-source searches that were needed.
+    pub searches: usize,
+}
+
+impl Graph {
+    /// Computes diameter, radius, center and periphery with the BoundingDiameters algorithm.
+    ///
+    /// Every vertex keeps a lower and an upper bound on its eccentricity. Each single-source
+    /// search tightens the bounds of all vertices through the triangle inequality, and vertices
+    /// that can be neither central nor peripheral, or whose eccentricity is already known, are
+    /// dropped. On real-world graphs this typically needs only a handful of searches instead of
+    /// one per vertex. Edge weights must be non-negative.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<DistanceExtremes, GraphError>` - The extremes and the number of searches run, or an
+    ///   error if the graph is directed, empty or not connected, or an edge weight is negative.
+    pub fn distance_extremes(&self) -> Result<DistanceExtremes, GraphError> {
+        self.require_distances("Distance extremes")?;
+        self.cached("distance_extremes", || self.bounding_diameters())
+    }
+
+    /// Runs the BoundingDiameters algorithm behind `distance_extremes`.
+    fn bounding_diameters(&self) -> Result<DistanceExtremes, GraphError> {
+        if self.directed {
+            return Err(GraphError::NeedsUndirected("Eccentricity bounds".to_string()));
+        }
+        let index: GraphIndex = self.index();
+        let n: usize = index.len();
+        if n == 0 {
+            return Err(GraphError::EmptyGraph);
+        }
+        reject_negative_weights(&index)?;
+
+        let mut lower: Vec<f32> = vec![0.0; n];
+        let mut upper: Vec<f32> = vec![f32::INFINITY; n];
+        let mut candidate: Vec<bool> = vec![true; n];
+        let mut remaining: usize = n;
+        let mut searches: usize = 0;
+        let mut pick_upper: bool = true;
+
+        while remaining > 0 {
+            // Alternate between the most promising periphery and center candidates.
+            let alive = (0..n).filter(|&w| candidate[w]);
+            let v: usize = if pick_upper {
+                alive.max_by(|&a, &b| upper[a].total_cmp(&upper[b]).then_with(|| b.cmp(&a))).unwrap()
+            } else {
+                alive.min_by(|&a, &b| lower[a].total_cmp(&lower[b]).then_with(|| a.cmp(&b))).unwrap()
+            };
+            pick_upper = !pick_upper;
+
+            let (distance, _) = dijkstra(&index, v, |_| true, |_, _, _| true);
+            searches += 1;
+            if distance.iter().any(|d| d.is_infinite()) {
+                return Err(GraphError::Disconnected);
+            }
+            let eccentricity: f32 = distance.iter().copied().fold(0.0, f32::max);
+            lower[v] = eccentricity;
+            upper[v] = eccentricity;
+
+            for w in 0..n {
+                if candidate[w] && w != v {
+                    lower[w] = lower[w].max((eccentricity - distance[w]).max(distance[w]));
+                    upper[w] = upper[w].min(eccentricity + distance[w]);
+                }
+            }
+
+            let diameter_lower: f32 = lower.iter().copied().fold(0.0, f32::max);
+            let radius_upper: f32 = upper.iter().copied().fold(f32::INFINITY, f32::min);
+            for w in 0..n {
+                if !candidate[w] {
+                    continue;
+                }
+                let exact: bool = upper[w] - lower[w] <= TOLERANCE;
+                let irrelevant: bool = upper[w] < diameter_lower - TOLERANCE && lower[w] > radius_upper + TOLERANCE;
+                if exact || irrelevant {
+                    candidate[w] = false;
+                    remaining -= 1;
+                }
+            }
+        }
+
+        let diameter: f32 = lower.iter().copied().fold(0.0, f32::max);
+        let radius: f32 = upper.iter().copied().fold(f32::INFINITY, f32::min);
+        let select = |target: f32| -> Vec<String> {
+            (0..n)
+                .filter(|&w| upper[w] - lower[w] <= TOLERANCE && (lower[w] - target).abs() <= TOLERANCE)
+                .map(|w| index.keys[w].clone())
+                .collect()
+        };
+
+        Ok(DistanceExtremes {
+            diameter,
+            radius,
+            center: select(radius),
+            periphery: select(diameter),
+            searches,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::graph::testing::graph_from;
+    use crate::graph::{Graph, GraphError};
+
+    #[test]
+    fn distance_extremes_rejects_negative_weights() {
+        let graph: Graph = graph_from(false, &[("A", "B", -1.0), ("B", "C", 1.0)]);
+        assert!(matches!(graph.distance_extremes(), Err(GraphError::NegativeWeight(_, _))));
+    }
+
+    #[test]
+    fn distance_extremes_of_a_path() {
+        let graph: Graph = graph_from(false, &[("A", "B", 1.0), ("B", "C", 2.0), ("C", "D", 1.0)]);
+        let extremes = graph.distance_extremes().unwrap();
+        assert_eq!((extremes.diameter, extremes.radius), (4.0, 3.0));
+        assert_eq!(extremes.center, vec!["B", "C"]);
+        assert_eq!(extremes.periphery, vec!["A", "D"]);
+    }
+}
@@ -1,42 +1,127 @@
-use crate::graph::vertex::Vertex;
+use crate::graph::AttrValue;
+use std::collections::HashMap;
 
 /// A struct representing an edge in a graph.
 /// An edge connects two vertices and can have an associated weight.
 #[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Edge {
-    /// The first vertex that the edge connects.
-    pub vertex1: Vertex,
-    /// The second vertex that the edge connects.
-    pub vertex2: Vertex,
+    /// The key of the first vertex that the edge connects.
+    pub vertex1: String,
+    /// The key of the second vertex that the edge connects.
+    pub vertex2: String,
     /// The weight of the edge, representing the cost or distance between the two vertices.
     pub weight: f32,
+    /// The cost of traversing an undirected edge from `vertex2` back to `vertex1`, when it
+    /// differs from `weight` (for example downhill instead of uphill). `None` means both
+    /// directions cost `weight`.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub reverse_weight: Option<f32>,
+    /// Named metadata attached to the edge.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "HashMap::is_empty"))]
+    pub attributes: HashMap<String, AttrValue>,
 }
 
-// impl PartialEq for Edge {
-//     fn eq(&self, other: &Self) -> bool {
-//         self.vertex1 == other.vertex1
-//             && self.vertex2 == other.vertex2
-//             && (self.weight - other.weight).abs() < f32::EPSILON
-//     }
-// }
+/// Edges are equal when they join the same vertices in the same order with the same
+/// weights. Attributes are not compared.
+impl PartialEq for Edge {
+    fn eq(&self, other: &Self) -> bool {
+        self.vertex1 == other.vertex1
+            && self.vertex2 == other.vertex2
+            && self.weight == other.weight
+            && self.reverse_weight == other.reverse_weight
+    }
+}
 
 impl Edge {
     /// Creates a new `Edge` with the given vertices and weight.
     ///
     /// # Arguments
     ///
-    /// * `vertex1` - The first `Vertex` connected by this edge.
-    /// * `vertex2` - The second `Vertex` connected by this edge.
+    /// * `vertex1` - The key of the first vertex connected by this edge.
+    /// * `vertex2` - The key of the second vertex connected by this edge.
     /// * `weight` - A `f32` value representing the weight of the edge.
     ///
     /// # Returns
     ///
     /// * An `Edge` instance connecting the two vertices with the specified weight.
-    pub fn new(vertex1: Vertex, vertex2: Vertex, weight: f32) -> Edge {
+    pub fn new(vertex1: String, vertex2: String, weight: f32) -> Edge {
         Edge {
             vertex1,
             vertex2,
             weight,
+            reverse_weight: None,
+            attributes: HashMap::new(),
+        }
+    }
+
+    /// Creates a new undirected `Edge` whose cost depends on the direction of travel.
+    ///
+    /// Path searches use the cost for the direction they traverse the edge in. Algorithms
+    /// that treat an edge as one symmetric connection, such as spanning trees, use the cost
+    /// seen from the endpoint with the smaller key. Only undirected graphs accept such edges;
+    /// a directed graph models the same thing with two opposite edges.
+    ///
+    /// # Arguments
+    ///
+    /// * `vertex1` - The key of the first vertex connected by this edge.
+    /// * `vertex2` - The key of the second vertex connected by this edge.
+    /// * `forward` - The cost of traversing the edge from `vertex1` to `vertex2`.
+    /// * `backward` - The cost of traversing the edge from `vertex2` to `vertex1`.
+    ///
+    /// # Returns
+    ///
+    /// * An `Edge` instance with the two directional weights.
+    pub fn asymmetric(vertex1: String, vertex2: String, forward: f32, backward: f32) -> Edge {
+        Edge {
+            vertex1,
+            vertex2,
+            weight: forward,
+            reverse_weight: Some(backward),
+            attributes: HashMap::new(),
+        }
+    }
+
+    /// Returns the cost of traversing the edge starting at the given endpoint.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - The key of the endpoint the traversal starts at.
+    ///
+    /// # Returns
+    ///
+    /// * An `f32` with `reverse_weight` when starting at `vertex2` of a non-loop edge that has
+    ///   one, and `weight` otherwise.
+    pub fn weight_from(&self, from: &str) -> f32 {
+        match self.reverse_weight {
+            Some(backward) if self.vertex2 == from && self.vertex1 != from => backward,
+            _ => self.weight,
         }
     }
+
+    /// Sets an attribute of the edge, replacing any previous value under the same name.
+    ///
+    /// Set attributes before adding the edge to a graph: an undirected graph stores a copy
+    /// under each endpoint.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The name of the attribute.
+    /// * `value` - The value to store.
+    pub fn set_attr(&mut self, key: &str, value: AttrValue) {
+        self.attributes.insert(key.to_string(), value);
+    }
+
+    /// Returns an attribute of the edge.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The name of the attribute.
+    ///
+    /// # Returns
+    ///
+    /// * An `Option<&AttrValue>` with the value, or `None` if the attribute is not set.
+    pub fn attr(&self, key: &str) -> Option<&AttrValue> {
+        self.attributes.get(key)
+    }
 }
@@ -0,0 +1,201 @@
+use crate::graph::{Edge, Graph, GraphError, Vertex};
+use std::fs;
+use std::path::Path;
+
+/// How the fields of an edge list line are separated.
+#[derive(Clone, Copy)]
+pub(crate) enum Separator {
+    /// Fields are separated by runs of spaces or tabs.
+    Whitespace,
+    /// Fields are separated by commas, and surrounding spaces are trimmed.
+    Comma,
+}
+
+impl Separator {
+    /// Splits a line into its fields.
+    pub(crate) fn split(self, line: &str) -> Vec<&str> {
+        match self {
+            Separator::Whitespace => line.split_whitespace().collect(),
+            Separator::Comma => line.split(',').map(str::trim).collect(),
+        }
+    }
+}
+
+/// Reads a whole file as text.
+fn read(path: &Path) -> Result<String, GraphError> {
+    fs::read_to_string(path).map_err(|e| GraphError::Io(format!("Could not read {}: {}", path.display(), e)))
+}
+
+/// The lines of `text` that hold data, with their 1-based line numbers.
+///
+/// Blank lines and lines starting with `#` are skipped.
+fn data_lines(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    text.lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line.trim()))
+        .filter(|(_, line)| is_data_line(line))
+}
+
+/// Returns whether a trimmed line holds data rather than being blank or a `#` comment.
+pub(crate) fn is_data_line(line: &str) -> bool {
+    !line.is_empty() && !line.starts_with('#')
+}
+
+/// Reads an edge weight, which must be a finite number.
+fn parse_weight(field: &str, line: usize) -> Result<f32, GraphError> {
+    field.parse::<f32>()
+        .ok()
+        .filter(|weight| weight.is_finite())
+        .ok_or_else(|| GraphError::Parse(format!("Line {}: {} is not a valid weight", line, field)))
+}
+
+/// Splits an edge list line into its two vertex keys and weight, which is `1` when absent.
+pub(crate) fn parse_edge_line(content: &str, line: usize, separator: Separator) -> Result<(&str, &str, f32), GraphError> {
+    let fields: Vec<&str> = separator.split(content);
+    let (from, to, weight): (&str, &str, f32) = match fields.as_slice() {
+        [from, to] => (from, to, 1.0),
+        [from, to, weight] => (from, to, parse_weight(weight, line)?),
+        _ => return Err(GraphError::Parse(format!("Line {}: expected 2 or 3 fields, found {}", line, fields.len()))),
+    };
+    if from.is_empty() || to.is_empty() {
+        return Err(GraphError::Parse(format!("Line {}: a vertex key is empty", line)));
+    }
+    Ok((from, to, weight))
+}
+
+impl Graph {
+    /// Adds a vertex with the given key unless the graph already has one.
+    fn ensure_vertex(&mut self, key: &str) {
+        if !self.has_vertex(key) {
+            self.add_vertex(Vertex::new(key.to_string()));
+        }
+    }
+
+    /// Builds a graph from edge list lines split with `separator`.
+    fn parse_edge_list(text: &str, directed: bool, separator: Separator) -> Result<Graph, GraphError> {
+        let mut graph: Graph = Graph::new(directed);
+        for (line, content) in data_lines(text) {
+            let (from, to, weight): (&str, &str, f32) = parse_edge_line(content, line, separator)?;
+            graph.ensure_vertex(from);
+            graph.ensure_vertex(to);
+            graph.add_edge(Edge::new(from.to_string(), to.to_string(), weight))
+                .map_err(|e| GraphError::Parse(format!("Line {}: {}", line, e)))?;
+        }
+        Ok(graph)
+    }
+
+    /// Reads a graph from an edge list with one edge per line.
+    ///
+    /// Each line holds two vertex keys and an optional weight separated by whitespace, such
+    /// as `A B 2.5`; the weight is `1` when absent. Vertices are created the first time they
+    /// are named. Blank lines and lines starting with `#` are skipped. Every edge is added as
+    /// listed, so an undirected edge written once in each direction becomes two parallel edges.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The edge list.
+    /// * `directed` - Whether the graph is directed.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Graph, GraphError>` - The graph, or an error naming the first line with the wrong
+    ///   number of fields or an invalid weight.
+    pub fn from_edge_list(text: &str, directed: bool) -> Result<Graph, GraphError> {
+        Graph::parse_edge_list(text, directed, Separator::Whitespace)
+    }
+
+    /// Reads a graph from comma-separated edge list lines such as `A,B,2.5`.
+    ///
+    /// Fields are trimmed, so keys may contain inner spaces but not commas; quoting is not
+    /// supported. Otherwise this reads the same as `Graph::from_edge_list`, and a header line
+    /// can be kept by starting it with `#`.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The CSV edge list.
+    /// * `directed` - Whether the graph is directed.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Graph, GraphError>` - The graph, or an error naming the first malformed line.
+    pub fn from_csv(text: &str, directed: bool) -> Result<Graph, GraphError> {
+        Graph::parse_edge_list(text, directed, Separator::Comma)
+    }
+
+    /// Reads a graph from an adjacency list.
+    ///
+    /// Each line starts with a vertex key followed by the keys of its neighbors, separated by
+    /// whitespace, such as `A B C`; a line with only a key adds an isolated vertex. Every
+    /// listed edge has weight `1` and is added once, so in an undirected graph each edge
+    /// should be listed under only one of its endpoints. Blank lines and lines starting with
+    /// `#` are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The adjacency list.
+    /// * `directed` - Whether the graph is directed.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Graph, GraphError>` - The graph, or an error naming the first line an edge could
+    ///   not be added from.
+    pub fn from_adjacency_list(text: &str, directed: bool) -> Result<Graph, GraphError> {
+        let mut graph: Graph = Graph::new(directed);
+        for (line, content) in data_lines(text) {
+            let mut fields = content.split_whitespace();
+            let from: &str = fields.next().unwrap();
+            graph.ensure_vertex(from);
+            for to in fields {
+                graph.ensure_vertex(to);
+                graph.add_edge(Edge::new(from.to_string(), to.to_string(), 1.0))
+                    .map_err(|e| GraphError::Parse(format!("Line {}: {}", line, e)))?;
+            }
+        }
+        Ok(graph)
+    }
+
+    /// Reads a graph from a whitespace-separated edge list file, as `Graph::from_edge_list`.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The file to read.
+    /// * `directed` - Whether the graph is directed.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Graph, GraphError>` - The graph, or an error if the file cannot be read or a line
+    ///   is malformed.
+    pub fn from_edge_list_file<P: AsRef<Path>>(path: P, directed: bool) -> Result<Graph, GraphError> {
+        Graph::from_edge_list(&read(path.as_ref())?, directed)
+    }
+
+    /// Reads a graph from a CSV edge list file, as `Graph::from_csv`.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The file to read.
+    /// * `directed` - Whether the graph is directed.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Graph, GraphError>` - The graph, or an error if the file cannot be read or a line
+    ///   is malformed.
+    pub fn from_csv_file<P: AsRef<Path>>(path: P, directed: bool) -> Result<Graph, GraphError> {
+        Graph::from_csv(&read(path.as_ref())?, directed)
+    }
+
+    /// Reads a graph from an adjacency list file, as `Graph::from_adjacency_list`.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The file to read.
+    /// * `directed` - Whether the graph is directed.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Graph, GraphError>` - The graph, or an error if the file cannot be read or a line
+    ///   is malformed.
+    pub fn from_adjacency_list_file<P: AsRef<Path>>(path: P, directed: bool) -> Result<Graph, GraphError> {
+        Graph::from_adjacency_list(&read(path.as_ref())?, directed)
+    }
+}
@@ -0,0 +1,77 @@
+use crate::graph::dag::CycleError;
+use std::fmt;
+
+/// The ways a graph operation can fail.
+#[derive(Clone, Debug, PartialEq)]
+pub enum GraphError {
+    /// A vertex with the given key does not exist.
+    VertexNotFound(String),
+    /// No edge joins the two vertices.
+    EdgeNotFound(String, String),
+    /// The two vertices are joined by more than one edge where only one is allowed.
+    DuplicateEdge(String, String),
+    /// The vertex has a self-loop where none is allowed.
+    SelfLoop(String),
+    /// The edge between the two vertices has a negative weight the operation cannot handle.
+    NegativeWeight(String, String),
+    /// A cycle of negative total weight makes distances unbounded. Holds the vertex keys
+    /// around the cycle, starting and ending at the same vertex, or only a vertex on it when
+    /// the rest of the cycle is not known.
+    NegativeCycle(Vec<String>),
+    /// The graph has a cycle where none is allowed. Holds the vertex keys around the cycle,
+    /// starting and ending at the same vertex, or only a vertex on it when the rest of the
+    /// cycle is not known.
+    CycleDetected(Vec<String>),
+    /// The operation, named here, is only supported on undirected graphs.
+    NeedsUndirected(String),
+    /// The operation, named here, is only supported on directed graphs.
+    NeedsDirected(String),
+    /// The operation needs a connected graph.
+    Disconnected,
+    /// The operation needs at least one vertex.
+    EmptyGraph,
+    /// An argument is out of range or inconsistent with the graph.
+    InvalidArgument(String),
+    /// The graph has no solution for the operation, or none could be found.
+    NoSolution(String),
+    /// A document or file being read is malformed.
+    Parse(String),
+    /// A file could not be read.
+    Io(String),
+    /// The run was stopped through its `CancellationToken`.
+    Cancelled,
+    /// The run was stopped because its deadline passed.
+    TimedOut,
+}
+
+impl fmt::Display for GraphError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GraphError::VertexNotFound(key) => write!(f, "Vertex {} does not exist", key),
+            GraphError::EdgeNotFound(from, to) => write!(f, "Edge ({}, {}) does not exist", from, to),
+            GraphError::DuplicateEdge(from, to) => write!(f, "Edge ({}, {}) is repeated", from, to),
+            GraphError::SelfLoop(key) => write!(f, "Vertex {} has a self-loop", key),
+            GraphError::NegativeWeight(from, to) => write!(f, "Edge ({}, {}) has a negative weight", from, to),
+            GraphError::NegativeCycle(cycle) => write!(f, "Negative cycle {}", cycle.join(" -> ")),
+            GraphError::CycleDetected(cycle) => write!(f, "Cycle {}", cycle.join(" -> ")),
+            GraphError::NeedsUndirected(operation) => write!(f, "{}: the graph must be undirected", operation),
+            GraphError::NeedsDirected(operation) => write!(f, "{}: the graph must be directed", operation),
+            GraphError::Disconnected => write!(f, "The graph is not connected"),
+            GraphError::EmptyGraph => write!(f, "The graph has no vertices"),
+            GraphError::Cancelled => write!(f, "The run was cancelled"),
+            GraphError::TimedOut => write!(f, "The run passed its deadline"),
+            GraphError::InvalidArgument(message)
+            | GraphError::NoSolution(message)
+            | GraphError::Parse(message)
+            | GraphError::Io(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for GraphError {}
+
+impl From<CycleError> for GraphError {
+    fn from(error: CycleError) -> GraphError {
+        GraphError::CycleDetected(error.cycle)
+    }
+}
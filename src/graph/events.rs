@@ -0,0 +1,334 @@
+use crate::graph::{AttrValue, Edge, Graph, GraphError, Vertex};
+use std::collections::HashMap;
+#[cfg(feature = "serde")]
+use std::io::{BufRead, Write};
+
+/// One change to a graph at a point in time, the unit of a graph's event log.
+///
+/// A log starts with `Graph::snapshot_events` for the graph as it was when logging began,
+/// followed by every later change as an event. The graph does not log its own changes: the
+/// caller records each event alongside the change it makes, or builds the graph from the log
+/// with `Graph::apply_events`. `Graph::apply_events` plays events onto a
+/// graph and `Graph::replay` rebuilds the graph as it was at any time. With the `serde`
+/// feature, logs are saved one JSON object per line (ndjson) with `GraphEvent::write_ndjson`,
+/// so a running log can be appended to.
+///
+/// Times are whatever the caller counts in, such as milliseconds since the Unix epoch; they
+/// only need to not decrease along the log.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "event", rename_all = "snake_case"))]
+pub enum GraphEvent {
+    /// Adds a vertex, or replaces the attributes of an existing one and keeps its edges.
+    AddVertex {
+        time: u64,
+        key: String,
+        #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "HashMap::is_empty"))]
+        attributes: HashMap<String, AttrValue>,
+    },
+    /// Removes a vertex and every edge incident to it.
+    RemoveVertex {
+        time: u64,
+        key: String,
+    },
+    /// Adds an edge between two existing vertices.
+    AddEdge {
+        time: u64,
+        from: String,
+        to: String,
+        weight: f32,
+        /// The cost from `to` back to `from` of an undirected edge, when it differs.
+        #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+        reverse_weight: Option<f32>,
+        #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "HashMap::is_empty"))]
+        attributes: HashMap<String, AttrValue>,
+    },
+    /// Removes one edge, as `Graph::remove_edge` does.
+    RemoveEdge {
+        time: u64,
+        from: String,
+        to: String,
+    },
+    /// Sets the weight of the edge `Graph::remove_edge` would remove, in both directions
+    /// for an undirected edge.
+    UpdateWeight {
+        time: u64,
+        from: String,
+        to: String,
+        weight: f32,
+    },
+}
+
+impl GraphEvent {
+    /// Returns the time the event happened at.
+    pub fn time(&self) -> u64 {
+        match self {
+            GraphEvent::AddVertex { time, .. }
+            | GraphEvent::RemoveVertex { time, .. }
+            | GraphEvent::AddEdge { time, .. }
+            | GraphEvent::RemoveEdge { time, .. }
+            | GraphEvent::UpdateWeight { time, .. } => *time,
+        }
+    }
+
+    /// Writes events as newline-delimited JSON, one event per line.
+    ///
+    /// # Arguments
+    ///
+    /// * `events` - The events to write, in log order.
+    /// * `writer` - The destination, such as a log file opened for appending.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), GraphError>` - `Ok(())`, or an error if the writer fails.
+    #[cfg(feature = "serde")]
+    pub fn write_ndjson<W: Write>(events: &[GraphEvent], mut writer: W) -> Result<(), GraphError> {
+        for event in events {
+            let line: String = serde_json::to_string(event).map_err(|e| GraphError::InvalidArgument(e.to_string()))?;
+            writeln!(writer, "{}", line).map_err(|e| GraphError::Io(e.to_string()))?;
+        }
+        Ok(())
+    }
+
+    /// Reads events written by `GraphEvent::write_ndjson`, skipping blank lines.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - The source of the log.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<GraphEvent>, GraphError>` - The events in log order, or an error if the
+    ///   reader fails or a line is not an event.
+    #[cfg(feature = "serde")]
+    pub fn read_ndjson<R: BufRead>(reader: R) -> Result<Vec<GraphEvent>, GraphError> {
+        let mut events: Vec<GraphEvent> = Vec::new();
+        for (index, text) in reader.lines().enumerate() {
+            let text: String = text.map_err(|e| GraphError::Io(format!("Line {}: {}", index + 1, e)))?;
+            if text.trim().is_empty() {
+                continue;
+            }
+            let event: GraphEvent = serde_json::from_str(&text).map_err(|e| GraphError::Parse(format!("Line {}: {}", index + 1, e)))?;
+            events.push(event);
+        }
+        Ok(events)
+    }
+}
+
+impl Graph {
+    /// Returns the events that build the graph as it is now, all at the same time: every
+    /// vertex in key order, then every edge once, each with its attributes.
+    ///
+    /// This is a snapshot, not a log: changes made to the graph afterwards are not recorded.
+    ///
+    /// # Arguments
+    ///
+    /// * `time` - The time to give the events, usually when logging starts.
+    ///
+    /// # Returns
+    ///
+    /// * A `Vec<GraphEvent>` that `apply_events` turns an empty graph with the same direction
+    ///   into an equal one.
+    pub fn snapshot_events(&self, time: u64) -> Vec<GraphEvent> {
+        let vertices = self.vertices.values().map(|vertex| GraphEvent::AddVertex {
+            time,
+            key: vertex.value.clone(),
+            attributes: vertex.attributes.clone(),
+        });
+        let edges = self.stored_edges().into_iter().map(|edge| GraphEvent::AddEdge {
+            time,
+            from: edge.vertex1.clone(),
+            to: edge.vertex2.clone(),
+            weight: edge.weight,
+            reverse_weight: edge.reverse_weight,
+            attributes: edge.attributes.clone(),
+        });
+        vertices.chain(edges).collect()
+    }
+
+    /// Applies events to the graph in order.
+    ///
+    /// Events are applied one at a time, so when one fails the ones before it stay applied.
+    ///
+    /// # Arguments
+    ///
+    /// * `events` - The events to apply, in log order.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), GraphError>` - `Ok(())`, or an error if the times decrease, or if an event
+    ///   refers to a missing vertex or edge or breaks the rules of the graph's mode.
+    pub fn apply_events(&mut self, events: &[GraphEvent]) -> Result<(), GraphError> {
+        let mut last: u64 = 0;
+        for event in events {
+            if event.time() < last {
+                return Err(GraphError::InvalidArgument(format!("Event at time {} comes after one at time {}", event.time(), last)));
+            }
+            last = event.time();
+            self.apply_event(event)?;
+        }
+        Ok(())
+    }
+
+    /// Rebuilds a graph from its event log as it was at a given time, applying every event
+    /// up to and including `until`.
+    ///
+    /// # Arguments
+    ///
+    /// * `directed` - Whether the logged graph is directed.
+    /// * `events` - The log, in order.
+    /// * `until` - The last time to include.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Graph, GraphError>` - The graph at `until`, or an error as `apply_events`
+    ///   returns.
+    pub fn replay(directed: bool, events: &[GraphEvent], until: u64) -> Result<Graph, GraphError> {
+        let mut graph: Graph = Graph::new(directed);
+        let end: usize = events.iter().position(|event| event.time() > until).unwrap_or(events.len());
+        graph.apply_events(&events[..end])?;
+        Ok(graph)
+    }
+
+    /// Applies one event.
+    fn apply_event(&mut self, event: &GraphEvent) -> Result<(), GraphError> {
+        match event {
+            GraphEvent::AddVertex { key, attributes, .. } => {
+                match self.vertices.get_mut(key) {
+                    Some(vertex) => {
+                        vertex.attributes = attributes.clone();
+                        self.generation += 1;
+                    }
+                    None => {
+                        let mut vertex: Vertex = Vertex::new(key.clone());
+                        vertex.attributes = attributes.clone();
+                        self.add_vertex(vertex);
+                    }
+                }
+            }
+            GraphEvent::RemoveVertex { key, .. } => {
+                self.remove_vertex(key.as_str())?;
+            }
+            GraphEvent::AddEdge { from, to, weight, reverse_weight, attributes, .. } => {
+                let mut edge: Edge = Edge::new(from.clone(), to.clone(), *weight);
+                edge.reverse_weight = *reverse_weight;
+                edge.attributes = attributes.clone();
+                self.add_edge(edge)?;
+            }
+            GraphEvent::RemoveEdge { from, to, .. } => {
+                self.remove_edge(from.as_str(), to.as_str())?;
+            }
+            GraphEvent::UpdateWeight { from, to, weight, .. } => {
+                let directed: bool = self.directed;
+                if !self.vertices.contains_key(to) {
+                    return Err(GraphError::VertexNotFound(to.clone()));
+                }
+                let source: &mut Vertex = self.vertices.get_mut(from).ok_or_else(|| GraphError::VertexNotFound(from.clone()))?;
+                let edge: &mut Edge = source.edges.iter_mut()
+                    .find(|edge| (edge.vertex1 == *from && edge.vertex2 == *to) || (!directed && edge.vertex1 == *to && edge.vertex2 == *from))
+                    .ok_or_else(|| GraphError::EdgeNotFound(from.clone(), to.clone()))?;
+                let old: Edge = edge.clone();
+                edge.weight = *weight;
+                edge.reverse_weight = None;
+
+                // The copy on the other endpoint is found the way `remove_edge` finds it.
+                if !directed {
+                    let target: &mut Vertex = self.vertices.get_mut(to).unwrap();
+                    if let Some(mirror) = target.edges.iter_mut().find(|other| **other == old) {
+                        mirror.weight = *weight;
+                        mirror.reverse_weight = None;
+                    }
+                }
+                self.generation += 1;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GraphEvent;
+    use crate::graph::testing::graph_from;
+    use crate::graph::{AttrValue, Edge, Graph, GraphError, Vertex};
+    use std::collections::HashMap;
+
+    fn add_vertex(time: u64, key: &str) -> GraphEvent {
+        GraphEvent::AddVertex { time, key: key.to_string(), attributes: HashMap::new() }
+    }
+
+    fn add_edge(time: u64, from: &str, to: &str, weight: f32) -> GraphEvent {
+        GraphEvent::AddEdge { time, from: from.to_string(), to: to.to_string(), weight, reverse_weight: None, attributes: HashMap::new() }
+    }
+
+    #[test]
+    fn snapshot_rebuilds_an_equal_graph_with_attributes() {
+        let mut graph: Graph = graph_from(false, &[("a", "b", 1.0), ("b", "c", 2.0)]);
+        graph.vertices.get_mut("a").unwrap().set_attr("color", AttrValue::Text("red".to_string()));
+        let mut edge: Edge = Edge::asymmetric("c".to_string(), "a".to_string(), 3.0, 4.0);
+        edge.set_attr("road", AttrValue::Bool(true));
+        graph.add_edge(edge).unwrap();
+
+        let mut rebuilt: Graph = Graph::new(false);
+        rebuilt.apply_events(&graph.snapshot_events(7)).unwrap();
+        assert_eq!(rebuilt, graph);
+        assert_eq!(rebuilt.vertices["a"].attr("color"), Some(&AttrValue::Text("red".to_string())));
+        let road: &Edge = rebuilt.vertices["a"].edges.iter().find(|edge| edge.weight_from("c") == 3.0).unwrap();
+        assert_eq!(road.attr("road"), Some(&AttrValue::Bool(true)));
+    }
+
+    #[test]
+    fn replay_stops_at_the_given_time() {
+        let events: Vec<GraphEvent> = vec![
+            add_vertex(1, "a"),
+            add_vertex(1, "b"),
+            add_edge(2, "a", "b", 1.0),
+            GraphEvent::UpdateWeight { time: 3, from: "b".to_string(), to: "a".to_string(), weight: 5.0 },
+            GraphEvent::RemoveVertex { time: 4, key: "b".to_string() },
+        ];
+        assert_eq!(Graph::replay(false, &events, 1).unwrap(), {
+            let mut graph: Graph = Graph::new(false);
+            graph.add_vertex(Vertex::new("a".to_string()));
+            graph.add_vertex(Vertex::new("b".to_string()));
+            graph
+        });
+        assert_eq!(Graph::replay(false, &events, 2).unwrap(), graph_from(false, &[("a", "b", 1.0)]));
+        assert_eq!(Graph::replay(false, &events, 3).unwrap(), graph_from(false, &[("a", "b", 5.0)]));
+        let last: Graph = Graph::replay(false, &events, 10).unwrap();
+        assert_eq!(last.vertex_keys(), vec!["a".to_string()]);
+        assert_eq!(last.edge_count, 0);
+    }
+
+    #[test]
+    fn decreasing_times_are_rejected() {
+        let mut graph: Graph = Graph::new(true);
+        let result = graph.apply_events(&[add_vertex(5, "a"), add_vertex(4, "b")]);
+        assert!(matches!(result, Err(GraphError::InvalidArgument(_))));
+        assert!(graph.has_vertex("a"));
+        assert!(!graph.has_vertex("b"));
+    }
+
+    #[test]
+    fn edge_to_a_missing_vertex_fails() {
+        let mut graph: Graph = Graph::new(true);
+        let result = graph.apply_events(&[add_vertex(1, "a"), add_edge(2, "a", "b", 1.0)]);
+        assert!(matches!(result, Err(GraphError::VertexNotFound(_))));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn ndjson_round_trip() {
+        let mut edge: GraphEvent = add_edge(2, "a", "b", 1.5);
+        if let GraphEvent::AddEdge { attributes, reverse_weight, .. } = &mut edge {
+            attributes.insert("lane".to_string(), AttrValue::Int(2));
+            *reverse_weight = Some(2.5);
+        }
+        let events: Vec<GraphEvent> = vec![add_vertex(1, "a"), add_vertex(1, "b"), edge, GraphEvent::RemoveEdge { time: 3, from: "a".to_string(), to: "b".to_string() }];
+        let mut buffer: Vec<u8> = Vec::new();
+        GraphEvent::write_ndjson(&events, &mut buffer).unwrap();
+        let text: String = String::from_utf8(buffer).unwrap();
+        assert_eq!(text.lines().count(), 4);
+        let read: Vec<GraphEvent> = GraphEvent::read_ndjson(format!("{}\n\n", text).as_bytes()).unwrap();
+        assert_eq!(read, events);
+    }
+}
@@ -0,0 +1,393 @@
+use crate::graph::control::ExecutionControl;
+use crate::graph::index::GraphIndex;
+use crate::graph::trace::{trace_counts, trace_span};
+use crate::graph::{Graph, GraphError};
+use std::collections::{BTreeMap, HashSet, VecDeque};
+
+/// Residual capacities at or below this value are treated as saturated.
+const EPSILON: f64 = 1e-9;
+
+/// A residual flow network used internally by the flow-based algorithms.
+///
+/// Arcs are stored in pairs: arc `2k` is a forward arc and arc `2k + 1` its reverse,
+/// so `arc ^ 1` always gives the partner of `arc`.
+pub(crate) struct FlowNetwork {
+    /// The arcs leaving every node.
+    pub outgoing: Vec<Vec<usize>>,
+    /// The head of every arc.
+    pub heads: Vec<usize>,
+    /// The remaining capacity of every arc.
+    pub residual: Vec<f64>,
+    /// The capacity every arc was created with.
+    pub capacity: Vec<f64>,
+    /// The cost per unit of flow on every arc.
+    pub cost: Vec<f64>,
+}
+
+impl FlowNetwork {
+    /// Creates a network with `n` nodes and no arcs.
+    pub fn new(n: usize) -> FlowNetwork {
+        FlowNetwork {
+            outgoing: vec![Vec::new(); n],
+            heads: Vec::new(),
+            residual: Vec::new(),
+            capacity: Vec::new(),
+            cost: Vec::new(),
+        }
+    }
+
+    /// Returns the number of nodes.
+    pub fn len(&self) -> usize {
+        self.outgoing.len()
+    }
+
+    /// Adds an arc `from -> to` and its zero-capacity reverse, returning the forward arc.
+    pub fn add_arc(&mut self, from: usize, to: usize, capacity: f64, cost: f64) -> usize {
+        let arc: usize = self.heads.len();
+        for (tail, head, cap, c) in [(from, to, capacity, cost), (to, from, 0.0, -cost)] {
+            self.outgoing[tail].push(self.heads.len());
+            self.heads.push(head);
+            self.residual.push(cap);
+            self.capacity.push(cap);
+            self.cost.push(c);
+        }
+        arc
+    }
+
+    /// Returns the flow currently routed through a forward arc.
+    pub fn flow(&self, arc: usize) -> f64 {
+        self.capacity[arc] - self.residual[arc]
+    }
+
+    /// Returns the tail of an arc.
+    pub fn tail(&self, arc: usize) -> usize {
+        self.heads[arc ^ 1]
+    }
+
+    /// Computes the maximum flow from `source` to `sink` with Dinic's algorithm.
+    pub fn max_flow(&mut self, source: usize, sink: usize) -> f64 {
+        // Without limits the run is never stopped.
+        self.max_flow_with(source, sink, &ExecutionControl::new()).unwrap()
+    }
+
+    /// Computes the maximum flow as `max_flow` does, checking `control` after every phase.
+    /// Each phase lengthens the shortest augmenting path, so there are fewer than `n` of them.
+    pub fn max_flow_with(&mut self, source: usize, sink: usize, control: &ExecutionControl) -> Result<f64, GraphError> {
+        if source == sink {
+            return Ok(0.0);
+        }
+        trace_span!("max_flow", nodes = self.len(), arcs = self.heads.len() / 2);
+        let mut total: f64 = 0.0;
+        let mut phase: usize = 0;
+        let mut augmentations: usize = 0;
+        loop {
+            let levels: Vec<Option<usize>> = self.levels(source);
+            if levels[sink].is_none() {
+                trace_counts!("max_flow finished", phases = phase, augmentations = augmentations);
+                return Ok(total);
+            }
+            let mut next_arc: Vec<usize> = vec![0; self.len()];
+            loop {
+                let pushed: f64 = self.augment(source, sink, &levels, &mut next_arc);
+                if pushed <= EPSILON {
+                    break;
+                }
+                total += pushed;
+                augmentations += 1;
+            }
+            phase += 1;
+            control.step(phase, Some(self.len()))?;
+        }
+    }
+
+    /// Breadth-first levels of the residual graph from `source`.
+    fn levels(&self, source: usize) -> Vec<Option<usize>> {
+        let mut levels: Vec<Option<usize>> = vec![None; self.len()];
+        levels[source] = Some(0);
+        let mut queue: VecDeque<usize> = VecDeque::from([source]);
+        while let Some(u) = queue.pop_front() {
+            for &arc in &self.outgoing[u] {
+                let v: usize = self.heads[arc];
+                if self.residual[arc] > EPSILON && levels[v].is_none() {
+                    levels[v] = Some(levels[u].unwrap() + 1);
+                    queue.push_back(v);
+                }
+            }
+        }
+        levels
+    }
+
+    /// Pushes a blocking-flow augmentation along the level graph.
+    ///
+    /// The search keeps the arcs of the current path on an explicit stack instead of
+    /// recursing, so paths as long as the graph do not overflow the call stack.
+    fn augment(&mut self, source: usize, sink: usize, levels: &[Option<usize>], next_arc: &mut [usize]) -> f64 {
+        let mut path: Vec<usize> = Vec::new();
+        let mut u: usize = source;
+        loop {
+            if u == sink {
+                let pushed: f64 = path.iter().map(|&arc| self.residual[arc]).fold(f64::INFINITY, f64::min);
+                for &arc in &path {
+                    self.residual[arc] -= pushed;
+                    self.residual[arc ^ 1] += pushed;
+                }
+                return pushed;
+            }
+            let mut advanced: bool = false;
+            while next_arc[u] < self.outgoing[u].len() {
+                let arc: usize = self.outgoing[u][next_arc[u]];
+                let v: usize = self.heads[arc];
+                if self.residual[arc] > EPSILON && levels[v] == levels[u].map(|l| l + 1) {
+                    path.push(arc);
+                    u = v;
+                    advanced = true;
+                    break;
+                }
+                next_arc[u] += 1;
+            }
+            if !advanced {
+                // `u` is a dead end: retreat and skip the arc that led to it.
+                let Some(arc) = path.pop() else {
+                    return 0.0;
+                };
+                u = self.tail(arc);
+                next_arc[u] += 1;
+            }
+        }
+    }
+
+    /// Sends up to `limit` units of flow from `source` to `sink` at minimum total cost,
+    /// using successive shortest paths (Bellman-Ford on the residual graph).
+    ///
+    /// Returns the amount of flow sent and its total cost, or `Err` with a node on a cycle of
+    /// negative cost in the residual graph, around which the cost has no lower bound.
+    pub fn min_cost_flow(&mut self, source: usize, sink: usize, limit: f64) -> Result<(f64, f64), usize> {
+        trace_span!("min_cost_flow", nodes = self.len(), arcs = self.heads.len() / 2);
+        let mut flow: f64 = 0.0;
+        let mut total_cost: f64 = 0.0;
+        let mut augmentations: usize = 0;
+        let mut relaxations: usize = 0;
+        while flow + EPSILON < limit {
+            let n: usize = self.len();
+            let mut distance: Vec<f64> = vec![f64::INFINITY; n];
+            let mut via: Vec<Option<usize>> = vec![None; n];
+            let mut in_queue: Vec<bool> = vec![false; n];
+            // Without negative cycles no node improves `n` times in one search.
+            let mut improved: Vec<usize> = vec![0; n];
+            let mut queue: VecDeque<usize> = VecDeque::from([source]);
+            distance[source] = 0.0;
+            while let Some(u) = queue.pop_front() {
+                in_queue[u] = false;
+                for &arc in &self.outgoing[u] {
+                    let v: usize = self.heads[arc];
+                    if self.residual[arc] > EPSILON && distance[u] + self.cost[arc] < distance[v] - EPSILON {
+                        distance[v] = distance[u] + self.cost[arc];
+                        via[v] = Some(arc);
+                        relaxations += 1;
+                        improved[v] += 1;
+                        if improved[v] >= n {
+                            return Err(v);
+                        }
+                        if !in_queue[v] {
+                            in_queue[v] = true;
+                            queue.push_back(v);
+                        }
+                    }
+                }
+            }
+            if distance[sink].is_infinite() {
+                break;
+            }
+
+            let mut push: f64 = limit - flow;
+            let mut v: usize = sink;
+            while let Some(arc) = via[v] {
+                push = push.min(self.residual[arc]);
+                v = self.tail(arc);
+            }
+            let mut v: usize = sink;
+            while let Some(arc) = via[v] {
+                self.residual[arc] -= push;
+                self.residual[arc ^ 1] += push;
+                v = self.tail(arc);
+            }
+            flow += push;
+            total_cost += push * distance[sink];
+            augmentations += 1;
+        }
+        trace_counts!("min_cost_flow finished", augmentations = augmentations, relaxations = relaxations);
+        Ok((flow, total_cost))
+    }
+
+    /// Returns which nodes are still reachable from `source` in the residual graph.
+    /// After a maximum flow this is the source side of a minimum cut.
+    pub fn source_side(&self, source: usize) -> Vec<bool> {
+        self.levels(source).iter().map(Option::is_some).collect()
+    }
+}
+
+/// The result of `Graph::max_flow`.
+#[derive(Clone, Debug)]
+pub struct MaxFlow {
+    /// The total flow sent from the source to the sink.
+    pub value: f32,
+    /// The net flow between every pair of vertices that carries some, as `(from, to, flow)`
+    /// in the direction it travels, in key order. Parallel edges are combined.
+    pub flows: Vec<(String, String, f32)>,
+    /// The edges of a minimum cut as `(from, to, capacity)`, each leaving the source side.
+    /// Their capacities sum to `value`.
+    pub cut: Vec<(String, String, f32)>,
+    /// The vertices on the source side of the minimum cut: those the source can still reach
+    /// through edges with spare capacity.
+    pub source_side: HashSet<String>,
+}
+
+impl Graph {
+    /// Computes a maximum flow from `source` to `sink` with Dinic's algorithm, reading edge
+    /// weights as capacities.
+    ///
+    /// An undirected edge can carry flow either way up to its capacity. Self-loops never carry
+    /// flow. The minimum cut is found from the final residual graph, so by max-flow min-cut
+    /// duality its capacity equals the flow value.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - The key of the vertex the flow leaves.
+    /// * `sink` - The key of the vertex the flow reaches.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<MaxFlow, GraphError>` - The flow value with the flow on every edge and a
+    ///   minimum cut, or an error if a vertex does not exist, both keys name the same vertex,
+    ///   or an edge has a negative capacity.
+    pub fn max_flow(&self, source: &str, sink: &str) -> Result<MaxFlow, GraphError> {
+        self.max_flow_with(source, sink, &ExecutionControl::new())
+    }
+
+    /// Computes a maximum flow as `max_flow` does, under a deadline, cancellation token or
+    /// progress callback.
+    ///
+    /// Progress is reported after each phase of Dinic's algorithm, out of at most `n` phases.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - The key of the vertex the flow leaves.
+    /// * `sink` - The key of the vertex the flow reaches.
+    /// * `control` - The limits and hooks for the run.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<MaxFlow, GraphError>` - The flow value with the flow on every edge and a
+    ///   minimum cut, or an error as for `max_flow` or if the run was stopped.
+    pub fn max_flow_with(&self, source: &str, sink: &str, control: &ExecutionControl) -> Result<MaxFlow, GraphError> {
+        let index: GraphIndex = self.index();
+        let s: usize = index.position(source)?;
+        let t: usize = index.position(sink)?;
+        if s == t {
+            return Err(GraphError::InvalidArgument(format!("Vertex {} cannot be both source and sink", source)));
+        }
+
+        let mut network: FlowNetwork = FlowNetwork::new(index.len());
+        for (u, neighbors) in index.adjacency.iter().enumerate() {
+            for &(v, capacity) in neighbors {
+                if capacity < 0.0 {
+                    return Err(GraphError::NegativeWeight(index.keys[u].clone(), index.keys[v].clone()));
+                }
+                if u != v {
+                    network.add_arc(u, v, f64::from(capacity), 0.0);
+                }
+            }
+        }
+        let value: f64 = network.max_flow_with(s, t, control)?;
+
+        // Opposite flows between the same pair cancel, leaving the net flow.
+        let mut net: BTreeMap<(usize, usize), f64> = BTreeMap::new();
+        for arc in (0..network.heads.len()).step_by(2) {
+            let (u, v): (usize, usize) = (network.tail(arc), network.heads[arc]);
+            let flow: f64 = network.flow(arc);
+            if u < v {
+                *net.entry((u, v)).or_default() += flow;
+            } else {
+                *net.entry((v, u)).or_default() -= flow;
+            }
+        }
+        let flows: Vec<(String, String, f32)> = net.into_iter()
+            .filter(|&(_, flow)| flow.abs() > EPSILON)
+            .map(|((u, v), flow)| {
+                let (from, to) = if flow > 0.0 { (u, v) } else { (v, u) };
+                (index.keys[from].clone(), index.keys[to].clone(), flow.abs() as f32)
+            })
+            .collect();
+
+        let side: Vec<bool> = network.source_side(s);
+        let cut: Vec<(String, String, f32)> = (0..network.heads.len())
+            .step_by(2)
+            .filter(|&arc| side[network.tail(arc)] && !side[network.heads[arc]])
+            .map(|arc| (index.keys[network.tail(arc)].clone(), index.keys[network.heads[arc]].clone(), network.capacity[arc] as f32))
+            .collect();
+        let source_side: HashSet<String> = (0..index.len())
+            .filter(|&v| side[v])
+            .map(|v| index.keys[v].clone())
+            .collect();
+
+        Ok(MaxFlow { value: value as f32, flows, cut, source_side })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FlowNetwork, MaxFlow};
+    use crate::graph::testing::graph_from;
+    use crate::graph::{Edge, Graph, GraphError, Vertex};
+
+    #[test]
+    fn min_cost_flow_stops_on_negative_cycle() {
+        let mut network: FlowNetwork = FlowNetwork::new(4);
+        network.add_arc(0, 1, 1.0, 1.0);
+        network.add_arc(1, 2, 1.0, -5.0);
+        network.add_arc(2, 1, 1.0, 1.0);
+        network.add_arc(1, 3, 1.0, 1.0);
+        assert!(network.min_cost_flow(0, 3, 1.0).is_err());
+    }
+
+    #[test]
+    fn max_flow_value_and_cut() {
+        let graph: Graph = graph_from(true, &[
+            ("s", "a", 3.0), ("s", "b", 2.0), ("a", "b", 1.0),
+            ("a", "t", 2.0), ("b", "t", 3.0),
+        ]);
+        let flow: MaxFlow = graph.max_flow("s", "t").unwrap();
+        assert_eq!(flow.value, 5.0);
+        let cut: f32 = flow.cut.iter().map(|&(_, _, capacity)| capacity).sum();
+        assert_eq!(cut, flow.value);
+        assert!(flow.source_side.contains("s"));
+        assert!(!flow.source_side.contains("t"));
+    }
+
+    #[test]
+    fn max_flow_uses_undirected_edges_both_ways() {
+        let graph: Graph = graph_from(false, &[("t", "a", 4.0), ("a", "s", 2.0), ("s", "t", 1.0)]);
+        let flow: MaxFlow = graph.max_flow("s", "t").unwrap();
+        assert_eq!(flow.value, 3.0);
+        assert!(flow.flows.contains(&("a".to_string(), "t".to_string(), 2.0)));
+    }
+
+    #[test]
+    fn max_flow_rejects_same_source_and_sink() {
+        let graph: Graph = graph_from(true, &[("s", "t", 1.0)]);
+        assert!(matches!(graph.max_flow("s", "s"), Err(GraphError::InvalidArgument(_))));
+    }
+
+    #[test]
+    fn max_flow_on_long_path() {
+        let n: usize = 300_000;
+        let mut graph: Graph = Graph::new(true);
+        for i in 0..n {
+            graph.add_vertex(Vertex::new(i.to_string()));
+        }
+        for i in 1..n {
+            graph.add_edge(Edge::new((i - 1).to_string(), i.to_string(), 1.0)).unwrap();
+        }
+        assert_eq!(graph.max_flow("0", &(n - 1).to_string()).unwrap().value, 1.0);
+    }
+}
@@ -0,0 +1,152 @@
+use crate::graph::rng::Rng;
+use crate::graph::{AttrValue, Edge, Graph, GraphError, Vertex};
+use std::collections::BTreeSet;
+
+/// Builds a graph on the vertices `0` to `n - 1` with an edge of weight `1` for every pair.
+fn numbered(n: usize, directed: bool, pairs: impl IntoIterator<Item = (usize, usize)>) -> Graph {
+    let mut graph: Graph = Graph::new(directed);
+    for v in 0..n {
+        graph.add_vertex(Vertex::new(v.to_string()));
+    }
+    for (u, v) in pairs {
+        graph.add_edge(Edge::new(u.to_string(), v.to_string(), 1.0)).unwrap();
+    }
+    graph
+}
+
+impl Graph {
+    /// Generates a random graph in which every pair of vertices is joined independently with
+    /// probability `p` (the Erdős-Rényi `G(n, p)` model).
+    ///
+    /// The vertices are keyed `0` to `n - 1` and every edge has weight `1`. In directed graphs
+    /// both orders of every pair are drawn separately. There are no self-loops.
+    ///
+    /// # Arguments
+    ///
+    /// * `n` - The number of vertices.
+    /// * `p` - The probability of each edge, in `[0, 1]`.
+    /// * `directed` - Whether the graph is directed.
+    /// * `seed` - The random seed; the same seed reproduces the same graph.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Graph, GraphError>` - The graph, or an error if `p` is outside `[0, 1]`.
+    pub fn erdos_renyi(n: usize, p: f32, directed: bool, seed: u64) -> Result<Graph, GraphError> {
+        if !(0.0..=1.0).contains(&p) {
+            return Err(GraphError::InvalidArgument(format!("Edge probability {} is outside [0, 1]", p)));
+        }
+        let mut rng: Rng = Rng::new(seed);
+        let pairs: Vec<(usize, usize)> = (0..n)
+            .flat_map(|u| (0..n).map(move |v| (u, v)))
+            .filter(|&(u, v)| if directed { u != v } else { u < v })
+            .filter(|_| rng.chance(p))
+            .collect();
+        Ok(numbered(n, directed, pairs))
+    }
+
+    /// Generates a random scale-free graph by preferential attachment (the Barabási-Albert
+    /// model).
+    ///
+    /// The vertices are keyed `0` to `n - 1`. The first `m` start without edges; every later
+    /// vertex joins `m` distinct earlier ones, chosen with probability proportional to their
+    /// degree, so the degrees follow a power law. The graph is undirected with edges of
+    /// weight `1`.
+    ///
+    /// # Arguments
+    ///
+    /// * `n` - The number of vertices.
+    /// * `m` - The number of edges each new vertex brings.
+    /// * `seed` - The random seed; the same seed reproduces the same graph.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Graph, GraphError>` - The graph, or an error unless `1 <= m < n`.
+    pub fn barabasi_albert(n: usize, m: usize, seed: u64) -> Result<Graph, GraphError> {
+        if m == 0 || m >= n {
+            return Err(GraphError::InvalidArgument(format!("Need 1 <= m < n, got m = {} and n = {}", m, n)));
+        }
+        let mut rng: Rng = Rng::new(seed);
+        // Every vertex appears once per edge end, so a uniform pick is a degree-weighted one.
+        let mut ends: Vec<usize> = Vec::with_capacity(2 * m * n);
+        let mut pairs: Vec<(usize, usize)> = Vec::with_capacity(m * n);
+        for v in m..n {
+            let mut targets: BTreeSet<usize> = BTreeSet::new();
+            if v == m {
+                targets.extend(0..m);
+            }
+            while targets.len() < m {
+                targets.insert(ends[rng.below(ends.len())]);
+            }
+            for u in targets {
+                pairs.push((u, v));
+                ends.extend([u, v]);
+            }
+        }
+        Ok(numbered(n, false, pairs))
+    }
+
+    /// Generates a random multigraph with a given degree sequence (the configuration model).
+    ///
+    /// The vertices are keyed `0` to `n - 1`, where `n` is the length of `degrees`. Every
+    /// vertex gets one stub per unit of degree, and the stubs are paired uniformly at random,
+    /// so the graph may have self-loops and parallel edges; a self-loop adds two to its
+    /// vertex's degree. The graph is undirected with edges of weight `1`.
+    ///
+    /// # Arguments
+    ///
+    /// * `degrees` - The degree of every vertex.
+    /// * `seed` - The random seed; the same seed reproduces the same graph.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Graph, GraphError>` - The graph, or an error if the degrees have an odd sum.
+    pub fn configuration_model(degrees: &[usize], seed: u64) -> Result<Graph, GraphError> {
+        let mut stubs: Vec<usize> = degrees.iter()
+            .enumerate()
+            .flat_map(|(v, &degree)| std::iter::repeat_n(v, degree))
+            .collect();
+        if !stubs.len().is_multiple_of(2) {
+            return Err(GraphError::InvalidArgument(format!("Degree sum {} is odd", stubs.len())));
+        }
+        let mut rng: Rng = Rng::new(seed);
+        for i in (1..stubs.len()).rev() {
+            stubs.swap(i, rng.below(i + 1));
+        }
+        let pairs: Vec<(usize, usize)> = stubs.chunks(2).map(|pair| (pair[0], pair[1])).collect();
+        Ok(numbered(degrees.len(), false, pairs))
+    }
+
+    /// Builds the complete undirected graph on `n` vertices keyed `0` to `n - 1`, with an edge
+    /// of weight `1` between every pair.
+    pub fn complete(n: usize) -> Graph {
+        numbered(n, false, (0..n).flat_map(|u| ((u + 1)..n).map(move |v| (u, v))))
+    }
+
+    /// Builds an undirected grid of `rows` by `cols` vertices, each joined to the vertices
+    /// directly above, below, left and right of it by an edge of weight `1`.
+    ///
+    /// Vertices are keyed `"row,col"` and store `[row, col]` in their `position` attribute,
+    /// as the geometric graph constructors do.
+    pub fn grid(rows: usize, cols: usize) -> Graph {
+        let key = |row: usize, col: usize| -> String { format!("{},{}", row, col) };
+        let mut graph: Graph = Graph::new(false);
+        for row in 0..rows {
+            for col in 0..cols {
+                let mut vertex: Vertex = Vertex::new(key(row, col));
+                vertex.set_attr("position", AttrValue::Vector(vec![row as f64, col as f64]));
+                graph.add_vertex(vertex);
+            }
+        }
+        for row in 0..rows {
+            for col in 0..cols {
+                if row + 1 < rows {
+                    graph.add_edge(Edge::new(key(row, col), key(row + 1, col), 1.0)).unwrap();
+                }
+                if col + 1 < cols {
+                    graph.add_edge(Edge::new(key(row, col), key(row, col + 1), 1.0)).unwrap();
+                }
+            }
+        }
+        graph
+    }
+}
@@ -0,0 +1,139 @@
+use crate::graph::{AttrValue, Edge, Graph, GraphError, Vertex};
+use std::collections::HashSet;
+
+/// How the distance between two points is measured.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Metric {
+    /// The straight-line distance.
+    Euclidean,
+    /// The sum of the coordinate differences.
+    Manhattan,
+    /// The largest coordinate difference.
+    Chebyshev,
+}
+
+impl Metric {
+    /// Measures the distance between two points of the same dimension.
+    ///
+    /// # Arguments
+    ///
+    /// * `a` - The coordinates of the first point.
+    /// * `b` - The coordinates of the second point.
+    ///
+    /// # Returns
+    ///
+    /// * An `f64` with the distance between the points.
+    pub fn distance(self, a: &[f64], b: &[f64]) -> f64 {
+        let differences = a.iter().zip(b).map(|(x, y)| (x - y).abs());
+        match self {
+            Metric::Euclidean => differences.map(|d| d * d).sum::<f64>().sqrt(),
+            Metric::Manhattan => differences.sum(),
+            Metric::Chebyshev => differences.fold(0.0, f64::max),
+        }
+    }
+}
+
+/// Sorts the points by key and checks that the keys are unique and the coordinates are
+/// finite and of one dimension.
+fn sorted_points(points: &[(String, Vec<f64>)]) -> Result<Vec<&(String, Vec<f64>)>, GraphError> {
+    let mut sorted: Vec<&(String, Vec<f64>)> = points.iter().collect();
+    sorted.sort_by(|a, b| a.0.cmp(&b.0));
+    for pair in sorted.windows(2) {
+        if pair[0].0 == pair[1].0 {
+            return Err(GraphError::InvalidArgument(format!("Vertex {} appears more than once", pair[0].0)));
+        }
+    }
+    if let Some((key, coordinates)) = sorted.iter().find(|(_, coordinates)| coordinates.len() != sorted[0].1.len()) {
+        return Err(GraphError::InvalidArgument(format!("Vertex {} has {} coordinates instead of {}", key, coordinates.len(), sorted[0].1.len())));
+    }
+    if let Some((key, _)) = sorted.iter().find(|(_, coordinates)| coordinates.iter().any(|x| !x.is_finite())) {
+        return Err(GraphError::InvalidArgument(format!("Vertex {} has a coordinate that is not a finite number", key)));
+    }
+    Ok(sorted)
+}
+
+/// Builds an undirected graph with a vertex per point, storing its coordinates in the
+/// `position` attribute, and an edge weighted by distance for every selected pair.
+fn geometric_graph(points: &[&(String, Vec<f64>)], pairs: HashSet<(usize, usize)>, metric: Metric) -> Graph {
+    let mut graph: Graph = Graph::new(false);
+    for point in points {
+        let mut vertex: Vertex = Vertex::new(point.0.clone());
+        vertex.set_attr("position", AttrValue::Vector(point.1.clone()));
+        graph.add_vertex(vertex);
+    }
+    let mut pairs: Vec<(usize, usize)> = pairs.into_iter().collect();
+    pairs.sort();
+    for (u, v) in pairs {
+        let weight: f64 = metric.distance(&points[u].1, &points[v].1);
+        graph.add_edge(Edge::new(points[u].0.clone(), points[v].0.clone(), weight as f32)).unwrap();
+    }
+    graph
+}
+
+impl Graph {
+    /// Builds a k-nearest-neighbor graph from point data.
+    ///
+    /// Every point is joined to the `k` points closest to it, so two points share an edge if
+    /// either is among the other's nearest neighbors. Ties in distance go to the smaller key.
+    /// Every pair of points is compared, which takes quadratic time.
+    ///
+    /// # Arguments
+    ///
+    /// * `points` - The key and coordinates of every point.
+    /// * `k` - How many neighbors each point is joined to.
+    /// * `metric` - How distances between points are measured.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Graph, GraphError>` - An undirected graph whose edges are weighted by distance
+    ///   and whose vertices keep their coordinates in the `position` attribute, or an error if
+    ///   a key repeats or the coordinates are not finite numbers of one dimension.
+    pub fn from_points(points: &[(String, Vec<f64>)], k: usize, metric: Metric) -> Result<Graph, GraphError> {
+        let sorted: Vec<&(String, Vec<f64>)> = sorted_points(points)?;
+        let mut pairs: HashSet<(usize, usize)> = HashSet::new();
+        for u in 0..sorted.len() {
+            let mut others: Vec<(f64, usize)> = (0..sorted.len())
+                .filter(|&v| v != u)
+                .map(|v| (metric.distance(&sorted[u].1, &sorted[v].1), v))
+                .collect();
+            others.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)));
+            for &(_, v) in others.iter().take(k) {
+                pairs.insert((u.min(v), u.max(v)));
+            }
+        }
+        Ok(geometric_graph(&sorted, pairs, metric))
+    }
+
+    /// Builds an ε-radius graph from point data.
+    ///
+    /// Two points share an edge when their distance is at most `radius`. Every pair of points
+    /// is compared, which takes quadratic time.
+    ///
+    /// # Arguments
+    ///
+    /// * `points` - The key and coordinates of every point.
+    /// * `radius` - The largest distance at which two points are joined.
+    /// * `metric` - How distances between points are measured.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Graph, GraphError>` - An undirected graph whose edges are weighted by distance
+    ///   and whose vertices keep their coordinates in the `position` attribute, or an error if
+    ///   the radius is negative, a key repeats or the coordinates are not finite numbers of one
+    ///   dimension.
+    pub fn from_points_within(points: &[(String, Vec<f64>)], radius: f64, metric: Metric) -> Result<Graph, GraphError> {
+        if radius.is_nan() || radius < 0.0 {
+            return Err(GraphError::InvalidArgument(format!("Radius {} is not a non-negative number", radius)));
+        }
+        let sorted: Vec<&(String, Vec<f64>)> = sorted_points(points)?;
+        let mut pairs: HashSet<(usize, usize)> = HashSet::new();
+        for u in 0..sorted.len() {
+            for v in u + 1..sorted.len() {
+                if metric.distance(&sorted[u].1, &sorted[v].1) <= radius {
+                    pairs.insert((u, v));
+                }
+            }
+        }
+        Ok(geometric_graph(&sorted, pairs, metric))
+    }
+}
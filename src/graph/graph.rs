@@ -1,15 +1,98 @@
-use crate::graph::{Vertex, Edge};
-use std::collections::{HashMap, HashSet};
+use crate::graph::cache::QueryCache;
+use crate::graph::csr::Csr;
+use crate::graph::index::GraphIndex;
+use crate::graph::{Vertex, Edge, GraphError};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+
+/// How the weights of several edges joining the same pair of vertices are combined into one value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AggregationPolicy {
+    /// The total weight of the edges.
+    Sum,
+    /// The smallest weight.
+    Min,
+    /// The largest weight.
+    Max,
+    /// The number of edges, ignoring their weights.
+    Count,
+}
+
+impl AggregationPolicy {
+    /// Folds another edge weight into the value aggregated so far.
+    pub(crate) fn combine(self, current: Option<f32>, weight: f32) -> f32 {
+        let weight: f32 = if self == AggregationPolicy::Count { 1.0 } else { weight };
+        match (self, current) {
+            (_, None) => weight,
+            (AggregationPolicy::Sum | AggregationPolicy::Count, Some(total)) => total + weight,
+            (AggregationPolicy::Min, Some(best)) => best.min(weight),
+            (AggregationPolicy::Max, Some(best)) => best.max(weight),
+        }
+    }
+}
+
+/// The order in which matrices, CSR rows and `display` list the vertices.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum VertexOrder {
+    /// Ascending key order.
+    #[default]
+    Sorted,
+    /// The order the vertices were first added in. Vertices inserted directly into
+    /// `vertices` come last, in key order.
+    Insertion,
+}
+
+/// Whether a graph may join a pair of vertices by more than one edge.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum GraphMode {
+    /// Parallel edges and self-loops are allowed, and every one is kept and counted.
+    #[default]
+    Multi,
+    /// `add_edge` rejects self-loops and a second edge between the same pair of vertices,
+    /// in either orientation when the graph is undirected.
+    Simple,
+}
+
+/// What the edge weights of a graph measure, so distance-based algorithms are not run on
+/// similarities by mistake.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum WeightSemantics {
+    /// Nothing has been recorded; the weights are used however an algorithm needs them.
+    #[default]
+    Unspecified,
+    /// Larger weights mean farther apart, as shortest paths expect.
+    Distance,
+    /// Larger weights mean more alike or more strongly connected.
+    Similarity,
+}
 
 /// A struct representing a graph, which can be either directed or undirected.
-/// The graph consists of vertices stored in a `HashMap` and edges connecting them.
+/// The graph consists of vertices stored in a `BTreeMap` and edges connecting them.
+///
+/// Edges refer to their endpoints by key, so the graph holds the only copy of each vertex;
+/// an undirected edge is listed under both of its endpoints.
+#[derive(Clone)]
 pub struct Graph {
     /// A collection of vertices in the graph, where each vertex is indexed by its value.
-    pub vertices: HashMap<String, Vertex>,
+    /// Iteration follows key order, so output built from it is the same on every run.
+    pub vertices: BTreeMap<String, Vertex>,
     /// A boolean indicating if the graph is directed (`true`) or undirected (`false`).
     pub directed: bool,
     /// The number of edges in the graph.
     pub edge_count: usize,
+    /// Advances with every change, so remembered query results can tell they are stale.
+    pub(crate) generation: u64,
+    /// Remembered results of pure queries, once enabled with `enable_cache`.
+    pub(crate) cache: QueryCache,
+    /// Whether parallel edges and self-loops are allowed.
+    pub(crate) mode: GraphMode,
+    /// How matrices and displays order the vertices.
+    pub(crate) vertex_order: VertexOrder,
+    /// What the edge weights measure.
+    pub(crate) semantics: WeightSemantics,
+    /// The generation at which each vertex was first added, for `VertexOrder::Insertion`.
+    pub(crate) arrivals: HashMap<String, u64>,
 }
 
 
@@ -24,10 +107,31 @@ impl Graph {
     ///
     /// * A `Graph` instance with an empty set of vertices.
     pub fn new(directed: bool) -> Graph {
+        Graph::with_mode(directed, GraphMode::Multi)
+    }
+
+    /// Creates a new `Graph` instance that does or does not allow parallel edges and
+    /// self-loops.
+    ///
+    /// # Arguments
+    ///
+    /// * `directed` - A `bool` indicating if the graph should be directed (`true`) or undirected (`false`).
+    /// * `mode` - The `GraphMode`; `Graph::new` uses `GraphMode::Multi`.
+    ///
+    /// # Returns
+    ///
+    /// * A `Graph` instance with an empty set of vertices.
+    pub fn with_mode(directed: bool, mode: GraphMode) -> Graph {
         Graph {
-            vertices: HashMap::new(),
+            vertices: BTreeMap::new(),
             directed,
             edge_count: 0,
+            generation: 0,
+            cache: QueryCache::default(),
+            mode,
+            vertex_order: VertexOrder::Sorted,
+            semantics: WeightSemantics::Unspecified,
+            arrivals: HashMap::new(),
         }
     }
     
@@ -37,7 +141,9 @@ impl Graph {
     ///
     /// * `vertex` - A `Vertex` instance to be added to the graph.
     pub fn add_vertex(&mut self, vertex: Vertex) {
+        self.arrivals.entry(vertex.value.clone()).or_insert(self.generation);
         self.vertices.insert(vertex.value.clone(), vertex);
+        self.generation += 1;
     }
 
     /// Adds an edge between two vertices in the graph.
@@ -46,8 +152,10 @@ impl Graph {
     /// * `edge` - The `Edge` struct connecting two vertices.
     ///
     /// # Returns
-    /// * `Result<(), String>` - Returns `Ok(())` if the edge is added successfully; 
-    /// returns an error if either vertex is missing.
+    /// * `Result<(), GraphError>` - Returns `Ok(())` if the edge is added successfully; 
+    ///   returns an error if either vertex is missing, if the edge has a `reverse_weight`
+    ///   and the graph is directed, or if the graph is `GraphMode::Simple` and the edge is a
+    ///   self-loop or parallel to an existing one.
     ///
     /// # Behavior
     /// * In directed graphs, adds the edge from `vertex1` to `vertex2`.
@@ -55,33 +163,436 @@ impl Graph {
     ///
     /// # Side Effects
     /// * Increments `edge_count` by one.
-    pub fn add_edge(&mut self, edge: Edge) -> Result<(), String> {
-        let vertex1_key: String = edge.vertex1.value.clone();
-        let vertex2_key: String = edge.vertex2.value.clone();
+    pub fn add_edge(&mut self, edge: Edge) -> Result<(), GraphError> {
+        let vertex1_key: String = edge.vertex1.clone();
+        let vertex2_key: String = edge.vertex2.clone();
+        if self.directed && edge.reverse_weight.is_some() {
+            return Err(GraphError::InvalidArgument(format!("Edge ({}, {}) has direction-dependent weights, which need an undirected graph", vertex1_key, vertex2_key)));
+        }
 
-        let vertices: &mut HashMap<String, Vertex> = &mut self.vertices;
+        // Check both endpoints first so an undirected edge is never stored on only one side.
+        if !self.vertices.contains_key(&vertex2_key) {
+            return Err(GraphError::VertexNotFound(vertex2_key.to_string()));
+        }
+        if self.mode == GraphMode::Simple {
+            if vertex1_key == vertex2_key {
+                return Err(GraphError::SelfLoop(vertex1_key.to_string()));
+            }
+            if self.has_edge(&vertex1_key, &vertex2_key) {
+                return Err(GraphError::DuplicateEdge(vertex1_key.to_string(), vertex2_key.to_string()));
+            }
+        }
 
-        let vertex1: &mut Vertex = vertices.get_mut(&vertex1_key).ok_or(format!("Vertex {} does not exist", vertex1_key))?;
+        let vertices: &mut BTreeMap<String, Vertex> = &mut self.vertices;
+
+        let vertex1: &mut Vertex = vertices.get_mut(&vertex1_key).ok_or_else(|| GraphError::VertexNotFound(vertex1_key.to_string()))?;
 
         vertex1.add_edge(edge.clone());
 
         if !self.directed {
-            let vertex2: &mut Vertex = vertices.get_mut(&vertex2_key).ok_or(format!("Vertex {} does not exist", vertex2_key))?;
+            let vertex2: &mut Vertex = vertices.get_mut(&vertex2_key).unwrap();
             vertex2.add_edge(edge);
         }
 
         self.edge_count += 1;
+        self.generation += 1;
         Ok(())
     }
 
+    /// Returns a graph with no vertices and the same direction, `GraphMode`, `VertexOrder` and
+    /// weight semantics as this one, for building graphs derived from it.
+    pub(crate) fn empty_like(&self) -> Graph {
+        let mut graph: Graph = Graph::with_mode(self.directed, self.mode);
+        graph.vertex_order = self.vertex_order;
+        graph.semantics = self.semantics;
+        graph
+    }
+
+    /// Adds a copy of a vertex of `source`, with its attributes but without its edges.
+    ///
+    /// Copying the vertices in `source.vertex_keys()` order keeps their insertion order.
+    pub(crate) fn add_vertex_like(&mut self, source: &Graph, key: &str) {
+        let mut copy: Vertex = Vertex::new(key.to_string());
+        copy.attributes = source.vertices[key].attributes.clone();
+        self.add_vertex(copy);
+    }
+
+    /// Removes a vertex and every edge incident to it.
+    ///
+    /// Incoming edges are stored on the other endpoint, so every vertex is scanned; this takes
+    /// time proportional to the size of the graph.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key of the vertex to remove.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vertex, GraphError>` - The removed vertex with the edges it stored, or an
+    ///   error if it does not exist.
+    ///
+    /// # Side Effects
+    /// * Decreases `edge_count` by the number of edges removed.
+    pub fn remove_vertex(&mut self, key: &str) -> Result<Vertex, GraphError> {
+        let vertex: Vertex = self.vertices.remove(key).ok_or_else(|| GraphError::VertexNotFound(key.to_string()))?;
+        self.arrivals.remove(&vertex.value);
+        let self_loops: usize = vertex.edges.iter().filter(|edge| edge.vertex1 == edge.vertex2).count();
+        let mut removed: usize = if self.directed {
+            vertex.edges.len()
+        } else {
+            // An undirected self-loop is stored twice in its vertex's list.
+            vertex.edges.len() - self_loops / 2
+        };
+
+        for other in self.vertices.values_mut() {
+            let before: usize = other.edges.len();
+            other.edges.retain(|edge| edge.vertex1 != key && edge.vertex2 != key);
+            // Undirected edges to the vertex were already counted from its own list.
+            if self.directed {
+                removed += before - other.edges.len();
+            }
+        }
+
+        self.edge_count -= removed;
+        self.generation += 1;
+        Ok(vertex)
+    }
+
+    /// Removes one edge between two vertices.
+    ///
+    /// In undirected graphs the edge may have been added in either orientation, and its copy
+    /// stored on the other endpoint is removed too. When there are parallel edges, the one
+    /// added first is removed.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - The key of the vertex the edge starts at.
+    /// * `to` - The key of the vertex the edge ends at.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Edge, GraphError>` - The removed edge, or an error if either vertex or the
+    ///   edge does not exist.
+    ///
+    /// # Side Effects
+    /// * Decrements `edge_count` by one.
+    pub fn remove_edge(&mut self, from: &str, to: &str) -> Result<Edge, GraphError> {
+        if !self.vertices.contains_key(to) {
+            return Err(GraphError::VertexNotFound(to.to_string()));
+        }
+        let directed: bool = self.directed;
+        let source: &mut Vertex = self.vertices.get_mut(from).ok_or_else(|| GraphError::VertexNotFound(from.to_string()))?;
+        let position: usize = source.edges.iter()
+            .position(|edge| {
+                (edge.vertex1 == from && edge.vertex2 == to)
+                    || (!directed && edge.vertex1 == to && edge.vertex2 == from)
+            })
+            .ok_or_else(|| GraphError::EdgeNotFound(from.to_string(), to.to_string()))?;
+        let edge: Edge = source.edges.remove(position);
+
+        if !directed {
+            let target: &mut Vertex = self.vertices.get_mut(to).unwrap();
+            let mirror: Option<usize> = target.edges.iter().position(|other| {
+                other.vertex1 == edge.vertex1
+                    && other.vertex2 == edge.vertex2
+                    && other.weight == edge.weight
+                    && other.reverse_weight == edge.reverse_weight
+            });
+            if let Some(mirror) = mirror {
+                target.edges.remove(mirror);
+            }
+        }
+
+        self.edge_count -= 1;
+        self.generation += 1;
+        Ok(edge)
+    }
+
+    /// Checks whether the graph has a vertex with the given key.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key of the vertex.
+    ///
+    /// # Returns
+    ///
+    /// * A `bool` that is `true` if the vertex exists.
+    pub fn has_vertex(&self, key: &str) -> bool {
+        self.vertices.contains_key(key)
+    }
+
+    /// Checks whether an edge leads from one vertex to another.
+    ///
+    /// In undirected graphs the edge may have been added in either orientation.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - The key of the vertex the edge starts at.
+    /// * `to` - The key of the vertex the edge ends at.
+    ///
+    /// # Returns
+    ///
+    /// * A `bool` that is `true` if the edge exists; `false` also when either vertex is missing.
+    pub fn has_edge(&self, from: &str, to: &str) -> bool {
+        self.vertices.get(from).is_some_and(|vertex| {
+            vertex.edges.iter().any(|edge| {
+                (edge.vertex1 == from && edge.vertex2 == to)
+                    || (!self.directed && edge.vertex1 == to && edge.vertex2 == from)
+            })
+        })
+    }
+
+    /// Returns the vertices adjacent to a vertex.
+    ///
+    /// In directed graphs these are the targets of its outgoing edges. Each neighbor is
+    /// returned once, however many parallel edges lead to it, in the order the first of
+    /// those edges was added. A vertex with a self-loop is its own neighbor.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key of the vertex.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<impl Iterator<Item = &Vertex>, GraphError>` - The neighboring vertices, or an
+    ///   error if the vertex does not exist.
+    pub fn neighbors(&self, key: &str) -> Result<impl Iterator<Item = &Vertex>, GraphError> {
+        let vertex: &Vertex = self.vertices.get(key).ok_or_else(|| GraphError::VertexNotFound(key.to_string()))?;
+        let mut seen: HashSet<&String> = HashSet::new();
+        let neighbors: Vec<&Vertex> = vertex.edges.iter()
+            .map(|edge| if edge.vertex1 == key { &edge.vertex2 } else { &edge.vertex1 })
+            .filter(|&neighbor| seen.insert(neighbor))
+            .filter_map(|neighbor| self.vertices.get(neighbor))
+            .collect();
+        Ok(neighbors.into_iter())
+    }
+
+    /// Returns the degree of a vertex: the number of edge endpoints at it.
+    ///
+    /// In undirected graphs a self-loop counts twice. In directed graphs the degree is the sum
+    /// of `in_degree` and `out_degree`.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key of the vertex.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<usize, GraphError>` - The degree, or an error if the vertex does not exist.
+    pub fn degree(&self, key: &str) -> Result<usize, GraphError> {
+        if self.directed {
+            Ok(self.in_degree(key)? + self.out_degree(key)?)
+        } else {
+            self.out_degree(key)
+        }
+    }
+
+    /// Returns the number of edges ending at a vertex.
+    ///
+    /// Incoming edges are stored on their source, so every edge of a directed graph is
+    /// scanned. In undirected graphs this is the same as `degree`.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key of the vertex.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<usize, GraphError>` - The in-degree, or an error if the vertex does not exist.
+    pub fn in_degree(&self, key: &str) -> Result<usize, GraphError> {
+        if !self.directed {
+            return self.out_degree(key);
+        }
+        if !self.vertices.contains_key(key) {
+            return Err(GraphError::VertexNotFound(key.to_string()));
+        }
+        Ok(self.vertices.values()
+            .flat_map(|vertex| &vertex.edges)
+            .filter(|edge| edge.vertex2 == key)
+            .count())
+    }
+
+    /// Returns the number of edges starting at a vertex.
+    ///
+    /// In undirected graphs this is the same as `degree`.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key of the vertex.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<usize, GraphError>` - The out-degree, or an error if the vertex does not exist.
+    pub fn out_degree(&self, key: &str) -> Result<usize, GraphError> {
+        let vertex: &Vertex = self.vertices.get(key).ok_or_else(|| GraphError::VertexNotFound(key.to_string()))?;
+        Ok(vertex.edges.len())
+    }
+
+    /// Returns every stored edge once, in key order, with its direction-dependent weight.
+    pub(crate) fn stored_edges(&self) -> Vec<&Edge> {
+        let mut edges: Vec<&Edge> = Vec::with_capacity(self.edge_count);
+        for (key, vertex) in &self.vertices {
+            self.push_owned_edges(key, vertex, &mut edges);
+        }
+        edges
+    }
+
+    /// Appends the edges listed under `key` that `stored_edges` attributes to it: all of them
+    /// in directed graphs, and in undirected graphs those starting there, with each self-loop
+    /// once.
+    fn push_owned_edges<'a>(&self, key: &String, vertex: &'a Vertex, edges: &mut Vec<&'a Edge>) {
+        let mut skip_loop: bool = false;
+        for edge in &vertex.edges {
+            if self.directed || (edge.vertex1 == *key && edge.vertex2 != *key) {
+                edges.push(edge);
+            } else if edge.vertex1 == edge.vertex2 {
+                // An undirected self-loop is stored twice in its vertex's list.
+                if !skip_loop {
+                    edges.push(edge);
+                }
+                skip_loop = !skip_loop;
+            }
+        }
+    }
+
+    /// Returns every edge as `(from, to, forward weight, backward weight)` in a form that does
+    /// not depend on how it was added: undirected edges run from the smaller key, and the
+    /// list is sorted. A directed edge's backward weight repeats its forward one.
+    fn normalized_edges(&self) -> Vec<(&String, &String, f32, f32)> {
+        let mut edges: Vec<(&String, &String, f32, f32)> = self.stored_edges()
+            .into_iter()
+            .map(|edge| {
+                let (from, to): (&String, &String) = if !self.directed && edge.vertex2 < edge.vertex1 {
+                    (&edge.vertex2, &edge.vertex1)
+                } else {
+                    (&edge.vertex1, &edge.vertex2)
+                };
+                let backward: f32 = if self.directed { edge.weight } else { edge.weight_from(to) };
+                (from, to, edge.weight_from(from), backward)
+            })
+            .collect();
+        let weights = |a: &f32, b: &f32| a.partial_cmp(b).unwrap_or(Ordering::Equal);
+        edges.sort_by(|a, b| (a.0, a.1).cmp(&(b.0, b.1)).then_with(|| weights(&a.2, &b.2)).then_with(|| weights(&a.3, &b.3)));
+        edges
+    }
+
+    /// Returns whether the graph allows parallel edges and self-loops.
+    pub fn mode(&self) -> GraphMode {
+        self.mode
+    }
+
+    /// Switches the graph between allowing and rejecting parallel edges and self-loops.
+    ///
+    /// # Arguments
+    ///
+    /// * `mode` - The new `GraphMode`.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), GraphError>` - `Ok(())` if the mode was changed, or an error naming a
+    ///   self-loop or parallel edge that `GraphMode::Simple` would not allow; the mode is then
+    ///   left as it was.
+    pub fn set_mode(&mut self, mode: GraphMode) -> Result<(), GraphError> {
+        if mode == GraphMode::Simple {
+            let mut pairs: HashSet<(&String, &String)> = HashSet::new();
+            for edge in self.stored_edges() {
+                let (a, b) = (&edge.vertex1, &edge.vertex2);
+                if a == b {
+                    return Err(GraphError::SelfLoop(a.to_string()));
+                }
+                let pair: (&String, &String) = if self.directed || a < b { (a, b) } else { (b, a) };
+                if !pairs.insert(pair) {
+                    return Err(GraphError::DuplicateEdge(a.to_string(), b.to_string()));
+                }
+            }
+        }
+        self.mode = mode;
+        Ok(())
+    }
+
+    /// Returns the transpose of the graph: the same vertices with every edge reversed.
+    ///
+    /// Vertices and edges keep their attributes and edges their weights, and parallel edges and
+    /// self-loops are kept. An undirected graph is its own transpose, so it is cloned.
+    ///
+    /// # Returns
+    ///
+    /// * A `Graph` in which every edge `(u, v)` of this graph becomes `(v, u)`.
+    pub fn transpose(&self) -> Graph {
+        if !self.directed {
+            return self.clone();
+        }
+        let mut graph: Graph = self.empty_like();
+        for key in self.vertex_keys() {
+            graph.add_vertex_like(self, &key);
+        }
+        for edge in self.stored_edges() {
+            let mut reversed: Edge = Edge::new(edge.vertex2.clone(), edge.vertex1.clone(), edge.weight);
+            reversed.attributes = edge.attributes.clone();
+            graph.add_edge(reversed).unwrap();
+        }
+        graph
+    }
+
+    /// Sets the order in which matrices, CSR rows and `display` list the vertices.
+    ///
+    /// # Arguments
+    ///
+    /// * `order` - The `VertexOrder` to use; new graphs use `VertexOrder::Sorted`.
+    pub fn set_vertex_order(&mut self, order: VertexOrder) {
+        self.vertex_order = order;
+    }
+
+    /// Returns the order in which matrices, CSR rows and `display` list the vertices.
+    pub fn vertex_order(&self) -> VertexOrder {
+        self.vertex_order
+    }
+
+    /// Records what the edge weights measure.
+    ///
+    /// Distance-based algorithms such as `shortest_path` refuse graphs marked
+    /// `WeightSemantics::Similarity`; `as_distance_graph` converts them.
+    ///
+    /// # Arguments
+    ///
+    /// * `semantics` - The `WeightSemantics` of the weights; new graphs are
+    ///   `WeightSemantics::Unspecified`.
+    pub fn set_weight_semantics(&mut self, semantics: WeightSemantics) {
+        self.semantics = semantics;
+    }
+
+    /// Returns what the edge weights have been recorded to measure.
+    pub fn weight_semantics(&self) -> WeightSemantics {
+        self.semantics
+    }
+
+    /// Returns the vertex keys in the graph's `VertexOrder`.
+    ///
+    /// Position `i` is the key of row `i` of `adjacency_matrix` and `incidence_matrix`, and
+    /// of column `i` of the adjacency matrix.
+    ///
+    /// # Returns
+    ///
+    /// * A `Vec<String>` with every vertex key once.
+    pub fn vertex_keys(&self) -> Vec<String> {
+        let mut keys: Vec<String> = self.vertices.keys().cloned().collect();
+        if self.vertex_order == VertexOrder::Insertion {
+            // The sort is stable, so unrecorded vertices keep their key order at the end.
+            keys.sort_by_key(|key| self.arrivals.get(key).copied().unwrap_or(u64::MAX));
+        }
+        keys
+    }
+
     /// Displays the graph by printing each vertex and its connected edges.
     ///
     /// The output shows each vertex key, followed by a list of vertices it is connected to.
+    /// Vertices are printed in the graph's `VertexOrder`.
     pub fn display(&self) {
         println!("Graph (Directed: {}):", self.directed);
-        for (vertex_key, vertex) in &self.vertices {
+        for vertex_key in self.vertex_keys() {
+            let vertex: &Vertex = &self.vertices[&vertex_key];
             let edges: Vec<String> = vertex.edges.iter()
-                .map(|e| format!("({}, {}) (weight: {})", e.vertex1.value, e.vertex2.value, e.weight))
+                .map(|e| format!("({}, {}) (weight: {})", e.vertex1, e.vertex2, e.weight))
                 .collect();
             println!("{}: {:?}", vertex_key, edges);
         }
@@ -89,39 +600,43 @@ impl Graph {
 
     /// Creates an adjacency matrix for the graph.
     ///
+    /// Rows and columns follow `vertex_keys`. When several edges join
+    /// the same pair of vertices, their weights are combined with `policy`;
+    /// `AggregationPolicy::Count` gives the multiplicity of every pair.
+    ///
+    /// # Arguments
+    ///
+    /// * `policy` - How to combine the weights of parallel edges.
+    ///
     /// # Returns
     ///
-    /// A 2D `Vec<Vec<Option<u32>>>` representing the adjacency matrix. `None` means no edge exists,
-    /// and `Some(weight)` contains the weight of an edge between vertices.
-    pub fn adjacency_matrix(&self) -> Vec<Vec<Option<f32>>> {
-        let mut index_map: HashMap<&String, usize> = HashMap::new();
-        let mut index: usize = 0;
-        for vertex in self.vertices.keys() {
-            index_map.insert(vertex, index);
-            index += 1;
-        }
-
-        let size: usize = self.vertices.len();
+    /// A 2D `Vec<Vec<Option<f32>>>` representing the adjacency matrix. `None` means no edge exists,
+    /// and `Some(weight)` contains the combined weight of the edges between vertices.
+    pub fn adjacency_matrix(&self, policy: AggregationPolicy) -> Vec<Vec<Option<f32>>> {
+        let index: GraphIndex = self.ordered_index();
+        let size: usize = index.len();
         let mut matrix: Vec<Vec<Option<f32>>> = vec![vec![None; size]; size];
 
-        for vertex in self.vertices.values() {
-            for edge in &vertex.edges {
-                let i: usize = *index_map.get(&edge.vertex1.value).unwrap();
-                let j: usize = *index_map.get(&edge.vertex2.value).unwrap();
-                matrix[i][j] = Some(edge.weight);
-
-                if !self.directed {
-                    matrix[j][i] = Some(edge.weight);
+        for (i, neighbors) in index.adjacency.iter().enumerate() {
+            // An undirected self-loop appears twice in its vertex's list, once per endpoint.
+            let mut loop_seen: bool = false;
+            for &(j, weight) in neighbors {
+                if i == j && !self.directed {
+                    loop_seen = !loop_seen;
+                    if !loop_seen {
+                        continue;
+                    }
                 }
+                matrix[i][j] = Some(policy.combine(matrix[i][j], weight));
             }
         }
 
         matrix
     }
 
-    /// Displays the graph's adjacency matrix.
+    /// Displays the graph's adjacency matrix, summing the weights of parallel edges.
     pub fn display_adjacency_matrix(&self) {
-        let matrix: Vec<Vec<Option<f32>>> = self.adjacency_matrix();
+        let matrix: Vec<Vec<Option<f32>>> = self.adjacency_matrix(AggregationPolicy::Sum);
 
         println!("Adjacency Matrix:");
         for row in &matrix {
@@ -137,52 +652,42 @@ impl Graph {
 
     /// Creates an incidence matrix for the graph.
     ///
+    /// Rows follow `vertex_keys`, and there is one column per edge, parallel edges included,
+    /// in the order the edges are met row by row.
+    ///
     /// # Returns
     ///
     /// A 2D `Vec<Vec<f32>>` representing the incidence matrix.
-    /// For undirected graphs, entries contain the edge weight (or `1` if unweighted).
-    /// For directed graphs, entries contain `weight` for start vertices and `-weight` for end vertices.
+    /// For undirected graphs, entries contain the edge weight, doubled for a self-loop.
+    /// For directed graphs, entries contain `weight` for start vertices and `-weight` for end
+    /// vertices, so a self-loop's column is all zeros.
     pub fn incidence_matrix(&self) -> Vec<Vec<f32>> {
+        let keys: Vec<String> = self.vertex_keys();
         let mut vertex_index: HashMap<&String, usize> = HashMap::new();
-        let mut index: usize = 0;
-        for vertex in self.vertices.keys() {
+        for (index, vertex) in keys.iter().enumerate() {
             vertex_index.insert(vertex, index);
-            index += 1;
         }
-    
-        let num_vertices: usize = self.vertices.len();
-        let mut edges_seen: HashSet<(String, String)> = HashSet::new();
-        let mut edges: Vec<(String, String, f32)> = Vec::new();
-    
-        for vertex in self.vertices.values() {
-            for edge in &vertex.edges {
-                let (v1, v2) = (edge.vertex1.value.clone(), edge.vertex2.value.clone());
-                if !edges_seen.contains(&(v1.clone(), v2.clone())) && !edges_seen.contains(&(v2.clone(), v1.clone())) {
-                    edges_seen.insert((v1.clone(), v2.clone()));
-                    edges.push((v1, v2, edge.weight));
-                }
-            }
+
+        let mut edges: Vec<&Edge> = Vec::with_capacity(self.edge_count);
+        for key in &keys {
+            self.push_owned_edges(key, &self.vertices[key], &mut edges);
         }
-    
-        let num_edges: usize = edges.len();
-        let mut matrix: Vec<Vec<f32>> = vec![vec![0.0; num_edges]; num_vertices];
-    
-        for (edge_index, (v1, v2, weight)) in edges.iter().enumerate() {
-            let i: usize = *vertex_index.get(v1).unwrap();
-            let j: usize = *vertex_index.get(v2).unwrap();
-    
+
+        let mut matrix: Vec<Vec<f32>> = vec![vec![0.0; edges.len()]; keys.len()];
+        for (edge_index, edge) in edges.iter().enumerate() {
+            let i: usize = vertex_index[&edge.vertex1];
+            let j: usize = vertex_index[&edge.vertex2];
+            matrix[i][edge_index] += edge.weight;
             if self.directed {
-                matrix[i][edge_index] = weight.clone();
-                matrix[j][edge_index] = -weight;
+                matrix[j][edge_index] -= edge.weight;
             } else {
-                matrix[i][edge_index] = weight.clone();
-                matrix[j][edge_index] = weight.clone();
+                matrix[j][edge_index] += edge.weight;
             }
         }
-    
+
         matrix
     }
-    
+
     /// Displays the graph's incidence matrix.
     pub fn display_incidence_matrix(&self) {
         let matrix: Vec<Vec<f32>> = self.incidence_matrix();
@@ -196,60 +701,130 @@ impl Graph {
         }
     }
 
-    /// Generates the graph's Compressed Sparse Row _(CSR)_ representation with `adjacents` and `indices`.
-    pub fn csr_representation(&self) -> (Vec<Option<String>>, Vec<usize>) {
-        let edge_count: usize = self.edge_count;
-        let mut adjacents: Vec<Option<String>> = Vec::with_capacity(2 * edge_count + 1);
-        let mut indices: Vec<usize> = vec![0; self.vertices.len() + 1];
-    
-        let mut vertex_indices: HashMap<&String, usize> = HashMap::new();
-        let mut index: usize = 0;
-        for (vertex_key, _) in &self.vertices {
-            vertex_indices.insert(vertex_key, index);
-            index += 1;
-        }
-    
-        for (vertex_key, vertex) in &self.vertices {
-            let vertex_index: usize = vertex_indices[&vertex_key];
-    
-            for edge in &vertex.edges {
-                let neighbor_value: &String = if &edge.vertex1.value == vertex_key {
-                    &edge.vertex2.value
-                } else {
-                    &edge.vertex1.value
-                };
-    
-                adjacents.push(Some(neighbor_value.clone()));
-                indices[vertex_index + 1] += 1;
+    /// Generates the graph's Compressed Sparse Row _(CSR)_ representation.
+    ///
+    /// Rows follow `vertex_keys`, and each row lists its edges in the
+    /// order they were added, with the cost of travelling along them from the row's vertex.
+    /// `Graph::from_csr` rebuilds the graph from the result.
+    ///
+    /// # Returns
+    ///
+    /// * A `Csr` with the vertex keys, row offsets, column indices and weights.
+    pub fn csr_representation(&self) -> Csr {
+        let index: GraphIndex = self.ordered_index();
+        let mut row_offsets: Vec<usize> = Vec::with_capacity(index.len() + 1);
+        row_offsets.push(0);
+        let mut column_indices: Vec<usize> = Vec::new();
+        let mut weights: Vec<f32> = Vec::new();
+        for neighbors in &index.adjacency {
+            for &(v, weight) in neighbors {
+                column_indices.push(v);
+                weights.push(weight);
             }
+            row_offsets.push(column_indices.len());
         }
-    
-        for i in 1..indices.len() {
-            indices[i] += indices[i - 1];
-        }
-    
-        adjacents.push(None); 
-
-        (adjacents, indices)
+        Csr { keys: index.keys, row_offsets, column_indices, weights, directed: self.directed }
     }
 
     /// Displays the Compressed Sparse Row _(CSR)_ representation of the graph.
     ///
-    /// * Prints `Indices`, showing the starting positions of each vertex's neighbors in `Adjacents`.
-    /// * Prints `Adjacents`, displaying each vertex's neighbors as a list, with `None` for empty slots.
+    /// * Prints `Keys`, the vertex of every row and column.
+    /// * Prints `Row offsets`, where each row's entries start.
+    /// * Prints `Column indices` and `Weights`, the entries of every row in turn.
     pub fn display_csr_representation(&self) {
-        let (adjacents, indices) = self.csr_representation();
-    
-        println!("Indices: {:?}", indices);
-    
-        let adjacents_display: String = adjacents.iter()
-        .map(|neighbor: &Option<String>| match neighbor {
-            Some(value) => value.clone(),
-            None => "None".to_string(),  
-        })
-        .collect::<Vec<String>>()
-        .join(", "); // Join with a comma and space
+        let csr: Csr = self.csr_representation();
+        println!("Keys: {:?}", csr.keys);
+        println!("Row offsets: {:?}", csr.row_offsets);
+        println!("Column indices: {:?}", csr.column_indices);
+        println!("Weights: {:?}", csr.weights);
+    }
+}
+
+/// Graphs are equal when they have the same direction, the same vertex keys and the same
+/// edges with the same weights, counting parallel edges. An undirected edge matches however
+/// its endpoints were ordered when added. Attributes, the `GraphMode`, the `VertexOrder` and
+/// the weight semantics are not compared; see `Graph::is_isomorphic_to` for comparing
+/// structure regardless of keys.
+impl PartialEq for Graph {
+    fn eq(&self, other: &Self) -> bool {
+        self.directed == other.directed
+            && self.edge_count == other.edge_count
+            && self.vertices.keys().eq(other.vertices.keys())
+            && self.normalized_edges() == other.normalized_edges()
+    }
+}
+
+/// Shows the direction, the vertex keys and the edges in the order `PartialEq` compares
+/// them, so failed equality assertions point at the difference.
+impl fmt::Debug for Graph {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let arrow: &str = if self.directed { "->" } else { "--" };
+        let edges: Vec<String> = self.normalized_edges()
+            .into_iter()
+            .map(|(from, to, forward, backward)| {
+                if self.directed || from == to || forward == backward {
+                    format!("{} {} {} ({})", from, arrow, to, forward)
+                } else {
+                    format!("{} {} {} ({}, back {})", from, arrow, to, forward, backward)
+                }
+            })
+            .collect();
+        f.debug_struct("Graph")
+            .field("directed", &self.directed)
+            .field("vertices", &self.vertices.keys().map(ToString::to_string).collect::<Vec<String>>())
+            .field("edges", &edges)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::graph::testing::graph_from;
+    use crate::graph::{Edge, Graph, Vertex};
+
+    #[test]
+    fn equality_ignores_insertion_order_and_undirected_orientation() {
+        let first: Graph = graph_from(false, &[("A", "B", 1.0), ("B", "C", 2.0)]);
+        let second: Graph = graph_from(false, &[("C", "B", 2.0), ("B", "A", 1.0)]);
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn equality_respects_direction() {
+        let undirected: Graph = graph_from(false, &[("A", "B", 1.0)]);
+        let directed: Graph = graph_from(true, &[("A", "B", 1.0)]);
+        assert_ne!(undirected, directed);
+        assert_ne!(directed, graph_from(true, &[("B", "A", 1.0)]));
+    }
+
+    #[test]
+    fn equality_counts_parallel_edges() {
+        let single: Graph = graph_from(false, &[("A", "B", 1.0)]);
+        let double: Graph = graph_from(false, &[("A", "B", 1.0), ("B", "A", 1.0)]);
+        assert_ne!(single, double);
+        assert_eq!(double, graph_from(false, &[("A", "B", 1.0), ("A", "B", 1.0)]));
+        assert_ne!(double, graph_from(false, &[("A", "B", 1.0), ("A", "B", 2.0)]));
+    }
+
+    #[test]
+    fn equality_compares_asymmetric_weights_by_direction() {
+        let mut uphill: Edge = Edge::new("A".to_string(), "B".to_string(), 1.0);
+        uphill.reverse_weight = Some(3.0);
+        let mut downhill: Edge = Edge::new("B".to_string(), "A".to_string(), 3.0);
+        downhill.reverse_weight = Some(1.0);
+        let mut swapped: Edge = Edge::new("A".to_string(), "B".to_string(), 3.0);
+        swapped.reverse_weight = Some(1.0);
 
-        println!("Adjacents: [{}]", adjacents_display);
+        let graphs: Vec<Graph> = [uphill, downhill, swapped].into_iter()
+            .map(|edge| {
+                let mut graph: Graph = Graph::new(false);
+                graph.add_vertex(Vertex::new("A".to_string()));
+                graph.add_vertex(Vertex::new("B".to_string()));
+                graph.add_edge(edge).unwrap();
+                graph
+            })
+            .collect();
+        assert_eq!(graphs[0], graphs[1]);
+        assert_ne!(graphs[0], graphs[2]);
     }
 }
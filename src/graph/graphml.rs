@@ -0,0 +1,493 @@
+use crate::graph::attribute::ExportAttributes;
+use crate::graph::{AttrValue, Edge, Graph, GraphError, Vertex};
+use std::collections::{BTreeMap, HashMap};
+use std::io::{self, Write};
+
+/// Escapes text for use in XML content and attribute values.
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// Replaces XML entity and character references with the text they stand for.
+fn unescape(text: &str) -> Result<String, GraphError> {
+    let mut result: String = String::with_capacity(text.len());
+    let mut rest: &str = text;
+    while let Some(start) = rest.find('&') {
+        result.push_str(&rest[..start]);
+        let end: usize = rest[start..].find(';').ok_or_else(|| GraphError::Parse(format!("Unterminated reference in {}", text)))? + start;
+        let reference: &str = &rest[start + 1..end];
+        let character: char = match reference {
+            "lt" => '<',
+            "gt" => '>',
+            "amp" => '&',
+            "quot" => '"',
+            "apos" => '\'',
+            _ => {
+                let code: Option<u32> = match reference.strip_prefix("#x").or(reference.strip_prefix("#X")) {
+                    Some(hex) => u32::from_str_radix(hex, 16).ok(),
+                    None => reference.strip_prefix('#').and_then(|decimal| decimal.parse().ok()),
+                };
+                code.and_then(char::from_u32).ok_or_else(|| GraphError::Parse(format!("Unknown reference &{};", reference)))?
+            }
+        };
+        result.push(character);
+        rest = &rest[end + 1..];
+    }
+    result.push_str(rest);
+    Ok(result)
+}
+
+/// The GraphML type name for an attribute value.
+fn type_name(value: &AttrValue) -> &'static str {
+    match value {
+        AttrValue::Bool(_) => "boolean",
+        AttrValue::Int(_) => "long",
+        AttrValue::Float(_) => "double",
+        AttrValue::Text(_) | AttrValue::Vector(_) => "string",
+    }
+}
+
+/// Gives every attribute name one GraphML type; names used with several types are strings.
+fn attribute_types<'a>(attributes: impl Iterator<Item = (&'a String, &'a AttrValue)>) -> BTreeMap<&'a String, &'static str> {
+    let mut types: BTreeMap<&String, &str> = BTreeMap::new();
+    for (name, value) in attributes {
+        let kind: &str = type_name(value);
+        let known: &mut &str = types.entry(name).or_insert(kind);
+        if *known != kind {
+            *known = "string";
+        }
+    }
+    types
+}
+
+/// Reads an attribute value written as GraphML data of the given type.
+fn parse_value(text: &str, kind: &str) -> Result<AttrValue, GraphError> {
+    let trimmed: &str = text.trim();
+    let invalid = || GraphError::Parse(format!("{} is not a valid {}", trimmed, kind));
+    match kind {
+        "boolean" => match trimmed.to_ascii_lowercase().as_str() {
+            "true" | "1" => Ok(AttrValue::Bool(true)),
+            "false" | "0" => Ok(AttrValue::Bool(false)),
+            _ => Err(invalid()),
+        },
+        "int" | "long" => trimmed.parse().map(AttrValue::Int).map_err(|_| invalid()),
+        "float" | "double" => trimmed.parse().map(AttrValue::Float).map_err(|_| invalid()),
+        _ => Ok(AttrValue::Text(text.to_string())),
+    }
+}
+
+/// A piece of an XML document.
+enum Token {
+    /// An opening tag, with its attributes and whether it closes itself.
+    Start { name: String, attributes: HashMap<String, String>, empty: bool },
+    /// A closing tag.
+    End(String),
+    /// Character data between tags.
+    Text(String),
+}
+
+/// Strips a namespace prefix from an element or attribute name.
+fn local_name(name: &str) -> &str {
+    name.rsplit(':').next().unwrap_or(name)
+}
+
+/// Parses the inside of an opening tag, such as `node id="a" /`.
+fn parse_tag(content: &str) -> Result<Token, GraphError> {
+    let (content, empty): (&str, bool) = match content.strip_suffix('/') {
+        Some(content) => (content, true),
+        None => (content, false),
+    };
+    let content: &str = content.trim();
+    let name_end: usize = content.find(char::is_whitespace).unwrap_or(content.len());
+    let name: String = local_name(&content[..name_end]).to_string();
+    let mut attributes: HashMap<String, String> = HashMap::new();
+    let mut rest: &str = content[name_end..].trim_start();
+    while !rest.is_empty() {
+        let equals: usize = rest.find('=').ok_or_else(|| GraphError::Parse(format!("Malformed attribute in <{}>", content)))?;
+        let attribute: &str = rest[..equals].trim();
+        let value: &str = rest[equals + 1..].trim_start();
+        let quote: char = value.chars().next().filter(|&c| c == '"' || c == '\'').ok_or_else(|| GraphError::Parse(format!("Unquoted attribute in <{}>", content)))?;
+        let end: usize = value[1..].find(quote).ok_or_else(|| GraphError::Parse(format!("Unterminated attribute in <{}>", content)))? + 1;
+        attributes.insert(local_name(attribute).to_string(), unescape(&value[1..end])?);
+        rest = value[end + 1..].trim_start();
+    }
+    Ok(Token::Start { name, attributes, empty })
+}
+
+/// Splits an XML document into tags and text, skipping declarations, comments and
+/// processing instructions.
+fn tokenize(xml: &str) -> Result<Vec<Token>, GraphError> {
+    let mut tokens: Vec<Token> = Vec::new();
+    let mut rest: &str = xml;
+    while !rest.is_empty() {
+        let unterminated = || GraphError::Parse("The XML ends inside a tag".to_string());
+        if let Some(after) = rest.strip_prefix("<!--") {
+            rest = &after[after.find("-->").ok_or_else(unterminated)? + 3..];
+        } else if let Some(after) = rest.strip_prefix("<![CDATA[") {
+            let end: usize = after.find("]]>").ok_or_else(unterminated)?;
+            tokens.push(Token::Text(after[..end].to_string()));
+            rest = &after[end + 3..];
+        } else if rest.starts_with("<?") || rest.starts_with("<!") {
+            rest = &rest[rest.find('>').ok_or_else(unterminated)? + 1..];
+        } else if let Some(after) = rest.strip_prefix("</") {
+            let end: usize = after.find('>').ok_or_else(unterminated)?;
+            tokens.push(Token::End(local_name(after[..end].trim()).to_string()));
+            rest = &after[end + 1..];
+        } else if let Some(after) = rest.strip_prefix('<') {
+            // Find the closing `>` outside quoted attribute values.
+            let mut quote: Option<char> = None;
+            let end: usize = after.char_indices()
+                .find(|&(_, c)| {
+                    match quote {
+                        Some(q) if c == q => quote = None,
+                        None if c == '"' || c == '\'' => quote = Some(c),
+                        None if c == '>' => return true,
+                        _ => {}
+                    }
+                    false
+                })
+                .map(|(i, _)| i)
+                .ok_or_else(unterminated)?;
+            tokens.push(parse_tag(&after[..end])?);
+            rest = &after[end + 1..];
+        } else {
+            let end: usize = rest.find('<').unwrap_or(rest.len());
+            tokens.push(Token::Text(unescape(&rest[..end])?));
+            rest = &rest[end..];
+        }
+    }
+    Ok(tokens)
+}
+
+/// A `<key>` declaration: which elements it applies to, its name, type and default value.
+struct Key {
+    target: String,
+    name: String,
+    kind: String,
+    default: Option<String>,
+}
+
+/// The element whose `<data>` children are being read.
+enum Owner {
+    Node,
+    Edge(usize),
+}
+
+/// An edge read from the document, added once every node is known.
+struct PendingEdge {
+    source: String,
+    target: String,
+    data: HashMap<String, String>,
+}
+
+impl Graph {
+    /// Writes the graph as GraphML, for tools such as Gephi, yEd and Cytoscape.
+    ///
+    /// Edge weights are written as the `weight` attribute, and the backward weight of a
+    /// direction-dependent edge as `reverse_weight`. Vertex and edge attributes are written
+    /// with their GraphML type; vectors become comma-separated strings. Vertices are written
+    /// in key order.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - Where to write the GraphML document.
+    ///
+    /// # Returns
+    ///
+    /// * `io::Result<()>` - An error if writing fails.
+    pub fn write_graphml<T: Write>(&self, writer: &mut T) -> io::Result<()> {
+        self.write_graphml_with(writer, &ExportAttributes::all())
+    }
+
+    /// Writes the graph as GraphML with only the selected vertex and edge attributes.
+    ///
+    /// Output is as for `write_graphml`. Edge attributes named `weight` or `reverse_weight`
+    /// are skipped, since those names carry the edge weights.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - Where to write the GraphML document.
+    /// * `attributes` - Which attributes to write.
+    ///
+    /// # Returns
+    ///
+    /// * `io::Result<()>` - An error if writing fails.
+    pub fn write_graphml_with<T: Write>(&self, writer: &mut T, attributes: &ExportAttributes) -> io::Result<()> {
+        let edges: Vec<&Edge> = self.stored_edges();
+        let vertex_data: Vec<(&String, Vec<(&String, &AttrValue)>)> = self.vertices.iter()
+            .map(|(key, vertex)| (key, attributes.vertex_selection(&vertex.attributes)))
+            .collect();
+        let edge_data: Vec<Vec<(&String, &AttrValue)>> = edges.iter()
+            .map(|edge| {
+                let mut selected: Vec<(&String, &AttrValue)> = attributes.edge_selection(&edge.attributes);
+                selected.retain(|(name, _)| name.as_str() != "weight" && name.as_str() != "reverse_weight");
+                selected
+            })
+            .collect();
+
+        let vertex_types: BTreeMap<&String, &str> = attribute_types(vertex_data.iter().flat_map(|(_, data)| data.iter().copied()));
+        let vertex_ids: HashMap<&String, String> = vertex_types.keys()
+            .enumerate()
+            .map(|(i, &name)| (name, format!("v{}", i)))
+            .collect();
+        let edge_types: BTreeMap<&String, &str> = attribute_types(edge_data.iter().flatten().copied());
+        let edge_ids: HashMap<&String, String> = edge_types.keys()
+            .enumerate()
+            .map(|(i, &name)| (name, format!("e{}", i)))
+            .collect();
+
+        writeln!(writer, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>")?;
+        writeln!(writer, "<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">")?;
+        writeln!(writer, "  <key id=\"weight\" for=\"edge\" attr.name=\"weight\" attr.type=\"double\"/>")?;
+        if edges.iter().any(|edge| edge.reverse_weight.is_some()) {
+            writeln!(writer, "  <key id=\"reverse_weight\" for=\"edge\" attr.name=\"reverse_weight\" attr.type=\"double\"/>")?;
+        }
+        for (name, kind) in &vertex_types {
+            writeln!(writer, "  <key id=\"{}\" for=\"node\" attr.name=\"{}\" attr.type=\"{}\"/>", vertex_ids[name], escape(name), kind)?;
+        }
+        for (name, kind) in &edge_types {
+            writeln!(writer, "  <key id=\"{}\" for=\"edge\" attr.name=\"{}\" attr.type=\"{}\"/>", edge_ids[name], escape(name), kind)?;
+        }
+        let edge_default: &str = if self.directed { "directed" } else { "undirected" };
+        writeln!(writer, "  <graph id=\"G\" edgedefault=\"{}\">", edge_default)?;
+
+        for (key, data) in &vertex_data {
+            if data.is_empty() {
+                writeln!(writer, "    <node id=\"{}\"/>", escape(key))?;
+                continue;
+            }
+            writeln!(writer, "    <node id=\"{}\">", escape(key))?;
+            for (name, value) in data {
+                writeln!(writer, "      <data key=\"{}\">{}</data>", vertex_ids[name], escape(&value.to_string()))?;
+            }
+            writeln!(writer, "    </node>")?;
+        }
+        for (edge, data) in edges.into_iter().zip(&edge_data) {
+            writeln!(writer, "    <edge source=\"{}\" target=\"{}\">", escape(&edge.vertex1), escape(&edge.vertex2))?;
+            writeln!(writer, "      <data key=\"weight\">{}</data>", edge.weight)?;
+            if let Some(backward) = edge.reverse_weight {
+                writeln!(writer, "      <data key=\"reverse_weight\">{}</data>", backward)?;
+            }
+            for (name, value) in data {
+                writeln!(writer, "      <data key=\"{}\">{}</data>", edge_ids[name], escape(&value.to_string()))?;
+            }
+            writeln!(writer, "    </edge>")?;
+        }
+        writeln!(writer, "  </graph>")?;
+        writeln!(writer, "</graphml>")
+    }
+
+    /// Renders the graph as a GraphML document.
+    ///
+    /// # Returns
+    ///
+    /// * A `String` with the GraphML document, as written by `write_graphml`.
+    pub fn to_graphml(&self) -> String {
+        let mut buffer: Vec<u8> = Vec::new();
+        self.write_graphml(&mut buffer).expect("Writing to memory cannot fail");
+        String::from_utf8(buffer).expect("GraphML output is built from strings")
+    }
+
+    /// Reads the first graph of a GraphML document.
+    ///
+    /// Node ids become vertex keys and node data becomes vertex attributes, typed as their
+    /// `<key>` declares. An edge's weight is read from the edge attribute named `weight`, and
+    /// is `1` when absent; `reverse_weight` makes an undirected edge direction-dependent.
+    /// Other edge data becomes edge attributes. Nested graphs, hyperedges and ports are
+    /// ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `xml` - The GraphML document.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Graph, GraphError>` - The graph, or an error if the document is malformed, has no
+    ///   graph, or an edge refers to a missing node.
+    pub fn from_graphml(xml: &str) -> Result<Graph, GraphError> {
+        let mut keys: HashMap<String, Key> = HashMap::new();
+        let mut directed: Option<bool> = None;
+        let mut nodes: Vec<(String, HashMap<String, String>)> = Vec::new();
+        let mut edges: Vec<PendingEdge> = Vec::new();
+        let mut owner: Option<Owner> = None;
+        // The key of the `<data>` or `<default>` being read, and its text so far.
+        let mut reading: Option<(Option<String>, String)> = None;
+        let mut current_key: Option<String> = None;
+
+        for token in tokenize(xml)? {
+            match token {
+                Token::Start { name, mut attributes, empty } => match name.as_str() {
+                    "key" => {
+                        let id: String = attributes.remove("id").ok_or_else(|| GraphError::Parse("A key has no id".to_string()))?;
+                        let key: Key = Key {
+                            target: attributes.remove("for").unwrap_or_else(|| "all".to_string()),
+                            name: attributes.remove("attr.name").unwrap_or_else(|| id.clone()),
+                            kind: attributes.remove("attr.type").unwrap_or_else(|| "string".to_string()),
+                            default: None,
+                        };
+                        keys.insert(id.clone(), key);
+                        if !empty {
+                            current_key = Some(id);
+                        }
+                    }
+                    "default" if current_key.is_some() && !empty => reading = Some((None, String::new())),
+                    "graph" if directed.is_none() => {
+                        directed = Some(attributes.get("edgedefault").is_some_and(|default| default == "directed"));
+                    }
+                    "node" => {
+                        let id: String = attributes.remove("id").ok_or_else(|| GraphError::Parse("A node has no id".to_string()))?;
+                        nodes.push((id, HashMap::new()));
+                        owner = (!empty).then_some(Owner::Node);
+                    }
+                    "edge" => {
+                        let source: String = attributes.remove("source").ok_or_else(|| GraphError::Parse("An edge has no source".to_string()))?;
+                        let target: String = attributes.remove("target").ok_or_else(|| GraphError::Parse("An edge has no target".to_string()))?;
+                        edges.push(PendingEdge { source, target, data: HashMap::new() });
+                        owner = (!empty).then_some(Owner::Edge(edges.len() - 1));
+                    }
+                    "data" if !empty => reading = Some((attributes.remove("key"), String::new())),
+                    _ => {}
+                },
+                Token::Text(text) => {
+                    if let Some((_, content)) = reading.as_mut() {
+                        content.push_str(&text);
+                    }
+                }
+                Token::End(name) => match name.as_str() {
+                    "key" => current_key = None,
+                    "default" => {
+                        if let (Some((_, content)), Some(id)) = (reading.take(), current_key.as_ref()) {
+                            if let Some(key) = keys.get_mut(id) {
+                                key.default = Some(content);
+                            }
+                        }
+                    }
+                    "node" | "edge" => owner = None,
+                    "data" => {
+                        if let Some((Some(key), content)) = reading.take() {
+                            match owner {
+                                Some(Owner::Node) => nodes.last_mut().unwrap().1.insert(key, content),
+                                Some(Owner::Edge(e)) => edges[e].data.insert(key, content),
+                                None => None,
+                            };
+                        }
+                    }
+                    _ => {}
+                },
+            }
+        }
+
+        let directed: bool = directed.ok_or_else(|| GraphError::Parse("The document has no graph".to_string()))?;
+        let mut graph: Graph = Graph::new(directed);
+        for (id, data) in nodes {
+            let mut vertex: Vertex = Vertex::new(id);
+            for (key_id, key) in keys.iter().filter(|(_, key)| key.target == "node" || key.target == "all") {
+                if let Some(text) = data.get(key_id).or(key.default.as_ref()) {
+                    vertex.set_attr(&key.name, parse_value(text, &key.kind)?);
+                }
+            }
+            graph.add_vertex(vertex);
+        }
+
+        let edge_value = |data: &HashMap<String, String>, name: &str| -> Result<Option<f32>, GraphError> {
+            let found: Option<(&String, &Key)> = keys.iter()
+                .find(|(_, key)| key.name == name && (key.target == "edge" || key.target == "all"));
+            let Some((key_id, key)) = found else {
+                return Ok(None);
+            };
+            match data.get(key_id).or(key.default.as_ref()) {
+                Some(text) => text.trim().parse().map(Some).map_err(|_| GraphError::Parse(format!("Edge {} {} is not a number", name, text))),
+                None => Ok(None),
+            }
+        };
+        for edge in edges {
+            let weight: f32 = edge_value(&edge.data, "weight")?.unwrap_or(1.0);
+            let mut built: Edge = match edge_value(&edge.data, "reverse_weight")? {
+                Some(backward) if !directed => Edge::asymmetric(edge.source, edge.target, weight, backward),
+                _ => Edge::new(edge.source, edge.target, weight),
+            };
+            let edge_keys = keys.iter()
+                .filter(|(_, key)| (key.target == "edge" || key.target == "all") && key.name != "weight" && key.name != "reverse_weight");
+            for (key_id, key) in edge_keys {
+                if let Some(text) = edge.data.get(key_id).or(key.default.as_ref()) {
+                    built.set_attr(&key.name, parse_value(text, &key.kind)?);
+                }
+            }
+            graph.add_edge(built)?;
+        }
+        Ok(graph)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::graph::{AttrValue, Edge, Graph, Vertex};
+
+    /// Builds a graph with escaped keys, typed attributes and, when undirected, an asymmetric
+    /// edge.
+    fn sample(directed: bool) -> Graph {
+        let mut graph: Graph = Graph::new(directed);
+        let mut a: Vertex = Vertex::new("a<&>\"".to_string());
+        a.set_attr("flag", AttrValue::Bool(false));
+        a.set_attr("count", AttrValue::Int(12));
+        a.set_attr("score", AttrValue::Float(0.5));
+        a.set_attr("name", AttrValue::Text("x & 'y'".to_string()));
+        graph.add_vertex(a);
+        graph.add_vertex(Vertex::new("b".to_string()));
+        let edge: Edge = if directed {
+            Edge::new("a<&>\"".to_string(), "b".to_string(), 1.5)
+        } else {
+            Edge::asymmetric("a<&>\"".to_string(), "b".to_string(), 1.5, 2.5)
+        };
+        graph.add_edge(edge).unwrap();
+        graph.add_edge(Edge::new("b".to_string(), "b".to_string(), 3.0)).unwrap();
+        graph
+    }
+
+    #[test]
+    fn round_trip_keeps_keys_weights_and_attributes() {
+        for directed in [false, true] {
+            let graph: Graph = sample(directed);
+            let xml: String = graph.to_graphml();
+            let loaded: Graph = Graph::from_graphml(&xml).unwrap();
+            assert_eq!(loaded.to_graphml(), xml);
+            assert_eq!(loaded.directed, directed);
+            assert_eq!(loaded.edge_count, 2);
+            assert_eq!(loaded.vertices["a<&>\""].attributes, graph.vertices["a<&>\""].attributes);
+        }
+    }
+
+    #[test]
+    fn reads_documents_from_other_tools() {
+        let xml: &str = r#"<?xml version="1.0"?>
+            <!-- written by hand -->
+            <graphml xmlns:y="http://www.yworks.com/xml/graphml">
+              <key id="d0" for="node" attr.name="color" attr.type="string"><default>blue</default></key>
+              <key id="d1" for="edge" attr.name="weight" attr.type="double"/>
+              <graph edgedefault="directed">
+                <node id="n0"><data key="d0">red</data></node>
+                <y:node id="n1"/>
+                <edge source="n0" target="n1"><data key="d1">2.5</data></edge>
+                <edge source="n1" target="n0"/>
+              </graph>
+            </graphml>"#;
+        let graph: Graph = Graph::from_graphml(xml).unwrap();
+        assert!(graph.directed);
+        assert_eq!(graph.vertices["n0"].attr("color"), Some(&AttrValue::Text("red".to_string())));
+        assert_eq!(graph.vertices["n1"].attr("color"), Some(&AttrValue::Text("blue".to_string())));
+        assert_eq!(graph.vertices["n0"].edges[0].weight, 2.5);
+        assert_eq!(graph.vertices["n1"].edges[0].weight, 1.0);
+    }
+
+    #[test]
+    fn malformed_documents_are_rejected() {
+        assert!(Graph::from_graphml("<graphml></graphml>").is_err());
+        assert!(Graph::from_graphml(r#"<graphml><graph edgedefault="undirected"><node id="a"/><edge source="a" target="b"/></graph></graphml>"#).is_err());
+        assert!(Graph::from_graphml(r#"<graphml><graph edgedefault="undirected"><node id="a"#).is_err());
+        assert!(Graph::from_graphml(r#"<graphml><key id="d" for="node" attr.type="int"/><graph><node id="a"><data key="d">x</data></node></graph></graphml>"#).is_err());
+    }
+}
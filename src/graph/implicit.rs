@@ -0,0 +1,245 @@
+use crate::graph::path::State;
+use crate::graph::GraphError;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::hash::Hash;
+use std::marker::PhantomData;
+
+/// A path through an implicit graph, from the start state to a goal state.
+#[derive(Clone, Debug)]
+pub struct ImplicitPath<N> {
+    /// The states along the path, from the start to the goal.
+    pub states: Vec<N>,
+    /// The total cost of the steps taken; the number of steps for unweighted searches.
+    pub cost: f32,
+}
+
+/// A graph defined by a successor function instead of stored vertices and edges.
+///
+/// States are only generated as a search reaches them, so state spaces far too large to
+/// build as a `Graph` (puzzles, game trees, configuration spaces) can still be searched.
+/// Built with chained calls, for example `ImplicitGraph::new(successors).limit(100_000)`.
+pub struct ImplicitGraph<N, F> {
+    successors: F,
+    /// The most states a search may expand before giving up.
+    limit: Option<usize>,
+    state: PhantomData<N>,
+}
+
+/// The states a search has seen, numbered in discovery order, with the parent of each.
+struct Explored<N> {
+    states: Vec<N>,
+    ids: HashMap<N, usize>,
+    parents: Vec<Option<usize>>,
+}
+
+impl<N: Clone + Eq + Hash> Explored<N> {
+    fn new(start: N) -> Explored<N> {
+        Explored { states: vec![start.clone()], ids: HashMap::from([(start, 0)]), parents: vec![None] }
+    }
+
+    /// Returns the id of `state` and whether it was seen for the first time.
+    fn insert(&mut self, state: N, parent: usize) -> (usize, bool) {
+        if let Some(&id) = self.ids.get(&state) {
+            return (id, false);
+        }
+        let id: usize = self.states.len();
+        self.ids.insert(state.clone(), id);
+        self.states.push(state);
+        self.parents.push(Some(parent));
+        (id, true)
+    }
+
+    /// Follows the parents back from `goal` to the start.
+    fn path(&self, goal: usize, cost: f32) -> ImplicitPath<N> {
+        let mut ids: Vec<usize> = vec![goal];
+        while let Some(parent) = self.parents[ids[ids.len() - 1]] {
+            ids.push(parent);
+        }
+        ImplicitPath { states: ids.into_iter().rev().map(|id| self.states[id].clone()).collect(), cost }
+    }
+}
+
+impl<N, F, I> ImplicitGraph<N, F>
+where
+    N: Clone + Eq + Hash,
+    F: Fn(&N) -> I,
+    I: IntoIterator<Item = (N, f32)>,
+{
+    /// Creates an implicit graph from a successor function.
+    ///
+    /// # Arguments
+    ///
+    /// * `successors` - Returns every `(state, cost)` reachable in one step from a state.
+    ///
+    /// # Returns
+    ///
+    /// * An `ImplicitGraph` whose searches expand any number of states.
+    pub fn new(successors: F) -> ImplicitGraph<N, F> {
+        ImplicitGraph { successors, limit: None, state: PhantomData }
+    }
+
+    /// Caps the number of states a search may expand, so a search in an enormous or infinite
+    /// space fails instead of running out of memory.
+    pub fn limit(mut self, max_expanded: usize) -> ImplicitGraph<N, F> {
+        self.limit = Some(max_expanded);
+        self
+    }
+
+    /// Returns an error once `expanded` states exceed the limit.
+    fn check_limit(&self, expanded: usize) -> Result<(), GraphError> {
+        match self.limit {
+            Some(limit) if expanded > limit => Err(GraphError::NoSolution(format!("Search gave up after expanding {} states", limit))),
+            _ => Ok(()),
+        }
+    }
+
+    /// Finds a path with the fewest steps from `start` to a goal state by breadth-first search.
+    ///
+    /// Step costs are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - The state to search from.
+    /// * `is_goal` - Returns whether a state ends the search.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<ImplicitPath<N>>, GraphError>` - The path, whose cost is its number of steps,
+    ///   `None` if no goal is reachable, or an error if the expansion limit was hit.
+    pub fn bfs<G>(&self, start: N, is_goal: G) -> Result<Option<ImplicitPath<N>>, GraphError>
+    where
+        G: Fn(&N) -> bool,
+    {
+        let mut explored: Explored<N> = Explored::new(start);
+        let mut queue: VecDeque<(usize, usize)> = VecDeque::from([(0, 0)]);
+        let mut expanded: usize = 0;
+        while let Some((id, depth)) = queue.pop_front() {
+            if is_goal(&explored.states[id]) {
+                return Ok(Some(explored.path(id, depth as f32)));
+            }
+            expanded += 1;
+            self.check_limit(expanded)?;
+            for (next, _) in (self.successors)(&explored.states[id]) {
+                let (next_id, new) = explored.insert(next, id);
+                if new {
+                    queue.push_back((next_id, depth + 1));
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    /// Finds a path from `start` to a goal state by depth-first search.
+    ///
+    /// The path found is the first one reached, not necessarily the shortest. Successors are
+    /// tried in the order the successor function returns them.
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - The state to search from.
+    /// * `is_goal` - Returns whether a state ends the search.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<ImplicitPath<N>>, GraphError>` - The path, whose cost is its number of steps,
+    ///   `None` if no goal is reachable, or an error if the expansion limit was hit.
+    pub fn dfs<G>(&self, start: N, is_goal: G) -> Result<Option<ImplicitPath<N>>, GraphError>
+    where
+        G: Fn(&N) -> bool,
+    {
+        let mut explored: Explored<N> = Explored::new(start);
+        let mut stack: Vec<(usize, usize)> = vec![(0, 0)];
+        let mut expanded: usize = 0;
+        while let Some((id, depth)) = stack.pop() {
+            if is_goal(&explored.states[id]) {
+                return Ok(Some(explored.path(id, depth as f32)));
+            }
+            expanded += 1;
+            self.check_limit(expanded)?;
+            let successors: Vec<(N, f32)> = (self.successors)(&explored.states[id]).into_iter().collect();
+            // Push in reverse so the first successor is explored first.
+            for (next, _) in successors.into_iter().rev() {
+                let (next_id, new) = explored.insert(next, id);
+                if new {
+                    stack.push((next_id, depth + 1));
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    /// Finds a cheapest path from `start` to a goal state with Dijkstra's algorithm.
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - The state to search from.
+    /// * `is_goal` - Returns whether a state ends the search.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<ImplicitPath<N>>, GraphError>` - The path and its cost, `None` if no goal is
+    ///   reachable, or an error if a step cost is negative or the expansion limit was hit.
+    pub fn dijkstra<G>(&self, start: N, is_goal: G) -> Result<Option<ImplicitPath<N>>, GraphError>
+    where
+        G: Fn(&N) -> bool,
+    {
+        self.astar(start, is_goal, |_| 0.0)
+    }
+
+    /// Finds a cheapest path from `start` to a goal state with A* search.
+    ///
+    /// The path is optimal when the heuristic is consistent: the estimate at a state never
+    /// exceeds the cost of a step plus the estimate after it, and is zero at goals. A
+    /// heuristic of zero everywhere gives Dijkstra's algorithm.
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - The state to search from.
+    /// * `is_goal` - Returns whether a state ends the search.
+    /// * `heuristic` - Estimates the cost from a state to the nearest goal.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<ImplicitPath<N>>, GraphError>` - The path and its cost, `None` if no goal is
+    ///   reachable, or an error if a step cost is negative or the expansion limit was hit.
+    pub fn astar<G, H>(&self, start: N, is_goal: G, heuristic: H) -> Result<Option<ImplicitPath<N>>, GraphError>
+    where
+        G: Fn(&N) -> bool,
+        H: Fn(&N) -> f32,
+    {
+        let mut explored: Explored<N> = Explored::new(start);
+        let mut costs: Vec<f32> = vec![0.0];
+        let mut closed: Vec<bool> = vec![false];
+        let mut heap: BinaryHeap<State> = BinaryHeap::from([State { cost: heuristic(&explored.states[0]), vertex: 0 }]);
+        let mut expanded: usize = 0;
+        while let Some(State { vertex: id, .. }) = heap.pop() {
+            if closed[id] {
+                continue;
+            }
+            closed[id] = true;
+            if is_goal(&explored.states[id]) {
+                return Ok(Some(explored.path(id, costs[id])));
+            }
+            expanded += 1;
+            self.check_limit(expanded)?;
+            for (next, step) in (self.successors)(&explored.states[id]) {
+                if step < 0.0 {
+                    return Err(GraphError::InvalidArgument(format!("A step has negative cost {}", step)));
+                }
+                let candidate: f32 = costs[id] + step;
+                let (next_id, new) = explored.insert(next, id);
+                if new {
+                    costs.push(candidate);
+                    closed.push(false);
+                } else if closed[next_id] || candidate >= costs[next_id] {
+                    continue;
+                } else {
+                    costs[next_id] = candidate;
+                    explored.parents[next_id] = Some(id);
+                }
+                heap.push(State { cost: candidate + heuristic(&explored.states[next_id]), vertex: next_id });
+            }
+        }
+        Ok(None)
+    }
+}
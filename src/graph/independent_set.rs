@@ -0,0 +1,130 @@
+use crate::graph::index::GraphIndex;
+use crate::graph::{Graph, GraphError};
+use std::collections::HashSet;
+
+/// Finds a maximum-weight set of pairwise non-overlapping intervals (weighted interval scheduling).
+///
+/// This is the maximum-weight independent set of the interval graph the intervals define.
+/// Intervals are half-open, `[start, end)`, so touching intervals do not overlap.
+///
+/// # Arguments
+///
+/// * `intervals` - The intervals as `(key, start, end, weight)`.
+///
+/// # Returns
+///
+/// * A `(Vec<String>, f32)` with the chosen keys in order of their end points and their total weight.
+pub fn max_weight_interval_set(intervals: &[(String, f32, f32, f32)]) -> (Vec<String>, f32) {
+    let mut order: Vec<usize> = (0..intervals.len()).collect();
+    order.sort_by(|&a, &b| intervals[a].2.total_cmp(&intervals[b].2));
+
+    // best[i] is the optimum over the first `i` intervals in end order.
+    let mut best: Vec<f32> = vec![0.0; order.len() + 1];
+    let mut compatible: Vec<usize> = vec![0; order.len()];
+    for (i, &current) in order.iter().enumerate() {
+        let start: f32 = intervals[current].1;
+        compatible[i] = order[..i].partition_point(|&other| intervals[other].2 <= start);
+        let take: f32 = intervals[current].3 + best[compatible[i]];
+        best[i + 1] = best[i].max(take);
+    }
+
+    let mut chosen: Vec<String> = Vec::new();
+    let mut i: usize = order.len();
+    while i > 0 {
+        let current: usize = order[i - 1];
+        if intervals[current].3 + best[compatible[i - 1]] >= best[i] && intervals[current].3 > 0.0 {
+            chosen.push(intervals[current].0.clone());
+            i = compatible[i - 1];
+        } else {
+            i -= 1;
+        }
+    }
+    chosen.reverse();
+    (chosen, best[order.len()])
+}
+
+impl Graph {
+    /// Finds an exact maximum-weight independent set of a forest by dynamic programming.
+    ///
+    /// Each tree is rooted arbitrarily and every vertex keeps the best weight of its subtree
+    /// with and without itself, giving a linear-time solution. Edge directions are ignored.
+    /// Vertices with non-positive weight are never chosen.
+    ///
+    /// # Arguments
+    ///
+    /// * `weight` - Returns the weight of a vertex given its key.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(HashSet<String>, f32), GraphError>` - The chosen vertices and their total weight,
+    ///   or an error if the graph contains a cycle.
+    pub fn max_weight_independent_set_forest<W>(&self, weight: W) -> Result<(HashSet<String>, f32), GraphError>
+    where
+        W: Fn(&str) -> f32,
+    {
+        let index: GraphIndex = self.index();
+        let n: usize = index.len();
+        let mut neighbors: Vec<Vec<usize>> = index.adjacency.iter()
+            .map(|list| list.iter().map(|&(v, _)| v).collect())
+            .collect();
+        if index.directed {
+            for (u, list) in index.adjacency.iter().enumerate() {
+                for &(v, _) in list {
+                    neighbors[v].push(u);
+                }
+            }
+        }
+
+        let weights: Vec<f32> = index.keys.iter().map(|key| weight(key)).collect();
+        let mut parent: Vec<Option<usize>> = vec![None; n];
+        let mut visited: Vec<bool> = vec![false; n];
+        let mut order: Vec<usize> = Vec::with_capacity(n);
+
+        for root in 0..n {
+            if visited[root] {
+                continue;
+            }
+            visited[root] = true;
+            let mut stack: Vec<usize> = vec![root];
+            while let Some(u) = stack.pop() {
+                order.push(u);
+                let mut parent_edge_seen: bool = false;
+                for &v in &neighbors[u] {
+                    if Some(v) == parent[u] && !parent_edge_seen {
+                        parent_edge_seen = true;
+                        continue;
+                    }
+                    if visited[v] {
+                        return Err(GraphError::CycleDetected(vec![index.keys[v].clone()]));
+                    }
+                    visited[v] = true;
+                    parent[v] = Some(u);
+                    stack.push(v);
+                }
+            }
+        }
+
+        // with[u] / without[u]: best subtree weight with and without `u` in the set.
+        let mut with: Vec<f32> = weights.iter().map(|&w| w.max(0.0)).collect();
+        let mut without: Vec<f32> = vec![0.0; n];
+        for &u in order.iter().rev() {
+            if let Some(p) = parent[u] {
+                with[p] += without[u];
+                without[p] += with[u].max(without[u]);
+            }
+        }
+
+        let mut chosen: HashSet<String> = HashSet::new();
+        let mut taken: Vec<bool> = vec![false; n];
+        let mut total: f32 = 0.0;
+        for &u in &order {
+            let parent_taken: bool = parent[u].is_some_and(|p| taken[p]);
+            if !parent_taken && weights[u] > 0.0 && with[u] >= without[u] {
+                taken[u] = true;
+                total += weights[u];
+                chosen.insert(index.keys[u].clone());
+            }
+        }
+        Ok((chosen, total))
+    }
+}
@@ -0,0 +1,121 @@
+use crate::graph::cycles::EdgeList;
+use crate::graph::{Edge, Graph, GraphError, Vertex};
+use std::collections::HashMap;
+
+/// An edge between two vertex positions, with its weight.
+pub(crate) type IndexedEdge = (usize, usize, f32);
+
+/// A dense, index-based view of a `Graph` used internally by the algorithms.
+///
+/// Vertices are numbered `0..n` in ascending key order so results are reproducible,
+/// and every vertex stores the list of `(neighbor, weight)` pairs it can reach directly.
+/// In undirected graphs each edge therefore appears in both endpoints' lists.
+#[derive(Clone, Debug)]
+pub(crate) struct GraphIndex {
+    /// The vertex keys, sorted; position `i` is the key of vertex `i`.
+    pub keys: Vec<String>,
+    /// Maps a vertex key back to its position in `keys`.
+    pub positions: HashMap<String, usize>,
+    /// Outgoing adjacency lists as `(neighbor, weight)` pairs.
+    pub adjacency: Vec<Vec<(usize, f32)>>,
+    /// Whether the underlying graph is directed.
+    pub directed: bool,
+}
+
+impl GraphIndex {
+    /// Builds the index view of `graph`.
+    pub fn new(graph: &Graph) -> GraphIndex {
+        GraphIndex::with_keys(graph, graph.vertices.keys().cloned().collect())
+    }
+
+    /// Builds the index view of `graph` with the vertices numbered in the order of `keys`.
+    pub fn with_keys(graph: &Graph, keys: Vec<String>) -> GraphIndex {
+
+        let positions: HashMap<String, usize> = keys.iter()
+            .enumerate()
+            .map(|(i, key)| (key.clone(), i))
+            .collect();
+
+        let mut adjacency: Vec<Vec<(usize, f32)>> = vec![Vec::new(); keys.len()];
+        for (i, key) in keys.iter().enumerate() {
+            for edge in &graph.vertices[key].edges {
+                let neighbor: &String = if &edge.vertex1 == key {
+                    &edge.vertex2
+                } else {
+                    &edge.vertex1
+                };
+                if let Some(&j) = positions.get(neighbor) {
+                    adjacency[i].push((j, edge.weight_from(key)));
+                }
+            }
+        }
+
+        GraphIndex {
+            keys,
+            positions,
+            adjacency,
+            directed: graph.directed,
+        }
+    }
+
+    /// Returns the number of vertices.
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Returns the position of the vertex with the given key, or an error if it does not exist.
+    pub fn position(&self, key: &str) -> Result<usize, GraphError> {
+        self.positions.get(key).copied().ok_or_else(|| GraphError::VertexNotFound(key.to_string()))
+    }
+
+    /// Returns the incoming adjacency lists; identical to `adjacency` for undirected graphs.
+    pub fn reversed(&self) -> Vec<Vec<(usize, f32)>> {
+        if !self.directed {
+            return self.adjacency.clone();
+        }
+        let mut reversed: Vec<Vec<(usize, f32)>> = vec![Vec::new(); self.len()];
+        for (u, neighbors) in self.adjacency.iter().enumerate() {
+            for &(v, weight) in neighbors {
+                reversed[v].push((u, weight));
+            }
+        }
+        reversed
+    }
+
+    /// Returns every edge once (every arc, in directed graphs).
+    pub fn edges(&self) -> Vec<IndexedEdge> {
+        if self.directed {
+            self.adjacency.iter()
+                .enumerate()
+                .flat_map(|(u, neighbors)| neighbors.iter().map(move |&(v, weight)| (u, v, weight)))
+                .collect()
+        } else {
+            EdgeList::new(self).edges
+        }
+    }
+
+    /// Builds a graph with every indexed vertex and only the given edges.
+    pub fn with_edges(&self, edges: &[IndexedEdge]) -> Graph {
+        let mut graph: Graph = Graph::new(self.directed);
+        for key in &self.keys {
+            graph.add_vertex(Vertex::new(key.clone()));
+        }
+        for &(u, v, weight) in edges {
+            let edge: Edge = Edge::new(self.keys[u].clone(), self.keys[v].clone(), weight);
+            graph.add_edge(edge).unwrap();
+        }
+        graph
+    }
+}
+
+impl Graph {
+    /// Builds the dense index view used by the algorithm modules.
+    pub(crate) fn index(&self) -> GraphIndex {
+        GraphIndex::new(self)
+    }
+
+    /// Builds the index view with the vertices numbered in the graph's `VertexOrder`.
+    pub(crate) fn ordered_index(&self) -> GraphIndex {
+        GraphIndex::with_keys(self, self.vertex_keys())
+    }
+}
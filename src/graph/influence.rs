@@ -0,0 +1,134 @@
+use crate::graph::index::GraphIndex;
+use crate::graph::rng::Rng;
+use crate::graph::simulate::{estimate_spread, SpreadModel};
+use crate::graph::Graph;
+use std::collections::BinaryHeap;
+use std::cmp::Ordering;
+
+/// The strategy used to pick influential seed vertices.
+#[derive(Clone, Debug)]
+pub enum InfluenceHeuristic {
+    /// Greedy hill climbing on Monte Carlo spread estimates, with lazy (CELF) re-evaluation.
+    Greedy {
+        /// The number of simulations per spread estimate.
+        simulations: usize,
+        /// The random seed for the simulations.
+        seed: u64,
+    },
+    /// The degree-discount heuristic of Chen, Wang and Yang, using the mean edge weight
+    /// as the propagation probability. Much faster than `Greedy`, with no simulations.
+    DegreeDiscount,
+}
+
+/// A candidate in the lazy greedy queue, ordered by marginal gain and then by position.
+struct Candidate {
+    gain: f64,
+    vertex: usize,
+    round: usize,
+}
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.gain.total_cmp(&other.gain).then_with(|| other.vertex.cmp(&self.vertex))
+    }
+}
+
+/// Lazy greedy (CELF) selection of `k` seeds maximizing the estimated spread.
+fn greedy(index: &GraphIndex, k: usize, model: &SpreadModel, simulations: usize, seed: u64) -> Vec<usize> {
+    let mut rng: Rng = Rng::new(seed);
+    let mut heap: BinaryHeap<Candidate> = (0..index.len())
+        .map(|v| Candidate {
+            gain: estimate_spread(index, &[v], model, simulations, &mut rng),
+            vertex: v,
+            round: 0,
+        })
+        .collect();
+
+    let mut seeds: Vec<usize> = Vec::with_capacity(k);
+    let mut current: f64 = 0.0;
+    while seeds.len() < k {
+        let Some(mut top) = heap.pop() else { break };
+        if top.round == seeds.len() {
+            current += top.gain;
+            seeds.push(top.vertex);
+            continue;
+        }
+        seeds.push(top.vertex);
+        let spread: f64 = estimate_spread(index, &seeds, model, simulations, &mut rng);
+        seeds.pop();
+        top.gain = spread - current;
+        top.round = seeds.len();
+        heap.push(top);
+    }
+    seeds
+}
+
+/// Degree-discount selection of `k` seeds.
+fn degree_discount(index: &GraphIndex, k: usize) -> Vec<usize> {
+    let n: usize = index.len();
+    let (weight_sum, edge_total) = index.adjacency.iter()
+        .flatten()
+        .fold((0.0f64, 0usize), |(sum, count), &(_, w)| (sum + f64::from(w.clamp(0.0, 1.0)), count + 1));
+    let p: f64 = if edge_total == 0 { 0.0 } else { weight_sum / edge_total as f64 };
+
+    let degree: Vec<f64> = index.adjacency.iter().map(|neighbors| neighbors.len() as f64).collect();
+    let mut discounted: Vec<f64> = degree.clone();
+    let mut selected_neighbors: Vec<f64> = vec![0.0; n];
+    let mut chosen: Vec<bool> = vec![false; n];
+    let mut seeds: Vec<usize> = Vec::with_capacity(k);
+
+    while seeds.len() < k.min(n) {
+        let best: usize = (0..n)
+            .filter(|&v| !chosen[v])
+            .max_by(|&a, &b| discounted[a].total_cmp(&discounted[b]).then_with(|| b.cmp(&a)))
+            .unwrap();
+        chosen[best] = true;
+        seeds.push(best);
+
+        for &(v, _) in &index.adjacency[best] {
+            if chosen[v] {
+                continue;
+            }
+            selected_neighbors[v] += 1.0;
+            let t: f64 = selected_neighbors[v];
+            discounted[v] = degree[v] - 2.0 * t - (degree[v] - t) * t * p;
+        }
+    }
+    seeds
+}
+
+impl Graph {
+    /// Selects up to `k` seed vertices that maximize the reach of a spreading process.
+    ///
+    /// # Arguments
+    ///
+    /// * `k` - The number of seeds to select.
+    /// * `model` - The spreading process the seeds should maximize (used by `Greedy`).
+    /// * `heuristic` - The selection strategy.
+    ///
+    /// # Returns
+    ///
+    /// * A `Vec<String>` of vertex keys in selection order.
+    pub fn top_k_influencers(&self, k: usize, model: &SpreadModel, heuristic: &InfluenceHeuristic) -> Vec<String> {
+        let index: GraphIndex = self.index();
+        let seeds: Vec<usize> = match heuristic {
+            InfluenceHeuristic::Greedy { simulations, seed } => greedy(&index, k, model, *simulations, *seed),
+            InfluenceHeuristic::DegreeDiscount => degree_discount(&index, k),
+        };
+        seeds.into_iter().map(|v| index.keys[v].clone()).collect()
+    }
+}
@@ -0,0 +1,442 @@
+use crate::graph::coloring::symmetric_neighbors;
+use crate::graph::index::GraphIndex;
+use crate::graph::{Graph, GraphError};
+use std::collections::{HashMap, HashSet};
+
+/// An interval model of an interval graph.
+///
+/// Interval endpoints are positions in the ordered list of maximal cliques, so two vertices
+/// are adjacent exactly when their intervals share a position.
+#[derive(Clone, Debug)]
+pub struct IntervalModel {
+    /// The closed interval `(first, last)` of clique positions of every vertex.
+    pub intervals: HashMap<String, (usize, usize)>,
+    /// The maximal cliques in path order, each sorted by key.
+    pub cliques: Vec<Vec<String>>,
+}
+
+impl IntervalModel {
+    /// Colors the vertices with the fewest possible colors.
+    ///
+    /// Vertices are colored in order of their left endpoints with the smallest color not used
+    /// by an overlapping interval, which needs exactly as many colors as the largest clique.
+    ///
+    /// # Returns
+    ///
+    /// * A `HashMap<String, usize>` with the color of every vertex.
+    pub fn coloring(&self) -> HashMap<String, usize> {
+        let mut colors: HashMap<String, usize> = HashMap::new();
+        for (position, clique) in self.cliques.iter().enumerate() {
+            let used: HashSet<usize> = clique.iter().filter_map(|key| colors.get(key).copied()).collect();
+            let mut next: usize = 0;
+            for key in clique {
+                if self.intervals[key].0 != position {
+                    continue;
+                }
+                while used.contains(&next) {
+                    next += 1;
+                }
+                colors.insert(key.clone(), next);
+                next += 1;
+            }
+        }
+        colors
+    }
+
+    /// Returns a maximum clique, i.e. the largest of the maximal cliques.
+    ///
+    /// # Returns
+    ///
+    /// * A `Vec<String>` with the keys of the clique, sorted; empty for an empty graph.
+    pub fn maximum_clique(&self) -> Vec<String> {
+        self.cliques.iter().rev().max_by_key(|clique| clique.len()).cloned().unwrap_or_default()
+    }
+}
+
+/// Orders the vertices by lexicographic breadth-first search with partition refinement.
+///
+/// The unvisited vertices are kept in an array of consecutive classes, ordered by label. Visiting a
+/// vertex moves each unvisited neighbor to a new class just before its old one, so the whole
+/// search takes time linear in the size of the graph.
+fn lex_bfs(neighbors: &[Vec<usize>]) -> Vec<usize> {
+    let n: usize = neighbors.len();
+    let mut order: Vec<usize> = (0..n).collect();
+    let mut position: Vec<usize> = (0..n).collect();
+    let mut class: Vec<usize> = vec![0; n];
+    // The first position of every class, and the class split off it in the current step.
+    let mut start: Vec<usize> = vec![0];
+    let mut split: Vec<Option<(usize, usize)>> = vec![None];
+    for step in 0..n {
+        let v: usize = order[step];
+        start[class[v]] += 1;
+        for &u in &neighbors[v] {
+            if position[u] <= step {
+                continue;
+            }
+            let old: usize = class[u];
+            let new: usize = match split[old] {
+                Some((at, new)) if at == step => new,
+                _ => {
+                    start.push(start[old]);
+                    split.push(None);
+                    split[old] = Some((step, start.len() - 1));
+                    start.len() - 1
+                }
+            };
+            // Swap `u` to the front of its old class, which then becomes the back of the new one.
+            let front: usize = start[old];
+            let other: usize = order[front];
+            order.swap(front, position[u]);
+            position[other] = position[u];
+            position[u] = front;
+            start[old] += 1;
+            class[u] = new;
+        }
+    }
+    order
+}
+
+/// Returns the maximal cliques of a chordal graph in the order LexBFS completes them, or
+/// `None` if the graph is not chordal.
+///
+/// The reverse of a LexBFS order is a perfect elimination order exactly when the graph is
+/// chordal, which holds when the earlier neighbors of every vertex, except the latest of
+/// them, are earlier neighbors of that latest one too. Every maximal clique is then a vertex
+/// together with its earlier neighbors, and the clique of a vertex is not maximal exactly when
+/// a vertex with one more earlier neighbor has it as its latest one.
+fn chordal_cliques(neighbors: &[Vec<usize>], adjacent: &[HashSet<usize>]) -> Option<Vec<Vec<usize>>> {
+    let n: usize = neighbors.len();
+    let order: Vec<usize> = lex_bfs(neighbors);
+    let mut rank: Vec<usize> = vec![0; n];
+    for (i, &v) in order.iter().enumerate() {
+        rank[v] = i;
+    }
+
+    let mut earlier: Vec<Vec<usize>> = vec![Vec::new(); n];
+    let mut contained: Vec<bool> = vec![false; n];
+    for &v in &order {
+        earlier[v] = neighbors[v].iter().copied().filter(|&u| rank[u] < rank[v]).collect();
+        if let Some(&parent) = earlier[v].iter().max_by_key(|&&u| rank[u]) {
+            if earlier[v].iter().any(|&u| u != parent && !adjacent[parent].contains(&u)) {
+                return None;
+            }
+            if earlier[v].len() == earlier[parent].len() + 1 {
+                contained[parent] = true;
+            }
+        }
+    }
+
+    let cliques: Vec<Vec<usize>> = order.into_iter()
+        .filter(|&v| !contained[v])
+        .map(|v| {
+            let mut clique: Vec<usize> = std::mem::take(&mut earlier[v]);
+            clique.push(v);
+            clique.sort_unstable();
+            clique
+        })
+        .collect();
+    Some(cliques)
+}
+
+/// An ordered partition of the maximal cliques, refined until every vertex's cliques are
+/// consecutive. The classes form a linked list.
+struct CliquePartition {
+    /// The class of every clique.
+    class: Vec<usize>,
+    /// The cliques in every class, with `slot` giving each clique's place in its class.
+    members: Vec<Vec<usize>>,
+    slot: Vec<usize>,
+    /// The classes before and after every class.
+    previous: Vec<Option<usize>>,
+    next: Vec<Option<usize>>,
+}
+
+impl CliquePartition {
+    /// Creates a partition with every clique in one class.
+    fn new(k: usize) -> CliquePartition {
+        CliquePartition {
+            class: vec![0; k],
+            members: vec![(0..k).collect()],
+            slot: (0..k).collect(),
+            previous: vec![None],
+            next: vec![None],
+        }
+    }
+
+    /// Moves `cliques`, all of class `from`, to a new class just after or before it.
+    fn split(&mut self, from: usize, cliques: &[usize], after: bool) {
+        let new: usize = self.members.len();
+        let (left, right): (Option<usize>, Option<usize>) = if after {
+            (Some(from), self.next[from])
+        } else {
+            (self.previous[from], Some(from))
+        };
+        self.previous.push(left);
+        self.next.push(right);
+        if let Some(left) = left {
+            self.next[left] = Some(new);
+        }
+        if let Some(right) = right {
+            self.previous[right] = Some(new);
+        }
+
+        self.members.push(Vec::with_capacity(cliques.len()));
+        for &c in cliques {
+            let place: usize = self.slot[c];
+            let members: &mut Vec<usize> = &mut self.members[from];
+            members.swap_remove(place);
+            if let Some(&moved) = members.get(place) {
+                self.slot[moved] = place;
+            }
+            self.slot[c] = self.members[new].len();
+            self.members[new].push(c);
+            self.class[c] = new;
+        }
+    }
+
+    /// Returns the cliques class by class along the list.
+    fn order(&self) -> Vec<usize> {
+        let mut head: usize = 0;
+        while let Some(previous) = self.previous[head] {
+            head = previous;
+        }
+        let mut order: Vec<usize> = Vec::with_capacity(self.class.len());
+        let mut current: Option<usize> = Some(head);
+        while let Some(c) = current {
+            order.extend(&self.members[c]);
+            current = self.next[c];
+        }
+        order
+    }
+}
+
+/// Orders the maximal cliques of a chordal graph so that the cliques containing any one vertex
+/// could be consecutive, by partition refinement.
+///
+/// Whenever the refinement stalls, the clique LexBFS completed last among those not yet placed
+/// alone is split off its class: for an interval graph it can end the order of that class.
+/// Each vertex whose cliques then span two classes is used once to split the first and last
+/// class it meets, so the order is found in time linear in the total size of the cliques.
+///
+/// For an interval graph every partition on the way is coarser than the final order, so the
+/// cliques of a vertex always fill a run of neighboring classes; `None` is returned as soon as
+/// they do not. Otherwise the caller still checks that the order works.
+fn order_cliques(cliques: &[Vec<usize>], rows: &[Vec<usize>]) -> Option<Vec<usize>> {
+    let k: usize = cliques.len();
+    if k == 0 {
+        return Some(Vec::new());
+    }
+    let mut partition: CliquePartition = CliquePartition::new(k);
+    let mut used: Vec<bool> = vec![false; rows.len()];
+    let mut pivots: Vec<usize> = Vec::new();
+    let mut counts: Vec<usize> = vec![0; rows.len()];
+    let mut next_end: usize = k;
+    let mut touched: Vec<usize> = vec![0; 1];
+    let mut stamp: usize = 0;
+
+    // Queues the unused vertices of the moved cliques that also lie in cliques left behind.
+    let queue_spanning = |moved: &[usize], used: &mut Vec<bool>, pivots: &mut Vec<usize>, counts: &mut Vec<usize>| {
+        for &c in moved {
+            for &v in &cliques[c] {
+                counts[v] += 1;
+            }
+        }
+        for &c in moved {
+            for &v in &cliques[c] {
+                // The first visit of a vertex sees its full count and clears it.
+                if counts[v] > 0 && !used[v] && counts[v] < rows[v].len() {
+                    used[v] = true;
+                    pivots.push(v);
+                }
+                counts[v] = 0;
+            }
+        }
+    };
+
+    loop {
+        if let Some(v) = pivots.pop() {
+            let row: &[usize] = &rows[v];
+            stamp += 1;
+            touched.resize(partition.members.len(), 0);
+            for &c in row {
+                touched[partition.class[c]] = stamp;
+            }
+            // The first and last class of the run are those whose neighbor on that side is
+            // not touched; a second candidate means the run has a gap.
+            let is_touched = |class: Option<usize>| class.is_some_and(|class| touched[class] == stamp);
+            let mut first: Option<usize> = None;
+            let mut last: Option<usize> = None;
+            for &c in row {
+                let class: usize = partition.class[c];
+                if !is_touched(partition.previous[class]) && first.replace(class).is_some_and(|other| other != class) {
+                    return None;
+                }
+                if !is_touched(partition.next[class]) && last.replace(class).is_some_and(|other| other != class) {
+                    return None;
+                }
+            }
+            let (first, last): (usize, usize) = (first?, last?);
+            if first == last {
+                continue;
+            }
+            for (class, after) in [(first, true), (last, false)] {
+                let inside: Vec<usize> = row.iter().copied().filter(|&c| partition.class[c] == class).collect();
+                if inside.len() < partition.members[class].len() {
+                    partition.split(class, &inside, after);
+                    queue_spanning(&inside, &mut used, &mut pivots, &mut counts);
+                }
+            }
+            continue;
+        }
+
+        while next_end > 0 && partition.members[partition.class[next_end - 1]].len() == 1 {
+            next_end -= 1;
+        }
+        if next_end == 0 {
+            return Some(partition.order());
+        }
+        next_end -= 1;
+        let end: usize = next_end;
+        partition.split(partition.class[end], &[end], true);
+        queue_spanning(&[end], &mut used, &mut pivots, &mut counts);
+    }
+}
+
+impl Graph {
+    /// Tests whether the graph is an interval graph and, if so, builds an interval model.
+    ///
+    /// A graph is an interval graph when its maximal cliques can be ordered so that the
+    /// cliques containing any one vertex are consecutive. Chordality is checked with LexBFS,
+    /// which also yields the maximal cliques; the consecutive order is then found by partition
+    /// refinement of the cliques and checked. Every step takes time linear in the size of the
+    /// graph and its cliques. Self-loops and edge weights are ignored.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<IntervalModel>, GraphError>` - The model if the graph is an interval graph,
+    ///   `None` if it is not, or an error if the graph is directed.
+    pub fn is_interval_graph(&self) -> Result<Option<IntervalModel>, GraphError> {
+        if self.directed {
+            return Err(GraphError::NeedsUndirected("Interval graph recognition".to_string()));
+        }
+        let index: GraphIndex = self.index();
+        let adjacent: Vec<HashSet<usize>> = symmetric_neighbors(&index);
+        let neighbors: Vec<Vec<usize>> = adjacent.iter()
+            .map(|set| {
+                let mut list: Vec<usize> = set.iter().copied().collect();
+                list.sort_unstable();
+                list
+            })
+            .collect();
+        let cliques: Vec<Vec<usize>> = match chordal_cliques(&neighbors, &adjacent) {
+            Some(cliques) => cliques,
+            None => return Ok(None),
+        };
+
+        let mut rows: Vec<Vec<usize>> = vec![Vec::new(); index.len()];
+        for (c, clique) in cliques.iter().enumerate() {
+            for &v in clique {
+                rows[v].push(c);
+            }
+        }
+        let order: Vec<usize> = match order_cliques(&cliques, &rows) {
+            Some(order) => order,
+            None => return Ok(None),
+        };
+        let mut position: Vec<usize> = vec![0; cliques.len()];
+        for (i, &c) in order.iter().enumerate() {
+            position[c] = i;
+        }
+        let consecutive: bool = rows.iter().all(|row| {
+            let first: usize = row.iter().map(|&c| position[c]).min().unwrap_or(0);
+            let last: usize = row.iter().map(|&c| position[c]).max().unwrap_or(0);
+            row.is_empty() || last - first + 1 == row.len()
+        });
+        if !consecutive {
+            return Ok(None);
+        }
+
+        let mut intervals: HashMap<String, (usize, usize)> = HashMap::new();
+        for (position, &c) in order.iter().enumerate() {
+            for &v in &cliques[c] {
+                intervals.entry(index.keys[v].clone())
+                    .and_modify(|interval| interval.1 = position)
+                    .or_insert((position, position));
+            }
+        }
+        let cliques: Vec<Vec<String>> = order.iter()
+            .map(|&c| cliques[c].iter().map(|&v| index.keys[v].clone()).collect())
+            .collect();
+        Ok(Some(IntervalModel { intervals, cliques }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::IntervalModel;
+    use crate::graph::testing::graph_from;
+    use crate::graph::{Edge, Graph, Vertex};
+
+    /// Checks that the model's intervals overlap exactly for the adjacent vertices.
+    fn assert_represents(graph: &Graph, model: &IntervalModel) {
+        for a in graph.vertices.keys() {
+            for b in graph.vertices.keys().filter(|&b| b != a) {
+                let (x, y) = (model.intervals[a], model.intervals[b]);
+                assert_eq!(x.0 <= y.1 && y.0 <= x.1, graph.has_edge(a, b), "{} and {}", a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn interval_graph_model() {
+        let graph: Graph = graph_from(false, &[
+            ("a", "b", 1.0), ("a", "c", 1.0), ("b", "c", 1.0),
+            ("c", "d", 1.0), ("d", "e", 1.0), ("c", "e", 1.0), ("e", "f", 1.0),
+        ]);
+        let model: IntervalModel = graph.is_interval_graph().unwrap().unwrap();
+        assert_represents(&graph, &model);
+        assert_eq!(model.cliques.len(), 3);
+        assert_eq!(model.maximum_clique().len(), 3);
+        let colors = model.coloring();
+        assert_eq!(colors.values().max(), Some(&2));
+        for (a, b) in [("a", "b"), ("a", "c"), ("b", "c"), ("c", "d"), ("c", "e"), ("d", "e"), ("e", "f")] {
+            assert_ne!(colors[a], colors[b]);
+        }
+    }
+
+    #[test]
+    fn long_path_is_interval() {
+        let mut graph: Graph = Graph::new(false);
+        for i in 0..2000 {
+            graph.add_vertex(Vertex::new(i.to_string()));
+        }
+        for i in 1..2000 {
+            graph.add_edge(Edge::new((i - 1).to_string(), i.to_string(), 1.0)).unwrap();
+        }
+        let model: IntervalModel = graph.is_interval_graph().unwrap().unwrap();
+        assert_eq!(model.cliques.len(), 1999);
+        assert_eq!(model.intervals["0"].0.abs_diff(model.intervals["1999"].0), 1998);
+    }
+
+    #[test]
+    fn chordal_graph_with_asteroidal_triple_is_not_interval() {
+        // A tree whose three long branches cannot all be laid out on one line.
+        let graph: Graph = graph_from(false, &[
+            ("c", "a1", 1.0), ("a1", "b1", 1.0),
+            ("c", "a2", 1.0), ("a2", "b2", 1.0),
+            ("c", "a3", 1.0), ("a3", "b3", 1.0),
+        ]);
+        assert!(graph.is_interval_graph().unwrap().is_none());
+    }
+
+    #[test]
+    fn cycle_is_not_interval() {
+        let graph: Graph = graph_from(false, &[("a", "b", 1.0), ("b", "c", 1.0), ("c", "d", 1.0), ("d", "a", 1.0)]);
+        assert!(graph.is_interval_graph().unwrap().is_none());
+    }
+
+    #[test]
+    fn directed_graph_is_rejected() {
+        assert!(graph_from(true, &[("a", "b", 1.0)]).is_interval_graph().is_err());
+    }
+}
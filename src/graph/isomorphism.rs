@@ -0,0 +1,265 @@
+use crate::graph::control::ExecutionControl;
+use crate::graph::index::GraphIndex;
+use crate::graph::trace::{trace_counts, trace_span};
+use crate::graph::{Graph, GraphError};
+use std::collections::{BTreeMap, HashMap};
+
+/// One side of a VF2 match: a graph's edges grouped by vertex pair, and how far the partial
+/// mapping has reached into it.
+struct Side {
+    /// The sorted weight bits of the edges from every vertex to each of its out-neighbors,
+    /// including self-loops; an undirected edge is listed from both endpoints.
+    out: Vec<BTreeMap<usize, Vec<u32>>>,
+    /// The same edges grouped by their head, for directed graphs.
+    into: Vec<BTreeMap<usize, Vec<u32>>>,
+    /// The vertex each vertex is mapped to on the other side.
+    core: Vec<Option<usize>>,
+    /// The depth at which each vertex first became adjacent to the mapping, or `0`.
+    terminal: Vec<usize>,
+}
+
+impl Side {
+    fn new(index: &GraphIndex) -> Side {
+        let n: usize = index.len();
+        let mut side: Side = Side { out: vec![BTreeMap::new(); n], into: vec![BTreeMap::new(); n], core: vec![None; n], terminal: vec![0; n] };
+        for (u, neighbors) in index.adjacency.iter().enumerate() {
+            for &(v, weight) in neighbors {
+                side.out[u].entry(v).or_default().push(weight.to_bits());
+                side.into[v].entry(u).or_default().push(weight.to_bits());
+            }
+        }
+        for groups in side.out.iter_mut().chain(side.into.iter_mut()) {
+            groups.values_mut().for_each(|bits| bits.sort_unstable());
+        }
+        side
+    }
+
+    /// Returns the neighbors of `v` in either direction, other than itself.
+    fn neighbors(&self, v: usize) -> impl Iterator<Item = usize> + '_ {
+        self.out[v].keys().chain(self.into[v].keys()).copied().filter(move |&w| w != v)
+    }
+
+    /// Counts the unmapped neighbors of `v` in the terminal set and outside it, each
+    /// direction apart.
+    fn look_ahead(&self, v: usize) -> [usize; 4] {
+        let mut counts: [usize; 4] = [0; 4];
+        for (offset, groups) in [(0, &self.out[v]), (2, &self.into[v])] {
+            for &w in groups.keys().filter(|&&w| w != v && self.core[w].is_none()) {
+                counts[offset + usize::from(self.terminal[w] == 0)] += 1;
+            }
+        }
+        counts
+    }
+
+    /// Adds `v` to the mapping at `depth`, pulling its neighbors into the terminal set.
+    fn extend(&mut self, v: usize, to: usize, depth: usize) {
+        self.core[v] = Some(to);
+        if self.terminal[v] == 0 {
+            self.terminal[v] = depth;
+        }
+        let neighbors: Vec<usize> = self.neighbors(v).collect();
+        for w in neighbors {
+            if self.terminal[w] == 0 {
+                self.terminal[w] = depth;
+            }
+        }
+    }
+
+    /// Undoes `extend` for `v` at `depth`.
+    fn retract(&mut self, v: usize, depth: usize) {
+        self.core[v] = None;
+        let neighbors: Vec<usize> = self.neighbors(v).chain([v]).collect();
+        for w in neighbors {
+            if self.terminal[w] == depth {
+                self.terminal[w] = 0;
+            }
+        }
+    }
+}
+
+/// Returns whether every edge between `v` and an already mapped vertex (or itself) in
+/// `from` has a counterpart with the same weights between `w` and its image in `to`, in
+/// both directions.
+fn compatible_edges(from: &Side, to: &Side, v: usize, w: usize) -> bool {
+    let matches = |mine: &BTreeMap<usize, Vec<u32>>, theirs: &BTreeMap<usize, Vec<u32>>, mapped: &Side| {
+        mine.iter()
+            .filter_map(|(&x, bits)| if x == v { Some((w, bits)) } else { from.core[x].map(|y| (y, bits)) })
+            .all(|(y, bits)| theirs.get(&y) == Some(bits))
+            && theirs.keys().filter(|&&y| y == w || mapped.core[y].is_some()).count()
+                == mine.keys().filter(|&&x| x == v || from.core[x].is_some()).count()
+    };
+    matches(&from.out[v], &to.out[w], to) && matches(&from.into[v], &to.into[w], to)
+}
+
+/// The VF2 search of Cordella et al., extending a partial mapping one vertex pair at a time.
+struct Matcher<'a> {
+    first: Side,
+    second: Side,
+    control: &'a ExecutionControl,
+    expanded: usize,
+}
+
+impl Matcher<'_> {
+    /// Extends the mapping of `depth - 1` pairs to a full isomorphism, if one exists.
+    fn search(&mut self, depth: usize) -> Result<bool, GraphError> {
+        self.expanded += 1;
+        self.control.step(self.expanded, None)?;
+        let n: usize = self.first.core.len();
+        if depth > n {
+            return Ok(true);
+        }
+        // Fix one vertex of the second graph, preferring the terminal set, and try every
+        // vertex of the first graph in the same set for it.
+        let in_terminal = |side: &Side, v: usize| side.core[v].is_none() && side.terminal[v] > 0;
+        let target: usize = match (0..n).find(|&w| in_terminal(&self.second, w)) {
+            Some(w) => w,
+            None => (0..n).find(|&w| self.second.core[w].is_none()).unwrap(),
+        };
+        let wants_terminal: bool = in_terminal(&self.second, target);
+        let target_ahead: [usize; 4] = self.second.look_ahead(target);
+        for candidate in 0..n {
+            if self.first.core[candidate].is_some() || in_terminal(&self.first, candidate) != wants_terminal {
+                continue;
+            }
+            if self.first.look_ahead(candidate) != target_ahead || !compatible_edges(&self.first, &self.second, candidate, target) {
+                continue;
+            }
+            self.first.extend(candidate, target, depth);
+            self.second.extend(target, candidate, depth);
+            if self.search(depth + 1)? {
+                return Ok(true);
+            }
+            self.first.retract(candidate, depth);
+            self.second.retract(target, depth);
+        }
+        Ok(false)
+    }
+}
+
+/// Returns the sorted `(in-degree, out-degree)` of every vertex, which isomorphic graphs share.
+fn degree_sequence(index: &GraphIndex) -> Vec<(usize, usize)> {
+    let mut in_degrees: Vec<usize> = vec![0; index.len()];
+    index.adjacency.iter().flatten().for_each(|&(v, _)| in_degrees[v] += 1);
+    let mut degrees: Vec<(usize, usize)> = in_degrees.into_iter().zip(index.adjacency.iter().map(Vec::len)).collect();
+    degrees.sort_unstable();
+    degrees
+}
+
+impl Graph {
+    /// Checks whether the two graphs have the same structure once their vertices are
+    /// renamed, as `isomorphism` finds.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The graph to compare with.
+    ///
+    /// # Returns
+    ///
+    /// * A `bool` that is `true` if the graphs are isomorphic.
+    pub fn is_isomorphic_to(&self, other: &Graph) -> bool {
+        self.isomorphism(other).is_some()
+    }
+
+    /// Finds a renaming of the vertices that turns this graph into `other`, with the VF2
+    /// algorithm.
+    ///
+    /// Vertex keys and attributes are ignored; direction, edge weights, parallel edges and
+    /// self-loops must all correspond. The search pairs vertices one at a time, growing the
+    /// mapping along edges and pruning pairs whose mapped edges or neighborhoods differ.
+    /// It is fast on most graphs but exponential in the worst case, such as on large
+    /// regular graphs; `isomorphism_with` can stop it early.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The graph to compare with.
+    ///
+    /// # Returns
+    ///
+    /// * An `Option<HashMap<String, String>>` with the key in `other` of every vertex, or
+    ///   `None` if the graphs are not isomorphic.
+    pub fn isomorphism(&self, other: &Graph) -> Option<HashMap<String, String>> {
+        // Without limits the run is never stopped.
+        self.isomorphism_with(other, &ExecutionControl::new()).unwrap()
+    }
+
+    /// Finds an isomorphism as `isomorphism` does, under a deadline, cancellation token or
+    /// progress callback.
+    ///
+    /// Progress counts the vertex pairs tried; their total is not known in advance.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The graph to compare with.
+    /// * `control` - The limits and hooks for the run.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<HashMap<String, String>>, GraphError>` - The mapping, `None` if the
+    ///   graphs are not isomorphic, or an error if the run was stopped.
+    pub fn isomorphism_with(&self, other: &Graph, control: &ExecutionControl) -> Result<Option<HashMap<String, String>>, GraphError> {
+        if self.directed != other.directed || self.vertices.len() != other.vertices.len() || self.edge_count != other.edge_count {
+            return Ok(None);
+        }
+        let (first, second): (GraphIndex, GraphIndex) = (self.index(), other.index());
+        if degree_sequence(&first) != degree_sequence(&second) {
+            return Ok(None);
+        }
+        trace_span!("isomorphism", vertices = first.len());
+        let mut matcher: Matcher = Matcher { first: Side::new(&first), second: Side::new(&second), control, expanded: 0 };
+        let found: bool = matcher.search(1)?;
+        trace_counts!("isomorphism finished", expanded = matcher.expanded);
+        if !found {
+            return Ok(None);
+        }
+        Ok(Some(first.keys.iter()
+            .zip(&matcher.first.core)
+            .map(|(key, &image)| (key.clone(), second.keys[image.unwrap()].clone()))
+            .collect()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::graph::testing::graph_from;
+    use crate::graph::Graph;
+    use std::collections::HashMap;
+
+    #[test]
+    fn isomorphism_maps_scrambled_labels() {
+        let first: Graph = graph_from(true, &[("A", "B", 1.0), ("B", "C", 2.0), ("C", "A", 3.0), ("C", "D", 1.0)]);
+        let second: Graph = graph_from(true, &[("x", "z", 1.0), ("y", "w", 1.0), ("w", "x", 2.0), ("x", "y", 3.0)]);
+        let mapping: HashMap<String, String> = first.isomorphism(&second).unwrap();
+        let expected: HashMap<String, String> = [("A", "y"), ("B", "w"), ("C", "x"), ("D", "z")]
+            .iter()
+            .map(|&(from, to)| (from.to_string(), to.to_string()))
+            .collect();
+        assert_eq!(mapping, expected);
+    }
+
+    #[test]
+    fn isomorphism_respects_weights_and_direction() {
+        let first: Graph = graph_from(true, &[("A", "B", 1.0), ("B", "C", 2.0)]);
+        assert!(!first.is_isomorphic_to(&graph_from(true, &[("A", "B", 2.0), ("B", "C", 1.0)])));
+        assert!(!first.is_isomorphic_to(&graph_from(false, &[("A", "B", 1.0), ("B", "C", 2.0)])));
+        assert!(first.is_isomorphic_to(&graph_from(true, &[("Q", "P", 2.0), ("R", "Q", 1.0)])));
+    }
+
+    #[test]
+    fn isomorphism_separates_graphs_with_equal_degree_sequences() {
+        // Two triangles against a hexagon: every vertex has degree 2 in both.
+        let triangles: Graph = graph_from(false, &[("A", "B", 1.0), ("B", "C", 1.0), ("C", "A", 1.0), ("D", "E", 1.0), ("E", "F", 1.0), ("F", "D", 1.0)]);
+        let hexagon: Graph = graph_from(false, &[("A", "B", 1.0), ("B", "C", 1.0), ("C", "D", 1.0), ("D", "E", 1.0), ("E", "F", 1.0), ("F", "A", 1.0)]);
+        assert!(!triangles.is_isomorphic_to(&hexagon));
+        assert!(hexagon.is_isomorphic_to(&graph_from(false, &[("u", "w", 1.0), ("w", "y", 1.0), ("y", "v", 1.0), ("v", "x", 1.0), ("x", "z", 1.0), ("z", "u", 1.0)])));
+    }
+
+    #[test]
+    fn isomorphism_counts_parallel_edges_and_self_loops() {
+        let parallel: Graph = graph_from(false, &[("A", "B", 1.0), ("A", "B", 1.0), ("B", "C", 1.0)]);
+        let spread: Graph = graph_from(false, &[("A", "B", 1.0), ("B", "C", 1.0), ("C", "B", 1.0)]);
+        assert!(parallel.is_isomorphic_to(&spread));
+        let looped: Graph = graph_from(false, &[("A", "A", 1.0), ("A", "B", 1.0), ("B", "C", 1.0)]);
+        assert!(!looped.is_isomorphic_to(&graph_from(false, &[("A", "B", 1.0), ("B", "B", 1.0), ("B", "C", 1.0)])));
+        assert!(looped.is_isomorphic_to(&graph_from(false, &[("A", "B", 1.0), ("B", "C", 1.0), ("C", "C", 1.0)])));
+    }
+}
@@ -0,0 +1,183 @@
+use crate::graph::serialize::{Migrations, FORMAT_VERSION};
+use crate::graph::{AttrValue, Edge, Graph, GraphError, Vertex};
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+
+/// The saved form of a graph: every vertex once with its attributes, and every edge once.
+#[derive(Serialize)]
+struct GraphRecordRef<'a> {
+    directed: bool,
+    vertices: Vec<VertexRecordRef<'a>>,
+    edges: Vec<&'a Edge>,
+}
+
+#[derive(Serialize)]
+struct VertexRecordRef<'a> {
+    key: &'a String,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    attributes: BTreeMap<&'a String, &'a AttrValue>,
+}
+
+#[derive(Deserialize)]
+struct GraphRecord {
+    directed: bool,
+    vertices: Vec<VertexRecord>,
+    edges: Vec<Edge>,
+}
+
+#[derive(Deserialize)]
+struct VertexRecord {
+    key: String,
+    #[serde(default)]
+    attributes: HashMap<String, AttrValue>,
+}
+
+/// Graphs are saved as a list of vertices and a list of edges, so an undirected edge appears
+/// once even though both endpoints store it. Vertices are written in key order.
+impl Serialize for Graph {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let record: GraphRecordRef = GraphRecordRef {
+            directed: self.directed,
+            vertices: self.vertices.iter()
+                .map(|(key, vertex)| VertexRecordRef { key, attributes: vertex.attributes.iter().collect() })
+                .collect(),
+            edges: self.stored_edges(),
+        };
+        record.serialize(serializer)
+    }
+}
+
+/// Graphs are rebuilt with `add_vertex` and `add_edge`, so saved data with an edge to a
+/// missing vertex is rejected.
+impl<'de> Deserialize<'de> for Graph {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Graph, D::Error> {
+        let record: GraphRecord = GraphRecord::deserialize(deserializer)?;
+        let mut graph: Graph = Graph::new(record.directed);
+        for saved in record.vertices {
+            let mut vertex: Vertex = Vertex::new(saved.key);
+            vertex.attributes = saved.attributes;
+            graph.add_vertex(vertex);
+        }
+        for edge in record.edges {
+            graph.add_edge(edge).map_err(D::Error::custom)?;
+        }
+        Ok(graph)
+    }
+}
+
+impl Graph {
+    /// Saves the graph as JSON.
+    ///
+    /// The graph is wrapped in an object with the `format_version` it was written with, so
+    /// `Graph::from_json_with` can upgrade files written by older versions of the crate.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<String, GraphError>` - The JSON text, or an error if a key or weight cannot be
+    ///   represented in JSON.
+    pub fn to_json(&self) -> Result<String, GraphError> {
+        let graph: Value = serde_json::to_value(self).map_err(|e| GraphError::InvalidArgument(e.to_string()))?;
+        let envelope: Value = serde_json::json!({ "format_version": FORMAT_VERSION, "graph": graph });
+        serde_json::to_string_pretty(&envelope).map_err(|e| GraphError::InvalidArgument(e.to_string()))
+    }
+
+    /// Loads a graph saved with `Graph::to_json`, by this or any newer version of the crate.
+    ///
+    /// # Arguments
+    ///
+    /// * `json` - The JSON text.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Graph, GraphError>` - The graph, or an error if the text is not a saved graph
+    ///   or comes from an older format version with no migration.
+    pub fn from_json(json: &str) -> Result<Graph, GraphError> {
+        Graph::from_json_with(json, &Migrations::new())
+    }
+
+    /// Loads a graph saved with `Graph::to_json`, upgrading older format versions with the
+    /// given migrations first.
+    ///
+    /// Fields this version does not know are ignored, so files from newer versions load as
+    /// long as the fields it needs are unchanged.
+    ///
+    /// # Arguments
+    ///
+    /// * `json` - The JSON text.
+    /// * `migrations` - The steps that upgrade the whole saved object of an older file.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Graph, GraphError>` - The graph, or an error if the text is not a saved graph
+    ///   or a migration is missing or fails.
+    pub fn from_json_with(json: &str, migrations: &Migrations<Value>) -> Result<Graph, GraphError> {
+        let mut envelope: Value = serde_json::from_str(json).map_err(|e| GraphError::Parse(e.to_string()))?;
+        let version: u32 = envelope.get("format_version")
+            .and_then(Value::as_u64)
+            .and_then(|version| u32::try_from(version).ok())
+            .ok_or_else(|| GraphError::Parse("The JSON has no format version".to_string()))?;
+        migrations.migrate(&mut envelope, version)?;
+        let graph: Value = envelope.get_mut("graph").map(Value::take).ok_or_else(|| GraphError::Parse("The JSON has no graph".to_string()))?;
+        serde_json::from_value(graph).map_err(|e| GraphError::Parse(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::graph::serialize::Migrations;
+    use crate::graph::{AttrValue, Edge, Graph, Vertex};
+    use serde_json::Value;
+
+    fn sample() -> Graph {
+        let mut graph: Graph = Graph::new(false);
+        let mut a: Vertex = Vertex::new("a".to_string());
+        a.set_attr("position", AttrValue::Vector(vec![1.0, 2.0]));
+        a.set_attr("name", AttrValue::Text("first".to_string()));
+        graph.add_vertex(a);
+        graph.add_vertex(Vertex::new("b".to_string()));
+        graph.add_edge(Edge::asymmetric("a".to_string(), "b".to_string(), 1.0, 4.0)).unwrap();
+        graph.add_edge(Edge::new("b".to_string(), "b".to_string(), 2.0)).unwrap();
+        graph
+    }
+
+    #[test]
+    fn round_trip_keeps_edges_and_attributes() {
+        let graph: Graph = sample();
+        let json: String = graph.to_json().unwrap();
+        let loaded: Graph = Graph::from_json(&json).unwrap();
+        assert_eq!(loaded.to_json().unwrap(), json);
+        assert_eq!(loaded.edge_count, 2);
+        assert_eq!(loaded.vertices["a"].attributes, graph.vertices["a"].attributes);
+        // An undirected edge is written once although both endpoints store it.
+        let saved: Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(saved["graph"]["edges"].as_array().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn older_versions_need_a_migration() {
+        let mut saved: Value = serde_json::from_str(&sample().to_json().unwrap()).unwrap();
+        saved["format_version"] = Value::from(0);
+        let graph: Value = saved.as_object_mut().unwrap().remove("graph").unwrap();
+        saved["network"] = graph;
+        let json: String = saved.to_string();
+
+        assert!(Graph::from_json(&json).is_err());
+        let migrations: Migrations<Value> = Migrations::new().register(0, |saved: &mut Value| {
+            let graph: Value = saved.as_object_mut().and_then(|fields| fields.remove("network")).ok_or("no network")?;
+            saved["graph"] = graph;
+            Ok(())
+        });
+        let loaded: Graph = Graph::from_json_with(&json, &migrations).unwrap();
+        assert_eq!(loaded.to_json().unwrap(), sample().to_json().unwrap());
+    }
+
+    #[test]
+    fn invalid_documents_are_rejected() {
+        assert!(Graph::from_json("{").is_err());
+        assert!(Graph::from_json(r#"{"graph": {"directed": true, "vertices": [], "edges": []}}"#).is_err());
+        let missing: &str = r#"{"format_version": 1, "graph": {"directed": true, "vertices": [{"key": "a"}], "edges": [{"vertex1": "a", "vertex2": "b", "weight": 1.0}]}}"#;
+        assert!(Graph::from_json(missing).is_err());
+    }
+}
@@ -0,0 +1,174 @@
+use crate::graph::index::GraphIndex;
+use crate::graph::trace::{trace_counts, trace_span};
+
+/// A dense row-major matrix.
+pub(crate) type Matrix = Vec<Vec<f64>>;
+
+/// Builds the weighted Laplacian `D - A` of an undirected index, ignoring self-loops.
+pub(crate) fn laplacian(index: &GraphIndex) -> Matrix {
+    let n: usize = index.len();
+    let mut matrix: Matrix = vec![vec![0.0; n]; n];
+    for (u, neighbors) in index.adjacency.iter().enumerate() {
+        for &(v, weight) in neighbors {
+            if u != v {
+                matrix[u][v] -= f64::from(weight);
+                matrix[u][u] += f64::from(weight);
+            }
+        }
+    }
+    matrix
+}
+
+/// Inverts a square matrix with Gauss-Jordan elimination and partial pivoting.
+///
+/// Returns `None` if the matrix is (numerically) singular.
+pub(crate) fn invert(matrix: &Matrix) -> Option<Matrix> {
+    let n: usize = matrix.len();
+    let mut a: Matrix = matrix.clone();
+    let mut inverse: Matrix = (0..n)
+        .map(|i| (0..n).map(|j| if i == j { 1.0 } else { 0.0 }).collect())
+        .collect();
+
+    for col in 0..n {
+        let pivot: usize = (col..n).max_by(|&x, &y| a[x][col].abs().total_cmp(&a[y][col].abs()))?;
+        if a[pivot][col].abs() < 1e-12 {
+            return None;
+        }
+        a.swap(col, pivot);
+        inverse.swap(col, pivot);
+
+        let scale: f64 = a[col][col];
+        for j in 0..n {
+            a[col][j] /= scale;
+            inverse[col][j] /= scale;
+        }
+        for row in 0..n {
+            if row == col || a[row][col] == 0.0 {
+                continue;
+            }
+            let factor: f64 = a[row][col];
+            for j in 0..n {
+                a[row][j] -= factor * a[col][j];
+                inverse[row][j] -= factor * inverse[col][j];
+            }
+        }
+    }
+    Some(inverse)
+}
+
+/// Returns the inverse of the Laplacian grounded at vertex `0`, padded back to `n x n`.
+///
+/// For a connected graph, `C[s][s] + C[t][t] - 2 C[s][t]` is the effective resistance
+/// between `s` and `t`, and `C (e_s - e_t)` gives node potentials for a unit current.
+pub(crate) fn grounded_inverse(index: &GraphIndex) -> Option<Matrix> {
+    let n: usize = index.len();
+    let full: Matrix = laplacian(index);
+    let reduced: Matrix = full[1..].iter().map(|row| row[1..].to_vec()).collect();
+    let inverse: Matrix = invert(&reduced)?;
+
+    let mut padded: Matrix = vec![vec![0.0; n]; n];
+    for i in 1..n {
+        padded[i][1..].copy_from_slice(&inverse[i - 1]);
+    }
+    Some(padded)
+}
+
+/// Solves `L x = b` for the weighted Laplacian of an undirected index with preconditioned
+/// conjugate gradient, using the weighted degrees as a Jacobi preconditioner.
+///
+/// `b` must sum to zero over every connected component; the solution is then unique up to a
+/// constant per component. Iteration stops once the residual norm drops below `tolerance`
+/// times the norm of `b`.
+pub(crate) fn solve_laplacian(index: &GraphIndex, b: &[f64], tolerance: f64) -> Vec<f64> {
+    let n: usize = index.len();
+    trace_span!("solve_laplacian", vertices = n);
+    let apply = |x: &[f64]| -> Vec<f64> {
+        (0..n)
+            .map(|u| {
+                index.adjacency[u].iter()
+                    .filter(|&&(v, _)| v != u)
+                    .map(|&(v, weight)| f64::from(weight) * (x[u] - x[v]))
+                    .sum()
+            })
+            .collect()
+    };
+    let dot = |a: &[f64], b: &[f64]| -> f64 { a.iter().zip(b).map(|(x, y)| x * y).sum() };
+    let degree: Vec<f64> = (0..n)
+        .map(|u| {
+            let total: f64 = index.adjacency[u].iter().filter(|&&(v, _)| v != u).map(|&(_, w)| f64::from(w)).sum();
+            if total > 0.0 { total } else { 1.0 }
+        })
+        .collect();
+
+    let mut x: Vec<f64> = vec![0.0; n];
+    let mut residual: Vec<f64> = b.to_vec();
+    let mut preconditioned: Vec<f64> = residual.iter().zip(&degree).map(|(r, d)| r / d).collect();
+    let mut direction: Vec<f64> = preconditioned.clone();
+    let mut rho: f64 = dot(&residual, &preconditioned);
+    let threshold: f64 = tolerance * dot(b, b).sqrt();
+
+    let mut iterations: usize = 0;
+    for _ in 0..(10 * n).max(100) {
+        if dot(&residual, &residual).sqrt() <= threshold {
+            break;
+        }
+        iterations += 1;
+        let image: Vec<f64> = apply(&direction);
+        let curvature: f64 = dot(&direction, &image);
+        if curvature <= 0.0 {
+            break;
+        }
+        let step: f64 = rho / curvature;
+        for i in 0..n {
+            x[i] += step * direction[i];
+            residual[i] -= step * image[i];
+            preconditioned[i] = residual[i] / degree[i];
+        }
+        let next: f64 = dot(&residual, &preconditioned);
+        let beta: f64 = next / rho;
+        rho = next;
+        for i in 0..n {
+            direction[i] = preconditioned[i] + beta * direction[i];
+        }
+    }
+    trace_counts!("solve_laplacian finished", iterations = iterations, residual = dot(&residual, &residual).sqrt());
+    x
+}
+
+/// Returns the eigenvalues of a symmetric matrix in descending order, found with cyclic
+/// Jacobi rotations.
+pub(crate) fn symmetric_eigenvalues(matrix: &Matrix) -> Vec<f64> {
+    let n: usize = matrix.len();
+    let mut a: Matrix = matrix.clone();
+    let total: f64 = a.iter().flatten().map(|x| x * x).sum();
+    for _ in 0..100 {
+        let off_diagonal: f64 = (0..n).flat_map(|p| ((p + 1)..n).map(move |q| (p, q))).map(|(p, q)| a[p][q] * a[p][q]).sum();
+        if off_diagonal <= 1e-24 * total {
+            break;
+        }
+        for p in 0..n {
+            for q in (p + 1)..n {
+                if a[p][q] == 0.0 {
+                    continue;
+                }
+                // The rotation angle that zeroes a[p][q], taking the smaller root for stability.
+                let theta: f64 = (a[q][q] - a[p][p]) / (2.0 * a[p][q]);
+                let t: f64 = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+                let c: f64 = 1.0 / (t * t + 1.0).sqrt();
+                let s: f64 = t * c;
+                for row in a.iter_mut() {
+                    let (kp, kq) = (row[p], row[q]);
+                    row[p] = c * kp - s * kq;
+                    row[q] = s * kp + c * kq;
+                }
+                let (upper, lower) = a.split_at_mut(q);
+                for (pk, qk) in upper[p].iter_mut().zip(lower[0].iter_mut()) {
+                    (*pk, *qk) = (c * *pk - s * *qk, s * *pk + c * *qk);
+                }
+            }
+        }
+    }
+    let mut eigenvalues: Vec<f64> = (0..n).map(|i| a[i][i]).collect();
+    eigenvalues.sort_by(|x, y| y.total_cmp(x));
+    eigenvalues
+}
@@ -0,0 +1,394 @@
+//! Graphs served from memory-mapped files.
+//!
+//! `Graph::save_mapped` keeps only the structure: vertex and edge attributes, the
+//! `GraphMode`, the `VertexOrder` and the weight semantics are dropped, so a graph loaded
+//! back with `MappedGraph::to_graph` has default settings and no attributes.
+
+use crate::graph::csr::Csr;
+use crate::graph::index::GraphIndex;
+use crate::graph::path::Path;
+use crate::graph::serialize::{section_ranges, write_sections, Encoder, Sections};
+use crate::graph::view::{self, GraphView};
+use crate::graph::{Graph, GraphError};
+use memmap2::Mmap;
+use std::fs::{self, File};
+use std::ops::Range;
+
+/// The sections of a mapped graph file, which together hold the CSR arrays.
+const SECTIONS: [&str; 6] = ["csr", "csr_key_offsets", "csr_keys", "csr_row_offsets", "csr_columns", "csr_weights"];
+
+/// A read-only graph served straight from a memory-mapped file, for graphs larger than
+/// memory.
+///
+/// The file, written by `Graph::save_mapped`, is a binary graph file whose sections hold the Compressed Sparse Row arrays,
+/// with vertices in key order. Nothing is read when the file is opened beyond its section
+/// table; the operating system pages in the rows a query touches and can drop them again
+/// under memory pressure. Lookups by key are binary searches. `MappedGraph` implements
+/// `GraphView`, so the traversals in `view` run on it as they do on a `Graph`, keeping in
+/// memory only the vertices they reach.
+pub struct MappedGraph {
+    map: Mmap,
+    directed: bool,
+    vertex_count: usize,
+    entry_count: usize,
+    key_offsets: Range<usize>,
+    keys: Range<usize>,
+    row_offsets: Range<usize>,
+    columns: Range<usize>,
+    weights: Range<usize>,
+}
+
+impl MappedGraph {
+    /// Opens a file written by `Graph::save_mapped`.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The file to map.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<MappedGraph, GraphError>` - The graph, or an error if the file cannot be read,
+    ///   is not a mapped graph or its sections have the wrong sizes.
+    pub fn open<P: AsRef<std::path::Path>>(path: P) -> Result<MappedGraph, GraphError> {
+        let path: &std::path::Path = path.as_ref();
+        let file: File = File::open(path).map_err(|e| GraphError::Io(format!("Could not read {}: {}", path.display(), e)))?;
+        // SAFETY: the map is only read, through bounds-checked slices. The file must not be
+        // changed while it is mapped, as with any memory-mapped file.
+        let map: Mmap = unsafe { Mmap::map(&file) }.map_err(|e| GraphError::Io(format!("Could not map {}: {}", path.display(), e)))?;
+
+        let (_, mut ranges) = section_ranges(&map)?;
+        let mut take = |name: &str| ranges.remove(name).ok_or_else(|| GraphError::Parse(format!("The saved graph has no {} section", name)));
+        let [header, key_offsets, keys, row_offsets, columns, weights] = SECTIONS.map(&mut take);
+        let header: Range<usize> = header?;
+        if header.len() != 17 {
+            return Err(GraphError::Parse("The csr section has the wrong size".to_string()));
+        }
+        let count = |at: usize| usize::try_from(read_u64(&map, at)).map_err(|_| GraphError::Parse("A length does not fit in memory".to_string()));
+        let (n, m): (usize, usize) = (count(header.start + 1)?, count(header.start + 9)?);
+        let graph: MappedGraph = MappedGraph {
+            directed: map[header.start] != 0,
+            vertex_count: n,
+            entry_count: m,
+            key_offsets: key_offsets?,
+            keys: keys?,
+            row_offsets: row_offsets?,
+            columns: columns?,
+            weights: weights?,
+            map,
+        };
+        let sizes: [(&Range<usize>, Option<usize>); 4] = [
+            (&graph.key_offsets, n.checked_add(1).and_then(|rows| rows.checked_mul(8))),
+            (&graph.row_offsets, n.checked_add(1).and_then(|rows| rows.checked_mul(8))),
+            (&graph.columns, m.checked_mul(8)),
+            (&graph.weights, m.checked_mul(4)),
+        ];
+        if sizes.iter().any(|&(range, size)| Some(range.len()) != size) {
+            return Err(GraphError::Parse("The CSR sections do not match the vertex and entry counts".to_string()));
+        }
+        if graph.offset(&graph.row_offsets, n) != m as u64 || graph.offset(&graph.key_offsets, n) != graph.keys.len() as u64 {
+            return Err(GraphError::Parse("The CSR offsets do not end at the end of their arrays".to_string()));
+        }
+        Ok(graph)
+    }
+
+    /// Reads entry `i` of a `u64` array section.
+    fn offset(&self, section: &Range<usize>, i: usize) -> u64 {
+        read_u64(&self.map, section.start + 8 * i)
+    }
+
+    /// Reads entry `i` of a `u64` array section as a bounds-checked position within `limit`.
+    fn bounded(&self, section: &Range<usize>, i: usize, limit: usize) -> Result<usize, GraphError> {
+        usize::try_from(self.offset(section, i))
+            .ok()
+            .filter(|&value| value <= limit)
+            .ok_or_else(|| GraphError::Parse(format!("The mapped graph has an offset or column out of range at entry {}", i)))
+    }
+
+    /// Returns the number of vertices.
+    pub fn vertex_count(&self) -> usize {
+        self.vertex_count
+    }
+
+    /// Returns the number of row entries, in which an undirected edge counts once for
+    /// each endpoint.
+    pub fn entry_count(&self) -> usize {
+        self.entry_count
+    }
+
+    /// Returns whether the graph is directed.
+    pub fn is_directed(&self) -> bool {
+        self.directed
+    }
+
+    /// Returns the key of the vertex at position `i`, in key order.
+    ///
+    /// # Arguments
+    ///
+    /// * `i` - The position, below `vertex_count`.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<&str, GraphError>` - The key, or an error if `i` is out of range or the file
+    ///   is damaged.
+    pub fn key(&self, i: usize) -> Result<&str, GraphError> {
+        if i >= self.vertex_count {
+            return Err(GraphError::InvalidArgument(format!("Position {} is out of range", i)));
+        }
+        let length: usize = self.keys.len();
+        let (start, end) = (self.bounded(&self.key_offsets, i, length)?, self.bounded(&self.key_offsets, i + 1, length)?);
+        let bytes: &[u8] = self.map[self.keys.clone()].get(start..end)
+            .ok_or_else(|| GraphError::Parse(format!("The key at position {} is out of range", i)))?;
+        std::str::from_utf8(bytes).map_err(|_| GraphError::Parse("A string is not valid UTF-8".to_string()))
+    }
+
+    /// Returns the position of a vertex, found by binary search over the sorted keys.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key of the vertex.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<usize>, GraphError>` - The position, `None` if the vertex does not
+    ///   exist, or an error if the file is damaged.
+    pub fn position(&self, key: &str) -> Result<Option<usize>, GraphError> {
+        let (mut low, mut high): (usize, usize) = (0, self.vertex_count);
+        while low < high {
+            let middle: usize = low + (high - low) / 2;
+            match self.key(middle)?.cmp(key) {
+                std::cmp::Ordering::Less => low = middle + 1,
+                std::cmp::Ordering::Greater => high = middle,
+                std::cmp::Ordering::Equal => return Ok(Some(middle)),
+            }
+        }
+        Ok(None)
+    }
+
+    /// Returns the position of a vertex, or an error if it does not exist.
+    fn require(&self, key: &str) -> Result<usize, GraphError> {
+        self.position(key)?.ok_or_else(|| GraphError::VertexNotFound(key.to_string()))
+    }
+
+    /// Returns the entries of row `u` as `(column, weight)` pairs.
+    fn row(&self, u: usize) -> Result<Vec<(usize, f32)>, GraphError> {
+        let m: usize = self.entry_count;
+        let (start, end) = (self.bounded(&self.row_offsets, u, m)?, self.bounded(&self.row_offsets, u + 1, m)?);
+        if end < start {
+            return Err(GraphError::Parse(format!("The row offsets of position {} decrease", u)));
+        }
+        (start..end)
+            .map(|i| {
+                let column: usize = self.bounded(&self.columns, i, self.vertex_count.saturating_sub(1))?;
+                let at: usize = self.weights.start + 4 * i;
+                Ok((column, f32::from_le_bytes(self.map[at..at + 4].try_into().unwrap())))
+            })
+            .collect()
+    }
+
+    /// Returns whether a vertex with the given key exists.
+    pub fn has_vertex(&self, key: &str) -> Result<bool, GraphError> {
+        Ok(self.position(key)?.is_some())
+    }
+
+    /// Returns whether an edge leads from `from` to `to`, in either direction in an
+    /// undirected graph.
+    pub fn has_edge(&self, from: &str, to: &str) -> Result<bool, GraphError> {
+        let (Some(u), Some(v)) = (self.position(from)?, self.position(to)?) else {
+            return Ok(false);
+        };
+        Ok(self.row(u)?.iter().any(|&(w, _)| w == v))
+    }
+
+    /// Returns the vertices reached from `key` in one step, with the cost of each step, in
+    /// the order the edges were saved. A vertex joined by parallel edges appears once per
+    /// edge.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key of the vertex.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<(String, f32)>, GraphError>` - The neighbors, or an error if the vertex
+    ///   does not exist or the file is damaged.
+    pub fn neighbors(&self, key: &str) -> Result<Vec<(String, f32)>, GraphError> {
+        self.row(self.require(key)?)?
+            .into_iter()
+            .map(|(v, weight)| Ok((self.key(v)?.to_string(), weight)))
+            .collect()
+    }
+
+    /// Returns the number of row entries of a vertex, its out-degree in a directed graph.
+    pub fn degree(&self, key: &str) -> Result<usize, GraphError> {
+        let u: usize = self.require(key)?;
+        let m: usize = self.entry_count;
+        Ok(self.bounded(&self.row_offsets, u + 1, m)?.saturating_sub(self.bounded(&self.row_offsets, u, m)?))
+    }
+
+    /// Traverses the graph breadth-first from `start`, as `view::bfs` does.
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - The key of the vertex to start from.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<String>, GraphError>` - The keys of the reachable vertices, nearest
+    ///   first, or an error if `start` does not exist or the file is damaged.
+    pub fn bfs(&self, start: &str) -> Result<Vec<String>, GraphError> {
+        view::bfs(self, start)
+    }
+
+    /// Finds a shortest path with Dijkstra's algorithm, as `view::shortest_path` does.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - The key of the start vertex.
+    /// * `to` - The key of the target vertex.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<Path>, GraphError>` - The path, `None` if `to` is unreachable, or an
+    ///   error if a vertex does not exist, a weight is negative or the file is damaged.
+    pub fn shortest_path(&self, from: &str, to: &str) -> Result<Option<Path>, GraphError> {
+        view::shortest_path(self, from, to)
+    }
+
+    /// Loads the whole graph into memory.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Graph, GraphError>` - The graph, with vertices in key order, or an error if
+    ///   the file is damaged.
+    pub fn to_graph(&self) -> Result<Graph, GraphError> {
+        let mut csr: Csr = Csr { keys: Vec::new(), row_offsets: vec![0], column_indices: Vec::new(), weights: Vec::new(), directed: self.directed };
+        for u in 0..self.vertex_count {
+            csr.keys.push(self.key(u)?.to_string());
+            for (v, weight) in self.row(u)? {
+                csr.column_indices.push(v);
+                csr.weights.push(weight);
+            }
+            csr.row_offsets.push(csr.column_indices.len());
+        }
+        Graph::from_csr(&csr)
+    }
+}
+
+impl GraphView for MappedGraph {
+    fn has_vertex(&self, key: &str) -> Result<bool, GraphError> {
+        MappedGraph::has_vertex(self, key)
+    }
+
+    fn neighbors(&self, key: &str) -> Result<Vec<(String, f32)>, GraphError> {
+        MappedGraph::neighbors(self, key)
+    }
+
+    fn degree(&self, key: &str) -> Result<usize, GraphError> {
+        MappedGraph::degree(self, key)
+    }
+}
+
+/// Reads a little-endian `u64` at `at`.
+fn read_u64(bytes: &[u8], at: usize) -> u64 {
+    u64::from_le_bytes(bytes[at..at + 8].try_into().unwrap())
+}
+
+impl Graph {
+    /// Saves the graph as a file that `MappedGraph::open` can serve without loading it.
+    ///
+    /// The file is in the crate's binary format, with the Compressed Sparse Row arrays in
+    /// fixed-width sections and the vertices in key order. Attributes, the `GraphMode`, the
+    /// `VertexOrder` and the weight semantics are not saved.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The file to write.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), GraphError>` - An error if the file cannot be written.
+    pub fn save_mapped<P: AsRef<std::path::Path>>(&self, path: P) -> Result<(), GraphError> {
+        let index: GraphIndex = self.index();
+        let entries: usize = index.adjacency.iter().map(Vec::len).sum();
+        let mut encoders: [Encoder; 6] = std::array::from_fn(|_| Encoder::new());
+        let [header, key_offsets, keys, row_offsets, columns, weights] = &mut encoders;
+
+        header.u8(u8::from(self.directed));
+        header.u64(index.len() as u64);
+        header.u64(entries as u64);
+        key_offsets.u64(0);
+        row_offsets.u64(0);
+        let mut entry: u64 = 0;
+        for (key, neighbors) in index.keys.iter().zip(&index.adjacency) {
+            keys.bytes.extend_from_slice(key.as_bytes());
+            key_offsets.u64(keys.bytes.len() as u64);
+            for &(v, weight) in neighbors {
+                columns.u64(v as u64);
+                weights.f32(weight);
+            }
+            entry += neighbors.len() as u64;
+            row_offsets.u64(entry);
+        }
+
+        let sections: Sections = SECTIONS.iter().map(|name| name.to_string()).zip(encoders.map(|encoder| encoder.bytes)).collect();
+        let path: &std::path::Path = path.as_ref();
+        fs::write(path, write_sections(&sections)).map_err(|e| GraphError::Io(format!("Could not write {}: {}", path.display(), e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MappedGraph;
+    use crate::graph::testing::graph_from;
+    use crate::graph::view::{self, GraphView};
+    use crate::graph::{AttrValue, Graph, GraphError};
+    use std::path::PathBuf;
+
+    /// Saves `graph` to a file named after the test and maps it.
+    fn mapped(graph: &Graph, name: &str) -> (MappedGraph, PathBuf) {
+        let path: PathBuf = std::env::temp_dir().join(format!("graphs-mapped-{}-{}.bin", name, std::process::id()));
+        graph.save_mapped(&path).unwrap();
+        (MappedGraph::open(&path).unwrap(), path)
+    }
+
+    #[test]
+    fn queries_match_the_graph_in_memory() {
+        let graph: Graph = graph_from(false, &[("a", "b", 1.0), ("b", "c", 2.0), ("c", "d", 1.0), ("a", "d", 5.0), ("e", "f", 1.0)]);
+        let (file, path) = mapped(&graph, "queries");
+        assert_eq!(file.vertex_count(), 6);
+        assert!(!file.is_directed());
+        for key in graph.vertex_keys() {
+            assert_eq!(GraphView::neighbors(&file, &key).unwrap(), GraphView::neighbors(&graph, &key).unwrap());
+            assert_eq!(GraphView::degree(&file, &key).unwrap(), graph.degree(&key).unwrap());
+            assert_eq!(file.bfs(&key).unwrap(), view::bfs(&graph, &key).unwrap());
+        }
+        assert!(file.has_edge("d", "c").unwrap());
+        assert!(!file.has_edge("a", "c").unwrap());
+        assert!(!file.has_vertex("z").unwrap());
+        let path_in_file = file.shortest_path("a", "d").unwrap().unwrap();
+        assert_eq!(path_in_file.vertices, graph.shortest_path("a", "d").unwrap().unwrap().vertices);
+        assert_eq!(path_in_file.cost, 4.0);
+        assert!(file.shortest_path("a", "e").unwrap().is_none());
+        assert!(matches!(file.bfs("z"), Err(GraphError::VertexNotFound(_))));
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn loading_keeps_structure_but_not_attributes() {
+        let mut graph: Graph = graph_from(true, &[("b", "a", 1.5), ("a", "c", 2.0), ("c", "c", 1.0)]);
+        graph.vertices.get_mut("a").unwrap().set_attr("color", AttrValue::Text("red".to_string()));
+        let (file, path) = mapped(&graph, "load");
+        let loaded: Graph = file.to_graph().unwrap();
+        assert_eq!(loaded, graph);
+        assert!(loaded.vertices["a"].attributes.is_empty());
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn damaged_files_are_rejected() {
+        let path: PathBuf = std::env::temp_dir().join(format!("graphs-mapped-damaged-{}.bin", std::process::id()));
+        std::fs::write(&path, b"not a graph").unwrap();
+        assert!(MappedGraph::open(&path).is_err());
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,178 @@
+use crate::graph::index::GraphIndex;
+use crate::graph::linalg::{invert, Matrix};
+use crate::graph::{Graph, GraphError};
+use std::collections::HashMap;
+
+/// Builds the row-stochastic transition matrix, treating edge weights as transition rates.
+///
+/// Vertices without outgoing weight stay where they are, i.e. they are absorbing.
+fn transition_matrix(index: &GraphIndex) -> Result<Matrix, GraphError> {
+    let n: usize = index.len();
+    let mut matrix: Matrix = vec![vec![0.0; n]; n];
+    for (u, neighbors) in index.adjacency.iter().enumerate() {
+        for &(v, weight) in neighbors {
+            if weight < 0.0 {
+                return Err(GraphError::NegativeWeight(index.keys[u].clone(), index.keys[v].clone()));
+            }
+            matrix[u][v] += f64::from(weight);
+        }
+        let total: f64 = matrix[u].iter().sum();
+        if total > 0.0 {
+            matrix[u].iter_mut().for_each(|p| *p /= total);
+        } else {
+            matrix[u][u] = 1.0;
+        }
+    }
+    Ok(matrix)
+}
+
+/// Returns the vertices from which some vertex in `targets` can be reached with positive probability.
+fn reaching(transition: &Matrix, targets: &[bool]) -> Vec<bool> {
+    let n: usize = transition.len();
+    let mut reached: Vec<bool> = targets.to_vec();
+    let mut stack: Vec<usize> = (0..n).filter(|&v| targets[v]).collect();
+    while let Some(v) = stack.pop() {
+        for u in 0..n {
+            if !reached[u] && transition[u][v] > 0.0 {
+                reached[u] = true;
+                stack.push(u);
+            }
+        }
+    }
+    reached
+}
+
+/// Returns `(I - Q)^-1` for the sub-chain `Q` restricted to `states`.
+fn fundamental_matrix(transition: &Matrix, states: &[usize]) -> Option<Matrix> {
+    let system: Matrix = states.iter()
+        .enumerate()
+        .map(|(i, &u)| {
+            states.iter()
+                .enumerate()
+                .map(|(j, &v)| if i == j { 1.0 } else { 0.0 } - transition[u][v])
+                .collect()
+        })
+        .collect();
+    invert(&system)
+}
+
+impl Graph {
+    /// Computes the stationary distribution of the Markov chain defined by the edge weights.
+    ///
+    /// The chain moves from a vertex along one of its edges with probability proportional to
+    /// the edge weight; vertices without outgoing weight are absorbing. The distribution `pi`
+    /// solves `pi P = pi` with entries summing to one, found by Gaussian elimination.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<HashMap<String, f32>, GraphError>` - The long-run probability of every vertex, or
+    ///   an error if a weight is negative or the distribution is not unique (the chain has
+    ///   more than one closed class).
+    pub fn stationary_distribution(&self) -> Result<HashMap<String, f32>, GraphError> {
+        let index: GraphIndex = self.index();
+        let transition: Matrix = transition_matrix(&index)?;
+        let n: usize = index.len();
+        if n == 0 {
+            return Ok(HashMap::new());
+        }
+
+        // (P^T - I) pi = 0, with the last equation replaced by sum(pi) = 1.
+        let mut system: Matrix = (0..n)
+            .map(|i| (0..n).map(|j| transition[j][i] - if i == j { 1.0 } else { 0.0 }).collect())
+            .collect();
+        system[n - 1] = vec![1.0; n];
+        let inverse: Matrix = invert(&system).ok_or_else(|| GraphError::NoSolution("Markov chain has no unique stationary distribution".to_string()))?;
+
+        Ok(index.keys.into_iter()
+            .enumerate()
+            .map(|(v, key)| (key, inverse[v][n - 1].max(0.0) as f32))
+            .collect())
+    }
+
+    /// Computes the expected number of steps to reach `target` from every vertex.
+    ///
+    /// Hitting times solve `h(target) = 0` and `h(u) = 1 + sum_v P(u, v) h(v)`. A vertex from
+    /// which the chain can wander off and never reach `target` has an infinite hitting time.
+    ///
+    /// # Arguments
+    ///
+    /// * `target` - The key of the vertex to reach.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<HashMap<String, f32>, GraphError>` - The expected hitting time of every vertex
+    ///   (`f32::INFINITY` if `target` is not reached almost surely), or an error if `target`
+    ///   does not exist or a weight is negative.
+    pub fn hitting_times(&self, target: &str) -> Result<HashMap<String, f32>, GraphError> {
+        let index: GraphIndex = self.index();
+        let t: usize = index.position(target)?;
+        let transition: Matrix = transition_matrix(&index)?;
+        let n: usize = index.len();
+
+        // Vertices that can get stuck away from the target, without passing through it first.
+        let mut target_set: Vec<bool> = vec![false; n];
+        target_set[t] = true;
+        let lost: Vec<bool> = reaching(&transition, &target_set).into_iter().map(|r| !r).collect();
+        let mut avoiding: Matrix = transition.clone();
+        avoiding[t].iter_mut().for_each(|p| *p = 0.0);
+        let infinite: Vec<bool> = reaching(&avoiding, &lost);
+
+        let states: Vec<usize> = (0..n).filter(|&v| v != t && !infinite[v]).collect();
+        let inverse: Matrix = fundamental_matrix(&transition, &states).ok_or_else(|| GraphError::NoSolution("Hitting times could not be solved".to_string()))?;
+        let mut times: Vec<f32> = (0..n).map(|v| if infinite[v] { f32::INFINITY } else { 0.0 }).collect();
+        for (i, &u) in states.iter().enumerate() {
+            times[u] = inverse[i].iter().sum::<f64>() as f32;
+        }
+        Ok(index.keys.into_iter().zip(times).collect())
+    }
+
+    /// Computes the probability of ending in each absorbing vertex from every vertex.
+    ///
+    /// A vertex is absorbing when it has no outgoing weight other than self-loops. With `Q`
+    /// the transitions among the other vertices and `R` those into absorbing ones, the
+    /// probabilities are `(I - Q)^-1 R`. Vertices that cannot reach any absorbing vertex get
+    /// an empty map.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<HashMap<String, HashMap<String, f32>>, GraphError>` - For every vertex, the
+    ///   probability of absorption in each reachable absorbing vertex, or an error if a weight
+    ///   is negative or there are no absorbing vertices.
+    pub fn absorption_probabilities(&self) -> Result<HashMap<String, HashMap<String, f32>>, GraphError> {
+        let index: GraphIndex = self.index();
+        let transition: Matrix = transition_matrix(&index)?;
+        let n: usize = index.len();
+        let absorbing: Vec<bool> = (0..n).map(|v| transition[v][v] == 1.0).collect();
+        if !absorbing.contains(&true) {
+            return Err(GraphError::NoSolution("Markov chain has no absorbing vertices".to_string()));
+        }
+
+        let reaches: Vec<bool> = reaching(&transition, &absorbing);
+        let states: Vec<usize> = (0..n).filter(|&v| !absorbing[v] && reaches[v]).collect();
+        let inverse: Matrix = fundamental_matrix(&transition, &states).ok_or_else(|| GraphError::NoSolution("Absorption probabilities could not be solved".to_string()))?;
+
+        let mut probabilities: HashMap<String, HashMap<String, f32>> = (0..n)
+            .map(|v| {
+                let own: HashMap<String, f32> = if absorbing[v] {
+                    HashMap::from([(index.keys[v].clone(), 1.0)])
+                } else {
+                    HashMap::new()
+                };
+                (index.keys[v].clone(), own)
+            })
+            .collect();
+        for (i, &u) in states.iter().enumerate() {
+            let row: &mut HashMap<String, f32> = probabilities.get_mut(&index.keys[u]).unwrap();
+            for a in (0..n).filter(|&a| absorbing[a]) {
+                let probability: f64 = states.iter()
+                    .enumerate()
+                    .map(|(j, &w)| inverse[i][j] * transition[w][a])
+                    .sum();
+                if probability > 0.0 {
+                    row.insert(index.keys[a].clone(), probability as f32);
+                }
+            }
+        }
+        Ok(probabilities)
+    }
+}
@@ -1,7 +1,85 @@
 pub mod vertex;
 pub mod edge;
+#[allow(clippy::module_inception)]
 pub mod graph;
+pub mod simulate;
+pub mod influence;
+pub mod path;
+pub mod connectivity;
+pub mod independent_set;
+pub mod steiner;
+pub mod cycles;
+pub mod ears;
+pub mod density;
+pub mod truss;
+pub mod centrality;
+pub mod neighborhood;
+pub mod eccentricity;
+pub mod coloring;
+pub mod interval;
+pub mod tournament;
+pub mod markov;
+pub mod resistance;
+pub mod sparsify;
+pub mod canonical;
+pub mod spanning;
+pub mod traversal;
+pub mod routing;
+pub mod dedup;
+pub mod sample;
+pub mod rng;
+pub mod implicit;
+pub mod dag;
+pub mod error;
+pub mod preprocess;
+pub mod attribute;
+pub mod geometric;
+pub mod dot;
+pub mod serialize;
+pub mod graphml;
+pub mod edgelist;
+pub mod snapshot;
+pub mod flow;
+pub mod control;
+pub mod matching;
+pub mod anonymize;
+pub mod generate;
+pub mod subgraph;
+pub mod combine;
+pub mod community;
+pub mod core_periphery;
+pub mod csr;
+pub mod rich_club;
+pub mod compare;
+pub mod classify;
+pub mod tours;
+pub mod semantics;
+pub mod bloom;
+pub mod biconnected;
+pub mod isomorphism;
+pub mod shard;
+pub mod events;
+pub mod window;
+pub mod regular_paths;
+pub mod path_dag;
+pub mod reachability;
+pub mod view;
+#[cfg(feature = "serde")]
+pub mod json;
+#[cfg(feature = "mmap")]
+pub mod mapped;
+#[cfg(feature = "rayon")]
+pub mod parallel;
+mod cache;
+mod index;
+mod linalg;
+#[cfg(test)]
+mod testing;
+mod trace;
 
 pub use vertex::Vertex;
 pub use edge::Edge;
-pub use graph::Graph;
\ No newline at end of file
+pub use graph::Graph;
+pub use attribute::AttrValue;
+pub use error::GraphError;
+pub use snapshot::Snapshot;
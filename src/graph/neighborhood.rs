@@ -0,0 +1,208 @@
+use crate::graph::index::GraphIndex;
+use crate::graph::{Graph, GraphError};
+use std::collections::{HashMap, VecDeque};
+
+/// A HyperLogLog counter estimating the number of distinct vertices added to it.
+#[derive(Clone, PartialEq)]
+struct HyperLogLog {
+    registers: Vec<u8>,
+}
+
+impl HyperLogLog {
+    fn new(bits: u8) -> HyperLogLog {
+        HyperLogLog { registers: vec![0; 1 << bits] }
+    }
+
+    fn insert(&mut self, hash: u64) {
+        let bits: u32 = self.registers.len().trailing_zeros();
+        let register: usize = (hash >> (64 - bits)) as usize;
+        let rank: u8 = ((hash << bits) | (1 << (bits - 1))).leading_zeros() as u8 + 1;
+        self.registers[register] = self.registers[register].max(rank);
+    }
+
+    /// Merges `other` into `self`, returning whether any register changed.
+    fn union(&mut self, other: &HyperLogLog) -> bool {
+        let mut changed: bool = false;
+        for (mine, &theirs) in self.registers.iter_mut().zip(&other.registers) {
+            if theirs > *mine {
+                *mine = theirs;
+                changed = true;
+            }
+        }
+        changed
+    }
+
+    fn estimate(&self) -> f64 {
+        let m: f64 = self.registers.len() as f64;
+        let alpha: f64 = match self.registers.len() {
+            16 => 0.673,
+            32 => 0.697,
+            64 => 0.709,
+            _ => 0.7213 / (1.0 + 1.079 / m),
+        };
+        let sum: f64 = self.registers.iter().map(|&r| 2f64.powi(-i32::from(r))).sum();
+        let raw: f64 = alpha * m * m / sum;
+        let zeros: usize = self.registers.iter().filter(|&&r| r == 0).count();
+        if raw <= 2.5 * m && zeros > 0 {
+            m * (m / zeros as f64).ln()
+        } else {
+            raw
+        }
+    }
+}
+
+/// Hashes a vertex position into 64 well-mixed bits.
+fn hash(value: usize) -> u64 {
+    let mut z: u64 = (value as u64).wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// The (approximate) neighborhood function of a graph.
+#[derive(Clone, Debug)]
+pub struct NeighborhoodFunction {
+    /// `hop_plot[t]` is the number of ordered pairs `(u, v)` with `v` reachable from `u` in at
+    /// most `t` hops, including the `n` pairs `(u, u)` at `t = 0`.
+    pub hop_plot: Vec<f64>,
+}
+
+impl NeighborhoodFunction {
+    /// Returns the number of hops within which `fraction` of all reachable pairs lie,
+    /// interpolating linearly between hops. The usual choice is `0.9`.
+    pub fn effective_diameter(&self, fraction: f64) -> f64 {
+        let base: f64 = self.hop_plot[0];
+        let total: f64 = self.hop_plot.last().unwrap() - base;
+        if total <= 0.0 {
+            return 0.0;
+        }
+        let target: f64 = fraction * total;
+        for t in 1..self.hop_plot.len() {
+            let (before, after) = (self.hop_plot[t - 1] - base, self.hop_plot[t] - base);
+            if after >= target {
+                return (t - 1) as f64 + if after > before { (target - before) / (after - before) } else { 1.0 };
+            }
+        }
+        (self.hop_plot.len() - 1) as f64
+    }
+
+    /// Returns the mean hop distance over all distinct reachable pairs.
+    pub fn average_distance(&self) -> f64 {
+        let total: f64 = self.hop_plot.last().unwrap() - self.hop_plot[0];
+        if total <= 0.0 {
+            return 0.0;
+        }
+        let weighted: f64 = (1..self.hop_plot.len())
+            .map(|t| t as f64 * (self.hop_plot[t] - self.hop_plot[t - 1]))
+            .sum();
+        weighted / total
+    }
+}
+
+impl Graph {
+    /// Extracts the ego network of a vertex: everything within `radius` hops of it.
+    ///
+    /// The result is the induced `subgraph`, so edges between two neighbors are kept along with
+    /// the edges to the center. In directed graphs only outgoing edges are followed. Edge
+    /// weights are ignored; `ego_graph_within` measures weighted distance instead.
+    ///
+    /// # Arguments
+    ///
+    /// * `center` - The key of the vertex at the center.
+    /// * `radius` - The largest number of hops to include; `0` gives the center alone.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Graph, GraphError>` - The ego network, or an error if the center does not exist.
+    pub fn ego_graph(&self, center: &str, radius: usize) -> Result<Graph, GraphError> {
+        let index: GraphIndex = self.index();
+        let source: usize = index.position(center)?;
+        let mut hops: Vec<Option<usize>> = vec![None; index.len()];
+        hops[source] = Some(0);
+        let mut queue: VecDeque<usize> = VecDeque::from([source]);
+        while let Some(u) = queue.pop_front() {
+            let next: usize = hops[u].unwrap() + 1;
+            if next > radius {
+                continue;
+            }
+            for &(v, _) in &index.adjacency[u] {
+                if hops[v].is_none() {
+                    hops[v] = Some(next);
+                    queue.push_back(v);
+                }
+            }
+        }
+        let keep: Vec<&str> = (0..index.len())
+            .filter(|&v| hops[v].is_some())
+            .map(|v| index.keys[v].as_str())
+            .collect();
+        Ok(self.subgraph(&keep))
+    }
+
+    /// Extracts the ego network of a vertex by weighted distance: everything whose shortest
+    /// path from the center costs at most `max_cost`.
+    ///
+    /// As with `ego_graph` the result is the induced subgraph and directed graphs are followed
+    /// along outgoing edges. Distances come from `dijkstra_within`.
+    ///
+    /// # Arguments
+    ///
+    /// * `center` - The key of the vertex at the center.
+    /// * `max_cost` - The largest distance to include.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Graph, GraphError>` - The ego network, or an error if the center does not exist
+    ///   or an edge weight is negative.
+    pub fn ego_graph_within(&self, center: &str, max_cost: f32) -> Result<Graph, GraphError> {
+        let distances: HashMap<String, f32> = self.dijkstra_within(center, max_cost)?;
+        let keep: Vec<&str> = distances.keys().map(String::as_str).collect();
+        Ok(self.subgraph(&keep))
+    }
+
+    /// Approximates the neighborhood function with HyperANF.
+    ///
+    /// Every vertex keeps a HyperLogLog counter of the vertices within `t` hops; one pass
+    /// over the edges turns the counters for `t` into those for `t + 1`. Iteration stops when
+    /// no counter changes, so the cost is `O(D m)` register operations for hop diameter `D`,
+    /// with `2^precision_bits` bytes per vertex. The relative standard error is about
+    /// `1.04 / sqrt(2^precision_bits)`. Edge weights are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `precision_bits` - The log2 of the register count per counter, between 4 and 16.
+    ///
+    /// # Returns
+    ///
+    /// * A `NeighborhoodFunction` from which the effective diameter and average distance follow.
+    pub fn approximate_neighborhood_function(&self, precision_bits: u8) -> NeighborhoodFunction {
+        let bits: u8 = precision_bits.clamp(4, 16);
+        let index: GraphIndex = self.index();
+        let n: usize = index.len();
+        let mut counters: Vec<HyperLogLog> = (0..n)
+            .map(|v| {
+                let mut counter: HyperLogLog = HyperLogLog::new(bits);
+                counter.insert(hash(v));
+                counter
+            })
+            .collect();
+
+        let mut hop_plot: Vec<f64> = vec![counters.iter().map(HyperLogLog::estimate).sum()];
+        loop {
+            let mut next: Vec<HyperLogLog> = counters.clone();
+            let mut changed: bool = false;
+            for (v, neighbors) in index.adjacency.iter().enumerate() {
+                for &(u, _) in neighbors {
+                    changed |= next[v].union(&counters[u]);
+                }
+            }
+            if !changed {
+                break;
+            }
+            counters = next;
+            let estimate: f64 = counters.iter().map(HyperLogLog::estimate).sum();
+            hop_plot.push(estimate.max(*hop_plot.last().unwrap()));
+        }
+        NeighborhoodFunction { hop_plot }
+    }
+}
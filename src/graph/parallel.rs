@@ -0,0 +1,291 @@
+use crate::graph::centrality::{add_dependencies, closeness_score, reject_zero_weight_cycles, PageRankOptions};
+use crate::graph::connectivity::group;
+use crate::graph::index::GraphIndex;
+use crate::graph::path::{dijkstra, reject_negative_weights, AllPairsShortestPaths};
+use crate::graph::trace::{trace_counts, trace_span};
+use crate::graph::{Graph, GraphError};
+use rayon::prelude::*;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Runs the PageRank power iteration of `Graph::pagerank` with every vertex's new score
+/// gathered from its in-neighbors in parallel.
+fn par_pagerank_scores(index: &GraphIndex, options: &PageRankOptions) -> Vec<f64> {
+    let n: usize = index.len();
+    if n == 0 {
+        return Vec::new();
+    }
+    trace_span!("par_pagerank", vertices = n);
+
+    let out_weight: Vec<f64> = index.adjacency.par_iter()
+        .map(|neighbors| neighbors.iter().map(|&(_, weight)| f64::from(weight)).sum())
+        .collect();
+    // Pulling along incoming edges lets every vertex be updated by one thread alone.
+    let incoming: Vec<Vec<(usize, f32)>> = index.reversed();
+    let mut scores: Vec<f64> = vec![1.0 / n as f64; n];
+    let mut iterations: usize = 0;
+    while iterations < options.max_iterations {
+        iterations += 1;
+        let dangling: f64 = (0..n).into_par_iter().filter(|&u| out_weight[u] <= 0.0).map(|u| scores[u]).sum();
+        let base: f64 = (1.0 - options.damping + options.damping * dangling) / n as f64;
+        let next: Vec<f64> = incoming.par_iter()
+            .map(|sources| {
+                let inflow: f64 = sources.iter()
+                    .filter(|&&(u, _)| out_weight[u] > 0.0)
+                    .map(|&(u, weight)| scores[u] * f64::from(weight) / out_weight[u])
+                    .sum();
+                base + options.damping * inflow
+            })
+            .collect();
+        let change: f64 = scores.par_iter().zip(&next).map(|(a, b)| (a - b).abs()).sum();
+        scores = next;
+        if change < options.tolerance {
+            break;
+        }
+    }
+    trace_counts!("par_pagerank finished", iterations = iterations);
+    scores
+}
+
+/// Returns the root of `v`'s set, halving the path to it on the way. Every vertex points to
+/// itself or to a smaller vertex, so the root of a set is its smallest vertex.
+fn find(parent: &[AtomicUsize], mut v: usize) -> usize {
+    loop {
+        let p: usize = parent[v].load(Ordering::Acquire);
+        if p == v {
+            return v;
+        }
+        let grandparent: usize = parent[p].load(Ordering::Acquire);
+        // Losing this race to another thread only leaves the path longer.
+        let _ = parent[v].compare_exchange(p, grandparent, Ordering::AcqRel, Ordering::Acquire);
+        v = grandparent;
+    }
+}
+
+/// Merges the sets of `u` and `v` without locks, hanging the larger root under the smaller.
+fn union(parent: &[AtomicUsize], u: usize, v: usize) {
+    loop {
+        let (a, b) = (find(parent, u), find(parent, v));
+        if a == b {
+            return;
+        }
+        let (low, high) = (a.min(b), a.max(b));
+        if parent[high].compare_exchange(high, low, Ordering::AcqRel, Ordering::Acquire).is_ok() {
+            return;
+        }
+    }
+}
+
+/// Labels every vertex with its weakly connected component as `component_labels` does,
+/// merging the endpoints of all edges in parallel in a concurrent union-find.
+fn par_component_labels(index: &GraphIndex) -> Vec<usize> {
+    let n: usize = index.len();
+    let parent: Vec<AtomicUsize> = (0..n).map(AtomicUsize::new).collect();
+    index.adjacency.par_iter().enumerate().for_each(|(u, neighbors)| {
+        for &(v, _) in neighbors {
+            union(&parent, u, v);
+        }
+    });
+    let roots: Vec<usize> = (0..n).into_par_iter().map(|v| find(&parent, v)).collect();
+
+    // Roots are the smallest vertex of their component, so numbering them in vertex order
+    // matches the sequential labels.
+    let mut number: Vec<usize> = vec![usize::MAX; n];
+    let mut count: usize = 0;
+    for v in 0..n {
+        if roots[v] == v {
+            number[v] = count;
+            count += 1;
+        }
+    }
+    roots.into_iter().map(|root| number[root]).collect()
+}
+
+impl Graph {
+    /// Computes PageRank as `pagerank` does, spreading every iteration over all threads of
+    /// the rayon pool.
+    ///
+    /// Scores agree with `pagerank` up to floating-point rounding, since sums are taken in
+    /// another order.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - The damping factor and stopping limits.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<HashMap<String, f64>, GraphError>` - The score of every vertex, summing to `1`, or
+    ///   an error if the damping factor is outside `[0, 1]`, the tolerance is negative or an edge
+    ///   weight is negative.
+    pub fn par_pagerank(&self, options: &PageRankOptions) -> Result<HashMap<String, f64>, GraphError> {
+        options.check()?;
+        self.cached(&options.cache_key("par_pagerank"), || {
+            let index: GraphIndex = self.index();
+            reject_negative_weights(&index)?;
+            let scores: Vec<f64> = par_pagerank_scores(&index, options);
+            Ok(index.keys.into_iter().zip(scores).collect())
+        })
+    }
+
+    /// Computes closeness centrality as `closeness_centrality` does, running the shortest-path
+    /// search from every vertex in parallel.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<HashMap<String, f64>, GraphError>` - The score of every vertex, `0` for one that
+    ///   reaches no other, or an error if an edge weight is negative.
+    pub fn par_closeness_centrality(&self) -> Result<HashMap<String, f64>, GraphError> {
+        self.require_distances("Closeness centrality")?;
+        let index: GraphIndex = self.index();
+        reject_negative_weights(&index)?;
+        let scores: Vec<f64> = (0..index.len()).into_par_iter().map(|v| closeness_score(&index, v)).collect();
+        Ok(index.keys.into_iter().zip(scores).collect())
+    }
+
+    /// Computes exact betweenness centrality as `betweenness_centrality` does, running
+    /// Brandes' search from every source in parallel and summing the dependencies per thread.
+    ///
+    /// Scores agree with `betweenness_centrality` up to floating-point rounding.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<HashMap<String, f64>, GraphError>` - The normalized score of every vertex, or an
+    ///   error if an edge weight is negative or zero-weight edges form a cycle.
+    pub fn par_betweenness_centrality(&self) -> Result<HashMap<String, f64>, GraphError> {
+        self.require_distances("Betweenness centrality")?;
+        let index: GraphIndex = self.index();
+        reject_negative_weights(&index)?;
+        reject_zero_weight_cycles(&index)?;
+        let n: usize = index.len();
+        let scores: Vec<f64> = (0..n).into_par_iter()
+            .fold(|| vec![0.0; n], |mut scores: Vec<f64>, source| {
+                add_dependencies(&index, source, &mut scores);
+                scores
+            })
+            .reduce(|| vec![0.0; n], |mut total: Vec<f64>, part: Vec<f64>| {
+                total.iter_mut().zip(part).for_each(|(a, b)| *a += b);
+                total
+            });
+
+        let pairs: f64 = if n > 1 { (n * (n - 1)) as f64 } else { 1.0 };
+        Ok(index.keys.into_iter().zip(scores).map(|(key, score)| (key, score / pairs)).collect())
+    }
+
+    /// Computes shortest paths between every pair of vertices with one Dijkstra search per
+    /// source, run in parallel.
+    ///
+    /// This takes `O(n m log n)` work instead of the `O(n³)` of `all_pairs_shortest_paths`,
+    /// which suits sparse graphs, but needs non-negative weights. Where several shortest
+    /// paths exist, the predecessors may differ from those `all_pairs_shortest_paths` picks.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<AllPairsShortestPaths, GraphError>` - The distance and predecessor matrices, or an
+    ///   error if an edge weight is negative.
+    pub fn par_all_pairs_shortest_paths(&self) -> Result<AllPairsShortestPaths, GraphError> {
+        self.require_distances("All-pairs shortest paths")?;
+        let index: GraphIndex = self.index();
+        reject_negative_weights(&index)?;
+        let n: usize = index.len();
+        trace_span!("par_all_pairs_shortest_paths", vertices = n);
+        let (distances, predecessors): (Vec<Vec<f32>>, Vec<Vec<Option<usize>>>) = (0..n).into_par_iter()
+            .map(|source| dijkstra(&index, source, |_| true, |_, _, _| true))
+            .unzip();
+
+        let mut direct: Vec<Vec<f32>> = vec![vec![f32::INFINITY; n]; n];
+        for (u, neighbors) in index.adjacency.iter().enumerate() {
+            direct[u][u] = 0.0;
+            for &(v, weight) in neighbors {
+                direct[u][v] = direct[u][v].min(weight);
+            }
+        }
+        Ok(AllPairsShortestPaths { keys: index.keys, distances, predecessors, direct })
+    }
+
+    /// Splits the graph into connected components as `connected_components` does, merging
+    /// the endpoints of the edges in parallel.
+    ///
+    /// The result is the same as `connected_components`, and shares its cache entry.
+    ///
+    /// # Returns
+    ///
+    /// * A `Vec<Vec<String>>` with the vertex keys of every component, each sorted, ordered by
+    ///   their first key.
+    pub fn par_connected_components(&self) -> Vec<Vec<String>> {
+        self.cached("connected_components", || {
+            let index: GraphIndex = self.index();
+            group(&index, &par_component_labels(&index))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::graph::centrality::PageRankOptions;
+    use crate::graph::path::AllPairsShortestPaths;
+    use crate::graph::rng::Rng;
+    use crate::graph::testing::graph_from;
+    use crate::graph::{Graph, GraphError};
+    use std::collections::HashMap;
+
+    /// Builds a random graph with weights from `1` to `4`, a few components and a
+    /// dangling vertex.
+    fn sample(directed: bool, seed: u64) -> Graph {
+        let mut rng: Rng = Rng::new(seed);
+        let keys: Vec<String> = (0..40).map(|v| format!("v{:02}", v)).collect();
+        let mut edges: Vec<(&str, &str, f32)> = Vec::new();
+        for _ in 0..70 {
+            let (u, v) = (rng.below(36), rng.below(36));
+            edges.push((&keys[u], &keys[v], 1.0 + rng.below(4) as f32));
+        }
+        edges.push((&keys[37], &keys[38], 1.0));
+        edges.push((&keys[38], &keys[39], 2.0));
+        graph_from(directed, &edges)
+    }
+
+    fn assert_close(parallel: &HashMap<String, f64>, sequential: &HashMap<String, f64>) {
+        assert_eq!(parallel.len(), sequential.len());
+        for (key, value) in sequential {
+            assert!((parallel[key] - value).abs() < 1e-9, "{}: {} vs {}", key, parallel[key], value);
+        }
+    }
+
+    #[test]
+    fn centralities_match_the_sequential_versions() {
+        for (directed, seed) in [(false, 1), (true, 2)] {
+            let graph: Graph = sample(directed, seed);
+            let options: PageRankOptions = PageRankOptions::default();
+            assert_close(&graph.par_pagerank(&options).unwrap(), &graph.pagerank(&options).unwrap());
+            assert_close(&graph.par_closeness_centrality().unwrap(), &graph.closeness_centrality().unwrap());
+            assert_close(&graph.par_betweenness_centrality().unwrap(), &graph.betweenness_centrality().unwrap());
+        }
+    }
+
+    #[test]
+    fn all_pairs_distances_match_floyd_warshall() {
+        for (directed, seed) in [(false, 3), (true, 4)] {
+            let graph: Graph = sample(directed, seed);
+            let parallel: AllPairsShortestPaths = graph.par_all_pairs_shortest_paths().unwrap();
+            let sequential: AllPairsShortestPaths = graph.all_pairs_shortest_paths().unwrap();
+            assert_eq!(parallel.keys, sequential.keys);
+            assert_eq!(parallel.distances, sequential.distances);
+        }
+    }
+
+    #[test]
+    fn components_match_the_sequential_version() {
+        for seed in 5..10 {
+            // Separate copies, since the two share a cache entry.
+            let expected: Vec<Vec<String>> = sample(false, seed).connected_components();
+            assert_eq!(sample(false, seed).par_connected_components(), expected);
+            assert_eq!(sample(true, seed).par_connected_components(), sample(true, seed).connected_components());
+        }
+    }
+
+    #[test]
+    fn negative_weights_are_rejected() {
+        let graph: Graph = graph_from(true, &[("a", "b", -1.0)]);
+        assert!(matches!(graph.par_all_pairs_shortest_paths(), Err(GraphError::NegativeWeight(_, _))));
+        assert!(matches!(graph.par_betweenness_centrality(), Err(GraphError::NegativeWeight(_, _))));
+    }
+}
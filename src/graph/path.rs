@@ -0,0 +1,1315 @@
+use crate::graph::control::ExecutionControl;
+use crate::graph::flow::FlowNetwork;
+use crate::graph::index::GraphIndex;
+use crate::graph::trace::{trace_counts, trace_span};
+use crate::graph::{Edge, Graph, GraphError, Vertex};
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::ops::Range;
+
+/// A priority-queue entry for Dijkstra-style searches, ordered so that the
+/// smallest cost is popped first from a `BinaryHeap`.
+#[derive(Clone, Copy)]
+pub(crate) struct State {
+    pub cost: f32,
+    pub vertex: usize,
+}
+
+impl PartialEq for State {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for State {}
+
+impl PartialOrd for State {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for State {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.cost.total_cmp(&self.cost).then_with(|| other.vertex.cmp(&self.vertex))
+    }
+}
+
+/// Single-source Dijkstra over `index`, restricted to the vertices and edges the filters allow.
+///
+/// Returns the distance to every vertex (`f32::INFINITY` if unreachable) and the
+/// predecessor of every vertex on its shortest path. Edge weights must be non-negative.
+pub(crate) fn dijkstra<V, E>(index: &GraphIndex, source: usize, allow_vertex: V, allow_edge: E) -> (Vec<f32>, Vec<Option<usize>>)
+where
+    V: Fn(usize) -> bool,
+    E: Fn(usize, usize, f32) -> bool,
+{
+    trace_span!("dijkstra", source = source);
+    let n: usize = index.len();
+    let mut distances: Vec<f32> = vec![f32::INFINITY; n];
+    let mut predecessors: Vec<Option<usize>> = vec![None; n];
+    if !allow_vertex(source) {
+        return (distances, predecessors);
+    }
+
+    let mut heap: BinaryHeap<State> = BinaryHeap::new();
+    distances[source] = 0.0;
+    heap.push(State { cost: 0.0, vertex: source });
+
+    let mut relaxations: usize = 0;
+    let mut largest_frontier: usize = 1;
+    while let Some(State { cost, vertex }) = heap.pop() {
+        if cost > distances[vertex] {
+            continue;
+        }
+        for &(next, weight) in &index.adjacency[vertex] {
+            if !allow_vertex(next) || !allow_edge(vertex, next, weight) {
+                continue;
+            }
+            let candidate: f32 = cost + weight;
+            if candidate < distances[next] {
+                distances[next] = candidate;
+                predecessors[next] = Some(vertex);
+                heap.push(State { cost: candidate, vertex: next });
+                relaxations += 1;
+            }
+        }
+        largest_frontier = largest_frontier.max(heap.len());
+    }
+    trace_counts!("dijkstra finished", relaxations = relaxations, largest_frontier = largest_frontier);
+
+    (distances, predecessors)
+}
+
+/// Returns an error naming the first edge with a negative weight, which Dijkstra's algorithm
+/// cannot handle.
+pub(crate) fn reject_negative_weights(index: &GraphIndex) -> Result<(), GraphError> {
+    for (u, neighbors) in index.adjacency.iter().enumerate() {
+        if let Some(&(v, _)) = neighbors.iter().find(|&&(_, weight)| weight < 0.0) {
+            return Err(GraphError::NegativeWeight(index.keys[u].clone(), index.keys[v].clone()));
+        }
+    }
+    Ok(())
+}
+
+/// Depth-limited search from the end of `path` for `target`, extending `path` and never
+/// revisiting a vertex already on it. Returns whether the target was found within `depth` more
+/// steps, and sets `cut_off` if the limit stopped the search from going further.
+fn depth_limited(index: &GraphIndex, path: &mut Vec<usize>, on_path: &mut [bool], target: usize, depth: usize, cut_off: &mut bool) -> bool {
+    let vertex: usize = path[path.len() - 1];
+    if vertex == target {
+        return true;
+    }
+    for &(next, _) in &index.adjacency[vertex] {
+        if on_path[next] {
+            continue;
+        }
+        if depth == 0 {
+            *cut_off = true;
+            return false;
+        }
+        path.push(next);
+        on_path[next] = true;
+        if depth_limited(index, path, on_path, target, depth - 1, cut_off) {
+            return true;
+        }
+        on_path[next] = false;
+        path.pop();
+    }
+    false
+}
+
+/// Walks the predecessor chain back from `target`, returning the vertices from the source to `target`.
+pub(crate) fn reconstruct(predecessors: &[Option<usize>], target: usize) -> Vec<usize> {
+    let mut path: Vec<usize> = vec![target];
+    let mut current: usize = target;
+    while let Some(previous) = predecessors[current] {
+        path.push(previous);
+        current = previous;
+    }
+    path.reverse();
+    path
+}
+
+/// A walk through a graph, as returned by the path-finding algorithms.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Path {
+    /// The vertex keys along the path, from the start to the target.
+    pub vertices: Vec<String>,
+    /// The edges taken as `(from, to, weight)`, one fewer than there are vertices.
+    pub edges: Vec<(String, String, f32)>,
+    /// The total cost of the path. This is the sum of the edge weights, plus any extra
+    /// charges an algorithm adds, such as turn penalties.
+    pub cost: f32,
+}
+
+impl Path {
+    /// Creates a path that consists of a single vertex and costs nothing.
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - The key of the vertex the path starts at.
+    ///
+    /// # Returns
+    ///
+    /// * A `Path` with one vertex and no edges.
+    pub fn new(start: &str) -> Path {
+        Path { vertices: vec![start.to_string()], edges: Vec::new(), cost: 0.0 }
+    }
+
+    /// Extends the path by an edge from its current end.
+    ///
+    /// # Arguments
+    ///
+    /// * `to` - The key of the vertex the edge leads to.
+    /// * `weight` - The weight of the edge, which is added to the cost.
+    pub fn push(&mut self, to: &str, weight: f32) {
+        self.edges.push((self.end().to_string(), to.to_string(), weight));
+        self.vertices.push(to.to_string());
+        self.cost += weight;
+    }
+
+    /// Returns the key of the first vertex.
+    pub fn start(&self) -> &str {
+        &self.vertices[0]
+    }
+
+    /// Returns the key of the last vertex.
+    pub fn end(&self) -> &str {
+        &self.vertices[self.vertices.len() - 1]
+    }
+
+    /// Checks that the path can be walked in `graph`.
+    ///
+    /// Every vertex must exist, consecutive vertices must match the edges, and every edge
+    /// must exist in the graph with the same weight, respecting direction in directed graphs.
+    /// The cost is not checked, since algorithms may add charges beyond the edge weights.
+    ///
+    /// # Arguments
+    ///
+    /// * `graph` - The graph to check against.
+    ///
+    /// # Returns
+    ///
+    /// * A `bool` that is `true` if the path is valid in the graph.
+    pub fn is_valid_in(&self, graph: &Graph) -> bool {
+        if self.vertices.len() != self.edges.len() + 1 || !graph.vertices.contains_key(self.start()) {
+            return false;
+        }
+        self.edges.iter().zip(self.vertices.windows(2)).all(|((from, to, weight), pair)| {
+            *from == pair[0] && *to == pair[1] && graph.vertices.get(from).is_some_and(|vertex| {
+                vertex.edges.iter().any(|edge| {
+                    edge.weight_from(from) == *weight
+                        && ((edge.vertex1 == *from && edge.vertex2 == *to)
+                            || (!graph.directed && edge.vertex1 == *to && edge.vertex2 == *from))
+                })
+            })
+        })
+    }
+
+    /// Joins another path onto the end of this one.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The path to append; it must start where this path ends.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Path, GraphError>` - The combined path with the summed cost, or an error if the
+    ///   paths do not meet.
+    pub fn concat(&self, other: &Path) -> Result<Path, GraphError> {
+        if self.end() != other.start() {
+            return Err(GraphError::InvalidArgument(format!("Path ends at {} but the next one starts at {}", self.end(), other.start())));
+        }
+        let mut joined: Path = self.clone();
+        joined.vertices.extend(other.vertices[1..].iter().cloned());
+        joined.edges.extend(other.edges.iter().cloned());
+        joined.cost += other.cost;
+        Ok(joined)
+    }
+
+    /// Returns the part of the path between two vertex positions.
+    ///
+    /// The cost of the slice is the sum of its edge weights; extra charges an algorithm added
+    /// to the whole path are not carried over.
+    ///
+    /// # Arguments
+    ///
+    /// * `range` - The positions in `vertices` to keep.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Path, GraphError>` - The sub-path, or an error if the range is empty or out of bounds.
+    pub fn slice(&self, range: Range<usize>) -> Result<Path, GraphError> {
+        if range.start >= range.end || range.end > self.vertices.len() {
+            return Err(GraphError::InvalidArgument(format!("Range {:?} does not select any part of a path with {} vertices", range, self.vertices.len())));
+        }
+        let edges: Vec<(String, String, f32)> = self.edges[range.start..range.end - 1].to_vec();
+        let cost: f32 = edges.iter().map(|&(_, _, weight)| weight).sum();
+        Ok(Path { vertices: self.vertices[range].to_vec(), edges, cost })
+    }
+
+    /// Builds the path through the given vertex positions, taking the lightest allowed edge
+    /// for every step.
+    pub(crate) fn through<E>(index: &GraphIndex, vertices: &[usize], allow_edge: E) -> Path
+    where
+        E: Fn(usize, usize, f32) -> bool,
+    {
+        let mut path: Path = Path::new(&index.keys[vertices[0]]);
+        for step in vertices.windows(2) {
+            let weight: f32 = index.adjacency[step[0]].iter()
+                .filter(|&&(v, weight)| v == step[1] && allow_edge(step[0], v, weight))
+                .map(|&(_, weight)| weight)
+                .fold(f32::INFINITY, f32::min);
+            path.push(&index.keys[step[1]], weight);
+        }
+        path
+    }
+}
+
+/// Shortest distances between every pair of vertices, from `Graph::all_pairs_shortest_paths`.
+///
+/// Rows and columns are numbered by vertex key in ascending order, as listed in `keys`.
+#[derive(Clone, Debug)]
+pub struct AllPairsShortestPaths {
+    /// The vertex keys in matrix order.
+    pub keys: Vec<String>,
+    /// `distances[i][j]` is the length of a shortest path from vertex `i` to vertex `j`, or
+    /// `f32::INFINITY` if there is none.
+    pub distances: Vec<Vec<f32>>,
+    /// `predecessors[i][j]` is the vertex before `j` on a shortest path from `i` to `j`, or
+    /// `None` if `i == j` or `j` is unreachable from `i`.
+    pub predecessors: Vec<Vec<Option<usize>>>,
+    /// `direct[i][j]` is the weight of the lightest edge from `i` to `j`.
+    pub(crate) direct: Vec<Vec<f32>>,
+}
+
+impl AllPairsShortestPaths {
+    /// Reconstructs a shortest path between two vertices from the predecessor matrix.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - The key of the start vertex.
+    /// * `to` - The key of the target vertex.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<Path>` - The path and its total weight, or `None` if a vertex does not exist
+    ///   or the target is unreachable.
+    pub fn path(&self, from: &str, to: &str) -> Option<Path> {
+        let source: usize = self.keys.binary_search_by(|key| key.as_str().cmp(from)).ok()?;
+        let target: usize = self.keys.binary_search_by(|key| key.as_str().cmp(to)).ok()?;
+        if self.distances[source][target].is_infinite() {
+            return None;
+        }
+        let vertices: Vec<usize> = reconstruct(&self.predecessors[source], target);
+        let mut path: Path = Path::new(&self.keys[source]);
+        for step in vertices.windows(2) {
+            path.push(&self.keys[step[1]], self.direct[step[0]][step[1]]);
+        }
+        path.cost = self.distances[source][target];
+        Some(path)
+    }
+}
+
+/// A predicate deciding whether a vertex, given its key, may be used.
+pub type VertexFilter = Box<dyn Fn(&str) -> bool>;
+
+/// A predicate deciding whether an edge `(from, to, weight)` may be used.
+pub type EdgeFilter = Box<dyn Fn(&str, &str, f32) -> bool>;
+
+/// A label of the resource-constrained search: the vertex, cost and resource use, and the
+/// parent label together with the weight of the edge from it.
+type Label = (usize, f32, f32, Option<(usize, f32)>);
+
+/// The predecessor of every vertex in a shortest-path tree (`None` for the root and unreached vertices).
+pub(crate) type Predecessors = Vec<Option<usize>>;
+
+/// The largest edge weight Dial's algorithm accepts.
+///
+/// The bucket queue holds one bucket per possible weight, so heavier edges would make it
+/// larger than the graph; plain Dijkstra suits such graphs better.
+pub const MAX_DIAL_WEIGHT: u64 = 1_000_000;
+
+/// Dial's bucket-queue variant of Dijkstra for small non-negative integer weights.
+///
+/// Uses a circular array of `max_weight + 1` buckets instead of a binary heap. Returns the
+/// distances (`None` if unreachable) and predecessors, or an error if a weight is negative,
+/// not a whole number or above `MAX_DIAL_WEIGHT`.
+pub(crate) fn dial(index: &GraphIndex, source: usize) -> Result<(Vec<Option<u64>>, Predecessors), GraphError> {
+    let mut max_weight: u64 = 0;
+    for (u, neighbors) in index.adjacency.iter().enumerate() {
+        for &(v, weight) in neighbors {
+            if weight < 0.0 || weight.fract() != 0.0 {
+                return Err(GraphError::InvalidArgument(format!(
+                    "Edge ({}, {}) has weight {}, but bucket search needs non-negative integer weights",
+                    index.keys[u], index.keys[v], weight
+                )));
+            }
+            if weight > MAX_DIAL_WEIGHT as f32 {
+                return Err(GraphError::InvalidArgument(format!(
+                    "Edge ({}, {}) has weight {}, but bucket search takes weights up to {}",
+                    index.keys[u], index.keys[v], weight, MAX_DIAL_WEIGHT
+                )));
+            }
+            max_weight = max_weight.max(weight as u64);
+        }
+    }
+
+    let n: usize = index.len();
+    let bucket_count: usize = max_weight as usize + 1;
+    let mut buckets: Vec<Vec<usize>> = vec![Vec::new(); bucket_count];
+    let mut distances: Vec<Option<u64>> = vec![None; n];
+    let mut predecessors: Vec<Option<usize>> = vec![None; n];
+    let mut settled: Vec<bool> = vec![false; n];
+
+    distances[source] = Some(0);
+    buckets[0].push(source);
+    let mut pending: usize = 1;
+    let mut current: u64 = 0;
+
+    while pending > 0 {
+        let slot: usize = (current % bucket_count as u64) as usize;
+        while let Some(u) = buckets[slot].pop() {
+            pending -= 1;
+            if settled[u] || distances[u] != Some(current) {
+                continue;
+            }
+            settled[u] = true;
+            for &(v, weight) in &index.adjacency[u] {
+                let candidate: u64 = current + weight as u64;
+                if !settled[v] && distances[v].is_none_or(|d| candidate < d) {
+                    distances[v] = Some(candidate);
+                    predecessors[v] = Some(u);
+                    buckets[(candidate % bucket_count as u64) as usize].push(v);
+                    pending += 1;
+                }
+            }
+        }
+        current += 1;
+    }
+
+    Ok((distances, predecessors))
+}
+
+/// Restrictions applied to a constrained shortest-path query.
+///
+/// Built with chained calls, for example
+/// `PathConstraints::new().avoid_vertex("X").via("W")`.
+#[derive(Default)]
+pub struct PathConstraints {
+    avoided_vertices: HashSet<String>,
+    avoided_edges: HashSet<(String, String)>,
+    waypoints: Vec<String>,
+    vertex_filter: Option<VertexFilter>,
+    edge_filter: Option<EdgeFilter>,
+}
+
+impl PathConstraints {
+    /// Creates an empty set of constraints.
+    pub fn new() -> PathConstraints {
+        PathConstraints::default()
+    }
+
+    /// Forbids the path from passing through the given vertex.
+    pub fn avoid_vertex(mut self, key: &str) -> PathConstraints {
+        self.avoided_vertices.insert(key.to_string());
+        self
+    }
+
+    /// Forbids the path from using the edge between the two vertices.
+    /// In undirected graphs the edge is forbidden in both directions.
+    pub fn avoid_edge(mut self, from: &str, to: &str) -> PathConstraints {
+        self.avoided_edges.insert((from.to_string(), to.to_string()));
+        self
+    }
+
+    /// Requires the path to pass through the given vertex, after any waypoints added before it.
+    pub fn via(mut self, key: &str) -> PathConstraints {
+        self.waypoints.push(key.to_string());
+        self
+    }
+
+    /// Only allows vertices for which `filter` returns `true`.
+    pub fn vertex_filter<F>(mut self, filter: F) -> PathConstraints
+    where
+        F: Fn(&str) -> bool + 'static,
+    {
+        self.vertex_filter = Some(Box::new(filter));
+        self
+    }
+
+    /// Only allows edges `(from, to, weight)` for which `filter` returns `true`.
+    pub fn edge_filter<F>(mut self, filter: F) -> PathConstraints
+    where
+        F: Fn(&str, &str, f32) -> bool + 'static,
+    {
+        self.edge_filter = Some(Box::new(filter));
+        self
+    }
+
+    fn allows_vertex(&self, key: &str) -> bool {
+        !self.avoided_vertices.contains(key) && self.vertex_filter.as_ref().is_none_or(|filter| filter(key))
+    }
+
+    fn allows_edge(&self, from: &str, to: &str, weight: f32, directed: bool) -> bool {
+        let avoided: bool = self.avoided_edges.contains(&(from.to_string(), to.to_string()))
+            || (!directed && self.avoided_edges.contains(&(to.to_string(), from.to_string())));
+        !avoided && self.edge_filter.as_ref().is_none_or(|filter| filter(from, to, weight))
+    }
+}
+
+impl Graph {
+    /// Finds a shortest path between two vertices with Dijkstra's algorithm.
+    ///
+    /// Edge direction is respected in directed graphs; undirected edges can be traversed
+    /// either way.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - The key of the start vertex.
+    /// * `to` - The key of the target vertex.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<Path>, GraphError>` - The path and its total weight, `None` if the target is
+    ///   unreachable, or an error if a vertex does not exist or an edge weight is negative.
+    pub fn shortest_path(&self, from: &str, to: &str) -> Result<Option<Path>, GraphError> {
+        self.require_distances("Shortest paths")?;
+        let index: GraphIndex = self.index();
+        let source: usize = index.position(from)?;
+        let target: usize = index.position(to)?;
+        reject_negative_weights(&index)?;
+
+        let (distances, predecessors) = dijkstra(&index, source, |_| true, |_, _, _| true);
+        if distances[target].is_infinite() {
+            return Ok(None);
+        }
+        Ok(Some(Path::through(&index, &reconstruct(&predecessors, target), |_, _, _| true)))
+    }
+
+    /// Finds a shortest path between two vertices with A* search.
+    ///
+    /// Vertices are explored in order of their distance from `from` plus the heuristic's
+    /// estimate of the rest of the way, so a good estimate, such as the straight-line distance
+    /// between grid coordinates, explores far fewer vertices than Dijkstra's algorithm. The
+    /// path is shortest when the heuristic is consistent: the estimate at a vertex never
+    /// exceeds the weight of an edge plus the estimate after it, and is zero at `to`. A
+    /// heuristic of zero everywhere gives Dijkstra's algorithm.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - The key of the start vertex.
+    /// * `to` - The key of the target vertex.
+    /// * `heuristic` - Estimates the cost from a vertex to `to`.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<Path>, GraphError>` - The path and its total weight, `None` if the target is
+    ///   unreachable, or an error if a vertex does not exist or an edge weight is negative.
+    pub fn astar<H>(&self, from: &str, to: &str, heuristic: H) -> Result<Option<Path>, GraphError>
+    where
+        H: Fn(&Vertex) -> f32,
+    {
+        self.require_distances("A* search")?;
+        let index: GraphIndex = self.index();
+        let source: usize = index.position(from)?;
+        let target: usize = index.position(to)?;
+        reject_negative_weights(&index)?;
+
+        trace_span!("astar", from = from, to = to);
+        let estimate = |v: usize| -> f32 { heuristic(&self.vertices[&index.keys[v]]) };
+        let n: usize = index.len();
+        let mut distances: Vec<f32> = vec![f32::INFINITY; n];
+        let mut predecessors: Predecessors = vec![None; n];
+        let mut closed: Vec<bool> = vec![false; n];
+        let mut heap: BinaryHeap<State> = BinaryHeap::from([State { cost: estimate(source), vertex: source }]);
+        distances[source] = 0.0;
+
+        let mut expanded: usize = 0;
+        let mut largest_frontier: usize = 1;
+        while let Some(State { vertex, .. }) = heap.pop() {
+            if closed[vertex] {
+                continue;
+            }
+            if vertex == target {
+                trace_counts!("astar reached the target", expanded = expanded, largest_frontier = largest_frontier);
+                return Ok(Some(Path::through(&index, &reconstruct(&predecessors, target), |_, _, _| true)));
+            }
+            closed[vertex] = true;
+            expanded += 1;
+            for &(next, weight) in &index.adjacency[vertex] {
+                let candidate: f32 = distances[vertex] + weight;
+                if !closed[next] && candidate < distances[next] {
+                    distances[next] = candidate;
+                    predecessors[next] = Some(vertex);
+                    heap.push(State { cost: candidate + estimate(next), vertex: next });
+                }
+            }
+            largest_frontier = largest_frontier.max(heap.len());
+        }
+        trace_counts!("astar exhausted the frontier", expanded = expanded, largest_frontier = largest_frontier);
+        Ok(None)
+    }
+
+    /// Finds every vertex within a cost budget of a source with Dijkstra's algorithm.
+    ///
+    /// The search stops as soon as the next closest vertex is beyond `max_cost`, so only the
+    /// ball around the source is explored; this suits queries such as everything within 30
+    /// minutes of a location on a large road network.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - The key of the vertex to search from.
+    /// * `max_cost` - The largest distance to include.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<HashMap<String, f32>, GraphError>` - The distance to every vertex within `max_cost`,
+    ///   including the source itself, or an error if the vertex does not exist or an edge weight
+    ///   is negative.
+    pub fn dijkstra_within(&self, source: &str, max_cost: f32) -> Result<HashMap<String, f32>, GraphError> {
+        self.require_distances("Dijkstra search")?;
+        let index: GraphIndex = self.index();
+        let source: usize = index.position(source)?;
+        reject_negative_weights(&index)?;
+
+        let mut distances: HashMap<usize, f32> = HashMap::from([(source, 0.0)]);
+        let mut settled: HashMap<String, f32> = HashMap::new();
+        let mut heap: BinaryHeap<State> = BinaryHeap::from([State { cost: 0.0, vertex: source }]);
+        while let Some(State { cost, vertex }) = heap.pop() {
+            if cost > max_cost {
+                break;
+            }
+            if cost > distances[&vertex] {
+                continue;
+            }
+            settled.insert(index.keys[vertex].clone(), cost);
+            for &(next, weight) in &index.adjacency[vertex] {
+                let candidate: f32 = cost + weight;
+                if candidate <= max_cost && distances.get(&next).is_none_or(|&current| candidate < current) {
+                    distances.insert(next, candidate);
+                    heap.push(State { cost: candidate, vertex: next });
+                }
+            }
+        }
+        Ok(settled)
+    }
+
+    /// Finds a path with the fewest edges between two vertices by iterative deepening
+    /// depth-first search.
+    ///
+    /// Depth-limited searches are repeated with limits `0, 1, 2, ...` up to `max_depth`, or
+    /// until a round is no longer cut short by its limit. Like breadth-first search this finds
+    /// a path with the fewest edges, but it only keeps the current path in memory. Each search
+    /// avoids vertices already on its path, and the work can grow exponentially with the depth
+    /// in graphs with many alternative routes.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - The key of the start vertex.
+    /// * `to` - The key of the target vertex.
+    /// * `max_depth` - The most edges the path may have.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<Path>, GraphError>` - The path, `None` if the target cannot be reached within
+    ///   `max_depth` edges, or an error if a vertex does not exist.
+    pub fn iterative_deepening_search(&self, from: &str, to: &str, max_depth: usize) -> Result<Option<Path>, GraphError> {
+        let index: GraphIndex = self.index();
+        let source: usize = index.position(from)?;
+        let target: usize = index.position(to)?;
+        let mut on_path: Vec<bool> = vec![false; index.len()];
+        on_path[source] = true;
+        for depth in 0..=max_depth {
+            let mut path: Vec<usize> = vec![source];
+            let mut cut_off: bool = false;
+            if depth_limited(&index, &mut path, &mut on_path, target, depth, &mut cut_off) {
+                return Ok(Some(Path::through(&index, &path, |_, _, _| true)));
+            }
+            // Nothing was left unexplored, so a deeper search cannot find more.
+            if !cut_off {
+                break;
+            }
+        }
+        Ok(None)
+    }
+
+    /// Computes shortest distances from a source with the Bellman-Ford algorithm.
+    ///
+    /// Unlike Dijkstra's algorithm this allows negative edge weights, as in currency arbitrage
+    /// graphs with log-transformed rates. Every edge is relaxed up to `n - 1` times, stopping
+    /// early once nothing changes; if an edge can still be relaxed afterwards, a negative cycle
+    /// is reachable and reported instead of distances. In undirected graphs a negative edge is
+    /// itself such a cycle, since it can be crossed back and forth.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - The key of the source vertex.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<HashMap<String, f32>, GraphError>` - The distance to every reachable vertex,
+    ///   or an error if the source does not exist or a negative cycle is reachable from it.
+    pub fn bellman_ford(&self, from: &str) -> Result<HashMap<String, f32>, GraphError> {
+        self.require_distances("Bellman-Ford")?;
+        let index: GraphIndex = self.index();
+        let source: usize = index.position(from)?;
+        trace_span!("bellman_ford", from = from);
+        let n: usize = index.len();
+        let mut distances: Vec<f32> = vec![f32::INFINITY; n];
+        let mut predecessors: Predecessors = vec![None; n];
+        distances[source] = 0.0;
+
+        let mut relaxations: usize = 0;
+        let mut relax = |distances: &mut Vec<f32>, predecessors: &mut Predecessors| -> Option<usize> {
+            let mut changed: Option<usize> = None;
+            for (u, neighbors) in index.adjacency.iter().enumerate() {
+                if distances[u].is_infinite() {
+                    continue;
+                }
+                for &(v, weight) in neighbors {
+                    if distances[u] + weight < distances[v] {
+                        distances[v] = distances[u] + weight;
+                        predecessors[v] = Some(u);
+                        changed = Some(v);
+                        relaxations += 1;
+                    }
+                }
+            }
+            changed
+        };
+        let mut rounds: usize = 0;
+        for _ in 1..n {
+            rounds += 1;
+            if relax(&mut distances, &mut predecessors).is_none() {
+                break;
+            }
+        }
+
+        let relaxed: Option<usize> = relax(&mut distances, &mut predecessors);
+        trace_counts!("bellman_ford finished", rounds = rounds, relaxations = relaxations);
+        if let Some(relaxed) = relaxed {
+            // Following `n` predecessors from a vertex relaxed in round `n` lands on the cycle.
+            let mut start: usize = relaxed;
+            for _ in 0..n {
+                start = predecessors[start].unwrap();
+            }
+            let mut cycle: Vec<usize> = vec![start];
+            let mut current: usize = predecessors[start].unwrap();
+            while current != start {
+                cycle.push(current);
+                current = predecessors[current].unwrap();
+            }
+            cycle.push(start);
+            cycle.reverse();
+            return Err(GraphError::NegativeCycle(cycle.into_iter().map(|v| index.keys[v].clone()).collect()));
+        }
+
+        Ok(distances.into_iter()
+            .enumerate()
+            .filter(|(_, distance)| distance.is_finite())
+            .map(|(v, distance)| (index.keys[v].clone(), distance))
+            .collect())
+    }
+
+    /// Finds the cheapest path between two vertices that satisfies the given constraints.
+    ///
+    /// Avoided vertices/edges and the filters are applied to every step. Waypoints are
+    /// visited in order by stitching together the shortest segments between consecutive
+    /// stops, so the resulting walk may revisit a vertex when the waypoints force it.
+    /// Edge weights must be non-negative.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - The key of the start vertex.
+    /// * `to` - The key of the target vertex.
+    /// * `constraints` - The restrictions the path must satisfy.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<Path>, GraphError>` - The path, `None` if no valid path exists, or an error
+    ///   if a vertex does not exist or an edge weight is negative.
+    pub fn constrained_shortest_path(
+        &self,
+        from: &str,
+        to: &str,
+        constraints: &PathConstraints,
+    ) -> Result<Option<Path>, GraphError> {
+        self.require_distances("Shortest paths")?;
+        let index: GraphIndex = self.index();
+        let mut stops: Vec<usize> = vec![index.position(from)?];
+        for waypoint in &constraints.waypoints {
+            stops.push(index.position(waypoint)?);
+        }
+        stops.push(index.position(to)?);
+        reject_negative_weights(&index)?;
+
+        let allowed: Vec<bool> = index.keys.iter().map(|key| constraints.allows_vertex(key)).collect();
+        let allow_vertex = |v: usize| allowed[v];
+        let allow_edge = |u: usize, v: usize, weight: f32| {
+            constraints.allows_edge(&index.keys[u], &index.keys[v], weight, index.directed)
+        };
+
+        let mut path: Vec<usize> = vec![stops[0]];
+        for segment in stops.windows(2) {
+            let (distances, predecessors) = dijkstra(&index, segment[0], allow_vertex, allow_edge);
+            if distances[segment[1]].is_infinite() {
+                return Ok(None);
+            }
+            path.extend(reconstruct(&predecessors, segment[1]).into_iter().skip(1));
+        }
+
+        Ok(Some(Path::through(&index, &path, allow_edge)))
+    }
+
+    /// Finds the cheapest path between two vertices whose total resource use stays within a budget.
+    ///
+    /// Every edge carries a secondary, non-negative resource (for example a toll) given by
+    /// `resource(edge)`, which sees the edge's attributes, so parallel edges can differ. An
+    /// undirected edge is passed as stored, whichever way it is traversed. The search is
+    /// label-correcting: each vertex keeps the Pareto-optimal `(cost, resource)` labels
+    /// reaching it, and labels exceeding the budget are discarded. Edge weights must be
+    /// non-negative.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - The key of the start vertex.
+    /// * `to` - The key of the target vertex.
+    /// * `budget` - The maximum total resource the path may consume.
+    /// * `resource` - Returns the resource consumed by traversing an edge.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<(Path, f32)>, GraphError>` - The path and its total resource use, `None` if no
+    ///   path fits the budget, or an error if a vertex does not exist, an edge weight is negative
+    ///   or `resource` returns a negative or NaN amount.
+    pub fn resource_constrained_shortest_path<R>(
+        &self,
+        from: &str,
+        to: &str,
+        budget: f32,
+        resource: R,
+    ) -> Result<Option<(Path, f32)>, GraphError>
+    where
+        R: Fn(&Edge) -> f32,
+    {
+        self.require_distances("Shortest paths")?;
+        let index: GraphIndex = self.index();
+        let source: usize = index.position(from)?;
+        let target: usize = index.position(to)?;
+        reject_negative_weights(&index)?;
+
+        let mut labels: Vec<Label> = vec![(source, 0.0, 0.0, None)];
+        let mut frontier: Vec<Vec<usize>> = vec![Vec::new(); index.len()];
+        frontier[source].push(0);
+        // Heap entries carry a label position rather than a vertex.
+        let mut heap: BinaryHeap<State> = BinaryHeap::new();
+        heap.push(State { cost: 0.0, vertex: 0 });
+
+        while let Some(State { vertex: label, .. }) = heap.pop() {
+            let (vertex, cost, used, _) = labels[label];
+            if !frontier[vertex].contains(&label) {
+                continue;
+            }
+            if vertex == target {
+                let mut steps: Vec<(usize, f32)> = Vec::new();
+                let mut current: usize = label;
+                while let Some((parent, weight)) = labels[current].3 {
+                    steps.push((labels[current].0, weight));
+                    current = parent;
+                }
+                let mut path: Path = Path::new(&index.keys[source]);
+                for &(v, weight) in steps.iter().rev() {
+                    path.push(&index.keys[v], weight);
+                }
+                return Ok(Some((path, used)));
+            }
+
+            // The index lists every vertex's neighbors in the order of its stored edges.
+            let edges: &[Edge] = &self.vertices[&index.keys[vertex]].edges;
+            for (edge, &(next, weight)) in edges.iter().zip(&index.adjacency[vertex]) {
+                let amount: f32 = resource(edge);
+                if amount < 0.0 || amount.is_nan() {
+                    return Err(GraphError::InvalidArgument(format!(
+                        "Edge ({}, {}) uses {} of the resource, but amounts must be non-negative",
+                        index.keys[vertex], index.keys[next], amount
+                    )));
+                }
+                let next_cost: f32 = cost + weight;
+                let next_used: f32 = used + amount;
+                if next_used > budget {
+                    continue;
+                }
+                let dominated: bool = frontier[next].iter().any(|&other| {
+                    labels[other].1 <= next_cost && labels[other].2 <= next_used
+                });
+                if dominated {
+                    continue;
+                }
+                frontier[next].retain(|&other| !(next_cost <= labels[other].1 && next_used <= labels[other].2));
+                labels.push((next, next_cost, next_used, Some((label, weight))));
+                frontier[next].push(labels.len() - 1);
+                heap.push(State { cost: next_cost, vertex: labels.len() - 1 });
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Finds the cheapest path between two vertices when turning between edges has a cost.
+    ///
+    /// The search runs Dijkstra over an implicit line graph whose states are the edges
+    /// themselves, so the penalty for entering `next` from `previous` through `via` is
+    /// charged by `turn_cost(previous, via, next)` on top of the edge weights. Returning
+    /// `f32::INFINITY` forbids the turn (for example a U-turn). Weights and penalties must
+    /// be non-negative.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - The key of the start vertex.
+    /// * `to` - The key of the target vertex.
+    /// * `turn_cost` - Returns the penalty for the turn `previous -> via -> next`.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<Path>, GraphError>` - The path, whose cost includes the turn penalties, `None`
+    ///   if the target is unreachable, or an error if a vertex does not exist, an edge weight is
+    ///   negative or `turn_cost` returns a negative or NaN penalty.
+    pub fn shortest_path_with_turns<T>(&self, from: &str, to: &str, turn_cost: T) -> Result<Option<Path>, GraphError>
+    where
+        T: Fn(&str, &str, &str) -> f32,
+    {
+        self.require_distances("Shortest paths")?;
+        let index: GraphIndex = self.index();
+        let source: usize = index.position(from)?;
+        let target: usize = index.position(to)?;
+        reject_negative_weights(&index)?;
+        if source == target {
+            return Ok(Some(Path::new(&index.keys[source])));
+        }
+
+        // Arc `offsets[u] + k` is the k-th outgoing edge of `u`.
+        let mut offsets: Vec<usize> = vec![0; index.len() + 1];
+        for (u, neighbors) in index.adjacency.iter().enumerate() {
+            offsets[u + 1] = offsets[u] + neighbors.len();
+        }
+        let mut tails: Vec<usize> = vec![0; offsets[index.len()]];
+        for u in 0..index.len() {
+            tails[offsets[u]..offsets[u + 1]].fill(u);
+        }
+        let head = |arc: usize| index.adjacency[tails[arc]][arc - offsets[tails[arc]]];
+
+        let mut distances: Vec<f32> = vec![f32::INFINITY; tails.len()];
+        let mut predecessors: Vec<Option<usize>> = vec![None; tails.len()];
+        let mut heap: BinaryHeap<State> = BinaryHeap::new();
+        for (k, &(_, weight)) in index.adjacency[source].iter().enumerate() {
+            let arc: usize = offsets[source] + k;
+            if weight < distances[arc] {
+                distances[arc] = weight;
+                heap.push(State { cost: weight, vertex: arc });
+            }
+        }
+
+        while let Some(State { cost, vertex: arc }) = heap.pop() {
+            if cost > distances[arc] {
+                continue;
+            }
+            let (via, _) = head(arc);
+            if via == target {
+                let mut arcs: Vec<usize> = vec![arc];
+                while let Some(previous) = predecessors[arcs[arcs.len() - 1]] {
+                    arcs.push(previous);
+                }
+                let mut path: Path = Path::new(&index.keys[source]);
+                for &step in arcs.iter().rev() {
+                    let (next, weight) = head(step);
+                    path.push(&index.keys[next], weight);
+                }
+                path.cost = cost;
+                return Ok(Some(path));
+            }
+
+            for (k, &(next, weight)) in index.adjacency[via].iter().enumerate() {
+                let penalty: f32 = turn_cost(&index.keys[tails[arc]], &index.keys[via], &index.keys[next]);
+                if penalty < 0.0 || penalty.is_nan() {
+                    return Err(GraphError::InvalidArgument(format!(
+                        "The turn {} -> {} -> {} has penalty {}, but penalties must be non-negative",
+                        index.keys[tails[arc]], index.keys[via], index.keys[next], penalty
+                    )));
+                }
+                let candidate: f32 = cost + penalty + weight;
+                let next_arc: usize = offsets[via] + k;
+                if candidate < distances[next_arc] {
+                    distances[next_arc] = candidate;
+                    predecessors[next_arc] = Some(arc);
+                    heap.push(State { cost: candidate, vertex: next_arc });
+                }
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Computes shortest-path distances from a vertex using Dial's bucket-queue algorithm.
+    ///
+    /// This is a specialization of Dijkstra for graphs whose weights are small non-negative
+    /// integers; it runs in `O(V + E + D)` where `D` is the largest distance, which is usually
+    /// much faster than the heap-based search on such graphs.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - The key of the start vertex.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<HashMap<String, u64>, GraphError>` - The distance to every reachable vertex, or an
+    ///   error if the vertex does not exist or a weight is not a non-negative integer up to
+    ///   `MAX_DIAL_WEIGHT`.
+    pub fn dial_distances(&self, from: &str) -> Result<HashMap<String, u64>, GraphError> {
+        self.require_distances("Dial's algorithm")?;
+        let index: GraphIndex = self.index();
+        let source: usize = index.position(from)?;
+        let (distances, _) = dial(&index, source)?;
+
+        Ok(distances.into_iter()
+            .enumerate()
+            .filter_map(|(v, distance)| distance.map(|d| (index.keys[v].clone(), d)))
+            .collect())
+    }
+
+    /// Finds a shortest path between two vertices using Dial's bucket-queue algorithm.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - The key of the start vertex.
+    /// * `to` - The key of the target vertex.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<Path>, GraphError>` - The path, `None` if the target is unreachable, or an
+    ///   error if a vertex does not exist or a weight is not a non-negative integer up to
+    ///   `MAX_DIAL_WEIGHT`.
+    pub fn dial_shortest_path(&self, from: &str, to: &str) -> Result<Option<Path>, GraphError> {
+        self.require_distances("Dial's algorithm")?;
+        let index: GraphIndex = self.index();
+        let source: usize = index.position(from)?;
+        let target: usize = index.position(to)?;
+        let (distances, predecessors) = dial(&index, source)?;
+
+        Ok(distances[target].map(|_| Path::through(&index, &reconstruct(&predecessors, target), |_, _, _| true)))
+    }
+
+    /// Finds two edge-disjoint paths between two vertices with minimum total weight (Suurballe).
+    ///
+    /// The pair is computed as a two-unit minimum-cost flow with unit edge capacities, which
+    /// is equivalent to Suurballe's algorithm. In undirected graphs the paths never share an
+    /// edge in either direction. Edge weights must be non-negative.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - The key of the start vertex.
+    /// * `to` - The key of the target vertex.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<(Path, Path)>, GraphError>` - The two paths, `None` if no two edge-disjoint
+    ///   paths exist, or an error if a vertex does not exist or an edge weight is negative.
+    pub fn two_disjoint_paths(&self, from: &str, to: &str) -> Result<Option<(Path, Path)>, GraphError> {
+        self.require_distances("Disjoint shortest paths")?;
+        let index: GraphIndex = self.index();
+        let source: usize = index.position(from)?;
+        let target: usize = index.position(to)?;
+        reject_negative_weights(&index)?;
+        if source == target {
+            return Ok(None);
+        }
+
+        let mut network: FlowNetwork = FlowNetwork::new(index.len());
+        let mut arcs: Vec<(usize, f32)> = Vec::new();
+        for (u, neighbors) in index.adjacency.iter().enumerate() {
+            for &(v, weight) in neighbors {
+                arcs.push((network.add_arc(u, v, 1.0, f64::from(weight)), weight));
+            }
+        }
+        let (flow, _) = network.min_cost_flow(source, target, 2.0)
+            .map_err(|v| GraphError::NegativeCycle(vec![index.keys[v].clone()]))?;
+        if flow < 2.0 - 1e-6 {
+            return Ok(None);
+        }
+
+        // Collect the used edges, cancelling any edge used once in each direction.
+        let mut used: Vec<(usize, usize, f32)> = Vec::new();
+        for &(arc, weight) in &arcs {
+            if network.flow(arc) < 0.5 {
+                continue;
+            }
+            let (u, v) = (network.tail(arc), network.heads[arc]);
+            match used.iter().position(|&(a, b, _)| a == v && b == u) {
+                Some(opposite) => {
+                    used.swap_remove(opposite);
+                }
+                None => used.push((u, v, weight)),
+            }
+        }
+
+        let mut paths: Vec<Path> = Vec::with_capacity(2);
+        for _ in 0..2 {
+            let mut path: Path = Path::new(&index.keys[source]);
+            let mut current: usize = source;
+            while current != target {
+                let position: usize = used.iter().position(|&(u, _, _)| u == current).unwrap();
+                let (_, v, weight) = used.swap_remove(position);
+                path.push(&index.keys[v], weight);
+                current = v;
+            }
+            paths.push(path);
+        }
+
+        let second: Path = paths.pop().unwrap();
+        let first: Path = paths.pop().unwrap();
+        Ok(Some((first, second)))
+    }
+
+    /// Finds up to `k` loopless paths between two vertices in order of total weight, with
+    /// Yen's algorithm.
+    ///
+    /// The first path is the one `shortest_path` finds. Every later one branches off an
+    /// earlier path at some vertex (the spur): the part before the spur is kept, the edges
+    /// the earlier paths take out of it are blocked, and Dijkstra's algorithm finds the rest
+    /// of the way without revisiting the kept part. The best of all branches found so far
+    /// comes next, so this runs `O(k n)` searches. Paths count as different when their
+    /// vertices differ; between parallel edges the lightest is taken. Paths of equal weight
+    /// come in order of their vertex keys.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - The key of the start vertex.
+    /// * `to` - The key of the target vertex.
+    /// * `k` - The largest number of paths to return.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<Path>, GraphError>` - Up to `k` paths from the lightest up, fewer if there
+    ///   are no more, or an error if a vertex does not exist or an edge weight is negative.
+    pub fn k_shortest_paths(&self, from: &str, to: &str, k: usize) -> Result<Vec<Path>, GraphError> {
+        self.require_distances("K shortest paths")?;
+        let index: GraphIndex = self.index();
+        let source: usize = index.position(from)?;
+        let target: usize = index.position(to)?;
+        reject_negative_weights(&index)?;
+        trace_span!("k_shortest_paths", k = k);
+
+        let (distances, predecessors) = dijkstra(&index, source, |_| true, |_, _, _| true);
+        if k == 0 || distances[target].is_infinite() {
+            return Ok(Vec::new());
+        }
+        let first: Vec<usize> = reconstruct(&predecessors, target);
+        let mut paths: Vec<Path> = vec![Path::through(&index, &first, |_, _, _| true)];
+        let mut found: Vec<Vec<usize>> = vec![first.clone()];
+        let mut seen: HashSet<Vec<usize>> = HashSet::from([first]);
+        let mut candidates: Vec<(Path, Vec<usize>)> = Vec::new();
+
+        while found.len() < k {
+            let last: Vec<usize> = found.last().unwrap().clone();
+            for i in 0..last.len() - 1 {
+                let root: &[usize] = &last[..=i];
+                let blocked_edges: HashSet<(usize, usize)> = found.iter()
+                    .filter(|path| path.len() > i + 1 && path[..=i] == *root)
+                    .map(|path| (path[i], path[i + 1]))
+                    .collect();
+                let mut on_root: Vec<bool> = vec![false; index.len()];
+                root[..i].iter().for_each(|&v| on_root[v] = true);
+
+                let (distances, predecessors) = dijkstra(&index, last[i], |v| !on_root[v], |u, v, _| !blocked_edges.contains(&(u, v)));
+                if distances[target].is_infinite() {
+                    continue;
+                }
+                let mut candidate: Vec<usize> = root[..i].to_vec();
+                candidate.extend(reconstruct(&predecessors, target));
+                if seen.insert(candidate.clone()) {
+                    candidates.push((Path::through(&index, &candidate, |_, _, _| true), candidate));
+                }
+            }
+
+            let Some(best) = (0..candidates.len()).min_by(|&a, &b| {
+                candidates[a].0.cost.total_cmp(&candidates[b].0.cost).then_with(|| candidates[a].1.cmp(&candidates[b].1))
+            }) else {
+                break;
+            };
+            let (path, vertices) = candidates.swap_remove(best);
+            paths.push(path);
+            found.push(vertices);
+        }
+        trace_counts!("k_shortest_paths finished", found = paths.len(), candidates = candidates.len());
+        Ok(paths)
+    }
+
+    /// Computes shortest paths between every pair of vertices with the Floyd-Warshall algorithm.
+    ///
+    /// Starting from the lightest direct edge between each pair, every vertex in turn is
+    /// allowed as an intermediate stop, for `O(n³)` time and `O(n²)` memory. Negative edge
+    /// weights are allowed as long as there is no negative cycle; in undirected graphs a
+    /// negative edge counts as one.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<AllPairsShortestPaths, GraphError>` - The distance and predecessor matrices, or an
+    ///   error if the graph has a negative cycle.
+    pub fn all_pairs_shortest_paths(&self) -> Result<AllPairsShortestPaths, GraphError> {
+        self.all_pairs_shortest_paths_with(&ExecutionControl::new())
+    }
+
+    /// Computes shortest paths between every pair of vertices as `all_pairs_shortest_paths`
+    /// does, under a deadline, cancellation token or progress callback.
+    ///
+    /// Progress is reported after each of the `n` intermediate vertices.
+    ///
+    /// # Arguments
+    ///
+    /// * `control` - The limits and hooks for the run.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<AllPairsShortestPaths, GraphError>` - The distance and predecessor matrices, or an
+    ///   error if the graph has a negative cycle or the run was stopped.
+    pub fn all_pairs_shortest_paths_with(&self, control: &ExecutionControl) -> Result<AllPairsShortestPaths, GraphError> {
+        self.require_distances("All-pairs shortest paths")?;
+        let index: GraphIndex = self.index();
+        let n: usize = index.len();
+        trace_span!("all_pairs_shortest_paths", vertices = n);
+        let mut distances: Vec<Vec<f32>> = vec![vec![f32::INFINITY; n]; n];
+        let mut predecessors: Vec<Vec<Option<usize>>> = vec![vec![None; n]; n];
+        for (u, neighbors) in index.adjacency.iter().enumerate() {
+            distances[u][u] = 0.0;
+            for &(v, weight) in neighbors {
+                if weight < distances[u][v] {
+                    distances[u][v] = weight;
+                    predecessors[u][v] = Some(u);
+                }
+            }
+        }
+
+        let direct: Vec<Vec<f32>> = distances.clone();
+
+        let mut relaxations: usize = 0;
+        for k in 0..n {
+            let through: Vec<f32> = distances[k].clone();
+            let through_predecessors: Vec<Option<usize>> = predecessors[k].clone();
+            for (row, row_predecessors) in distances.iter_mut().zip(predecessors.iter_mut()) {
+                if row[k].is_infinite() {
+                    continue;
+                }
+                for j in 0..n {
+                    if row[k] + through[j] < row[j] {
+                        row[j] = row[k] + through[j];
+                        row_predecessors[j] = through_predecessors[j];
+                        relaxations += 1;
+                    }
+                }
+            }
+            control.step(k + 1, Some(n))?;
+        }
+        trace_counts!("all_pairs_shortest_paths finished", relaxations = relaxations);
+
+        if let Some(v) = (0..n).find(|&v| distances[v][v] < 0.0) {
+            return Err(GraphError::NegativeCycle(vec![index.keys[v].clone()]));
+        }
+        for (v, row) in predecessors.iter_mut().enumerate() {
+            row[v] = None;
+        }
+        Ok(AllPairsShortestPaths { keys: index.keys, distances, predecessors, direct })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Path, PathConstraints, MAX_DIAL_WEIGHT};
+    use crate::graph::testing::graph_from;
+    use crate::graph::{AttrValue, Edge, Graph, GraphError};
+
+    #[test]
+    fn dial_rejects_huge_weights() {
+        let graph: Graph = graph_from(true, &[("A", "B", 4e9), ("B", "C", 1.0)]);
+        assert!(matches!(graph.dial_distances("A"), Err(GraphError::InvalidArgument(_))));
+        assert!(matches!(graph.dial_shortest_path("A", "C"), Err(GraphError::InvalidArgument(_))));
+    }
+
+    #[test]
+    fn dial_accepts_the_largest_weight() {
+        let graph: Graph = graph_from(true, &[("A", "B", MAX_DIAL_WEIGHT as f32), ("B", "C", 1.0)]);
+        assert_eq!(graph.dial_distances("A").unwrap()["C"], MAX_DIAL_WEIGHT + 1);
+    }
+
+    #[test]
+    fn shortest_path_with_turns_rejects_negative_weights() {
+        let graph: Graph = graph_from(true, &[("A", "B", 5.0), ("A", "C", 1.0), ("B", "C", -10.0)]);
+        assert_eq!(
+            graph.shortest_path_with_turns("A", "C", |_, _, _| 0.0),
+            Err(GraphError::NegativeWeight("B".to_string(), "C".to_string()))
+        );
+    }
+
+    #[test]
+    fn shortest_path_with_turns_rejects_negative_penalties() {
+        let graph: Graph = graph_from(true, &[("A", "B", 1.0), ("B", "C", 1.0)]);
+        assert!(matches!(graph.shortest_path_with_turns("A", "C", |_, _, _| -1.0), Err(GraphError::InvalidArgument(_))));
+        assert_eq!(graph.shortest_path_with_turns("A", "C", |_, _, _| 0.5).unwrap().unwrap().cost, 2.5);
+    }
+
+    #[test]
+    fn constrained_shortest_path_rejects_negative_weights() {
+        let graph: Graph = graph_from(true, &[("A", "B", 5.0), ("A", "C", 1.0), ("B", "C", -10.0)]);
+        assert_eq!(
+            graph.constrained_shortest_path("A", "C", &PathConstraints::new()),
+            Err(GraphError::NegativeWeight("B".to_string(), "C".to_string()))
+        );
+    }
+
+    #[test]
+    fn constrained_shortest_path_visits_waypoints() {
+        let graph: Graph = graph_from(true, &[("A", "B", 5.0), ("A", "C", 1.0), ("B", "C", 1.0)]);
+        let path: Path = graph.constrained_shortest_path("A", "C", &PathConstraints::new().via("B")).unwrap().unwrap();
+        assert_eq!((path.vertices, path.cost), (vec!["A".to_string(), "B".to_string(), "C".to_string()], 6.0));
+    }
+
+    #[test]
+    fn resource_constrained_shortest_path_tells_parallel_edges_apart() {
+        let mut graph: Graph = graph_from(true, &[("A", "B", 1.0), ("B", "C", 1.0)]);
+        let mut free: Edge = Edge::new("A".to_string(), "B".to_string(), 3.0);
+        free.attributes.insert("toll".to_string(), AttrValue::Float(0.0));
+        graph.add_edge(free).unwrap();
+        let toll = |edge: &Edge| edge.attributes.get("toll").map_or(5.0, |toll| match toll {
+            AttrValue::Float(amount) => *amount as f32,
+            _ => 5.0,
+        });
+        let (path, used) = graph.resource_constrained_shortest_path("A", "C", 6.0, toll).unwrap().unwrap();
+        assert_eq!((path.cost, used), (4.0, 5.0));
+        let (path, used) = graph.resource_constrained_shortest_path("A", "C", 10.0, toll).unwrap().unwrap();
+        assert_eq!((path.cost, used), (2.0, 10.0));
+    }
+
+    #[test]
+    fn resource_constrained_shortest_path_rejects_negative_values() {
+        let graph: Graph = graph_from(true, &[("A", "B", 1.0), ("B", "C", -1.0)]);
+        assert_eq!(
+            graph.resource_constrained_shortest_path("A", "C", 1.0, |_| 0.0),
+            Err(GraphError::NegativeWeight("B".to_string(), "C".to_string()))
+        );
+        let graph: Graph = graph_from(true, &[("A", "B", 1.0), ("B", "C", 1.0)]);
+        assert!(matches!(graph.resource_constrained_shortest_path("A", "C", 1.0, |_| -1.0), Err(GraphError::InvalidArgument(_))));
+    }
+
+    #[test]
+    fn two_disjoint_paths_rejects_negative_weights() {
+        let graph: Graph = graph_from(false, &[("A", "B", -1.0), ("B", "C", 1.0), ("A", "C", 1.0)]);
+        assert_eq!(graph.two_disjoint_paths("A", "C"), Err(GraphError::NegativeWeight("A".to_string(), "B".to_string())));
+    }
+
+    #[test]
+    fn two_disjoint_paths_finds_both_routes() {
+        let graph: Graph = graph_from(false, &[("A", "B", 1.0), ("B", "C", 1.0), ("A", "C", 1.0)]);
+        let (first, second) = graph.two_disjoint_paths("A", "C").unwrap().unwrap();
+        assert_eq!(first.cost + second.cost, 3.0);
+    }
+}
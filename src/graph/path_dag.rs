@@ -0,0 +1,198 @@
+use crate::graph::centrality::{reject_zero_weight_cycles, shortest_path_dag, ShortestPathDag};
+use crate::graph::index::GraphIndex;
+use crate::graph::path::{reject_negative_weights, Path};
+use crate::graph::{Graph, GraphError};
+use std::collections::VecDeque;
+
+/// Every shortest path from one source, as built by `Graph::shortest_path_dag` and
+/// `Graph::fewest_hops_dag`.
+///
+/// A vertex's predecessors are all the vertices that come right before it on some shortest
+/// path, so ties are kept instead of broken. Paths that differ only in which of two parallel
+/// edges they take count as one.
+#[derive(Clone, Debug)]
+pub struct PathDag {
+    index: GraphIndex,
+    source: usize,
+    /// The length minimized: total weight, or number of edges.
+    distance: Vec<f32>,
+    predecessors: Vec<Vec<usize>>,
+    counts: Vec<f64>,
+}
+
+impl PathDag {
+    /// Finishes a DAG from its predecessors, counting paths in `order`, which lists the
+    /// reached vertices with every vertex after its predecessors.
+    fn new(index: GraphIndex, source: usize, distance: Vec<f32>, mut predecessors: Vec<Vec<usize>>, order: &[usize]) -> PathDag {
+        predecessors.iter_mut().for_each(|list| {
+            list.sort_unstable();
+            list.dedup();
+        });
+        let mut counts: Vec<f64> = vec![0.0; index.len()];
+        counts[source] = 1.0;
+        for &w in order {
+            for &p in &predecessors[w] {
+                counts[w] += counts[p];
+            }
+        }
+        PathDag { index, source, distance, predecessors, counts }
+    }
+
+    /// Returns the key of the source vertex.
+    pub fn source(&self) -> &str {
+        &self.index.keys[self.source]
+    }
+
+    /// Returns the shortest distance from the source to a vertex: the total weight, or the
+    /// number of edges for a DAG from `fewest_hops_dag`.
+    ///
+    /// # Arguments
+    ///
+    /// * `to` - The key of the vertex.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<f32>, GraphError>` - The distance, `None` if the vertex is unreachable,
+    ///   or an error if it does not exist.
+    pub fn distance(&self, to: &str) -> Result<Option<f32>, GraphError> {
+        let target: usize = self.index.position(to)?;
+        Ok(Some(self.distance[target]).filter(|d| d.is_finite()))
+    }
+
+    /// Returns the vertices that come right before a vertex on its shortest paths.
+    ///
+    /// # Arguments
+    ///
+    /// * `to` - The key of the vertex.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<&str>, GraphError>` - The predecessor keys in sorted order, empty for the
+    ///   source and unreachable vertices, or an error if the vertex does not exist.
+    pub fn predecessors(&self, to: &str) -> Result<Vec<&str>, GraphError> {
+        let target: usize = self.index.position(to)?;
+        Ok(self.predecessors[target].iter().map(|&p| self.index.keys[p].as_str()).collect())
+    }
+
+    /// Counts the shortest paths from the source to a vertex without listing them.
+    ///
+    /// Counts grow exponentially on some graphs and are exact up to `2^53`.
+    ///
+    /// # Arguments
+    ///
+    /// * `to` - The key of the vertex.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<f64, GraphError>` - The number of shortest paths, `1` for the source and `0` for
+    ///   an unreachable vertex, or an error if the vertex does not exist.
+    pub fn count(&self, to: &str) -> Result<f64, GraphError> {
+        let target: usize = self.index.position(to)?;
+        Ok(self.counts[target])
+    }
+
+    /// Lists every shortest path from the source to a vertex.
+    ///
+    /// There can be exponentially many; `count` tells how many before listing them.
+    ///
+    /// # Arguments
+    ///
+    /// * `to` - The key of the vertex.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<Path>, GraphError>` - The paths in order of their vertex keys, with the
+    ///   lightest edge for every step, none if the vertex is unreachable, or an error if it does
+    ///   not exist.
+    pub fn paths(&self, to: &str) -> Result<Vec<Path>, GraphError> {
+        let target: usize = self.index.position(to)?;
+        if self.distance[target].is_infinite() {
+            return Ok(Vec::new());
+        }
+        let mut paths: Vec<Path> = Vec::new();
+        // Walks back from the target; every frame is `(vertex, next predecessor to try)`.
+        let mut stack: Vec<(usize, usize)> = vec![(target, 0)];
+        while let Some(&mut (v, ref mut cursor)) = stack.last_mut() {
+            if v == self.source {
+                let vertices: Vec<usize> = stack.iter().rev().map(|&(u, _)| u).collect();
+                paths.push(Path::through(&self.index, &vertices, |_, _, _| true));
+                stack.pop();
+                continue;
+            }
+            match self.predecessors[v].get(*cursor) {
+                Some(&p) => {
+                    *cursor += 1;
+                    stack.push((p, 0));
+                }
+                None => {
+                    stack.pop();
+                }
+            }
+        }
+        paths.sort_by(|a, b| a.vertices.cmp(&b.vertices));
+        Ok(paths)
+    }
+}
+
+impl Graph {
+    /// Builds the DAG of every shortest path from a vertex with Dijkstra's algorithm, for
+    /// counting or listing all co-optimal paths instead of one.
+    ///
+    /// Paths tie when their total weights are exactly equal, so weights that are not whole
+    /// numbers may split ties through rounding. Zero-weight edges are allowed as long as they
+    /// form no cycle, around which there would be endlessly many shortest walks; in an
+    /// undirected graph that rules out zero-weight edges altogether.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - The key of the source vertex.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<PathDag, GraphError>` - The shortest paths to every vertex, or an error if the
+    ///   vertex does not exist, an edge weight is negative or zero-weight edges form a cycle.
+    pub fn shortest_path_dag(&self, from: &str) -> Result<PathDag, GraphError> {
+        self.require_distances("Shortest paths")?;
+        let index: GraphIndex = self.index();
+        let source: usize = index.position(from)?;
+        reject_negative_weights(&index)?;
+        reject_zero_weight_cycles(&index)?;
+        let dag: ShortestPathDag = shortest_path_dag(&index, source);
+        Ok(PathDag::new(index, source, dag.distance, dag.predecessors, &dag.order))
+    }
+
+    /// Builds the DAG of every path with the fewest edges from a vertex, by breadth-first
+    /// search, ignoring weights.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - The key of the source vertex.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<PathDag, GraphError>` - The fewest-edge paths to every vertex, or an error if
+    ///   the vertex does not exist.
+    pub fn fewest_hops_dag(&self, from: &str) -> Result<PathDag, GraphError> {
+        let index: GraphIndex = self.index();
+        let source: usize = index.position(from)?;
+        let n: usize = index.len();
+        let mut distance: Vec<f32> = vec![f32::INFINITY; n];
+        let mut predecessors: Vec<Vec<usize>> = vec![Vec::new(); n];
+        let mut order: Vec<usize> = Vec::with_capacity(n);
+        distance[source] = 0.0;
+        let mut queue: VecDeque<usize> = VecDeque::from([source]);
+        while let Some(u) = queue.pop_front() {
+            order.push(u);
+            for &(v, _) in &index.adjacency[u] {
+                if distance[v].is_infinite() {
+                    distance[v] = distance[u] + 1.0;
+                    queue.push_back(v);
+                }
+                if distance[v] == distance[u] + 1.0 {
+                    predecessors[v].push(u);
+                }
+            }
+        }
+        Ok(PathDag::new(index, source, distance, predecessors, &order))
+    }
+}
@@ -0,0 +1,170 @@
+use crate::graph::graph::AggregationPolicy;
+use crate::graph::{Edge, Graph};
+use std::collections::BTreeMap;
+
+/// The combined weights of a group of parallel edges, for each direction of travel.
+type Directions = (Option<f32>, Option<f32>);
+
+/// How `Graph::normalize_weights` rescales edge weights.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Norm {
+    /// Divides every weight by the largest absolute weight, so weights fall in `[-1, 1]`.
+    MaxAbs,
+    /// Divides every weight by the sum of absolute weights, so positive weights sum to one.
+    Sum,
+    /// Subtracts the mean weight and divides by the standard deviation.
+    ZScore,
+}
+
+impl Graph {
+    /// Builds a graph with the same vertices, attributes, direction and settings as this one
+    /// and the given edges.
+    fn with_stored_edges(&self, edges: Vec<Edge>) -> Graph {
+        let mut graph: Graph = self.empty_like();
+        for key in self.vertex_keys() {
+            graph.add_vertex_like(self, &key);
+        }
+        for edge in edges {
+            graph.add_edge(edge).unwrap();
+        }
+        graph
+    }
+
+    /// Rescales the edge weights, for example to compare graphs imported from sources with
+    /// different units.
+    ///
+    /// An undirected edge with direction-dependent weights contributes both of its weights
+    /// to the statistics, and both are rescaled. When the weights are all zero, or all equal
+    /// for `Norm::ZScore`, there is no scale to divide by and the weights are only shifted.
+    ///
+    /// # Arguments
+    ///
+    /// * `norm` - How to rescale the weights.
+    ///
+    /// # Returns
+    ///
+    /// * A `Graph` with the same vertices and edges and rescaled weights.
+    pub fn normalize_weights(&self, norm: Norm) -> Graph {
+        let edges: Vec<&Edge> = self.stored_edges();
+        let weights: Vec<f32> = edges.iter()
+            .flat_map(|edge| std::iter::once(edge.weight).chain(edge.reverse_weight))
+            .collect();
+        let (shift, scale): (f32, f32) = match norm {
+            Norm::MaxAbs => (0.0, weights.iter().fold(0.0, |max: f32, w| max.max(w.abs()))),
+            Norm::Sum => (0.0, weights.iter().map(|w| w.abs()).sum()),
+            Norm::ZScore => {
+                let n: f32 = weights.len().max(1) as f32;
+                let mean: f32 = weights.iter().sum::<f32>() / n;
+                let variance: f32 = weights.iter().map(|w| (w - mean) * (w - mean)).sum::<f32>() / n;
+                (mean, variance.sqrt())
+            }
+        };
+        let scale: f32 = if scale > 0.0 { scale } else { 1.0 };
+        let rescale = |w: f32| (w - shift) / scale;
+
+        let normalized: Vec<Edge> = edges.into_iter()
+            .map(|edge| Edge {
+                vertex1: edge.vertex1.clone(),
+                vertex2: edge.vertex2.clone(),
+                weight: rescale(edge.weight),
+                reverse_weight: edge.reverse_weight.map(rescale),
+                attributes: edge.attributes.clone(),
+            })
+            .collect();
+        self.with_stored_edges(normalized)
+    }
+
+    /// Drops every edge from a vertex to itself.
+    ///
+    /// # Returns
+    ///
+    /// * A `Graph` with the same vertices and every edge except the self-loops.
+    pub fn remove_self_loops(&self) -> Graph {
+        let edges: Vec<Edge> = self.stored_edges().into_iter()
+            .filter(|edge| edge.vertex1 != edge.vertex2)
+            .map(|edge| Edge {
+                vertex1: edge.vertex1.clone(),
+                vertex2: edge.vertex2.clone(),
+                weight: edge.weight,
+                reverse_weight: edge.reverse_weight,
+                attributes: edge.attributes.clone(),
+            })
+            .collect();
+        self.with_stored_edges(edges)
+    }
+
+    /// Replaces every group of parallel edges with a single edge.
+    ///
+    /// In directed graphs only edges with the same source and target are parallel. In
+    /// undirected graphs the weights for each direction of travel are combined separately,
+    /// and the merged edge is direction-dependent only if the two results differ.
+    ///
+    /// # Arguments
+    ///
+    /// * `policy` - How to combine the weights of parallel edges.
+    ///
+    /// # Returns
+    ///
+    /// * A `Graph` with the same vertices and at most one edge per pair of vertices (per
+    ///   ordered pair, in directed graphs).
+    pub fn collapse_multi_edges(&self, policy: AggregationPolicy) -> Graph {
+        // Undirected pairs are keyed with the smaller key first; the first weight is the
+        // cost of travelling from the first key.
+        let mut groups: BTreeMap<(&String, &String), Directions> = BTreeMap::new();
+        for edge in self.stored_edges() {
+            let (a, b) = (&edge.vertex1, &edge.vertex2);
+            let pair: (&String, &String) = if self.directed || a <= b { (a, b) } else { (b, a) };
+            let (forward, backward) = groups.entry(pair).or_insert((None, None));
+            *forward = Some(policy.combine(*forward, edge.weight_from(pair.0.as_str())));
+            *backward = Some(policy.combine(*backward, edge.weight_from(pair.1.as_str())));
+        }
+
+        let edges: Vec<Edge> = groups.into_iter()
+            .map(|((a, b), (forward, backward))| {
+                let (forward, backward): (f32, f32) = (forward.unwrap(), backward.unwrap());
+                if self.directed || forward == backward {
+                    Edge::new(a.clone(), b.clone(), forward)
+                } else {
+                    Edge::asymmetric(a.clone(), b.clone(), forward, backward)
+                }
+            })
+            .collect();
+        self.with_stored_edges(edges)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Norm;
+    use crate::graph::graph::{AggregationPolicy, GraphMode, VertexOrder, WeightSemantics};
+    use crate::graph::testing::graph_from;
+    use crate::graph::{AttrValue, Graph};
+
+    #[test]
+    fn preprocessing_keeps_attributes_and_settings() {
+        let mut graph: Graph = graph_from(true, &[("b", "a", 2.0), ("b", "a", 4.0), ("a", "a", 1.0)]);
+        graph.vertices.get_mut("b").unwrap().set_attr("color", AttrValue::Text("red".to_string()));
+        graph.set_vertex_order(VertexOrder::Insertion);
+        graph.set_weight_semantics(WeightSemantics::Distance);
+
+        for result in [
+            graph.normalize_weights(Norm::MaxAbs),
+            graph.remove_self_loops(),
+            graph.collapse_multi_edges(AggregationPolicy::Sum),
+        ] {
+            assert_eq!(result.vertices["b"].attr("color"), Some(&AttrValue::Text("red".to_string())));
+            assert_eq!(result.vertex_order(), VertexOrder::Insertion);
+            assert_eq!(result.vertex_keys(), vec!["b".to_string(), "a".to_string()]);
+            assert_eq!(result.weight_semantics(), WeightSemantics::Distance);
+        }
+    }
+
+    #[test]
+    fn simple_graph_stays_simple() {
+        let mut graph: Graph = graph_from(false, &[("a", "b", 2.0), ("b", "c", 4.0)]);
+        graph.set_mode(GraphMode::Simple).unwrap();
+        let normalized: Graph = graph.normalize_weights(Norm::Sum);
+        assert_eq!(normalized.mode(), GraphMode::Simple);
+        assert_eq!(normalized.vertices["b"].edges.iter().map(|edge| edge.weight).sum::<f32>(), 1.0);
+    }
+}
@@ -0,0 +1,83 @@
+use crate::graph::index::GraphIndex;
+use crate::graph::linalg::solve_laplacian;
+use crate::graph::{Graph, GraphError};
+
+/// The relative residual at which the Laplacian solver stops.
+const TOLERANCE: f64 = 1e-9;
+
+/// Returns whether `to` can be reached from `from`.
+fn connected(index: &GraphIndex, from: usize, to: usize) -> bool {
+    let mut seen: Vec<bool> = vec![false; index.len()];
+    let mut stack: Vec<usize> = vec![from];
+    seen[from] = true;
+    while let Some(u) = stack.pop() {
+        for &(v, _) in &index.adjacency[u] {
+            if !seen[v] {
+                seen[v] = true;
+                stack.push(v);
+            }
+        }
+    }
+    seen[to]
+}
+
+impl Graph {
+    /// Computes the effective resistance between two vertices.
+    ///
+    /// Every edge is a resistor with conductance equal to its weight. Injecting a unit current
+    /// at `u` and extracting it at `v` gives node potentials `L x = e_u - e_v`, solved with
+    /// conjugate gradient, and the resistance is the potential difference `x_u - x_v`. Only
+    /// matrix-vector products with the sparse Laplacian are needed. Self-loops are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `u` - The key of the first vertex.
+    /// * `v` - The key of the second vertex.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<f32, GraphError>` - The effective resistance (`f32::INFINITY` if the vertices are
+    ///   not connected), or an error if the graph is directed or a vertex does not exist.
+    pub fn effective_resistance(&self, u: &str, v: &str) -> Result<f32, GraphError> {
+        if self.directed {
+            return Err(GraphError::NeedsUndirected("Effective resistance".to_string()));
+        }
+        let index: GraphIndex = self.index();
+        let (s, t) = (index.position(u)?, index.position(v)?);
+        if s == t {
+            return Ok(0.0);
+        }
+        if !connected(&index, s, t) {
+            return Ok(f32::INFINITY);
+        }
+        let mut current: Vec<f64> = vec![0.0; index.len()];
+        current[s] = 1.0;
+        current[t] = -1.0;
+        let potential: Vec<f64> = solve_laplacian(&index, &current, TOLERANCE);
+        Ok((potential[s] - potential[t]) as f32)
+    }
+
+    /// Computes the commute time between two vertices of the weighted random walk.
+    ///
+    /// The expected number of steps to walk from `u` to `v` and back equals the effective
+    /// resistance times the total weighted degree (twice the total edge weight).
+    ///
+    /// # Arguments
+    ///
+    /// * `u` - The key of the first vertex.
+    /// * `v` - The key of the second vertex.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<f32, GraphError>` - The commute time (`f32::INFINITY` if the vertices are not
+    ///   connected), or an error if the graph is directed or a vertex does not exist.
+    pub fn commute_time(&self, u: &str, v: &str) -> Result<f32, GraphError> {
+        let resistance: f32 = self.effective_resistance(u, v)?;
+        let volume: f32 = self.vertices.values()
+            .flat_map(|vertex| &vertex.edges)
+            .filter(|edge| edge.vertex1 != edge.vertex2)
+            .map(|edge| edge.weight)
+            .sum();
+        Ok(resistance * volume)
+    }
+}
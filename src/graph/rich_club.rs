@@ -0,0 +1,85 @@
+use crate::graph::coloring::symmetric_neighbors;
+use crate::graph::rng::Rng;
+use crate::graph::{Graph, GraphError};
+use std::collections::HashSet;
+
+/// Returns the density of the subgraph induced by the vertices with more than `k`
+/// neighbors, or `None` if there are fewer than two such vertices.
+fn rich_club(neighbors: &[HashSet<usize>], k: usize) -> Option<f64> {
+    let rich: Vec<bool> = neighbors.iter().map(|list| list.len() > k).collect();
+    let members: usize = rich.iter().filter(|&&is_rich| is_rich).count();
+    if members < 2 {
+        return None;
+    }
+    let links: usize = neighbors.iter()
+        .enumerate()
+        .filter(|&(u, _)| rich[u])
+        .map(|(u, list)| list.iter().filter(|&&v| v > u && rich[v]).count())
+        .sum();
+    Some(2.0 * links as f64 / (members * (members - 1)) as f64)
+}
+
+impl Graph {
+    /// Computes the rich-club coefficient: how densely the vertices of degree above `k`
+    /// link to each other.
+    ///
+    /// The coefficient is `2 E / (N (N - 1))`, where `N` vertices have degree above `k` and
+    /// `E` edges join them. Degrees count distinct neighbors, ignoring direction, weights,
+    /// parallel edges and self-loops. Even random graphs have rising coefficients, since
+    /// high-degree vertices meet more often by chance; compare against
+    /// `normalized_rich_club_coefficient` before reading it as elite connectivity.
+    ///
+    /// # Arguments
+    ///
+    /// * `k` - The degree a vertex must exceed to belong to the club.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<f64, GraphError>` - The coefficient in `[0, 1]`, or an error if fewer than
+    ///   two vertices have degree above `k`.
+    pub fn rich_club_coefficient(&self, k: usize) -> Result<f64, GraphError> {
+        let neighbors: Vec<HashSet<usize>> = symmetric_neighbors(&self.index());
+        rich_club(&neighbors, k).ok_or_else(|| GraphError::NoSolution(format!("Fewer than two vertices have degree above {}", k)))
+    }
+
+    /// Computes the rich-club coefficient relative to random graphs with the same degrees.
+    ///
+    /// The baseline is the mean coefficient over `samples` graphs drawn with
+    /// `Graph::configuration_model` from this graph's degree sequence. The random graphs'
+    /// self-loops and parallel edges are ignored, as they are here, so their degrees can fall
+    /// slightly short. A ratio above `1` means the rich vertices link to each other more than
+    /// their degrees alone explain.
+    ///
+    /// # Arguments
+    ///
+    /// * `k` - The degree a vertex must exceed to belong to the club.
+    /// * `samples` - The number of random graphs to average over.
+    /// * `seed` - The random seed; the same seed reproduces the same baseline.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<f64, GraphError>` - The ratio of the coefficient to the baseline, or an
+    ///   error if `samples` is `0`, fewer than two vertices have degree above `k`, or no
+    ///   random graph has a rich-club edge.
+    pub fn normalized_rich_club_coefficient(&self, k: usize, samples: usize, seed: u64) -> Result<f64, GraphError> {
+        if samples == 0 {
+            return Err(GraphError::InvalidArgument("Need at least one random sample".to_string()));
+        }
+        let neighbors: Vec<HashSet<usize>> = symmetric_neighbors(&self.index());
+        let observed: f64 = rich_club(&neighbors, k).ok_or_else(|| GraphError::NoSolution(format!("Fewer than two vertices have degree above {}", k)))?;
+        let degrees: Vec<usize> = neighbors.iter().map(HashSet::len).collect();
+
+        let mut rng: Rng = Rng::new(seed);
+        let mut total: f64 = 0.0;
+        for _ in 0..samples {
+            let random: Graph = Graph::configuration_model(&degrees, rng.next_u64())?;
+            // A sample whose club shrank below two vertices has no links among them.
+            total += rich_club(&symmetric_neighbors(&random.index()), k).unwrap_or(0.0);
+        }
+        let baseline: f64 = total / samples as f64;
+        if baseline == 0.0 {
+            return Err(GraphError::NoSolution(format!("No random graph links two vertices of degree above {}", k)));
+        }
+        Ok(observed / baseline)
+    }
+}
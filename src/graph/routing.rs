@@ -0,0 +1,118 @@
+use crate::graph::index::GraphIndex;
+use crate::graph::path::dijkstra;
+use crate::graph::{Graph, GraphError};
+
+/// One vehicle route of a routing plan.
+#[derive(Clone, Debug)]
+pub struct Route {
+    /// The customers in visiting order; the route starts and ends at the depot.
+    pub stops: Vec<String>,
+    /// The total demand served.
+    pub load: f32,
+    /// The travel cost from the depot through every stop and back.
+    pub cost: f32,
+}
+
+impl Graph {
+    /// Plans capacitated vehicle routes from a depot with the Clarke-Wright savings heuristic.
+    ///
+    /// Every customer starts on its own out-and-back route. Serving `j` right after `i`
+    /// instead saves `d(i, depot) + d(depot, j) - d(i, j)`, so route ends are joined in order
+    /// of decreasing savings whenever the combined demand fits in one vehicle. Distances are
+    /// shortest-path distances, which equal the edge weights on a complete metric graph. In
+    /// undirected graphs routes may be reversed to make a join possible.
+    ///
+    /// # Arguments
+    ///
+    /// * `depot` - The key of the vertex every route starts and ends at.
+    /// * `capacity` - The demand one vehicle can serve.
+    /// * `demand` - Returns the demand of a vertex given its key; vertices other than the depot
+    ///   with positive demand are the customers.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<Route>, GraphError>` - The routes, most expensive first, or an error if the depot
+    ///   does not exist, a customer's demand exceeds the capacity, or a customer cannot be
+    ///   reached from and return to the depot.
+    pub fn clarke_wright_routes<D>(&self, depot: &str, capacity: f32, demand: D) -> Result<Vec<Route>, GraphError>
+    where
+        D: Fn(&str) -> f32,
+    {
+        self.require_distances("Vehicle routing")?;
+        let index: GraphIndex = self.index();
+        let depot: usize = index.position(depot)?;
+        let customers: Vec<usize> = (0..index.len())
+            .filter(|&v| v != depot && demand(&index.keys[v]) > 0.0)
+            .collect();
+        let load: Vec<f32> = customers.iter().map(|&v| demand(&index.keys[v])).collect();
+        if let Some(i) = (0..customers.len()).find(|&i| load[i] > capacity) {
+            return Err(GraphError::NoSolution(format!("Demand of {} exceeds the vehicle capacity", index.keys[customers[i]])));
+        }
+
+        // Distances between the depot (slot `k`) and the customers (slots `0..k`).
+        let k: usize = customers.len();
+        let mut sites: Vec<usize> = customers.clone();
+        sites.push(depot);
+        let distance: Vec<Vec<f32>> = sites.iter()
+            .map(|&s| {
+                let (from_s, _) = dijkstra(&index, s, |_| true, |_, _, _| true);
+                sites.iter().map(|&t| from_s[t]).collect()
+            })
+            .collect();
+        if let Some(i) = (0..k).find(|&i| distance[k][i].is_infinite() || distance[i][k].is_infinite()) {
+            return Err(GraphError::NoSolution(format!("Customer {} cannot be served from the depot", index.keys[customers[i]])));
+        }
+
+        let mut savings: Vec<(f32, usize, usize)> = (0..k)
+            .flat_map(|i| (0..k).filter(move |&j| j != i).map(move |j| (i, j)))
+            .map(|(i, j)| (distance[i][k] + distance[k][j] - distance[i][j], i, j))
+            .filter(|&(saving, _, _)| saving > 0.0)
+            .collect();
+        savings.sort_by(|a, b| b.0.total_cmp(&a.0).then_with(|| (a.1, a.2).cmp(&(b.1, b.2))));
+
+        // Routes are slots that may be emptied by merges; `route_of` maps a customer to its slot.
+        let mut routes: Vec<Vec<usize>> = (0..k).map(|i| vec![i]).collect();
+        let mut loads: Vec<f32> = load;
+        let mut route_of: Vec<usize> = (0..k).collect();
+        for (_, i, j) in savings {
+            let (a, b) = (route_of[i], route_of[j]);
+            if a == b || loads[a] + loads[b] > capacity {
+                continue;
+            }
+            // `i` must end route `a` and `j` must start route `b`, reversing routes if allowed.
+            if *routes[a].last().unwrap() != i {
+                if self.directed || routes[a][0] != i {
+                    continue;
+                }
+                routes[a].reverse();
+            }
+            if routes[b][0] != j {
+                if self.directed || *routes[b].last().unwrap() != j {
+                    continue;
+                }
+                routes[b].reverse();
+            }
+            let moved: Vec<usize> = std::mem::take(&mut routes[b]);
+            for &c in &moved {
+                route_of[c] = a;
+            }
+            routes[a].extend(moved);
+            loads[a] += loads[b];
+            loads[b] = 0.0;
+        }
+
+        let mut plan: Vec<Route> = routes.into_iter()
+            .zip(loads)
+            .filter(|(stops, _)| !stops.is_empty())
+            .map(|(stops, load)| {
+                let inner: f32 = stops.windows(2).map(|pair| distance[pair[0]][pair[1]]).sum();
+                let cost: f32 = distance[k][stops[0]] + inner + distance[*stops.last().unwrap()][k];
+                let stops: Vec<String> = stops.into_iter().map(|c| index.keys[customers[c]].clone()).collect();
+                Route { stops, load, cost }
+            })
+            .collect();
+        plan.sort_by(|a, b| b.cost.total_cmp(&a.cost).then_with(|| a.stops.cmp(&b.stops)));
+        Ok(plan)
+    }
+}
+
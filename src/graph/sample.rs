@@ -0,0 +1,204 @@
+use crate::graph::index::{GraphIndex, IndexedEdge};
+use crate::graph::rng::Rng;
+use crate::graph::{Graph, GraphError, Vertex};
+
+/// Draws indices with probability proportional to fixed weights, in constant time per draw.
+///
+/// Built with Walker's alias method: every index owns a slot that it shares with at most one
+/// other index, so a draw picks a slot and flips one biased coin.
+#[derive(Clone, Debug)]
+pub struct WeightedSampler {
+    /// The probability that a draw landing on slot `i` returns `i` rather than `alias[i]`.
+    probability: Vec<f64>,
+    alias: Vec<usize>,
+}
+
+impl WeightedSampler {
+    /// Prepares a sampler over the given weights.
+    ///
+    /// # Arguments
+    ///
+    /// * `weights` - The relative weight of every index; zero weights are never drawn.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<WeightedSampler, GraphError>` - The sampler, or an error if a weight is negative or
+    ///   not finite, or no weight is positive.
+    pub fn new(weights: &[f32]) -> Result<WeightedSampler, GraphError> {
+        if let Some(position) = weights.iter().position(|weight| !weight.is_finite() || *weight < 0.0) {
+            return Err(GraphError::InvalidArgument(format!("Weight {} at position {} is not a non-negative number", weights[position], position)));
+        }
+        let total: f64 = weights.iter().map(|&weight| f64::from(weight)).sum();
+        if total <= 0.0 {
+            return Err(GraphError::InvalidArgument("There is no positive weight to sample from".to_string()));
+        }
+
+        let n: usize = weights.len();
+        let mut probability: Vec<f64> = weights.iter().map(|&weight| f64::from(weight) * n as f64 / total).collect();
+        let mut alias: Vec<usize> = (0..n).collect();
+        let (mut small, mut large): (Vec<usize>, Vec<usize>) = (0..n).partition(|&i| probability[i] < 1.0);
+        while let (Some(&under), Some(&over)) = (small.last(), large.last()) {
+            small.pop();
+            alias[under] = over;
+            probability[over] -= 1.0 - probability[under];
+            if probability[over] < 1.0 {
+                large.pop();
+                small.push(over);
+            }
+        }
+        // Whatever is left is full up to rounding error.
+        for i in small.into_iter().chain(large) {
+            probability[i] = 1.0;
+        }
+        Ok(WeightedSampler { probability, alias })
+    }
+
+    /// Draws an index.
+    ///
+    /// # Arguments
+    ///
+    /// * `rng` - The random generator to draw from.
+    ///
+    /// # Returns
+    ///
+    /// * A `usize` position into the weights the sampler was built from.
+    pub fn sample(&self, rng: &mut Rng) -> usize {
+        let slot: usize = rng.below(self.probability.len());
+        if rng.next_f64() < self.probability[slot] {
+            slot
+        } else {
+            self.alias[slot]
+        }
+    }
+}
+
+/// Picks a position with probability proportional to `weights`, or `None` if no weight is
+/// positive. `name` describes the item at a position for the error on a negative weight.
+fn pick<N>(weights: &[f32], name: N, rng: &mut Rng) -> Result<Option<usize>, GraphError>
+where
+    N: Fn(usize) -> String,
+{
+    if let Some(position) = weights.iter().position(|&weight| weight < 0.0) {
+        return Err(GraphError::InvalidArgument(format!("{} has negative weight {}", name(position), weights[position])));
+    }
+    if weights.iter().all(|&weight| weight == 0.0) {
+        return Ok(None);
+    }
+    Ok(Some(WeightedSampler::new(weights)?.sample(rng)))
+}
+
+impl Graph {
+    /// Picks a vertex uniformly at random.
+    ///
+    /// Vertices are taken in key order, so the same generator state always gives the same
+    /// vertex. Collecting the keys costs `O(V)` per call; for many draws, sample positions
+    /// into a key list instead.
+    ///
+    /// # Arguments
+    ///
+    /// * `rng` - The random generator to draw from.
+    ///
+    /// # Returns
+    ///
+    /// * An `Option<String>` with the key of the vertex, or `None` if the graph is empty.
+    pub fn random_vertex(&self, rng: &mut Rng) -> Option<String> {
+        let keys: Vec<&String> = self.vertices.keys().collect();
+        if keys.is_empty() {
+            return None;
+        }
+        Some(keys[rng.below(keys.len())].clone())
+    }
+
+    /// Picks a vertex at random with probability proportional to a weight.
+    ///
+    /// # Arguments
+    ///
+    /// * `rng` - The random generator to draw from.
+    /// * `weight` - Returns the non-negative weight of a vertex given its key, for example its degree.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<String>, GraphError>` - The key of the vertex, `None` if no vertex has a positive
+    ///   weight, or an error if a weight is negative.
+    pub fn random_weighted_vertex<W>(&self, rng: &mut Rng, weight: W) -> Result<Option<String>, GraphError>
+    where
+        W: Fn(&str) -> f32,
+    {
+        let keys: Vec<&String> = self.vertices.keys().collect();
+        let weights: Vec<f32> = keys.iter().map(|key| weight(key)).collect();
+        let picked: Option<usize> = pick(&weights, |v| format!("Vertex {}", keys[v]), rng)?;
+        Ok(picked.map(|v| keys[v].clone()))
+    }
+
+    /// Picks an edge uniformly at random.
+    ///
+    /// Every edge is equally likely, and an undirected edge is one candidate however it is
+    /// stored.
+    ///
+    /// # Arguments
+    ///
+    /// * `rng` - The random generator to draw from.
+    ///
+    /// # Returns
+    ///
+    /// * An `Option<(String, String, f32)>` with the endpoints and weight of the edge, or `None`
+    ///   if the graph has no edges.
+    pub fn random_edge(&self, rng: &mut Rng) -> Option<(String, String, f32)> {
+        let index: GraphIndex = self.index();
+        let edges: Vec<IndexedEdge> = index.edges();
+        if edges.is_empty() {
+            return None;
+        }
+        let (u, v, weight) = edges[rng.below(edges.len())];
+        Some((index.keys[u].clone(), index.keys[v].clone(), weight))
+    }
+
+    /// Picks an edge at random with probability proportional to its weight.
+    ///
+    /// # Arguments
+    ///
+    /// * `rng` - The random generator to draw from.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<(String, String, f32)>, GraphError>` - The endpoints and weight of the edge,
+    ///   `None` if no edge has a positive weight, or an error if a weight is negative.
+    pub fn random_weighted_edge(&self, rng: &mut Rng) -> Result<Option<(String, String, f32)>, GraphError> {
+        let index: GraphIndex = self.index();
+        let edges: Vec<IndexedEdge> = index.edges();
+        let weights: Vec<f32> = edges.iter().map(|&(_, _, weight)| weight).collect();
+        let name = |e: usize| format!("Edge ({}, {})", index.keys[edges[e].0], index.keys[edges[e].1]);
+        let picked: Option<usize> = pick(&weights, name, rng)?;
+        Ok(picked.map(|e| {
+            let (u, v, weight) = edges[e];
+            (index.keys[u].clone(), index.keys[v].clone(), weight)
+        }))
+    }
+
+    /// Takes one step of a weighted random walk.
+    ///
+    /// Each edge leaving the vertex is chosen with probability proportional to its weight,
+    /// respecting direction in directed graphs. Only the vertex's own edges are read, so a
+    /// step costs time proportional to its degree. In undirected graphs a self-loop counts
+    /// twice, as it does in the vertex's degree.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key of the vertex to step from.
+    /// * `rng` - The random generator to draw from.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<String>, GraphError>` - The key of the neighbor reached, `None` if no edge with
+    ///   positive weight leaves the vertex, or an error if the vertex does not exist or an edge
+    ///   weight is negative.
+    pub fn random_neighbor(&self, key: &str, rng: &mut Rng) -> Result<Option<String>, GraphError> {
+        let vertex: &Vertex = self.vertices.get(key).ok_or_else(|| GraphError::VertexNotFound(key.to_string()))?;
+        let neighbors: Vec<&String> = vertex.edges.iter()
+            .map(|edge| if edge.vertex1 == key { &edge.vertex2 } else { &edge.vertex1 })
+            .collect();
+        let weights: Vec<f32> = vertex.edges.iter().map(|edge| edge.weight_from(key)).collect();
+        let picked: Option<usize> = pick(&weights, |e| format!("Edge ({}, {})", key, neighbors[e]), rng)?;
+        Ok(picked.map(|e| neighbors[e].clone()))
+    }
+}
@@ -0,0 +1,147 @@
+use crate::graph::graph::WeightSemantics;
+use crate::graph::{Graph, GraphError};
+
+/// The formula `Graph::as_distance_graph` and `Graph::as_similarity_graph` convert weights
+/// with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WeightTransform {
+    /// `1 / x` both ways, for positive weights such as interaction counts.
+    Inverse,
+    /// `1 - x` both ways, for weights in `[0, 1]` such as correlations or Jaccard scores.
+    Complement,
+    /// `-ln(x)` from similarities in `(0, 1]`, such as probabilities, and `exp(-x)` back from
+    /// non-negative distances. Shortest paths then find the most probable chains.
+    NegativeLog,
+}
+
+impl WeightTransform {
+    /// Converts a weight to a distance (`to_distance`) or a similarity, or returns `None` if
+    /// it is outside the formula's domain.
+    fn apply(self, weight: f32, to_distance: bool) -> Option<f32> {
+        match (self, to_distance) {
+            (WeightTransform::Inverse, _) => (weight > 0.0).then(|| 1.0 / weight),
+            (WeightTransform::Complement, _) => (0.0..=1.0).contains(&weight).then_some(1.0 - weight),
+            (WeightTransform::NegativeLog, true) => (weight > 0.0 && weight <= 1.0).then(|| -weight.ln()),
+            (WeightTransform::NegativeLog, false) => (weight >= 0.0).then(|| (-weight).exp()),
+        }
+    }
+}
+
+impl Graph {
+    /// Returns an error if the weights are recorded as similarities, which distance-based
+    /// algorithms would read backwards.
+    pub(crate) fn require_distances(&self, task: &str) -> Result<(), GraphError> {
+        if self.semantics == WeightSemantics::Similarity {
+            return Err(GraphError::InvalidArgument(format!(
+                "{} needs distance weights, but the weights are similarities; convert them with as_distance_graph",
+                task
+            )));
+        }
+        Ok(())
+    }
+
+    /// Converts every edge weight with `transform`, into a graph marked with `semantics`.
+    fn convert_weights(&self, transform: WeightTransform, semantics: WeightSemantics) -> Result<Graph, GraphError> {
+        let to_distance: bool = semantics == WeightSemantics::Distance;
+        if self.semantics == semantics {
+            let kind: &str = if to_distance { "distances" } else { "similarities" };
+            return Err(GraphError::InvalidArgument(format!("The weights are already {}", kind)));
+        }
+        let mut graph: Graph = self.clone();
+        for vertex in graph.vertices.values_mut() {
+            for edge in &mut vertex.edges {
+                let out_of_domain = |weight: f32| {
+                    GraphError::InvalidArgument(format!(
+                        "Edge ({}, {}) has weight {}, which {:?} cannot convert", edge.vertex1, edge.vertex2, weight, transform
+                    ))
+                };
+                let weight: f32 = transform.apply(edge.weight, to_distance).ok_or_else(|| out_of_domain(edge.weight))?;
+                let reverse_weight: Option<f32> = match edge.reverse_weight {
+                    Some(backward) => Some(transform.apply(backward, to_distance).ok_or_else(|| out_of_domain(backward))?),
+                    None => None,
+                };
+                edge.weight = weight;
+                edge.reverse_weight = reverse_weight;
+            }
+        }
+        graph.semantics = semantics;
+        graph.mark_changed();
+        Ok(graph)
+    }
+
+    /// Converts similarity weights into distances, so that shortest paths prefer strong
+    /// connections, and marks the result `WeightSemantics::Distance`.
+    ///
+    /// A graph whose weights are unmarked is taken to hold similarities. Vertices, edges,
+    /// attributes and settings are kept.
+    ///
+    /// # Arguments
+    ///
+    /// * `transform` - The formula to convert every weight with.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Graph, GraphError>` - The converted graph, or an error if the weights are
+    ///   already marked as distances or one is outside the domain of `transform`.
+    pub fn as_distance_graph(&self, transform: WeightTransform) -> Result<Graph, GraphError> {
+        self.convert_weights(transform, WeightSemantics::Distance)
+    }
+
+    /// Converts distance weights into similarities, for example for community detection or
+    /// centrality measures that treat weights as connection strength, and marks the result
+    /// `WeightSemantics::Similarity`.
+    ///
+    /// A graph whose weights are unmarked is taken to hold distances. Vertices, edges,
+    /// attributes and settings are kept.
+    ///
+    /// # Arguments
+    ///
+    /// * `transform` - The formula to convert every weight with.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Graph, GraphError>` - The converted graph, or an error if the weights are
+    ///   already marked as similarities or one is outside the domain of `transform`.
+    pub fn as_similarity_graph(&self, transform: WeightTransform) -> Result<Graph, GraphError> {
+        self.convert_weights(transform, WeightSemantics::Similarity)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{WeightSemantics, WeightTransform};
+    use crate::graph::testing::graph_from;
+    use crate::graph::{Graph, GraphError};
+
+    fn similarities() -> Graph {
+        let mut graph: Graph = graph_from(false, &[("a", "b", 0.5), ("b", "c", 0.25), ("a", "c", 0.125)]);
+        graph.set_weight_semantics(WeightSemantics::Similarity);
+        graph
+    }
+
+    fn assert_rejected<T>(result: Result<T, GraphError>) {
+        assert!(matches!(result, Err(GraphError::InvalidArgument(message)) if message.contains("similarities")));
+    }
+
+    #[test]
+    fn distance_algorithms_reject_similarities() {
+        let graph: Graph = similarities();
+        assert_rejected(graph.shortest_path("a", "c"));
+        assert_rejected(graph.shortest_path_tree("a"));
+        assert_rejected(graph.distance_extremes());
+        assert_rejected(graph.steiner_tree(&["a", "c"]));
+        assert_rejected(graph.steiner_tree_exact(&["a", "c"]));
+        assert_rejected(graph.steiner_tree_approx(&["a", "c"]));
+        assert_rejected(graph.approximate_betweenness(0.1, 0.1, 7));
+        assert_rejected(graph.clarke_wright_routes("a", 10.0, |_| 1.0));
+        assert_rejected(graph.greedy_spanner(2.0));
+        assert_rejected(graph.minimum_cycle_basis());
+    }
+
+    #[test]
+    fn converted_graph_is_accepted() {
+        let graph: Graph = similarities().as_distance_graph(WeightTransform::Inverse).unwrap();
+        assert_eq!(graph.weight_semantics(), WeightSemantics::Distance);
+        assert_eq!(graph.shortest_path("a", "c").unwrap().unwrap().cost, 6.0);
+    }
+}
@@ -0,0 +1,494 @@
+use crate::graph::{AttrValue, Edge, Graph, GraphError, Vertex};
+use std::collections::BTreeMap;
+use std::ops::Range;
+
+/// The version of the serialization format written by this crate.
+///
+/// It increases whenever the saved layout changes. Files from older versions are upgraded
+/// with `Migrations` when read; files from newer versions are read as far as this version
+/// understands them, skipping sections and attribute types it does not know.
+pub const FORMAT_VERSION: u32 = 1;
+
+/// The bytes every binary graph file starts with.
+const MAGIC: &[u8; 4] = b"GRPH";
+
+/// The named sections of a binary graph file, each holding its raw bytes.
+pub type Sections = BTreeMap<String, Vec<u8>>;
+
+/// One migration step, upgrading a document by one format version.
+type Step<D> = Box<dyn Fn(&mut D) -> Result<(), String>>;
+
+/// The steps that upgrade a saved document from older format versions to `FORMAT_VERSION`.
+///
+/// `D` is the document being upgraded before it is decoded, such as the `Sections` of a
+/// binary file. Built with chained calls, for example
+/// `Migrations::new().register(1, upgrade_from_1)`.
+pub struct Migrations<D> {
+    steps: BTreeMap<u32, Step<D>>,
+}
+
+impl<D> Default for Migrations<D> {
+    fn default() -> Migrations<D> {
+        Migrations::new()
+    }
+}
+
+impl<D> Migrations<D> {
+    /// Creates a set of migrations with no steps.
+    pub fn new() -> Migrations<D> {
+        Migrations { steps: BTreeMap::new() }
+    }
+
+    /// Adds the step that upgrades a document from version `from` to version `from + 1`,
+    /// replacing any step already registered for that version.
+    pub fn register<F>(mut self, from: u32, step: F) -> Migrations<D>
+    where
+        F: Fn(&mut D) -> Result<(), String> + 'static,
+    {
+        self.steps.insert(from, Box::new(step));
+        self
+    }
+
+    /// Upgrades a document written with format `version` to `FORMAT_VERSION`.
+    ///
+    /// # Arguments
+    ///
+    /// * `document` - The document to upgrade in place.
+    /// * `version` - The format version the document was written with.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), GraphError>` - An error if a step between the two versions is missing or fails.
+    ///   Documents at or above `FORMAT_VERSION` are left unchanged.
+    pub fn migrate(&self, document: &mut D, version: u32) -> Result<(), GraphError> {
+        for from in version..FORMAT_VERSION {
+            let step: &Step<D> = self.steps.get(&from).ok_or_else(|| GraphError::Parse(format!("No migration from format version {}", from)))?;
+            step(document).map_err(|e| GraphError::Parse(format!("Migration from format version {} failed: {}", from, e)))?;
+        }
+        Ok(())
+    }
+}
+
+/// Appends little-endian values to a byte buffer.
+pub(crate) struct Encoder {
+    pub bytes: Vec<u8>,
+}
+
+impl Encoder {
+    pub fn new() -> Encoder {
+        Encoder { bytes: Vec::new() }
+    }
+
+    pub fn u8(&mut self, value: u8) {
+        self.bytes.push(value);
+    }
+
+    pub fn u64(&mut self, value: u64) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub fn f32(&mut self, value: f32) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn bytes(&mut self, value: &[u8]) {
+        self.u64(value.len() as u64);
+        self.bytes.extend_from_slice(value);
+    }
+
+    fn string(&mut self, value: &str) {
+        self.bytes(value.as_bytes());
+    }
+}
+
+/// Reads little-endian values from a byte slice, failing cleanly on truncated input.
+struct Decoder<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Decoder<'a> {
+    fn new(bytes: &'a [u8]) -> Decoder<'a> {
+        Decoder { bytes, position: 0 }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.position == self.bytes.len()
+    }
+
+    fn take(&mut self, length: usize) -> Result<&'a [u8], GraphError> {
+        if self.bytes.len() - self.position < length {
+            return Err(GraphError::Parse("The data ends unexpectedly".to_string()));
+        }
+        let taken: &'a [u8] = &self.bytes[self.position..self.position + length];
+        self.position += length;
+        Ok(taken)
+    }
+
+    fn u8(&mut self) -> Result<u8, GraphError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u32(&mut self) -> Result<u32, GraphError> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> Result<u64, GraphError> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    fn length(&mut self) -> Result<usize, GraphError> {
+        usize::try_from(self.u64()?).map_err(|_| GraphError::Parse("A length does not fit in memory".to_string()))
+    }
+
+    fn f32(&mut self) -> Result<f32, GraphError> {
+        Ok(f32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn f64(&mut self) -> Result<f64, GraphError> {
+        Ok(f64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    fn bytes(&mut self) -> Result<&'a [u8], GraphError> {
+        let length: usize = self.length()?;
+        self.take(length)
+    }
+
+    fn string(&mut self) -> Result<String, GraphError> {
+        String::from_utf8(self.bytes()?.to_vec()).map_err(|_| GraphError::Parse("A string is not valid UTF-8".to_string()))
+    }
+}
+
+/// Wraps named sections into a binary graph file: the magic bytes, the format version and
+/// every section as a length-prefixed name and content.
+pub(crate) fn write_sections(sections: &Sections) -> Vec<u8> {
+    let mut file: Encoder = Encoder::new();
+    file.bytes.extend_from_slice(MAGIC);
+    file.bytes.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+    file.u64(sections.len() as u64);
+    for (name, content) in sections {
+        file.string(name);
+        file.bytes(content);
+    }
+    file.bytes
+}
+
+/// Reads the section table of a binary graph file without copying the sections.
+///
+/// Returns the format version the file was written with and where in `bytes` every
+/// section's content lies.
+pub(crate) fn section_ranges(bytes: &[u8]) -> Result<(u32, BTreeMap<String, Range<usize>>), GraphError> {
+    let mut file: Decoder = Decoder::new(bytes);
+    if file.take(MAGIC.len()).ok() != Some(MAGIC.as_slice()) {
+        return Err(GraphError::Parse("The data is not a saved graph".to_string()));
+    }
+    let version: u32 = file.u32()?;
+    let mut ranges: BTreeMap<String, Range<usize>> = BTreeMap::new();
+    for _ in 0..file.u64()? {
+        let name: String = file.string()?;
+        let length: usize = file.length()?;
+        let start: usize = file.position;
+        file.take(length)?;
+        ranges.insert(name, start..start + length);
+    }
+    Ok((version, ranges))
+}
+
+/// Encodes an attribute value as a type tag and its length-prefixed content, so readers can
+/// skip types they do not know.
+fn encode_attribute(encoder: &mut Encoder, value: &AttrValue) {
+    let mut content: Encoder = Encoder::new();
+    let tag: u8 = match value {
+        AttrValue::Bool(flag) => {
+            content.u8(u8::from(*flag));
+            0
+        }
+        AttrValue::Int(number) => {
+            content.bytes.extend_from_slice(&number.to_le_bytes());
+            1
+        }
+        AttrValue::Float(number) => {
+            content.bytes.extend_from_slice(&number.to_le_bytes());
+            2
+        }
+        AttrValue::Text(text) => {
+            content.bytes.extend_from_slice(text.as_bytes());
+            3
+        }
+        AttrValue::Vector(values) => {
+            for value in values {
+                content.bytes.extend_from_slice(&value.to_le_bytes());
+            }
+            4
+        }
+    };
+    encoder.u8(tag);
+    encoder.bytes(&content.bytes);
+}
+
+/// Decodes an attribute value, or returns `None` for a type tag this version does not know.
+fn decode_attribute(decoder: &mut Decoder) -> Result<Option<AttrValue>, GraphError> {
+    let tag: u8 = decoder.u8()?;
+    let content: &[u8] = decoder.bytes()?;
+    let mut inner: Decoder = Decoder::new(content);
+    let value: AttrValue = match tag {
+        0 => AttrValue::Bool(inner.u8()? != 0),
+        1 => AttrValue::Int(i64::from_le_bytes(inner.take(8)?.try_into().unwrap())),
+        2 => AttrValue::Float(inner.f64()?),
+        3 => AttrValue::Text(String::from_utf8(content.to_vec()).map_err(|_| GraphError::Parse("A string is not valid UTF-8".to_string()))?),
+        4 => {
+            let mut values: Vec<f64> = Vec::with_capacity(content.len() / 8);
+            while !inner.is_empty() {
+                values.push(inner.f64()?);
+            }
+            AttrValue::Vector(values)
+        }
+        _ => return Ok(None),
+    };
+    Ok(Some(value))
+}
+
+impl Graph {
+    /// Saves the graph in the crate's binary format.
+    ///
+    /// The data starts with the format version, followed by named sections: `graph` with
+    /// the vertices and edges, `attributes` with the vertex attributes, and
+    /// `edge_attributes` with the attributes of the edges in the order `graph` lists them.
+    /// Vertices are written in key order, so equal graphs give equal bytes.
+    ///
+    /// # Returns
+    ///
+    /// * A `Vec<u8>` with the saved graph, readable by `Graph::from_bytes`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let keys: Vec<&String> = self.vertices.keys().collect();
+
+        let mut graph: Encoder = Encoder::new();
+        graph.u8(u8::from(self.directed));
+        graph.u64(keys.len() as u64);
+        for key in &keys {
+            graph.string(key);
+        }
+        let edges: Vec<&Edge> = self.stored_edges();
+        graph.u64(edges.len() as u64);
+        for edge in &edges {
+            graph.string(&edge.vertex1);
+            graph.string(&edge.vertex2);
+            graph.f32(edge.weight);
+            match edge.reverse_weight {
+                Some(backward) => {
+                    graph.u8(1);
+                    graph.f32(backward);
+                }
+                None => graph.u8(0),
+            }
+        }
+
+        let mut attributes: Encoder = Encoder::new();
+        attributes.u64(keys.len() as u64);
+        for key in &keys {
+            let mut names: Vec<(&String, &AttrValue)> = self.vertices[*key].attributes.iter().collect();
+            names.sort_by(|a, b| a.0.cmp(b.0));
+            attributes.string(key);
+            attributes.u64(names.len() as u64);
+            for (name, value) in names {
+                attributes.string(name);
+                encode_attribute(&mut attributes, value);
+            }
+        }
+
+        let mut edge_attributes: Encoder = Encoder::new();
+        edge_attributes.u64(edges.len() as u64);
+        for edge in &edges {
+            let mut names: Vec<(&String, &AttrValue)> = edge.attributes.iter().collect();
+            names.sort_by(|a, b| a.0.cmp(b.0));
+            edge_attributes.u64(names.len() as u64);
+            for (name, value) in names {
+                edge_attributes.string(name);
+                encode_attribute(&mut edge_attributes, value);
+            }
+        }
+
+        let sections: Sections = Sections::from([
+            ("graph".to_string(), graph.bytes),
+            ("attributes".to_string(), attributes.bytes),
+            ("edge_attributes".to_string(), edge_attributes.bytes),
+        ]);
+        write_sections(&sections)
+    }
+
+    /// Loads a graph saved with `Graph::to_bytes`, by this or any other version of the crate.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - The saved graph.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Graph, GraphError>` - The graph, or an error if the data is not a saved graph, is
+    ///   damaged, or comes from an older format version with no migration.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Graph, GraphError> {
+        Graph::from_bytes_with(bytes, &Migrations::new())
+    }
+
+    /// Loads a graph saved with `Graph::to_bytes`, upgrading older format versions with the
+    /// given migrations first.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - The saved graph.
+    /// * `migrations` - The steps that upgrade the sections of an older file.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Graph, GraphError>` - The graph, or an error if the data is not a saved graph, is
+    ///   damaged, or a migration is missing or fails.
+    pub fn from_bytes_with(bytes: &[u8], migrations: &Migrations<Sections>) -> Result<Graph, GraphError> {
+        let (version, ranges) = section_ranges(bytes)?;
+        let mut sections: Sections = ranges.into_iter().map(|(name, range)| (name, bytes[range].to_vec())).collect();
+        migrations.migrate(&mut sections, version)?;
+
+        // Files from before edge attributes have no such section.
+        let mut edge_attributes: Vec<Vec<(String, AttrValue)>> = Vec::new();
+        if let Some(content) = sections.get("edge_attributes") {
+            let mut section: Decoder = Decoder::new(content);
+            for _ in 0..section.u64()? {
+                let mut named: Vec<(String, AttrValue)> = Vec::new();
+                for _ in 0..section.u64()? {
+                    let name: String = section.string()?;
+                    if let Some(value) = decode_attribute(&mut section)? {
+                        named.push((name, value));
+                    }
+                }
+                edge_attributes.push(named);
+            }
+        }
+
+        let content: &Vec<u8> = sections.get("graph").ok_or_else(|| GraphError::Parse("The saved graph has no graph section".to_string()))?;
+        let mut graph_section: Decoder = Decoder::new(content);
+        let mut graph: Graph = Graph::new(graph_section.u8()? != 0);
+        for _ in 0..graph_section.u64()? {
+            graph.add_vertex(Vertex::new(graph_section.string()?));
+        }
+        for position in 0..graph_section.u64()? as usize {
+            let (vertex1, vertex2): (String, String) = (graph_section.string()?, graph_section.string()?);
+            let weight: f32 = graph_section.f32()?;
+            let mut edge: Edge = match graph_section.u8()? {
+                0 => Edge::new(vertex1, vertex2, weight),
+                _ => Edge::asymmetric(vertex1, vertex2, weight, graph_section.f32()?),
+            };
+            for (name, value) in edge_attributes.get_mut(position).map(std::mem::take).unwrap_or_default() {
+                edge.set_attr(&name, value);
+            }
+            graph.add_edge(edge)?;
+        }
+
+        if let Some(content) = sections.get("attributes") {
+            let mut attributes: Decoder = Decoder::new(content);
+            for _ in 0..attributes.u64()? {
+                let key: String = attributes.string()?;
+                for _ in 0..attributes.u64()? {
+                    let name: String = attributes.string()?;
+                    let value: Option<AttrValue> = decode_attribute(&mut attributes)?;
+                    if let (Some(value), Some(vertex)) = (value, graph.vertices.get_mut(&key)) {
+                        vertex.set_attr(&name, value);
+                    }
+                }
+            }
+        }
+        Ok(graph)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{section_ranges, write_sections, Encoder, Migrations, Sections};
+    use crate::graph::{AttrValue, Edge, Graph, Vertex};
+
+    /// Builds a small undirected graph with every attribute type, an asymmetric edge, a
+    /// parallel edge and a self-loop.
+    fn sample() -> Graph {
+        let mut graph: Graph = Graph::new(false);
+        let mut a: Vertex = Vertex::new("a".to_string());
+        a.set_attr("flag", AttrValue::Bool(true));
+        a.set_attr("count", AttrValue::Int(-3));
+        a.set_attr("score", AttrValue::Float(0.25));
+        a.set_attr("name", AttrValue::Text("first".to_string()));
+        a.set_attr("position", AttrValue::Vector(vec![1.0, -2.5]));
+        graph.add_vertex(a);
+        graph.add_vertex(Vertex::new("b".to_string()));
+        graph.add_vertex(Vertex::new("c".to_string()));
+        graph.add_edge(Edge::asymmetric("a".to_string(), "b".to_string(), 1.0, 2.0)).unwrap();
+        graph.add_edge(Edge::new("a".to_string(), "b".to_string(), 3.0)).unwrap();
+        graph.add_edge(Edge::new("c".to_string(), "c".to_string(), 4.0)).unwrap();
+        graph
+    }
+
+    /// Returns the sections of a saved graph.
+    fn sections_of(bytes: &[u8]) -> Sections {
+        let (_, ranges) = section_ranges(bytes).unwrap();
+        ranges.into_iter().map(|(name, range)| (name, bytes[range].to_vec())).collect()
+    }
+
+    #[test]
+    fn round_trip_keeps_edges_and_attributes() {
+        let graph: Graph = sample();
+        let bytes: Vec<u8> = graph.to_bytes();
+        let loaded: Graph = Graph::from_bytes(&bytes).unwrap();
+        assert_eq!(loaded.to_bytes(), bytes);
+        assert!(!loaded.directed);
+        assert_eq!(loaded.edge_count, 3);
+        assert_eq!(loaded.vertices["a"].attributes, graph.vertices["a"].attributes);
+        let asymmetric: &Edge = loaded.vertices["b"].edges.iter().find(|edge| edge.reverse_weight.is_some()).unwrap();
+        assert_eq!((asymmetric.weight_from("a"), asymmetric.weight_from("b")), (1.0, 2.0));
+    }
+
+    #[test]
+    fn unknown_sections_and_attribute_types_are_skipped() {
+        let bytes: Vec<u8> = sample().to_bytes();
+        let mut sections: Sections = sections_of(&bytes);
+        sections.insert("from_the_future".to_string(), vec![1, 2, 3]);
+        let mut attributes: Encoder = Encoder::new();
+        attributes.u64(1);
+        attributes.string("b");
+        attributes.u64(2);
+        attributes.string("shape");
+        attributes.u8(200);
+        attributes.bytes(b"unknown");
+        attributes.string("size");
+        attributes.u8(1);
+        attributes.bytes(&7i64.to_le_bytes());
+        sections.insert("attributes".to_string(), attributes.bytes);
+        let loaded: Graph = Graph::from_bytes(&write_sections(&sections)).unwrap();
+        assert_eq!(loaded.edge_count, 3);
+        assert_eq!(loaded.vertices["b"].attr("shape"), None);
+        assert_eq!(loaded.vertices["b"].attr("size"), Some(&AttrValue::Int(7)));
+    }
+
+    #[test]
+    fn older_versions_need_a_migration() {
+        // A version-0 file that named the graph section differently.
+        let mut sections: Sections = sections_of(&sample().to_bytes());
+        let content: Vec<u8> = sections.remove("graph").unwrap();
+        sections.insert("edges".to_string(), content);
+        let mut bytes: Vec<u8> = write_sections(&sections);
+        bytes[4..8].copy_from_slice(&0u32.to_le_bytes());
+
+        assert!(Graph::from_bytes(&bytes).is_err());
+        let migrations: Migrations<Sections> = Migrations::new().register(0, |sections: &mut Sections| {
+            let content: Vec<u8> = sections.remove("edges").ok_or("no edges section")?;
+            sections.insert("graph".to_string(), content);
+            Ok(())
+        });
+        let loaded: Graph = Graph::from_bytes_with(&bytes, &migrations).unwrap();
+        assert_eq!(loaded.to_bytes(), sample().to_bytes());
+    }
+
+    #[test]
+    fn damaged_data_is_rejected() {
+        let bytes: Vec<u8> = sample().to_bytes();
+        assert!(Graph::from_bytes(b"not a graph").is_err());
+        for end in [3, 10, bytes.len() / 2, bytes.len() - 1] {
+            assert!(Graph::from_bytes(&bytes[..end]).is_err(), "cut at {}", end);
+        }
+    }
+}
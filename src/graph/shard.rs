@@ -0,0 +1,329 @@
+use crate::graph::bloom::hash_pair;
+use crate::graph::{Graph, GraphError};
+use std::collections::{BTreeSet, HashMap};
+
+/// How `Graph::shard` splits a graph.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ShardStrategy {
+    /// Every vertex goes to one shard, with all of its edges (an edge cut). An edge between
+    /// shards is copied into both, so every shard sees the full neighborhood of the vertices
+    /// it owns; the far endpoint becomes a ghost.
+    Vertices,
+    /// Every edge goes to one shard, and vertices are copied into each shard that has one
+    /// of their edges (a vertex cut). Suits graphs with very high-degree vertices, whose
+    /// edges would otherwise all land on one shard.
+    Edges,
+}
+
+/// One part of a `ShardedGraph`.
+#[derive(Clone, Debug)]
+pub struct Shard {
+    /// The vertices and edges of the shard, with the direction and mode of the original.
+    pub graph: Graph,
+    /// The keys of the vertices copied into the shard but owned by another one.
+    pub ghosts: BTreeSet<String>,
+}
+
+impl Shard {
+    /// Returns whether the shard owns the vertex, holding it as more than a ghost.
+    pub fn owns(&self, key: &str) -> bool {
+        self.graph.has_vertex(key) && !self.ghosts.contains(key)
+    }
+
+    /// Returns the keys of the vertices the shard owns, in key order.
+    pub fn owned(&self) -> impl Iterator<Item = &str> {
+        self.graph.vertices.keys().map(String::as_str).filter(|key| !self.ghosts.contains(*key))
+    }
+}
+
+/// A graph split into shards that can be processed apart, for example in separate
+/// processes, as built by `Graph::shard` and `Graph::shard_by`.
+///
+/// Every vertex is owned by exactly one shard, recorded in `partition`, and may appear in
+/// others as a ghost. Per-shard results are combined with `merge_owned` or `merge_with`,
+/// and `reassemble` rebuilds the original graph.
+#[derive(Clone, Debug)]
+pub struct ShardedGraph {
+    /// The shards, numbered by position.
+    pub shards: Vec<Shard>,
+    /// The shard that owns every vertex.
+    pub partition: HashMap<String, usize>,
+    /// How the edges were split.
+    pub strategy: ShardStrategy,
+}
+
+impl ShardedGraph {
+    /// Returns the shard that owns a vertex, or `None` if the vertex does not exist.
+    pub fn owner(&self, key: &str) -> Option<usize> {
+        self.partition.get(key).copied()
+    }
+
+    /// Returns the average number of shards holding each vertex, owned or as a ghost: `1`
+    /// means no vertex is copied, and higher values mean more communication between shards.
+    pub fn replication_factor(&self) -> f64 {
+        if self.partition.is_empty() {
+            return 1.0;
+        }
+        let copies: usize = self.shards.iter().map(|shard| shard.graph.vertices.len()).sum();
+        copies as f64 / self.partition.len() as f64
+    }
+
+    /// Returns an error unless there is one result per shard.
+    fn check_results<T>(&self, results: &[HashMap<String, T>]) -> Result<(), GraphError> {
+        if results.len() != self.shards.len() {
+            return Err(GraphError::InvalidArgument(format!("Expected {} shard results, got {}", self.shards.len(), results.len())));
+        }
+        Ok(())
+    }
+
+    /// Combines per-vertex results computed on every shard, keeping for each vertex the
+    /// value from the shard that owns it and dropping the values computed for ghosts.
+    ///
+    /// Suits results that the owning shard computes completely on its own, such as degrees
+    /// under `ShardStrategy::Vertices`.
+    ///
+    /// # Arguments
+    ///
+    /// * `results` - The result of every shard, in shard order.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<HashMap<String, T>, GraphError>` - The value of every vertex that its owner
+    ///   reported, or an error if there is not one result per shard.
+    pub fn merge_owned<T>(&self, results: Vec<HashMap<String, T>>) -> Result<HashMap<String, T>, GraphError> {
+        self.check_results(&results)?;
+        Ok(results.into_iter()
+            .enumerate()
+            .flat_map(|(s, result)| result.into_iter().filter(move |(key, _)| self.owner(key) == Some(s)))
+            .collect())
+    }
+
+    /// Combines per-vertex partial results from every shard, folding together the values
+    /// reported for each copy of a vertex, owned or ghost, in shard order.
+    ///
+    /// Suits results that every shard computes in part, such as degrees under
+    /// `ShardStrategy::Edges`, summed with `|a, b| a + b`.
+    ///
+    /// # Arguments
+    ///
+    /// * `results` - The result of every shard, in shard order.
+    /// * `combine` - Folds two partial values of the same vertex into one.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<HashMap<String, T>, GraphError>` - The combined value of every vertex
+    ///   reported by any shard, or an error if there is not one result per shard or a key
+    ///   is not in the graph.
+    pub fn merge_with<T, F>(&self, results: Vec<HashMap<String, T>>, combine: F) -> Result<HashMap<String, T>, GraphError>
+    where
+        F: Fn(T, T) -> T,
+    {
+        self.check_results(&results)?;
+        let mut merged: HashMap<String, T> = HashMap::new();
+        for result in results {
+            // Sorted, so that combining in shard order does not depend on hash order.
+            let mut values: Vec<(String, T)> = result.into_iter().collect();
+            values.sort_by(|a, b| a.0.cmp(&b.0));
+            for (key, value) in values {
+                if self.owner(&key).is_none() {
+                    return Err(GraphError::VertexNotFound(key));
+                }
+                let value: T = match merged.remove(&key) {
+                    Some(previous) => combine(previous, value),
+                    None => value,
+                };
+                merged.insert(key, value);
+            }
+        }
+        Ok(merged)
+    }
+
+    /// Rebuilds the original graph from the shards, with every vertex and edge once.
+    ///
+    /// Vertices keep the attributes of their owned copy. Edges come back in shard order, so
+    /// the result equals the original graph but its edges may be listed in another order.
+    ///
+    /// # Returns
+    ///
+    /// * A `Graph` with the vertices and edges of every shard.
+    pub fn reassemble(&self) -> Graph {
+        let template: &Graph = &self.shards[0].graph;
+        let mut graph: Graph = template.empty_like();
+        for shard in &self.shards {
+            for key in shard.owned() {
+                graph.add_vertex_like(&shard.graph, key);
+            }
+        }
+        for (s, shard) in self.shards.iter().enumerate() {
+            for edge in shard.graph.stored_edges() {
+                // Under an edge cut, an edge between shards is kept by its first endpoint's.
+                if self.strategy == ShardStrategy::Edges || self.owner(&edge.vertex1) == Some(s) {
+                    graph.add_edge(edge.clone()).unwrap();
+                }
+            }
+        }
+        graph
+    }
+}
+
+impl Graph {
+    /// Splits the graph into `k` shards, assigning vertices (and, under
+    /// `ShardStrategy::Edges`, edges) by a hash of their keys.
+    ///
+    /// The hash is the same on every platform and run, so separate processes that shard
+    /// the same graph agree on who owns what without talking to each other.
+    ///
+    /// # Arguments
+    ///
+    /// * `k` - The number of shards.
+    /// * `strategy` - Whether to split the vertices or the edges.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<ShardedGraph, GraphError>` - The shards, or an error if `k` is zero.
+    pub fn shard(&self, k: usize, strategy: ShardStrategy) -> Result<ShardedGraph, GraphError> {
+        if k == 0 {
+            return Err(GraphError::InvalidArgument("The number of shards must be positive".to_string()));
+        }
+        let partition: HashMap<String, usize> = self.vertices.keys()
+            .map(|key| (key.clone(), (hash_pair(key, "", 0) % k as u64) as usize))
+            .collect();
+        Ok(self.split(k, partition, strategy))
+    }
+
+    /// Splits the graph into shards following a given owner for every vertex, such as the
+    /// communities found by a clustering, which keeps most edges within one shard.
+    ///
+    /// There are as many shards as the largest shard number plus one. Under
+    /// `ShardStrategy::Edges` the edges are still assigned by hash, and `partition` only
+    /// decides which copy of each vertex is the owned one.
+    ///
+    /// # Arguments
+    ///
+    /// * `partition` - The shard that owns every vertex.
+    /// * `strategy` - Whether to split the vertices or the edges.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<ShardedGraph, GraphError>` - The shards, or an error if a vertex has no shard
+    ///   or a key is not in the graph.
+    pub fn shard_by(&self, partition: &HashMap<String, usize>, strategy: ShardStrategy) -> Result<ShardedGraph, GraphError> {
+        if let Some(key) = partition.keys().find(|key| !self.vertices.contains_key(*key)) {
+            return Err(GraphError::VertexNotFound(key.clone()));
+        }
+        if let Some(key) = self.vertices.keys().find(|key| !partition.contains_key(*key)) {
+            return Err(GraphError::InvalidArgument(format!("Vertex {} has no shard", key)));
+        }
+        let k: usize = partition.values().max().map_or(1, |&s| s + 1);
+        Ok(self.split(k, partition.clone(), strategy))
+    }
+
+    /// Builds the shards for an owner of every vertex.
+    fn split(&self, k: usize, partition: HashMap<String, usize>, strategy: ShardStrategy) -> ShardedGraph {
+        let mut shards: Vec<Shard> = (0..k)
+            .map(|_| Shard { graph: self.empty_like(), ghosts: BTreeSet::new() })
+            .collect();
+        let copy_vertex = |shard: &mut Shard, key: &str| {
+            if !shard.graph.has_vertex(key) {
+                shard.graph.add_vertex_like(self, key);
+            }
+        };
+        for (key, &s) in &partition {
+            copy_vertex(&mut shards[s], key);
+        }
+        for edge in self.stored_edges() {
+            let targets: Vec<usize> = match strategy {
+                ShardStrategy::Vertices => {
+                    let (first, second) = (partition[&edge.vertex1], partition[&edge.vertex2]);
+                    if first == second { vec![first] } else { vec![first, second] }
+                }
+                ShardStrategy::Edges => {
+                    let (from, to) = if !self.directed && edge.vertex2 < edge.vertex1 { (&edge.vertex2, &edge.vertex1) } else { (&edge.vertex1, &edge.vertex2) };
+                    vec![(hash_pair(from, to, 1) % k as u64) as usize]
+                }
+            };
+            for s in targets {
+                copy_vertex(&mut shards[s], &edge.vertex1);
+                copy_vertex(&mut shards[s], &edge.vertex2);
+                shards[s].graph.add_edge(edge.clone()).unwrap();
+            }
+        }
+        for (s, shard) in shards.iter_mut().enumerate() {
+            shard.ghosts = shard.graph.vertices.keys().filter(|key| partition[*key] != s).cloned().collect();
+        }
+        ShardedGraph { shards, partition, strategy }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ShardStrategy, ShardedGraph};
+    use crate::graph::testing::graph_from;
+    use crate::graph::{Edge, Graph, GraphError, Vertex};
+    use std::collections::HashMap;
+
+    fn sample() -> Graph {
+        let mut graph: Graph = Graph::erdos_renyi(30, 0.15, false, 5).unwrap();
+        graph.add_vertex(Vertex::new("lonely".to_string()));
+        graph.add_edge(Edge::new("0".to_string(), "0".to_string(), 2.0)).unwrap();
+        graph
+    }
+
+    fn degrees(graph: &Graph) -> HashMap<String, usize> {
+        graph.vertex_keys().into_iter().map(|key| {
+            let degree: usize = graph.degree(&key).unwrap();
+            (key, degree)
+        }).collect()
+    }
+
+    #[test]
+    fn every_vertex_has_one_owner_and_reassembles() {
+        let graph: Graph = sample();
+        for strategy in [ShardStrategy::Vertices, ShardStrategy::Edges] {
+            for k in 1..5 {
+                let sharded: ShardedGraph = graph.shard(k, strategy).unwrap();
+                assert_eq!(sharded.shards.len(), k);
+                for key in graph.vertex_keys() {
+                    let owners: Vec<usize> = (0..k).filter(|&s| sharded.shards[s].owns(&key)).collect();
+                    assert_eq!(owners, vec![sharded.owner(&key).unwrap()], "{} under {:?}", key, strategy);
+                }
+                assert!(sharded.replication_factor() >= 1.0);
+                assert_eq!(sharded.reassemble(), graph, "{} shards under {:?}", k, strategy);
+            }
+        }
+    }
+
+    #[test]
+    fn merged_degrees_match_the_whole_graph() {
+        let graph: Graph = sample();
+        let by_vertex: ShardedGraph = graph.shard(3, ShardStrategy::Vertices).unwrap();
+        let owned = by_vertex.merge_owned(by_vertex.shards.iter().map(|shard| degrees(&shard.graph)).collect()).unwrap();
+        assert_eq!(owned, degrees(&graph));
+        let by_edge: ShardedGraph = graph.shard(3, ShardStrategy::Edges).unwrap();
+        let summed = by_edge.merge_with(by_edge.shards.iter().map(|shard| degrees(&shard.graph)).collect(), |a, b| a + b).unwrap();
+        assert_eq!(summed, degrees(&graph));
+    }
+
+    #[test]
+    fn shard_by_follows_the_partition() {
+        let graph: Graph = graph_from(true, &[("a", "b", 1.0), ("b", "c", 1.0), ("c", "a", 1.0)]);
+        let partition: HashMap<String, usize> = HashMap::from([("a".to_string(), 0), ("b".to_string(), 0), ("c".to_string(), 2)]);
+        let sharded: ShardedGraph = graph.shard_by(&partition, ShardStrategy::Vertices).unwrap();
+        assert_eq!(sharded.shards.len(), 3);
+        assert_eq!(sharded.shards[0].owned().collect::<Vec<&str>>(), vec!["a", "b"]);
+        assert!(sharded.shards[0].ghosts.contains("c"));
+        assert_eq!(sharded.shards[1].graph.vertices.len(), 0);
+        assert_eq!(sharded.reassemble(), graph);
+    }
+
+    #[test]
+    fn invalid_requests_are_rejected() {
+        let graph: Graph = graph_from(false, &[("a", "b", 1.0)]);
+        assert!(matches!(graph.shard(0, ShardStrategy::Vertices), Err(GraphError::InvalidArgument(_))));
+        let partial: HashMap<String, usize> = HashMap::from([("a".to_string(), 0)]);
+        assert!(matches!(graph.shard_by(&partial, ShardStrategy::Edges), Err(GraphError::InvalidArgument(_))));
+        let sharded: ShardedGraph = graph.shard(2, ShardStrategy::Vertices).unwrap();
+        assert!(sharded.merge_owned::<usize>(vec![HashMap::new()]).is_err());
+        assert!(matches!(sharded.merge_with(vec![HashMap::from([("z".to_string(), 1)]), HashMap::new()], |a, b| a + b), Err(GraphError::VertexNotFound(_))));
+    }
+}
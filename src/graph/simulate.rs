@@ -0,0 +1,251 @@
+use crate::graph::index::GraphIndex;
+use crate::graph::rng::Rng;
+use crate::graph::{Graph, GraphError};
+use std::collections::HashSet;
+
+/// The number of vertices in each compartment at one step of an SIR simulation.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SirStep {
+    /// Vertices that have not been infected yet.
+    pub susceptible: usize,
+    /// Vertices that are currently infectious.
+    pub infected: usize,
+    /// Vertices that were infected and have recovered.
+    pub recovered: usize,
+}
+
+/// The result of an SIR (susceptible-infected-recovered) simulation.
+#[derive(Clone, Debug)]
+pub struct SirOutcome {
+    /// The compartment sizes at every step, starting with the initial state.
+    pub curve: Vec<SirStep>,
+    /// Every vertex that was infected at some point, including the seeds.
+    pub reached: HashSet<String>,
+}
+
+/// The result of an Independent Cascade simulation.
+#[derive(Clone, Debug)]
+pub struct CascadeOutcome {
+    /// The cumulative number of active vertices after every round, starting with the seeds.
+    pub curve: Vec<usize>,
+    /// Every vertex that became active, including the seeds.
+    pub reached: HashSet<String>,
+}
+
+/// A spreading process used to evaluate the reach of a seed set.
+#[derive(Clone, Debug)]
+pub enum SpreadModel {
+    /// The Independent Cascade model (see `Graph::simulate_cascade`).
+    IndependentCascade,
+    /// The SIR model (see `Graph::simulate_sir`).
+    Sir {
+        /// The per-step recovery probability.
+        recovery: f32,
+        /// The maximum number of steps to simulate.
+        max_steps: usize,
+    },
+}
+
+/// Resolves seed keys to positions, ignoring duplicates.
+pub(crate) fn seed_positions(index: &GraphIndex, seeds: &[&str]) -> Result<Vec<usize>, GraphError> {
+    let mut positions: Vec<usize> = Vec::with_capacity(seeds.len());
+    for seed in seeds {
+        let position: usize = index.position(seed)?;
+        if !positions.contains(&position) {
+            positions.push(position);
+        }
+    }
+    Ok(positions)
+}
+
+/// Runs one Independent Cascade from `seeds` and returns the activation flags
+/// together with the cumulative activation curve.
+pub(crate) fn run_cascade(index: &GraphIndex, seeds: &[usize], rng: &mut Rng) -> (Vec<bool>, Vec<usize>) {
+    let mut active: Vec<bool> = vec![false; index.len()];
+    for &s in seeds {
+        active[s] = true;
+    }
+
+    let mut frontier: Vec<usize> = seeds.to_vec();
+    let mut total: usize = frontier.len();
+    let mut curve: Vec<usize> = vec![total];
+
+    while !frontier.is_empty() {
+        let mut next: Vec<usize> = Vec::new();
+        for &u in &frontier {
+            for &(v, weight) in &index.adjacency[u] {
+                if !active[v] && rng.chance(weight) {
+                    active[v] = true;
+                    next.push(v);
+                }
+            }
+        }
+        if next.is_empty() {
+            break;
+        }
+        total += next.len();
+        curve.push(total);
+        frontier = next;
+    }
+
+    (active, curve)
+}
+
+/// Runs one SIR process from `seeds` and returns the ever-infected flags together with the curve.
+pub(crate) fn run_sir(
+    index: &GraphIndex,
+    seeds: &[usize],
+    recovery: f32,
+    max_steps: usize,
+    rng: &mut Rng,
+) -> (Vec<bool>, Vec<SirStep>) {
+    let n: usize = index.len();
+    let mut reached: Vec<bool> = vec![false; n];
+    let mut infected: Vec<usize> = seeds.to_vec();
+    for &s in seeds {
+        reached[s] = true;
+    }
+    let mut recovered: usize = 0;
+
+    let step = |infected: usize, recovered: usize| SirStep {
+        susceptible: n - infected - recovered,
+        infected,
+        recovered,
+    };
+    let mut curve: Vec<SirStep> = vec![step(infected.len(), recovered)];
+
+    for _ in 0..max_steps {
+        if infected.is_empty() {
+            break;
+        }
+        let mut still_infected: Vec<usize> = Vec::with_capacity(infected.len());
+        let mut newly_infected: Vec<usize> = Vec::new();
+        for &u in &infected {
+            for &(v, weight) in &index.adjacency[u] {
+                if !reached[v] && rng.chance(weight) {
+                    reached[v] = true;
+                    newly_infected.push(v);
+                }
+            }
+            if rng.chance(recovery) {
+                recovered += 1;
+            } else {
+                still_infected.push(u);
+            }
+        }
+        still_infected.extend(newly_infected);
+        infected = still_infected;
+        curve.push(step(infected.len(), recovered));
+    }
+
+    (reached, curve)
+}
+
+/// Runs `model` once from `seeds` and returns the number of vertices reached.
+pub(crate) fn run_model(index: &GraphIndex, seeds: &[usize], model: &SpreadModel, rng: &mut Rng) -> usize {
+    let reached: Vec<bool> = match model {
+        SpreadModel::IndependentCascade => run_cascade(index, seeds, rng).0,
+        SpreadModel::Sir { recovery, max_steps } => run_sir(index, seeds, *recovery, *max_steps, rng).0,
+    };
+    reached.iter().filter(|&&flag| flag).count()
+}
+
+/// Estimates the expected number of vertices `model` reaches from `seeds` over `simulations` runs.
+pub(crate) fn estimate_spread(
+    index: &GraphIndex,
+    seeds: &[usize],
+    model: &SpreadModel,
+    simulations: usize,
+    rng: &mut Rng,
+) -> f64 {
+    if simulations == 0 {
+        return 0.0;
+    }
+    let total: usize = (0..simulations).map(|_| run_model(index, seeds, model, rng)).sum();
+    total as f64 / simulations as f64
+}
+
+/// Collects the keys of all flagged vertices.
+fn reached_keys(index: &GraphIndex, flags: &[bool]) -> HashSet<String> {
+    flags.iter()
+        .enumerate()
+        .filter(|(_, &flag)| flag)
+        .map(|(i, _)| index.keys[i].clone())
+        .collect()
+}
+
+impl Graph {
+    /// Simulates an SIR epidemic spreading from the given seed vertices.
+    ///
+    /// At every step each infected vertex infects each susceptible neighbor with a
+    /// probability equal to the edge weight (clamped to `[0, 1]`), then recovers with
+    /// probability `recovery`. The run stops once nobody is infected or after `max_steps` steps.
+    ///
+    /// # Arguments
+    ///
+    /// * `seeds` - The keys of the initially infected vertices.
+    /// * `recovery` - The per-step recovery probability.
+    /// * `max_steps` - The maximum number of steps to simulate.
+    /// * `seed` - The random seed; the same seed reproduces the same run.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<SirOutcome, GraphError>` - The infection curve and the set of vertices ever infected,
+    ///   or an error if a seed vertex does not exist.
+    pub fn simulate_sir(&self, seeds: &[&str], recovery: f32, max_steps: usize, seed: u64) -> Result<SirOutcome, GraphError> {
+        let index: GraphIndex = self.index();
+        let seeds: Vec<usize> = seed_positions(&index, seeds)?;
+        let mut rng: Rng = Rng::new(seed);
+        let (reached, curve) = run_sir(&index, &seeds, recovery, max_steps, &mut rng);
+
+        Ok(SirOutcome {
+            curve,
+            reached: reached_keys(&index, &reached),
+        })
+    }
+
+    /// Simulates an Independent Cascade spreading from the given seed vertices.
+    ///
+    /// Every newly activated vertex gets a single chance to activate each inactive
+    /// neighbor, succeeding with a probability equal to the edge weight (clamped to `[0, 1]`).
+    ///
+    /// # Arguments
+    ///
+    /// * `seeds` - The keys of the initially active vertices.
+    /// * `seed` - The random seed; the same seed reproduces the same run.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<CascadeOutcome, GraphError>` - The activation curve and the set of reached vertices,
+    ///   or an error if a seed vertex does not exist.
+    pub fn simulate_cascade(&self, seeds: &[&str], seed: u64) -> Result<CascadeOutcome, GraphError> {
+        let index: GraphIndex = self.index();
+        let seeds: Vec<usize> = seed_positions(&index, seeds)?;
+        let mut rng: Rng = Rng::new(seed);
+        let (active, curve) = run_cascade(&index, &seeds, &mut rng);
+
+        Ok(CascadeOutcome {
+            curve,
+            reached: reached_keys(&index, &active),
+        })
+    }
+
+    /// Estimates the expected number of vertices reached from `seeds` under `model`.
+    ///
+    /// # Arguments
+    ///
+    /// * `seeds` - The keys of the initially active vertices.
+    /// * `model` - The spreading process to simulate.
+    /// * `simulations` - The number of Monte Carlo runs to average over.
+    /// * `seed` - The random seed; the same seed reproduces the same estimate.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<f64, GraphError>` - The average reach, or an error if a seed vertex does not exist.
+    pub fn expected_spread(&self, seeds: &[&str], model: &SpreadModel, simulations: usize, seed: u64) -> Result<f64, GraphError> {
+        let index: GraphIndex = self.index();
+        let seeds: Vec<usize> = seed_positions(&index, seeds)?;
+        let mut rng: Rng = Rng::new(seed);
+        Ok(estimate_spread(&index, &seeds, model, simulations, &mut rng))
+    }
+}
@@ -0,0 +1,45 @@
+use crate::graph::Graph;
+use std::ops::Deref;
+use std::sync::Arc;
+
+/// A frozen, read-only copy of a graph.
+///
+/// A snapshot dereferences to the `Graph` it froze, so every query and algorithm runs on it
+/// unchanged, while the original graph keeps being modified. Cloning a snapshot only shares
+/// the frozen copy, and a snapshot can be sent to other threads when its keys and weights can,
+/// so several long-running readers can work from the same state without blocking writers or
+/// seeing a half-applied change.
+#[derive(Clone)]
+pub struct Snapshot {
+    graph: Arc<Graph>,
+}
+
+impl Deref for Snapshot {
+    type Target = Graph;
+
+    fn deref(&self) -> &Graph {
+        &self.graph
+    }
+}
+
+impl Snapshot {
+    /// Returns an independent, modifiable copy of the frozen graph.
+    pub fn to_graph(&self) -> Graph {
+        Graph::clone(&self.graph)
+    }
+}
+
+impl Graph {
+    /// Freezes the current state of the graph for readers that must not see later changes.
+    ///
+    /// Taking the snapshot copies the graph once, in time proportional to its size; after
+    /// that, the snapshot and its clones share the copy, and changes to this graph are not
+    /// visible through them.
+    ///
+    /// # Returns
+    ///
+    /// * A `Snapshot` of the graph as it is now.
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot { graph: Arc::new(self.clone()) }
+    }
+}
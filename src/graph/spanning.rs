@@ -0,0 +1,426 @@
+use crate::graph::cycles::EdgeList;
+use crate::graph::index::{GraphIndex, IndexedEdge};
+use crate::graph::path::State;
+use crate::graph::{Graph, GraphError};
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+
+/// A spanning tree of an undirected graph.
+#[derive(Clone, Debug)]
+pub struct SpanningTree {
+    /// The tree edges as `(vertex, vertex, weight)`.
+    pub edges: Vec<(String, String, f32)>,
+    /// The total weight of the edges.
+    pub weight: f32,
+}
+
+/// The algorithms `Graph::minimum_spanning_tree` can use.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SpanningTreeAlgorithm {
+    /// Kruskal's algorithm: add edges lightest first unless they close a cycle, tracked with
+    /// a union-find structure. `O(E log E)`; a good fit for sparse graphs.
+    Kruskal,
+    /// Prim's algorithm: grow one tree from a start vertex, always adding the lightest edge
+    /// leaving it, found with a binary heap. `O(E log V)`; a good fit for dense graphs.
+    Prim,
+}
+
+/// Disjoint sets of vertex positions with path halving and union by size.
+pub(crate) struct UnionFind {
+    parent: Vec<usize>,
+    size: Vec<usize>,
+}
+
+impl UnionFind {
+    pub fn new(n: usize) -> UnionFind {
+        UnionFind { parent: (0..n).collect(), size: vec![1; n] }
+    }
+
+    /// Returns the representative of the set containing `v`.
+    pub fn find(&mut self, mut v: usize) -> usize {
+        while self.parent[v] != v {
+            self.parent[v] = self.parent[self.parent[v]];
+            v = self.parent[v];
+        }
+        v
+    }
+
+    /// Merges the sets containing `u` and `v`, returning `false` if they were already one set.
+    pub fn union(&mut self, u: usize, v: usize) -> bool {
+        let (mut a, mut b) = (self.find(u), self.find(v));
+        if a == b {
+            return false;
+        }
+        if self.size[a] < self.size[b] {
+            std::mem::swap(&mut a, &mut b);
+        }
+        self.parent[b] = a;
+        self.size[a] += self.size[b];
+        true
+    }
+}
+
+/// Kruskal's algorithm over numbered edges, returning the chosen edges of a minimum spanning forest.
+fn kruskal(n: usize, mut edges: Vec<IndexedEdge>) -> Vec<IndexedEdge> {
+    edges.sort_by(|a, b| a.2.total_cmp(&b.2).then_with(|| (a.0, a.1).cmp(&(b.0, b.1))));
+    let mut sets: UnionFind = UnionFind::new(n);
+    edges.into_iter().filter(|&(u, v, _)| sets.union(u, v)).collect()
+}
+
+/// Prim's algorithm from every not yet reached vertex, returning the chosen edges of a
+/// minimum spanning forest.
+fn prim(index: &GraphIndex) -> Vec<IndexedEdge> {
+    let n: usize = index.len();
+    let mut in_tree: Vec<bool> = vec![false; n];
+    // The lightest known edge `(from, weight)` joining each vertex to the tree.
+    let mut best: Vec<Option<(usize, f32)>> = vec![None; n];
+    let mut chosen: Vec<IndexedEdge> = Vec::new();
+    for root in 0..n {
+        if in_tree[root] {
+            continue;
+        }
+        let mut heap: BinaryHeap<State> = BinaryHeap::from([State { cost: f32::NEG_INFINITY, vertex: root }]);
+        while let Some(State { vertex: u, .. }) = heap.pop() {
+            if in_tree[u] {
+                continue;
+            }
+            in_tree[u] = true;
+            if let Some((from, weight)) = best[u] {
+                chosen.push((from.min(u), from.max(u), weight));
+            }
+            for &(v, weight) in &index.adjacency[u] {
+                if !in_tree[v] && best[v].is_none_or(|(_, current)| weight < current) {
+                    best[v] = Some((u, weight));
+                    heap.push(State { cost: weight, vertex: v });
+                }
+            }
+        }
+    }
+    chosen
+}
+
+/// A spanning forest under construction, stored as a selection of numbered edges.
+struct Forest<'a> {
+    edges: &'a [IndexedEdge],
+    chosen: Vec<bool>,
+    degree: Vec<usize>,
+}
+
+impl<'a> Forest<'a> {
+    fn new(n: usize, edges: &'a [IndexedEdge]) -> Forest<'a> {
+        Forest { edges, chosen: vec![false; edges.len()], degree: vec![0; n] }
+    }
+
+    fn toggle(&mut self, e: usize) {
+        let (u, v, _) = self.edges[e];
+        if self.chosen[e] {
+            self.degree[u] -= 1;
+            self.degree[v] -= 1;
+        } else {
+            self.degree[u] += 1;
+            self.degree[v] += 1;
+        }
+        self.chosen[e] = !self.chosen[e];
+    }
+
+    /// Labels every vertex with the component it belongs to in the forest, skipping `without`.
+    fn components(&self, without: Option<usize>) -> Vec<usize> {
+        let n: usize = self.degree.len();
+        let mut adjacency: Vec<Vec<usize>> = vec![Vec::new(); n];
+        for (e, &(u, v, _)) in self.edges.iter().enumerate() {
+            if self.chosen[e] && Some(e) != without {
+                adjacency[u].push(v);
+                adjacency[v].push(u);
+            }
+        }
+        let mut label: Vec<usize> = vec![usize::MAX; n];
+        for root in 0..n {
+            if label[root] != usize::MAX {
+                continue;
+            }
+            label[root] = root;
+            let mut stack: Vec<usize> = vec![root];
+            while let Some(u) = stack.pop() {
+                for &v in &adjacency[u] {
+                    if label[v] == usize::MAX {
+                        label[v] = root;
+                        stack.push(v);
+                    }
+                }
+            }
+        }
+        label
+    }
+
+    /// Returns the edge ids on the forest path from `from` to `to`, if they are connected.
+    fn path(&self, from: usize, to: usize) -> Option<Vec<usize>> {
+        let n: usize = self.degree.len();
+        let mut incident: Vec<Vec<(usize, usize)>> = vec![Vec::new(); n];
+        for (e, &(u, v, _)) in self.edges.iter().enumerate() {
+            if self.chosen[e] {
+                incident[u].push((v, e));
+                incident[v].push((u, e));
+            }
+        }
+        let mut parent: Vec<Option<(usize, usize)>> = vec![None; n];
+        let mut visited: Vec<bool> = vec![false; n];
+        visited[from] = true;
+        let mut queue: VecDeque<usize> = VecDeque::from([from]);
+        while let Some(u) = queue.pop_front() {
+            for &(v, e) in &incident[u] {
+                if !visited[v] {
+                    visited[v] = true;
+                    parent[v] = Some((u, e));
+                    queue.push_back(v);
+                }
+            }
+        }
+        if !visited[to] {
+            return None;
+        }
+        let mut path: Vec<usize> = Vec::new();
+        let mut current: usize = to;
+        while let Some((up, e)) = parent[current] {
+            path.push(e);
+            current = up;
+        }
+        Some(path)
+    }
+}
+
+/// Counts the distinct labels in a component labeling.
+fn count_components(label: &[usize]) -> usize {
+    label.iter().enumerate().filter(|&(v, &l)| v == l).count()
+}
+
+impl Graph {
+    /// Builds a light spanning tree in which no vertex exceeds its maximum degree.
+    ///
+    /// Finding the minimum such tree is NP-hard, so a heuristic is used. Kruskal's algorithm
+    /// first adds the lightest edges whose endpoints still have spare degree. If that leaves
+    /// several pieces, a saturated vertex gives up one tree edge so it can join another piece,
+    /// and the cut-off part is reattached elsewhere. Finally, single edge swaps that lower the
+    /// weight without breaking a degree bound are applied until none is left. Self-loops and
+    /// parallel edges beyond the lightest are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_degree` - Returns the largest number of tree edges allowed at a vertex given its key.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<SpanningTree>, GraphError>` - The tree, `None` if the graph is disconnected
+    ///   or the heuristic could not meet the degree bounds, or an error if the graph is directed.
+    pub fn degree_constrained_spanning_tree<D>(&self, max_degree: D) -> Result<Option<SpanningTree>, GraphError>
+    where
+        D: Fn(&str) -> usize,
+    {
+        if self.directed {
+            return Err(GraphError::NeedsUndirected("Spanning trees".to_string()));
+        }
+        let index: GraphIndex = self.index();
+        let n: usize = index.len();
+        let cap: Vec<usize> = index.keys.iter().map(|key| max_degree(key)).collect();
+        let mut edges: Vec<IndexedEdge> = EdgeList::new(&index).edges.into_iter().filter(|&(u, v, _)| u != v).collect();
+        edges.sort_by(|a, b| a.2.total_cmp(&b.2).then_with(|| (a.0, a.1).cmp(&(b.0, b.1))));
+        let fits = |degree: &[usize], v: usize| degree[v] < cap[v];
+
+        // Degree-bounded Kruskal.
+        let mut forest: Forest = Forest::new(n, &edges);
+        let mut label: Vec<usize> = forest.components(None);
+        for (e, &(u, v, _)) in edges.iter().enumerate() {
+            if label[u] != label[v] && fits(&forest.degree, u) && fits(&forest.degree, v) {
+                forest.toggle(e);
+                let (old, new) = (label[u], label[v]);
+                label.iter_mut().filter(|l| **l == old).for_each(|l| *l = new);
+            }
+        }
+
+        // Merge the remaining pieces, freeing a degree at a saturated endpoint when needed.
+        while count_components(&label) > 1 {
+            let mut best: Option<(f32, Vec<usize>)> = None;
+            for (join, &(u, v, weight)) in edges.iter().enumerate() {
+                if label[u] == label[v] {
+                    continue;
+                }
+                if fits(&forest.degree, u) && fits(&forest.degree, v) {
+                    if best.as_ref().is_none_or(|(current, _)| weight < *current) {
+                        best = Some((weight, vec![join]));
+                    }
+                    continue;
+                }
+                // Only one side may be saturated; it drops a tree edge and the cut-off part is reattached.
+                let saturated: usize = if fits(&forest.degree, u) { v } else { u };
+                let other: usize = if saturated == u { v } else { u };
+                if !fits(&forest.degree, other) {
+                    continue;
+                }
+                for (drop, &(a, b, dropped)) in edges.iter().enumerate() {
+                    if !forest.chosen[drop] || (a != saturated && b != saturated) {
+                        continue;
+                    }
+                    let split: Vec<usize> = forest.components(Some(drop));
+                    let detached: usize = split[if a == saturated { b } else { a }];
+                    let mut degree: Vec<usize> = forest.degree.clone();
+                    degree[a] -= 1;
+                    degree[b] -= 1;
+                    degree[saturated] += 1;
+                    degree[other] += 1;
+                    let reattach = edges.iter().enumerate().find(|&(_, &(x, y, _))| {
+                        (split[x] == detached) != (split[y] == detached)
+                            && fits(&degree, x)
+                            && fits(&degree, y)
+                            && !(x == saturated || y == saturated)
+                    });
+                    if let Some((again, &(_, _, extra))) = reattach {
+                        let cost: f32 = weight + extra - dropped;
+                        if best.as_ref().is_none_or(|(current, _)| cost < *current) {
+                            best = Some((cost, vec![drop, join, again]));
+                        }
+                    }
+                }
+            }
+            match best {
+                Some((_, moves)) => moves.into_iter().for_each(|e| forest.toggle(e)),
+                None => return Ok(None),
+            }
+            label = forest.components(None);
+        }
+
+        // Swap a non-tree edge for the heaviest tree edge on its cycle while that helps.
+        loop {
+            let mut improved: bool = false;
+            for (add, &(u, v, weight)) in edges.iter().enumerate() {
+                if forest.chosen[add] {
+                    continue;
+                }
+                let path: Vec<usize> = forest.path(u, v).unwrap();
+                let candidate: Option<usize> = path.into_iter()
+                    .filter(|&e| edges[e].2 > weight)
+                    .filter(|&e| {
+                        let (a, b, _) = edges[e];
+                        [u, v].iter().all(|&p| forest.degree[p] + 1 - usize::from(p == a || p == b) <= cap[p])
+                    })
+                    .max_by(|&x, &y| edges[x].2.total_cmp(&edges[y].2));
+                if let Some(remove) = candidate {
+                    forest.toggle(remove);
+                    forest.toggle(add);
+                    improved = true;
+                }
+            }
+            if !improved {
+                break;
+            }
+        }
+
+        let mut tree: SpanningTree = SpanningTree { edges: Vec::new(), weight: 0.0 };
+        for (e, &(u, v, weight)) in edges.iter().enumerate() {
+            if forest.chosen[e] {
+                tree.weight += weight;
+                tree.edges.push((index.keys[u].clone(), index.keys[v].clone(), weight));
+            }
+        }
+        Ok(Some(tree))
+    }
+
+    /// Builds a minimum spanning tree of a connected undirected graph.
+    ///
+    /// Self-loops are ignored and only the lightest of parallel edges can be chosen. Ties
+    /// between equal weights are broken by vertex key, so the result is reproducible.
+    ///
+    /// # Arguments
+    ///
+    /// * `algorithm` - Whether to use Kruskal's or Prim's algorithm; both give a tree of the
+    ///   same total weight.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Graph, GraphError>` - A graph with every vertex and only the tree edges, or an error
+    ///   if the graph is directed or disconnected.
+    pub fn minimum_spanning_tree(&self, algorithm: SpanningTreeAlgorithm) -> Result<Graph, GraphError> {
+        if self.directed {
+            return Err(GraphError::NeedsUndirected("Spanning trees".to_string()));
+        }
+        let index: GraphIndex = self.index();
+        let edges: Vec<IndexedEdge> = match algorithm {
+            SpanningTreeAlgorithm::Kruskal => {
+                let edges: Vec<IndexedEdge> = EdgeList::new(&index).edges.into_iter().filter(|&(u, v, _)| u != v).collect();
+                kruskal(index.len(), edges)
+            }
+            SpanningTreeAlgorithm::Prim => prim(&index),
+        };
+        if edges.len() + 1 < index.len() {
+            return Err(GraphError::Disconnected);
+        }
+        Ok(index.with_edges(&edges))
+    }
+
+    /// Builds a minimum bottleneck spanning tree of a connected undirected graph: a spanning
+    /// tree whose heaviest edge is as light as possible.
+    ///
+    /// Every minimum spanning tree is also a minimum bottleneck spanning tree, so this is
+    /// Kruskal's algorithm; the bottleneck is the smallest weight at which every vertex can
+    /// reach every other.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(Graph, f32), GraphError>` - A graph with every vertex and only the tree edges, and
+    ///   the weight of its heaviest edge (`0` for a single vertex), or an error if the graph is
+    ///   directed or disconnected.
+    pub fn minimum_bottleneck_spanning_tree(&self) -> Result<(Graph, f32), GraphError> {
+        let tree: Graph = self.minimum_spanning_tree(SpanningTreeAlgorithm::Kruskal)?;
+        let index: GraphIndex = tree.index();
+        let bottleneck: f32 = index.edges().iter().map(|&(_, _, weight)| weight).fold(0.0, f32::max);
+        Ok((tree, bottleneck))
+    }
+
+    /// Groups the vertices of an undirected graph into `k` clusters by single linkage.
+    ///
+    /// Runs Kruskal's algorithm and stops once `k` trees are left, so the clusters are the
+    /// components of a minimum spanning forest without its `k - 1` heaviest edges. This
+    /// maximizes the lightest edge between different clusters.
+    ///
+    /// # Arguments
+    ///
+    /// * `k` - The number of clusters.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<HashMap<String, usize>, GraphError>` - The cluster of every vertex key, numbered
+    ///   from `0` in order of each cluster's smallest key, or an error if the graph is directed,
+    ///   `k` is not between `1` and the number of vertices, or the graph has more than `k`
+    ///   connected components.
+    pub fn k_clusters(&self, k: usize) -> Result<HashMap<String, usize>, GraphError> {
+        if self.directed {
+            return Err(GraphError::NeedsUndirected("Clustering".to_string()));
+        }
+        let index: GraphIndex = self.index();
+        let n: usize = index.len();
+        if k == 0 || k > n {
+            return Err(GraphError::InvalidArgument(format!("Cannot split {} vertices into {} clusters", n, k)));
+        }
+        let mut edges: Vec<IndexedEdge> = EdgeList::new(&index).edges;
+        edges.sort_by(|a, b| a.2.total_cmp(&b.2).then_with(|| (a.0, a.1).cmp(&(b.0, b.1))));
+        let mut sets: UnionFind = UnionFind::new(n);
+        let mut clusters: usize = n;
+        for (u, v, _) in edges {
+            if clusters == k {
+                break;
+            }
+            if sets.union(u, v) {
+                clusters -= 1;
+            }
+        }
+        if clusters > k {
+            return Err(GraphError::NoSolution(format!("The graph has {} connected components, more than {} clusters", clusters, k)));
+        }
+
+        let mut numbers: HashMap<usize, usize> = HashMap::new();
+        let mut assignment: HashMap<String, usize> = HashMap::with_capacity(n);
+        for (v, key) in index.keys.iter().enumerate() {
+            let next: usize = numbers.len();
+            let cluster: usize = *numbers.entry(sets.find(v)).or_insert(next);
+            assignment.insert(key.clone(), cluster);
+        }
+        Ok(assignment)
+    }
+}
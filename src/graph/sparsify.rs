@@ -0,0 +1,140 @@
+use crate::graph::index::{GraphIndex, IndexedEdge};
+use crate::graph::linalg::solve_laplacian;
+use crate::graph::path::State;
+use crate::graph::rng::Rng;
+use crate::graph::{Graph, GraphError};
+use std::collections::BinaryHeap;
+
+/// The relative residual at which the Laplacian solver stops.
+const TOLERANCE: f64 = 1e-6;
+
+/// Lists every non-loop edge once (every arc, in directed graphs), rejecting negative weights.
+fn weighted_edges(index: &GraphIndex) -> Result<Vec<IndexedEdge>, GraphError> {
+    let edges: Vec<IndexedEdge> = index.edges();
+    if let Some(&(u, v, _)) = edges.iter().find(|&&(_, _, weight)| weight < 0.0) {
+        return Err(GraphError::NegativeWeight(index.keys[u].clone(), index.keys[v].clone()));
+    }
+    Ok(edges.into_iter().filter(|&(u, v, _)| u != v).collect())
+}
+
+/// Returns the distance from `from` to `to`, or infinity if it exceeds `limit`.
+fn bounded_distance(adjacency: &[Vec<(usize, f32)>], from: usize, to: usize, limit: f32) -> f32 {
+    let mut distance: Vec<f32> = vec![f32::INFINITY; adjacency.len()];
+    distance[from] = 0.0;
+    let mut heap: BinaryHeap<State> = BinaryHeap::from([State { cost: 0.0, vertex: from }]);
+    while let Some(State { cost, vertex }) = heap.pop() {
+        if cost > limit {
+            break;
+        }
+        if vertex == to {
+            return cost;
+        }
+        if cost > distance[vertex] {
+            continue;
+        }
+        for &(next, weight) in &adjacency[vertex] {
+            let candidate: f32 = cost + weight;
+            if candidate < distance[next] {
+                distance[next] = candidate;
+                heap.push(State { cost: candidate, vertex: next });
+            }
+        }
+    }
+    f32::INFINITY
+}
+
+impl Graph {
+    /// Builds a greedy `t`-spanner: a subgraph in which every distance is at most `t` times the
+    /// original distance.
+    ///
+    /// Edges are considered from lightest to heaviest, and an edge is kept only if the spanner
+    /// built so far has no path between its endpoints within `t` times its weight. For
+    /// `t = 2k - 1` the result has `O(n^(1 + 1/k))` edges. Self-loops are dropped.
+    ///
+    /// # Arguments
+    ///
+    /// * `t` - The allowed stretch, at least `1`.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Graph, GraphError>` - The spanner with every vertex of the graph, or an error if
+    ///   `t < 1` or a weight is negative.
+    pub fn greedy_spanner(&self, t: f32) -> Result<Graph, GraphError> {
+        self.require_distances("Spanners")?;
+        if t.is_nan() || t < 1.0 {
+            return Err(GraphError::InvalidArgument("Spanner stretch must be at least 1".to_string()));
+        }
+        let index: GraphIndex = self.index();
+        let mut edges: Vec<IndexedEdge> = weighted_edges(&index)?;
+        edges.sort_by(|a, b| a.2.total_cmp(&b.2).then_with(|| (a.0, a.1).cmp(&(b.0, b.1))));
+
+        let mut adjacency: Vec<Vec<(usize, f32)>> = vec![Vec::new(); index.len()];
+        let mut kept: Vec<IndexedEdge> = Vec::new();
+        for (u, v, weight) in edges {
+            if bounded_distance(&adjacency, u, v, t * weight) <= t * weight {
+                continue;
+            }
+            adjacency[u].push((v, weight));
+            if !index.directed {
+                adjacency[v].push((u, weight));
+            }
+            kept.push((u, v, weight));
+        }
+        Ok(index.with_edges(&kept))
+    }
+
+    /// Builds a spectral sparsifier by sampling edges by effective resistance (Spielman-Srivastava).
+    ///
+    /// Edge `e` is drawn with probability proportional to `w_e R_e`, its weight times the
+    /// effective resistance between its endpoints, and every draw adds `w_e / (samples p_e)` to
+    /// its weight in the result. The Laplacian quadratic form, and so every cut, is preserved
+    /// in expectation; `O(n log n / epsilon^2)` samples give a `(1 + epsilon)` approximation
+    /// with high probability. Self-loops are dropped.
+    ///
+    /// # Arguments
+    ///
+    /// * `samples` - The number of edge draws.
+    /// * `seed` - The random seed; the same seed reproduces the same sparsifier.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Graph, GraphError>` - The reweighted sparse subgraph with every vertex of the graph,
+    ///   or an error if the graph is directed or a weight is negative.
+    pub fn spectral_sparsifier(&self, samples: usize, seed: u64) -> Result<Graph, GraphError> {
+        if self.directed {
+            return Err(GraphError::NeedsUndirected("Spectral sparsification".to_string()));
+        }
+        let index: GraphIndex = self.index();
+        let edges: Vec<IndexedEdge> = weighted_edges(&index)?;
+
+        // The sampling weight of every edge is its weight times its effective resistance.
+        let mut cumulative: Vec<f64> = Vec::with_capacity(edges.len());
+        let mut total: f64 = 0.0;
+        for &(u, v, weight) in &edges {
+            let mut current: Vec<f64> = vec![0.0; index.len()];
+            current[u] = 1.0;
+            current[v] = -1.0;
+            let potential: Vec<f64> = solve_laplacian(&index, &current, TOLERANCE);
+            total += f64::from(weight) * (potential[u] - potential[v]).max(0.0);
+            cumulative.push(total);
+        }
+
+        let mut sampled: Vec<f64> = vec![0.0; edges.len()];
+        if total > 0.0 {
+            let mut rng: Rng = Rng::new(seed);
+            for _ in 0..samples {
+                let pick: f64 = rng.next_f64() * total;
+                let e: usize = cumulative.partition_point(|&c| c <= pick).min(edges.len() - 1);
+                let probability: f64 = (cumulative[e] - if e > 0 { cumulative[e - 1] } else { 0.0 }) / total;
+                sampled[e] += f64::from(edges[e].2) / (samples as f64 * probability);
+            }
+        }
+
+        let kept: Vec<IndexedEdge> = edges.iter()
+            .zip(sampled)
+            .filter(|&(_, weight)| weight > 0.0)
+            .map(|(&(u, v, _), weight)| (u, v, weight as f32))
+            .collect();
+        Ok(index.with_edges(&kept))
+    }
+}
@@ -0,0 +1,312 @@
+use crate::graph::index::GraphIndex;
+use crate::graph::path::{dijkstra, reconstruct, reject_negative_weights, State};
+use crate::graph::{Graph, GraphError};
+use std::collections::{BinaryHeap, HashSet};
+
+/// The largest terminal count for which `Graph::steiner_tree` uses the exact algorithm.
+///
+/// The Dreyfus-Wagner program costs `O(3^k n + 2^k m log n)` for `k` terminals, which stays
+/// fast up to about a dozen terminals and grows quickly after that.
+pub const EXACT_STEINER_TERMINALS: usize = 12;
+
+/// The most terminals `Graph::steiner_tree_exact` accepts.
+///
+/// Its tables hold an entry for every subset of terminals and every vertex, `2^k n` in all,
+/// which no longer fits in memory long before the subset masks would overflow.
+pub const MAX_EXACT_STEINER_TERMINALS: usize = 20;
+
+/// A tree connecting a set of terminal vertices.
+#[derive(Clone, Debug)]
+pub struct SteinerTree {
+    /// The tree edges as `(vertex, vertex, weight)`.
+    pub edges: Vec<(String, String, f32)>,
+    /// The total weight of the edges.
+    pub weight: f32,
+}
+
+/// How a Dreyfus-Wagner table entry was obtained.
+#[derive(Clone, Copy)]
+enum Step {
+    /// A terminal on its own, or an unreached entry.
+    Base,
+    /// The union of the trees for a submask and its complement, meeting at the same vertex.
+    Split(usize),
+    /// The tree at the given vertex extended by one edge of the given weight.
+    Extend(usize, f32),
+}
+
+/// Removes duplicate undirected edges and sums the weights.
+fn into_tree(index: &GraphIndex, edges: Vec<(usize, usize, f32)>) -> SteinerTree {
+    let mut seen: HashSet<(usize, usize)> = HashSet::new();
+    let mut tree: SteinerTree = SteinerTree { edges: Vec::new(), weight: 0.0 };
+    for (u, v, weight) in edges {
+        if seen.insert((u.min(v), u.max(v))) {
+            tree.weight += weight;
+            tree.edges.push((index.keys[u].clone(), index.keys[v].clone(), weight));
+        }
+    }
+    tree
+}
+
+/// Resolves terminal keys to positions, rejecting directed graphs and negative weights and
+/// ignoring duplicates.
+fn terminal_positions(graph: &Graph, index: &GraphIndex, terminals: &[&str]) -> Result<Vec<usize>, GraphError> {
+    if graph.directed {
+        return Err(GraphError::NeedsUndirected("Steiner trees".to_string()));
+    }
+    reject_negative_weights(index)?;
+    let mut positions: Vec<usize> = Vec::with_capacity(terminals.len());
+    for terminal in terminals {
+        let position: usize = index.position(terminal)?;
+        if !positions.contains(&position) {
+            positions.push(position);
+        }
+    }
+    Ok(positions)
+}
+
+impl Graph {
+    /// Computes a Steiner tree, picking the algorithm by the number of terminals.
+    ///
+    /// Up to `EXACT_STEINER_TERMINALS` terminals the exact Dreyfus-Wagner program is used;
+    /// above that the metric-closure 2-approximation is used, since the exact cost grows
+    /// as `3^k`.
+    ///
+    /// # Arguments
+    ///
+    /// * `terminals` - The keys of the vertices the tree must connect.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<SteinerTree>, GraphError>` - The tree, `None` if the terminals are not all
+    ///   connected, or an error if a vertex does not exist, the graph is directed or an edge
+    ///   weight is negative.
+    pub fn steiner_tree(&self, terminals: &[&str]) -> Result<Option<SteinerTree>, GraphError> {
+        self.require_distances("Steiner trees")?;
+        if terminals.len() <= EXACT_STEINER_TERMINALS {
+            self.steiner_tree_exact(terminals)
+        } else {
+            self.steiner_tree_approx(terminals)
+        }
+    }
+
+    /// Computes a minimum Steiner tree exactly with the Dreyfus-Wagner dynamic program.
+    ///
+    /// For every subset of terminals and every vertex the program stores the cheapest tree
+    /// connecting the subset to that vertex, combining subsets at shared vertices and
+    /// growing them along edges with Dijkstra. Edge weights must be non-negative.
+    ///
+    /// # Arguments
+    ///
+    /// * `terminals` - The keys of the vertices the tree must connect.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<SteinerTree>, GraphError>` - The optimal tree, `None` if the terminals are not
+    ///   all connected, or an error if a vertex does not exist, the graph is directed, an edge
+    ///   weight is negative or there are more than `MAX_EXACT_STEINER_TERMINALS` terminals.
+    pub fn steiner_tree_exact(&self, terminals: &[&str]) -> Result<Option<SteinerTree>, GraphError> {
+        self.require_distances("Steiner trees")?;
+        let index: GraphIndex = self.index();
+        let terminals: Vec<usize> = terminal_positions(self, &index, terminals)?;
+        if terminals.len() > MAX_EXACT_STEINER_TERMINALS {
+            return Err(GraphError::InvalidArgument(format!(
+                "The exact Steiner tree takes at most {} terminals, not {}", MAX_EXACT_STEINER_TERMINALS, terminals.len()
+            )));
+        }
+        if terminals.is_empty() {
+            return Ok(Some(SteinerTree { edges: Vec::new(), weight: 0.0 }));
+        }
+
+        let n: usize = index.len();
+        let full: usize = (1 << terminals.len()) - 1;
+        let mut cost: Vec<Vec<f32>> = vec![vec![f32::INFINITY; n]; full + 1];
+        let mut steps: Vec<Vec<Step>> = vec![vec![Step::Base; n]; full + 1];
+
+        for mask in 1..=full {
+            if mask.count_ones() == 1 {
+                cost[mask][terminals[mask.trailing_zeros() as usize]] = 0.0;
+            } else {
+                // Only submasks containing the lowest bit, so each split is tried once.
+                let low: usize = mask & mask.wrapping_neg();
+                let mut sub: usize = (mask - 1) & mask;
+                while sub > 0 {
+                    if sub & low != 0 {
+                        for v in 0..n {
+                            let combined: f32 = cost[sub][v] + cost[mask ^ sub][v];
+                            if combined < cost[mask][v] {
+                                cost[mask][v] = combined;
+                                steps[mask][v] = Step::Split(sub);
+                            }
+                        }
+                    }
+                    sub = (sub - 1) & mask;
+                }
+            }
+
+            let mut heap: BinaryHeap<State> = (0..n)
+                .filter(|&v| cost[mask][v].is_finite())
+                .map(|v| State { cost: cost[mask][v], vertex: v })
+                .collect();
+            while let Some(State { cost: current, vertex }) = heap.pop() {
+                if current > cost[mask][vertex] {
+                    continue;
+                }
+                for &(next, weight) in &index.adjacency[vertex] {
+                    let candidate: f32 = current + weight;
+                    if candidate < cost[mask][next] {
+                        cost[mask][next] = candidate;
+                        steps[mask][next] = Step::Extend(vertex, weight);
+                        heap.push(State { cost: candidate, vertex: next });
+                    }
+                }
+            }
+        }
+
+        let root: usize = terminals[0];
+        if cost[full][root].is_infinite() {
+            return Ok(None);
+        }
+
+        let mut edges: Vec<(usize, usize, f32)> = Vec::new();
+        let mut pending: Vec<(usize, usize)> = vec![(full, root)];
+        while let Some((mask, vertex)) = pending.pop() {
+            match steps[mask][vertex] {
+                Step::Base => {}
+                Step::Split(sub) => {
+                    pending.push((sub, vertex));
+                    pending.push((mask ^ sub, vertex));
+                }
+                Step::Extend(previous, weight) => {
+                    edges.push((previous, vertex, weight));
+                    pending.push((mask, previous));
+                }
+            }
+        }
+        Ok(Some(into_tree(&index, edges)))
+    }
+
+    /// Computes a Steiner tree within twice the optimum using the metric-closure heuristic.
+    ///
+    /// A minimum spanning tree is built over the shortest-path distances between terminals,
+    /// its edges are expanded back into graph paths, cycles are removed and non-terminal
+    /// leaves are pruned. Suitable for any number of terminals. Edge weights must be non-negative.
+    ///
+    /// # Arguments
+    ///
+    /// * `terminals` - The keys of the vertices the tree must connect.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<SteinerTree>, GraphError>` - The tree, `None` if the terminals are not all
+    ///   connected, or an error if a vertex does not exist, the graph is directed or an edge
+    ///   weight is negative.
+    pub fn steiner_tree_approx(&self, terminals: &[&str]) -> Result<Option<SteinerTree>, GraphError> {
+        self.require_distances("Steiner trees")?;
+        let index: GraphIndex = self.index();
+        let terminals: Vec<usize> = terminal_positions(self, &index, terminals)?;
+        let k: usize = terminals.len();
+        if k == 0 {
+            return Ok(Some(SteinerTree { edges: Vec::new(), weight: 0.0 }));
+        }
+
+        let searches: Vec<(Vec<f32>, Vec<Option<usize>>)> = terminals.iter()
+            .map(|&t| dijkstra(&index, t, |_| true, |_, _, _| true))
+            .collect();
+
+        // Prim's algorithm on the metric closure of the terminals.
+        let mut in_tree: Vec<bool> = vec![false; k];
+        let mut best: Vec<(f32, usize)> = vec![(f32::INFINITY, 0); k];
+        best[0].0 = 0.0;
+        let mut union: Vec<(usize, usize, f32)> = Vec::new();
+        for _ in 0..k {
+            let next: usize = (0..k)
+                .filter(|&i| !in_tree[i])
+                .min_by(|&a, &b| best[a].0.total_cmp(&best[b].0))
+                .unwrap();
+            if best[next].0.is_infinite() {
+                return Ok(None);
+            }
+            in_tree[next] = true;
+            if next != 0 {
+                let (distances, predecessors) = &searches[best[next].1];
+                let path: Vec<usize> = reconstruct(predecessors, terminals[next]);
+                for pair in path.windows(2) {
+                    let weight: f32 = distances[pair[1]] - distances[pair[0]];
+                    union.push((pair[0], pair[1], weight));
+                }
+            }
+            for i in 0..k {
+                let distance: f32 = searches[next].0[terminals[i]];
+                if !in_tree[i] && distance < best[i].0 {
+                    best[i] = (distance, next);
+                }
+            }
+        }
+
+        // Drop cycles with Kruskal over the union of paths, then prune non-terminal leaves.
+        union.sort_by(|a, b| a.2.total_cmp(&b.2));
+        let mut component: Vec<usize> = (0..index.len()).collect();
+        fn find(component: &mut [usize], mut v: usize) -> usize {
+            while component[v] != v {
+                component[v] = component[component[v]];
+                v = component[v];
+            }
+            v
+        }
+        let mut edges: Vec<(usize, usize, f32)> = Vec::new();
+        for (u, v, weight) in union {
+            let (a, b) = (find(&mut component, u), find(&mut component, v));
+            if a != b {
+                component[a] = b;
+                edges.push((u, v, weight));
+            }
+        }
+
+        let is_terminal: HashSet<usize> = terminals.into_iter().collect();
+        loop {
+            let mut degree: Vec<usize> = vec![0; index.len()];
+            for &(u, v, _) in &edges {
+                degree[u] += 1;
+                degree[v] += 1;
+            }
+            let before: usize = edges.len();
+            let keep = |v: usize| degree[v] != 1 || is_terminal.contains(&v);
+            edges.retain(|&(u, v, _)| keep(u) && keep(v));
+            if edges.len() == before {
+                break;
+            }
+        }
+        Ok(Some(into_tree(&index, edges)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MAX_EXACT_STEINER_TERMINALS;
+    use crate::graph::testing::graph_from;
+    use crate::graph::{Graph, GraphError};
+
+    #[test]
+    fn steiner_tree_exact_rejects_too_many_terminals() {
+        let keys: Vec<String> = (0..70).map(|i| format!("T{}", i)).collect();
+        let edges: Vec<(&str, &str, f32)> = keys.windows(2).map(|pair| (pair[0].as_str(), pair[1].as_str(), 1.0)).collect();
+        let graph: Graph = graph_from(false, &edges);
+        let terminals: Vec<&str> = keys.iter().map(String::as_str).collect();
+        assert!(matches!(graph.steiner_tree_exact(&terminals), Err(GraphError::InvalidArgument(_))));
+        assert!(matches!(graph.steiner_tree_exact(&terminals[..MAX_EXACT_STEINER_TERMINALS + 1]), Err(GraphError::InvalidArgument(_))));
+        assert_eq!(graph.steiner_tree(&terminals).unwrap().unwrap().weight, 69.0);
+    }
+
+    #[test]
+    fn steiner_tree_exact_rejects_negative_weights() {
+        let graph: Graph = graph_from(false, &[("A", "B", -1.0), ("B", "C", 1.0)]);
+        assert_eq!(graph.steiner_tree_exact(&["A", "C"]).unwrap_err(), GraphError::NegativeWeight("A".to_string(), "B".to_string()));
+    }
+
+    #[test]
+    fn steiner_tree_exact_joins_through_a_hub() {
+        let graph: Graph = graph_from(false, &[("A", "H", 1.0), ("B", "H", 1.0), ("C", "H", 1.0), ("A", "B", 3.0), ("B", "C", 3.0)]);
+        assert_eq!(graph.steiner_tree_exact(&["A", "B", "C"]).unwrap().unwrap().weight, 3.0);
+    }
+}
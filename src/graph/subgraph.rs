@@ -0,0 +1,94 @@
+use crate::graph::{Edge, Graph};
+use std::collections::HashSet;
+
+impl Graph {
+    /// Builds a graph with the same direction and settings as this one from the vertices
+    /// `keep_vertex` accepts, with their attributes, and the edges between them that
+    /// `keep_edge` accepts.
+    fn restricted<K, E>(&self, keep_vertex: K, keep_edge: E) -> Graph
+    where
+        K: Fn(&str) -> bool,
+        E: Fn(&Edge) -> bool,
+    {
+        let mut graph: Graph = self.empty_like();
+        for key in self.vertex_keys() {
+            if keep_vertex(&key) {
+                graph.add_vertex_like(self, &key);
+            }
+        }
+        for edge in self.stored_edges() {
+            if keep_vertex(&edge.vertex1) && keep_vertex(&edge.vertex2) && keep_edge(edge) {
+                graph.add_edge(edge.clone()).unwrap();
+            }
+        }
+        graph
+    }
+
+    /// Returns the subgraph induced by the given vertices: those vertices, with their
+    /// attributes, and every edge between two of them.
+    ///
+    /// Keys that are not in the graph are ignored. The direction of the graph is kept, and
+    /// `edge_count` counts only the edges kept.
+    ///
+    /// # Arguments
+    ///
+    /// * `vertex_keys` - The keys of the vertices to keep.
+    ///
+    /// # Returns
+    ///
+    /// * A `Graph` with the given vertices and the edges among them.
+    pub fn subgraph(&self, vertex_keys: &[&str]) -> Graph {
+        let keep: HashSet<&str> = vertex_keys.iter().copied().collect();
+        self.restricted(|key| keep.contains(key), |_| true)
+    }
+
+    /// Returns the subgraph of the edges matching a predicate, such as a weight threshold.
+    ///
+    /// Every vertex is kept, with its attributes, even if none of its edges are. The
+    /// direction of the graph is kept, and `edge_count` counts only the edges kept.
+    ///
+    /// # Arguments
+    ///
+    /// * `predicate` - Returns `true` for the edges to keep; each edge is offered once.
+    ///
+    /// # Returns
+    ///
+    /// * A `Graph` with every vertex and the matching edges.
+    pub fn filter_edges<F>(&self, predicate: F) -> Graph
+    where
+        F: Fn(&Edge) -> bool,
+    {
+        self.restricted(|_| true, predicate)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::graph::graph::{GraphMode, VertexOrder, WeightSemantics};
+    use crate::graph::testing::graph_from;
+    use crate::graph::{AttrValue, Graph, GraphError};
+
+    #[test]
+    fn subgraph_keeps_attributes_and_settings() {
+        let mut graph: Graph = graph_from(false, &[("c", "b", 1.0), ("b", "a", 2.0)]);
+        graph.vertices.get_mut("c").unwrap().set_attr("size", AttrValue::Int(3));
+        graph.set_mode(GraphMode::Simple).unwrap();
+        graph.set_vertex_order(VertexOrder::Insertion);
+        graph.set_weight_semantics(WeightSemantics::Similarity);
+
+        let sub: Graph = graph.subgraph(&["a", "c", "b"]);
+        assert_eq!(sub.vertices["c"].attr("size"), Some(&AttrValue::Int(3)));
+        assert_eq!(sub.mode(), GraphMode::Simple);
+        assert_eq!(sub.vertex_keys(), vec!["c".to_string(), "b".to_string(), "a".to_string()]);
+        assert!(matches!(sub.shortest_path("a", "c"), Err(GraphError::InvalidArgument(_))));
+        assert_eq!(graph.filter_edges(|edge| edge.weight > 1.0).weight_semantics(), WeightSemantics::Similarity);
+    }
+
+    #[test]
+    fn subgraph_ignores_unknown_keys() {
+        let graph: Graph = graph_from(true, &[("a", "b", 1.0), ("b", "c", 1.0)]);
+        let sub: Graph = graph.subgraph(&["a", "b", "x"]);
+        assert_eq!(sub.vertices.len(), 2);
+        assert_eq!(sub.edge_count, 1);
+    }
+}
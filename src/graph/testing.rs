@@ -0,0 +1,15 @@
+use crate::graph::{Edge, Graph, Vertex};
+
+/// Builds a graph from `(from, to, weight)` triples, adding every endpoint as a vertex.
+pub(crate) fn graph_from(directed: bool, edges: &[(&str, &str, f32)]) -> Graph {
+    let mut graph: Graph = Graph::new(directed);
+    for &(from, to, weight) in edges {
+        for key in [from, to] {
+            if !graph.vertices.contains_key(key) {
+                graph.add_vertex(Vertex::new(key.to_string()));
+            }
+        }
+        graph.add_edge(Edge::new(from.to_string(), to.to_string(), weight)).unwrap();
+    }
+    graph
+}
@@ -0,0 +1,211 @@
+use crate::graph::index::GraphIndex;
+use crate::graph::path::Path;
+use crate::graph::{Graph, GraphError};
+
+/// Returns the total weight of the arcs from every vertex to every other vertex, ignoring self-loops.
+fn preferences(index: &GraphIndex) -> Vec<Vec<f64>> {
+    let n: usize = index.len();
+    let mut preference: Vec<Vec<f64>> = vec![vec![0.0; n]; n];
+    for (u, neighbors) in index.adjacency.iter().enumerate() {
+        for &(v, weight) in neighbors {
+            if u != v {
+                preference[u][v] += f64::from(weight);
+            }
+        }
+    }
+    preference
+}
+
+/// Builds the "beats" relation of a tournament, or an error naming the first offending pair.
+fn tournament_relation(graph: &Graph) -> Result<(GraphIndex, Vec<Vec<bool>>), GraphError> {
+    if !graph.directed {
+        return Err(GraphError::NeedsDirected("Tournaments".to_string()));
+    }
+    let index: GraphIndex = graph.index();
+    let n: usize = index.len();
+    let mut beats: Vec<Vec<bool>> = vec![vec![false; n]; n];
+    for (u, neighbors) in index.adjacency.iter().enumerate() {
+        for &(v, _) in neighbors {
+            if u == v {
+                return Err(GraphError::SelfLoop(index.keys[u].clone()));
+            }
+            if beats[u][v] {
+                return Err(GraphError::DuplicateEdge(index.keys[u].clone(), index.keys[v].clone()));
+            }
+            beats[u][v] = true;
+        }
+    }
+    let missing = (0..n).flat_map(|u| ((u + 1)..n).map(move |v| (u, v))).find(|&(u, v)| beats[u][v] == beats[v][u]);
+    if let Some((u, v)) = missing {
+        return Err(GraphError::InvalidArgument(format!("Vertices {} and {} must be joined by exactly one arc", index.keys[u], index.keys[v])));
+    }
+    Ok((index, beats))
+}
+
+/// Builds a Hamiltonian path of a tournament by binary insertion.
+fn hamiltonian_path(beats: &[Vec<bool>]) -> Vec<usize> {
+    let mut path: Vec<usize> = Vec::with_capacity(beats.len());
+    for (v, row) in beats.iter().enumerate() {
+        if path.is_empty() || row[path[0]] {
+            path.insert(0, v);
+            continue;
+        }
+        if beats[path[path.len() - 1]][v] {
+            path.push(v);
+            continue;
+        }
+        // `path[low]` beats `v` and `v` beats `path[high]`, so `v` fits somewhere in between.
+        let (mut low, mut high) = (0, path.len() - 1);
+        while high - low > 1 {
+            let middle: usize = (low + high) / 2;
+            if beats[path[middle]][v] {
+                low = middle;
+            } else {
+                high = middle;
+            }
+        }
+        path.insert(high, v);
+    }
+    path
+}
+
+impl Graph {
+    /// Checks whether the graph is a tournament.
+    ///
+    /// A tournament is a directed graph without self-loops in which every pair of distinct
+    /// vertices is joined by exactly one arc.
+    ///
+    /// # Returns
+    ///
+    /// * A `bool` that is `true` if the graph is a tournament.
+    pub fn is_tournament(&self) -> bool {
+        tournament_relation(self).is_ok()
+    }
+
+    /// Builds a Hamiltonian path of a tournament, where every vertex beats the next one.
+    ///
+    /// Every tournament has one (Rédei's theorem). Vertices are inserted one at a time with a
+    /// binary search over the current path, so `O(n log n)` comparisons are made.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Path, GraphError>` - The path through every vertex, or an error if the graph is not
+    ///   a tournament.
+    pub fn tournament_hamiltonian_path(&self) -> Result<Path, GraphError> {
+        let (index, beats) = tournament_relation(self)?;
+        Ok(Path::through(&index, &hamiltonian_path(&beats), |_, _, _| true))
+    }
+
+    /// Finds a directed cycle in a tournament.
+    ///
+    /// A tournament is acyclic exactly when it is transitive, and any cycle can be shortened
+    /// to a triangle. Starting from a back arc of the Hamiltonian path, the cycle is shortened
+    /// one vertex at a time until three remain.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<Vec<String>>, GraphError>` - A directed triangle `[a, b, c]` with arcs
+    ///   `a -> b -> c -> a`, `None` if the tournament is transitive, or an error if the graph is
+    ///   not a tournament.
+    pub fn tournament_cycle(&self) -> Result<Option<Vec<String>>, GraphError> {
+        let (index, beats) = tournament_relation(self)?;
+        let path: Vec<usize> = hamiltonian_path(&beats);
+        let n: usize = path.len();
+        let back = (0..n).flat_map(|i| ((i + 2)..n).map(move |j| (i, j))).find(|&(i, j)| beats[path[j]][path[i]]);
+        let (i, j) = match back {
+            Some(arc) => arc,
+            None => return Ok(None),
+        };
+
+        let mut cycle: Vec<usize> = path[i..=j].to_vec();
+        while cycle.len() > 3 && !beats[cycle[2]][cycle[0]] {
+            cycle.remove(1);
+        }
+        Ok(Some(cycle[..3].iter().map(|&v| index.keys[v].clone()).collect()))
+    }
+
+    /// Ranks the vertices of a comparison graph by their Copeland score.
+    ///
+    /// An arc `u -> v` records that `u` was preferred over `v`, with its weight as the strength
+    /// or count of that preference. For every pair, the vertex with the larger total weight
+    /// against the other wins a point; a tie gives each half a point.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<(String, f32)>, GraphError>` - Every vertex with its score, best first (ties
+    ///   by key), or an error if the graph is undirected.
+    pub fn copeland_ranking(&self) -> Result<Vec<(String, f32)>, GraphError> {
+        if !self.directed {
+            return Err(GraphError::NeedsDirected("Comparison graphs".to_string()));
+        }
+        let index: GraphIndex = self.index();
+        let preference: Vec<Vec<f64>> = preferences(&index);
+        let n: usize = index.len();
+        let mut ranking: Vec<(String, f32)> = (0..n)
+            .map(|u| {
+                let score: f32 = (0..n)
+                    .filter(|&v| v != u)
+                    .map(|v| match preference[u][v].total_cmp(&preference[v][u]) {
+                        std::cmp::Ordering::Greater => 1.0,
+                        std::cmp::Ordering::Equal => 0.5,
+                        std::cmp::Ordering::Less => 0.0,
+                    })
+                    .sum();
+                (index.keys[u].clone(), score)
+            })
+            .collect();
+        ranking.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        Ok(ranking)
+    }
+
+    /// Approximates a Kemeny ranking of a comparison graph.
+    ///
+    /// A Kemeny ranking minimizes the total weight of arcs that point backwards, i.e. from a
+    /// vertex to one ranked above it. Finding one is NP-hard, so the Copeland ranking is
+    /// improved by moving single vertices to better positions until no move helps.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(Vec<String>, f32), GraphError>` - The ranking, best first, and the total weight
+    ///   of the preferences it violates, or an error if the graph is undirected.
+    pub fn kemeny_ranking(&self) -> Result<(Vec<String>, f32), GraphError> {
+        let copeland: Vec<(String, f32)> = self.copeland_ranking()?;
+        let index: GraphIndex = self.index();
+        let preference: Vec<Vec<f64>> = preferences(&index);
+        let mut order: Vec<usize> = copeland.iter().map(|(key, _)| index.positions[key]).collect();
+        let n: usize = order.len();
+
+        loop {
+            let mut best: (f64, usize, usize) = (0.0, 0, 0);
+            for from in 0..n {
+                let v: usize = order[from];
+                // Moving `v` past a vertex `w` trades the violated weight of one order for the other.
+                let mut change: f64 = 0.0;
+                for (to, &w) in order.iter().enumerate().skip(from + 1) {
+                    change += preference[v][w] - preference[w][v];
+                    if change < best.0 - 1e-9 {
+                        best = (change, from, to);
+                    }
+                }
+                change = 0.0;
+                for (to, &w) in order.iter().enumerate().take(from).rev() {
+                    change += preference[w][v] - preference[v][w];
+                    if change < best.0 - 1e-9 {
+                        best = (change, from, to);
+                    }
+                }
+            }
+            if best.0 >= 0.0 {
+                break;
+            }
+            let v: usize = order.remove(best.1);
+            order.insert(best.2, v);
+        }
+
+        let violated: f64 = (0..n)
+            .flat_map(|i| ((i + 1)..n).map(move |j| (i, j)))
+            .map(|(i, j)| preference[order[j]][order[i]])
+            .sum();
+        Ok((order.into_iter().map(|v| index.keys[v].clone()).collect(), violated as f32))
+    }
+}
@@ -0,0 +1,249 @@
+use crate::graph::control::ExecutionControl;
+use crate::graph::index::GraphIndex;
+use crate::graph::path::Path;
+use crate::graph::{Edge, Graph, GraphError};
+
+/// The edges of a graph numbered for Hierholzer's algorithm, with the edges each vertex can
+/// leave by.
+struct Trails {
+    /// Every edge once as `(from, to, forward weight, backward weight)`.
+    edges: Vec<(usize, usize, f32, f32)>,
+    /// The edges leaving every vertex as `(edge, other endpoint)`; an undirected edge is
+    /// listed under both endpoints, and a self-loop once.
+    leaving: Vec<Vec<(usize, usize)>>,
+    /// How many edge ends enter every vertex.
+    in_degree: Vec<usize>,
+}
+
+impl Trails {
+    fn new(graph: &Graph, index: &GraphIndex) -> Result<Trails, GraphError> {
+        let n: usize = index.len();
+        let mut trails: Trails = Trails { edges: Vec::new(), leaving: vec![Vec::new(); n], in_degree: vec![0; n] };
+        let stored: Vec<&Edge> = graph.stored_edges();
+        for edge in stored {
+            let (u, v) = (index.position(&edge.vertex1)?, index.position(&edge.vertex2)?);
+            let id: usize = trails.edges.len();
+            trails.edges.push((u, v, edge.weight_from(edge.vertex1.as_str()), edge.weight_from(edge.vertex2.as_str())));
+            trails.leaving[u].push((id, v));
+            trails.in_degree[v] += 1;
+            if !graph.directed && u != v {
+                trails.leaving[v].push((id, u));
+                trails.in_degree[u] += 1;
+            }
+        }
+        Ok(trails)
+    }
+
+    /// Returns the number of edge ends at `v` in an undirected graph, counting a self-loop
+    /// twice.
+    fn degree(&self, v: usize) -> usize {
+        self.leaving[v].iter().map(|&(_, w)| if w == v { 2 } else { 1 }).sum()
+    }
+
+    /// Walks every edge once from `start` with Hierholzer's algorithm, returning the vertices
+    /// and edges in travel order, or `None` if some edges cannot be reached.
+    fn walk(&self, start: usize) -> Option<(Vec<usize>, Vec<usize>)> {
+        let mut used: Vec<bool> = vec![false; self.edges.len()];
+        let mut next: Vec<usize> = vec![0; self.leaving.len()];
+        // The current trail as `(vertex, edge taken into it)`.
+        let mut stack: Vec<(usize, Option<usize>)> = vec![(start, None)];
+        let mut vertices: Vec<usize> = Vec::with_capacity(self.edges.len() + 1);
+        let mut edges: Vec<usize> = Vec::with_capacity(self.edges.len());
+        while let Some(&(v, via)) = stack.last() {
+            while next[v] < self.leaving[v].len() && used[self.leaving[v][next[v]].0] {
+                next[v] += 1;
+            }
+            match self.leaving[v].get(next[v]) {
+                Some(&(edge, w)) => {
+                    used[edge] = true;
+                    stack.push((w, Some(edge)));
+                }
+                None => {
+                    stack.pop();
+                    vertices.push(v);
+                    edges.extend(via);
+                }
+            }
+        }
+        if edges.len() != self.edges.len() {
+            return None;
+        }
+        vertices.reverse();
+        edges.reverse();
+        Some((vertices, edges))
+    }
+
+    /// Builds the `Path` for a walk, taking each edge's weight in the direction travelled.
+    fn path(&self, index: &GraphIndex, vertices: &[usize], edges: &[usize]) -> Path {
+        let mut path: Path = Path::new(&index.keys[vertices[0]]);
+        for (step, &edge) in vertices.windows(2).zip(edges) {
+            let (u, _, forward, backward) = self.edges[edge];
+            path.push(&index.keys[step[1]], if step[0] == u { forward } else { backward });
+        }
+        path
+    }
+}
+
+/// Extends `cycle` to a Hamiltonian cycle by depth-first search, in ascending key order.
+fn extend_cycle(neighbors: &[Vec<usize>], cycle: &mut Vec<usize>, on_cycle: &mut [bool], control: &ExecutionControl, expanded: &mut usize) -> Result<bool, GraphError> {
+    *expanded += 1;
+    control.step(*expanded, None)?;
+    let last: usize = cycle[cycle.len() - 1];
+    if cycle.len() == neighbors.len() {
+        return Ok(neighbors[last].contains(&cycle[0]));
+    }
+    for &next in &neighbors[last] {
+        if on_cycle[next] {
+            continue;
+        }
+        cycle.push(next);
+        on_cycle[next] = true;
+        if extend_cycle(neighbors, cycle, on_cycle, control, expanded)? {
+            return Ok(true);
+        }
+        cycle.pop();
+        on_cycle[next] = false;
+    }
+    Ok(false)
+}
+
+impl Graph {
+    /// Finds a walk that uses every edge exactly once (an Eulerian path), with Hierholzer's
+    /// algorithm.
+    ///
+    /// An undirected graph has one when at most two vertices have odd degree, and a directed
+    /// graph when at most one vertex has one more outgoing than incoming edge and at most one
+    /// the reverse; in both cases every edge must be reachable. The walk starts at an
+    /// odd-degree (or surplus) vertex when there is one, and otherwise at the vertex with the
+    /// smallest key that has edges, in which case it is also a circuit. Parallel edges and
+    /// self-loops are each walked once, in linear time.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<Path>, GraphError>` - The walk, with each edge in the direction
+    ///   travelled and its weight for that direction, `None` if there is none, or an error if
+    ///   the graph has no vertices.
+    pub fn eulerian_path(&self) -> Result<Option<Path>, GraphError> {
+        self.eulerian_walk(false)
+    }
+
+    /// Finds a closed walk that uses every edge exactly once (an Eulerian circuit), with
+    /// Hierholzer's algorithm.
+    ///
+    /// An undirected graph has one when every vertex has even degree, and a directed graph
+    /// when every vertex has as many incoming as outgoing edges; in both cases every edge
+    /// must be reachable. The circuit starts and ends at the vertex with the smallest key
+    /// that has edges.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<Path>, GraphError>` - The circuit, with each edge in the direction
+    ///   travelled and its weight for that direction, `None` if there is none, or an error if
+    ///   the graph has no vertices.
+    pub fn eulerian_circuit(&self) -> Result<Option<Path>, GraphError> {
+        self.eulerian_walk(true)
+    }
+
+    /// Runs `eulerian_path` or, if `closed`, `eulerian_circuit`.
+    fn eulerian_walk(&self, closed: bool) -> Result<Option<Path>, GraphError> {
+        if self.vertices.is_empty() {
+            return Err(GraphError::EmptyGraph);
+        }
+        let index: GraphIndex = self.index();
+        let trails: Trails = Trails::new(self, &index)?;
+        let n: usize = index.len();
+
+        // The vertices a walk must start at, and how many must end one.
+        let (starts, ends): (Vec<usize>, usize) = if self.directed {
+            let out_degree = |v: usize| trails.leaving[v].len();
+            if (0..n).any(|v| out_degree(v).abs_diff(trails.in_degree[v]) > 1) {
+                return Ok(None);
+            }
+            let starts: Vec<usize> = (0..n).filter(|&v| out_degree(v) > trails.in_degree[v]).collect();
+            let ends: usize = (0..n).filter(|&v| trails.in_degree[v] > out_degree(v)).count();
+            (starts, ends)
+        } else {
+            let odd: Vec<usize> = (0..n).filter(|&v| trails.degree(v) % 2 == 1).collect();
+            let ends: usize = odd.len().saturating_sub(1);
+            (odd.into_iter().take(1).collect(), ends)
+        };
+        if starts.len() > 1 || ends > 1 || (closed && !starts.is_empty()) {
+            return Ok(None);
+        }
+
+        let start: usize = match starts.first() {
+            Some(&start) => start,
+            None => match (0..n).find(|&v| !trails.leaving[v].is_empty()) {
+                Some(start) => start,
+                // With no edges, the walk is a single vertex.
+                None => return Ok(Some(Path::new(&index.keys[0]))),
+            },
+        };
+        Ok(trails.walk(start).map(|(vertices, edges)| trails.path(&index, &vertices, &edges)))
+    }
+
+    /// Finds a cycle that visits every vertex exactly once (a Hamiltonian cycle) by
+    /// backtracking.
+    ///
+    /// The search can take time exponential in the number of vertices, so it is meant for
+    /// small graphs, such as puzzles; `hamiltonian_cycle_with` can stop it early. Self-loops
+    /// are ignored, and of parallel edges the lightest is used. An undirected cycle needs at
+    /// least three vertices and a directed one at least two.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<Path>, GraphError>` - The cycle, starting and ending at the vertex with
+    ///   the smallest key, `None` if there is none, or an error if the graph has no vertices.
+    pub fn hamiltonian_cycle(&self) -> Result<Option<Path>, GraphError> {
+        self.hamiltonian_cycle_with(&ExecutionControl::new())
+    }
+
+    /// Finds a Hamiltonian cycle as `hamiltonian_cycle` does, under a cancellation token,
+    /// deadline or progress callback.
+    ///
+    /// Progress is reported once per partial cycle tried, with no known total.
+    ///
+    /// # Arguments
+    ///
+    /// * `control` - The `ExecutionControl` to honor.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<Path>, GraphError>` - The cycle, `None` if there is none, or an error if
+    ///   the graph has no vertices or the search was cancelled or ran out of time.
+    pub fn hamiltonian_cycle_with(&self, control: &ExecutionControl) -> Result<Option<Path>, GraphError> {
+        if self.vertices.is_empty() {
+            return Err(GraphError::EmptyGraph);
+        }
+        let index: GraphIndex = self.index();
+        let n: usize = index.len();
+        if n < if self.directed { 2 } else { 3 } {
+            return Ok(None);
+        }
+        let neighbors: Vec<Vec<usize>> = index.adjacency.iter()
+            .enumerate()
+            .map(|(u, list)| {
+                let mut distinct: Vec<usize> = list.iter().map(|&(v, _)| v).filter(|&v| v != u).collect();
+                distinct.sort_unstable();
+                distinct.dedup();
+                distinct
+            })
+            .collect();
+        // Every vertex must be left and entered by the cycle.
+        let mut entered: Vec<bool> = vec![false; n];
+        neighbors.iter().flatten().for_each(|&v| entered[v] = true);
+        if neighbors.iter().any(|list| list.is_empty() || (!self.directed && list.len() < 2)) || entered.contains(&false) {
+            return Ok(None);
+        }
+
+        let mut cycle: Vec<usize> = vec![0];
+        let mut on_cycle: Vec<bool> = vec![false; n];
+        on_cycle[0] = true;
+        let mut expanded: usize = 0;
+        if !extend_cycle(&neighbors, &mut cycle, &mut on_cycle, control, &mut expanded)? {
+            return Ok(None);
+        }
+        cycle.push(0);
+        Ok(Some(Path::through(&index, &cycle, |_, _, _| true)))
+    }
+}
@@ -0,0 +1,29 @@
+//! Instrumentation reported through `tracing` when the `tracing` feature is enabled.
+//!
+//! Without the feature the macros compile to nothing beyond borrowing their field values, so
+//! counters kept only for them are not reported as unused.
+
+/// Enters a `debug` span named `$name` with the given fields for the rest of the enclosing
+/// block.
+macro_rules! trace_span {
+    ($name:literal $(, $field:ident = $value:expr)* $(,)?) => {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!($name $(, $field = $value)*).entered();
+        #[cfg(not(feature = "tracing"))]
+        $(let _ = &$value;)*
+    };
+}
+
+/// Records a `debug` event with the given counters in the current span.
+macro_rules! trace_counts {
+    ($message:literal $(, $field:ident = $value:expr)* $(,)?) => {
+        #[cfg(feature = "tracing")]
+        tracing::debug!($($field = $value,)* $message);
+        #[cfg(not(feature = "tracing"))]
+        {
+            $(let _ = &$value;)*
+        }
+    };
+}
+
+pub(crate) use {trace_counts, trace_span};
@@ -0,0 +1,350 @@
+use crate::graph::index::GraphIndex;
+use crate::graph::path::{dijkstra, reject_negative_weights};
+use crate::graph::{AttrValue, Edge, Graph, GraphError, Vertex};
+use std::collections::{HashSet, VecDeque};
+
+/// Returns the key of the endpoint of `edge` opposite to `from`.
+fn neighbor<'a>(edge: &'a Edge, from: &str) -> &'a str {
+    if edge.vertex1 == from {
+        &edge.vertex2
+    } else {
+        &edge.vertex1
+    }
+}
+
+/// Looks up the stored key of `start`, or returns an error if the vertex does not exist.
+fn root<'a>(graph: &'a Graph, start: &str) -> Result<&'a str, GraphError> {
+    graph.vertices.get_key_value(start)
+        .map(|(key, _)| key.as_str())
+        .ok_or_else(|| GraphError::VertexNotFound(start.to_string()))
+}
+
+/// Builds a search tree as a directed graph of the reached vertices, with an edge from each
+/// parent to its child carrying the weight of the lightest edge between them.
+///
+/// Every vertex stores `label(v)` under the attribute `name`, and every vertex but the root
+/// stores the key of its parent under `parent`.
+fn search_tree<L>(index: &GraphIndex, parents: &[Option<usize>], order: &[usize], name: &str, label: L) -> Graph
+where
+    L: Fn(usize) -> AttrValue,
+{
+    let mut tree: Graph = Graph::new(true);
+    for &v in order {
+        let mut vertex: Vertex = Vertex::new(index.keys[v].clone());
+        vertex.set_attr(name, label(v));
+        if let Some(p) = parents[v] {
+            vertex.set_attr("parent", AttrValue::Text(index.keys[p].clone()));
+        }
+        tree.add_vertex(vertex);
+    }
+    for &v in order {
+        if let Some(p) = parents[v] {
+            // Of several parallel edges, the lightest is the one a shortest path takes.
+            let weight: f32 = index.adjacency[p].iter()
+                .filter(|&&(w, _)| w == v)
+                .map(|&(_, weight)| weight)
+                .fold(f32::INFINITY, f32::min);
+            tree.add_edge(Edge::new(index.keys[p].clone(), index.keys[v].clone(), weight)).unwrap();
+        }
+    }
+    tree
+}
+
+/// A lazy breadth-first traversal, created by `Graph::bfs`.
+///
+/// Yields the key of every vertex reachable from the start, nearest first. Neighbors are
+/// visited in the order their edges were added. The traversal keeps its state between
+/// calls, so it can be stopped early, inspected with `depth` and `is_visited`, and continued
+/// from further start vertices with `resume`.
+pub struct Bfs<'a> {
+    graph: &'a Graph,
+    /// Discovered vertices waiting to be yielded, with their depth.
+    queue: VecDeque<(&'a str, usize)>,
+    visited: HashSet<&'a str>,
+    /// Start vertices still to be traversed once the queue runs dry.
+    roots: VecDeque<&'a str>,
+    /// The depth of the most recently yielded vertex.
+    depth: Option<usize>,
+}
+
+impl<'a> Bfs<'a> {
+    /// Queues another start vertex, traversed once everything reachable so far is done.
+    ///
+    /// Vertices already visited are not yielded again, so resuming from every vertex in
+    /// turn visits each connected part of the graph once.
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - The key of the vertex to continue from.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), GraphError>` - An error if the vertex does not exist.
+    pub fn resume(&mut self, start: &str) -> Result<(), GraphError> {
+        self.roots.push_back(root(self.graph, start)?);
+        Ok(())
+    }
+
+    /// Returns the number of edges between the most recently yielded vertex and the start it
+    /// was reached from, or `None` before the first vertex.
+    pub fn depth(&self) -> Option<usize> {
+        self.depth
+    }
+
+    /// Returns whether the vertex has been discovered, including vertices queued but not yet yielded.
+    pub fn is_visited(&self, key: &str) -> bool {
+        self.visited.contains(key)
+    }
+}
+
+impl<'a> Iterator for Bfs<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        while self.queue.is_empty() {
+            let start: &'a str = self.roots.pop_front()?;
+            if self.visited.insert(start) {
+                self.queue.push_back((start, 0));
+            }
+        }
+        let (current, depth) = self.queue.pop_front()?;
+        for edge in &self.graph.vertices[current].edges {
+            let next: &'a str = neighbor(edge, current);
+            if self.graph.vertices.contains_key(next) && self.visited.insert(next) {
+                self.queue.push_back((next, depth + 1));
+            }
+        }
+        self.depth = Some(depth);
+        Some(current)
+    }
+}
+
+/// A lazy depth-first (preorder) traversal, created by `Graph::dfs`.
+///
+/// Yields the key of every vertex reachable from the start in the order a recursive
+/// depth-first search would first reach it. Neighbors are tried in the order their edges
+/// were added. The traversal keeps its state between calls, so it can be stopped early,
+/// pruned with `skip_subtree`, and continued from further start vertices with `resume`.
+pub struct Dfs<'a> {
+    graph: &'a Graph,
+    /// The vertices on the current search path, each with the index of its next edge to try.
+    stack: Vec<(&'a Vertex, usize)>,
+    visited: HashSet<&'a str>,
+    /// Start vertices still to be traversed once the stack runs dry.
+    roots: VecDeque<&'a str>,
+    /// Whether the top of the stack is the most recently yielded vertex, not yet explored.
+    fresh: bool,
+}
+
+impl<'a> Dfs<'a> {
+    /// Queues another start vertex, traversed once everything reachable so far is done.
+    ///
+    /// Vertices already visited are not yielded again, so resuming from every vertex in
+    /// turn visits each connected part of the graph once.
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - The key of the vertex to continue from.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), GraphError>` - An error if the vertex does not exist.
+    pub fn resume(&mut self, start: &str) -> Result<(), GraphError> {
+        self.roots.push_back(root(self.graph, start)?);
+        Ok(())
+    }
+
+    /// Stops the traversal from descending below the most recently yielded vertex.
+    ///
+    /// Its unvisited neighbors may still be reached later through other vertices. Has no
+    /// effect once the traversal has moved on from that vertex.
+    pub fn skip_subtree(&mut self) {
+        if self.fresh {
+            self.stack.pop();
+            self.fresh = false;
+        }
+    }
+
+    /// Returns whether the vertex has already been yielded.
+    pub fn is_visited(&self, key: &str) -> bool {
+        self.visited.contains(key)
+    }
+}
+
+impl<'a> Iterator for Dfs<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        self.fresh = false;
+        loop {
+            let Some(&mut (vertex, ref mut next_edge)) = self.stack.last_mut() else {
+                let start: &'a str = self.roots.pop_front()?;
+                if self.visited.insert(start) {
+                    self.stack.push((&self.graph.vertices[start], 0));
+                    self.fresh = true;
+                    return Some(start);
+                }
+                continue;
+            };
+            if *next_edge == vertex.edges.len() {
+                self.stack.pop();
+                continue;
+            }
+            let next: &'a str = neighbor(&vertex.edges[*next_edge], &vertex.value);
+            *next_edge += 1;
+            if let Some(found) = self.graph.vertices.get(next) {
+                if self.visited.insert(next) {
+                    self.stack.push((found, 0));
+                    self.fresh = true;
+                    return Some(next);
+                }
+            }
+        }
+    }
+}
+
+impl Graph {
+    /// Traverses the graph breadth-first from `start`.
+    ///
+    /// The traversal is lazy: vertices are discovered only as the iterator is advanced, so it
+    /// can be stopped early or combined with iterator adapters. Edge direction is respected.
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - The key of the vertex to start from.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Bfs, GraphError>` - An iterator over the keys of the reachable vertices, or an
+    ///   error if `start` does not exist.
+    pub fn bfs(&self, start: &str) -> Result<Bfs<'_>, GraphError> {
+        Ok(Bfs {
+            graph: self,
+            queue: VecDeque::new(),
+            visited: HashSet::new(),
+            roots: VecDeque::from([root(self, start)?]),
+            depth: None,
+        })
+    }
+
+    /// Traverses the graph depth-first from `start`.
+    ///
+    /// The traversal is lazy: vertices are discovered only as the iterator is advanced, so it
+    /// can be stopped early or combined with iterator adapters. Edge direction is respected.
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - The key of the vertex to start from.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Dfs, GraphError>` - An iterator over the keys of the reachable vertices in
+    ///   preorder, or an error if `start` does not exist.
+    pub fn dfs(&self, start: &str) -> Result<Dfs<'_>, GraphError> {
+        Ok(Dfs {
+            graph: self,
+            stack: Vec::new(),
+            visited: HashSet::new(),
+            roots: VecDeque::from([root(self, start)?]),
+            fresh: false,
+        })
+    }
+
+    /// Returns the breadth-first search tree from `start` as a graph.
+    ///
+    /// The tree is a directed graph with an edge from every reached vertex's parent to it,
+    /// keeping the weight of the edge between them. Every vertex stores its hop count
+    /// from `start` in the `depth` attribute and, except `start`, its parent's key in `parent`.
+    /// Parents are chosen as `bfs` discovers the vertices.
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - The key of the root.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Graph, GraphError>` - The tree, or an error if `start` does not exist.
+    pub fn bfs_tree(&self, start: &str) -> Result<Graph, GraphError> {
+        let index: GraphIndex = self.index();
+        let source: usize = index.position(start)?;
+        let mut parents: Vec<Option<usize>> = vec![None; index.len()];
+        let mut depth: Vec<usize> = vec![usize::MAX; index.len()];
+        depth[source] = 0;
+        let mut order: Vec<usize> = vec![source];
+        let mut next: usize = 0;
+        while next < order.len() {
+            let u: usize = order[next];
+            next += 1;
+            for &(v, _) in &index.adjacency[u] {
+                if depth[v] == usize::MAX {
+                    depth[v] = depth[u] + 1;
+                    parents[v] = Some(u);
+                    order.push(v);
+                }
+            }
+        }
+        Ok(search_tree(&index, &parents, &order, "depth", |v| AttrValue::Int(depth[v] as i64)))
+    }
+
+    /// Returns the depth-first search tree from `start` as a graph.
+    ///
+    /// Built as `bfs_tree` is, following the preorder of `dfs`; the `depth` attribute is the
+    /// vertex's depth in this tree, which can exceed its hop distance from `start`.
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - The key of the root.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Graph, GraphError>` - The tree, or an error if `start` does not exist.
+    pub fn dfs_tree(&self, start: &str) -> Result<Graph, GraphError> {
+        let index: GraphIndex = self.index();
+        let source: usize = index.position(start)?;
+        let mut parents: Vec<Option<usize>> = vec![None; index.len()];
+        let mut depth: Vec<usize> = vec![usize::MAX; index.len()];
+        depth[source] = 0;
+        let mut order: Vec<usize> = vec![source];
+        let mut stack: Vec<(usize, usize)> = vec![(source, 0)];
+        while let Some((u, next_edge)) = stack.last_mut() {
+            let u: usize = *u;
+            let Some(&(v, _)) = index.adjacency[u].get(*next_edge) else {
+                stack.pop();
+                continue;
+            };
+            *next_edge += 1;
+            if depth[v] == usize::MAX {
+                depth[v] = depth[u] + 1;
+                parents[v] = Some(u);
+                order.push(v);
+                stack.push((v, 0));
+            }
+        }
+        Ok(search_tree(&index, &parents, &order, "depth", |v| AttrValue::Int(depth[v] as i64)))
+    }
+
+    /// Returns the tree of shortest paths from `source` as a graph.
+    ///
+    /// The tree is a directed graph in which the path from `source` to every reachable vertex
+    /// is a shortest path of this graph, found with Dijkstra's algorithm. Every vertex stores
+    /// its distance from `source` in the `distance` attribute and, except `source`, its
+    /// parent's key in `parent`.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - The key of the root.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Graph, GraphError>` - The tree, or an error if `source` does not exist or an
+    ///   edge weight is negative.
+    pub fn shortest_path_tree(&self, source: &str) -> Result<Graph, GraphError> {
+        self.require_distances("Shortest-path trees")?;
+        let index: GraphIndex = self.index();
+        let origin: usize = index.position(source)?;
+        reject_negative_weights(&index)?;
+        let (distances, parents) = dijkstra(&index, origin, |_| true, |_, _, _| true);
+        let order: Vec<usize> = (0..index.len()).filter(|&v| distances[v].is_finite()).collect();
+        Ok(search_tree(&index, &parents, &order, "distance", |v| AttrValue::Float(f64::from(distances[v]))))
+    }
+}
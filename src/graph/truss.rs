@@ -0,0 +1,113 @@
+use crate::graph::index::{GraphIndex, IndexedEdge};
+use crate::graph::{Edge, Graph, GraphError, Vertex};
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+/// The distinct non-loop edges of an undirected graph with the first weight seen for each.
+fn simple_edges(graph: &Graph) -> Result<(GraphIndex, Vec<IndexedEdge>), GraphError> {
+    if graph.directed {
+        return Err(GraphError::NeedsUndirected("Truss decomposition".to_string()));
+    }
+    let index: GraphIndex = graph.index();
+    let mut seen: HashSet<(usize, usize)> = HashSet::new();
+    let mut edges: Vec<IndexedEdge> = Vec::new();
+    for (u, neighbors) in index.adjacency.iter().enumerate() {
+        for &(v, weight) in neighbors {
+            if u < v && seen.insert((u, v)) {
+                edges.push((u, v, weight));
+            }
+        }
+    }
+    Ok((index, edges))
+}
+
+/// Computes the truss number of every edge by support peeling.
+fn truss_numbers(n: usize, edges: &[IndexedEdge]) -> Vec<usize> {
+    let mut ids: HashMap<(usize, usize), usize> = HashMap::new();
+    let mut neighbors: Vec<HashSet<usize>> = vec![HashSet::new(); n];
+    for (e, &(u, v, _)) in edges.iter().enumerate() {
+        ids.insert((u, v), e);
+        neighbors[u].insert(v);
+        neighbors[v].insert(u);
+    }
+    let id = |a: usize, b: usize| ids[&(a.min(b), a.max(b))];
+
+    let mut support: Vec<usize> = edges.iter()
+        .map(|&(u, v, _)| neighbors[u].intersection(&neighbors[v]).count())
+        .collect();
+    let mut queue: BTreeSet<(usize, usize)> = support.iter().enumerate().map(|(e, &s)| (s, e)).collect();
+    let mut truss: Vec<usize> = vec![0; edges.len()];
+    let mut k: usize = 2;
+
+    while let Some(&(s, e)) = queue.first() {
+        if s + 2 > k {
+            k = s + 2;
+            continue;
+        }
+        queue.remove(&(s, e));
+        truss[e] = k;
+        let (u, v, _) = edges[e];
+        let common: Vec<usize> = neighbors[u].intersection(&neighbors[v]).copied().collect();
+        for w in common {
+            for other in [id(u, w), id(v, w)] {
+                if queue.remove(&(support[other], other)) {
+                    support[other] -= 1;
+                    queue.insert((support[other], other));
+                }
+            }
+        }
+        neighbors[u].remove(&v);
+        neighbors[v].remove(&u);
+    }
+    truss
+}
+
+impl Graph {
+    /// Computes the truss number of every edge of an undirected graph.
+    ///
+    /// An edge has truss number `k` when it belongs to the `k`-truss (the largest subgraph
+    /// in which every edge lies on at least `k - 2` triangles) but not to the `(k + 1)`-truss.
+    /// Parallel edges are treated as one edge and self-loops are ignored.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<HashMap<(String, String), usize>, GraphError>` - The truss number keyed by the edge's
+    ///   endpoints in ascending key order, or an error if the graph is directed.
+    pub fn truss_decomposition(&self) -> Result<HashMap<(String, String), usize>, GraphError> {
+        let (index, edges) = simple_edges(self)?;
+        let truss: Vec<usize> = truss_numbers(index.len(), &edges);
+        Ok(edges.iter()
+            .zip(truss)
+            .map(|(&(u, v, _), k)| ((index.keys[u].clone(), index.keys[v].clone()), k))
+            .collect())
+    }
+
+    /// Extracts the maximal `k`-truss of an undirected graph as a new graph.
+    ///
+    /// # Arguments
+    ///
+    /// * `k` - The truss order; every edge of the result lies on at least `k - 2` triangles.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Graph, GraphError>` - The `k`-truss with the vertices it touches, or an error if the
+    ///   graph is directed.
+    pub fn k_truss(&self, k: usize) -> Result<Graph, GraphError> {
+        let (index, edges) = simple_edges(self)?;
+        let truss: Vec<usize> = truss_numbers(index.len(), &edges);
+
+        let mut result: Graph = Graph::new(false);
+        for (&(u, v, weight), t) in edges.iter().zip(truss) {
+            if t < k {
+                continue;
+            }
+            for key in [&index.keys[u], &index.keys[v]] {
+                if !result.vertices.contains_key(key) {
+                    result.add_vertex(Vertex::new(key.clone()));
+                }
+            }
+            let edge: Edge = Edge::new(index.keys[u].clone(), index.keys[v].clone(), weight);
+            result.add_edge(edge)?;
+        }
+        Ok(result)
+    }
+}
@@ -1,13 +1,18 @@
-use crate::graph::Edge;
+use crate::graph::{AttrValue, Edge};
+use std::collections::HashMap;
 
 /// A struct representing a vertex in a graph.
 /// Each vertex has a `value` of type `f32` and a list of `edges` that connect it to other vertices.
 #[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Vertex {
     /// The value of the vertex.
     pub value: String,
     /// The list of edges that connect this vertex to other vertices.
     pub edges: Vec<Edge>,
+    /// Named metadata attached to the vertex.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub attributes: HashMap<String, AttrValue>,
 }
 
 
@@ -20,11 +25,12 @@ impl Vertex {
     ///
     /// # Returns
     ///
-    /// * A `Vertex` instance with an empty list of edges.
+    /// * A `Vertex` instance with an empty list of edges and no attributes.
     pub fn new(value: String) -> Vertex {
         Vertex {
             value,
             edges: Vec::new(),
+            attributes: HashMap::new(),
         }
     }
 
@@ -36,4 +42,27 @@ impl Vertex {
     pub fn add_edge(&mut self, edge: Edge) {
         self.edges.push(edge);
     }
+
+    /// Sets an attribute of the vertex, replacing any previous value under the same name.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The name of the attribute.
+    /// * `value` - The value to store.
+    pub fn set_attr(&mut self, key: &str, value: AttrValue) {
+        self.attributes.insert(key.to_string(), value);
+    }
+
+    /// Returns an attribute of the vertex.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The name of the attribute.
+    ///
+    /// # Returns
+    ///
+    /// * An `Option<&AttrValue>` with the value, or `None` if the attribute is not set.
+    pub fn attr(&self, key: &str) -> Option<&AttrValue> {
+        self.attributes.get(key)
+    }
 }
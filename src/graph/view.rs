@@ -0,0 +1,174 @@
+use crate::graph::path::{Path, State};
+use crate::graph::{Graph, GraphError};
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+
+/// Read-only access to a graph's adjacency, shared by graphs held in memory and graphs
+/// served from elsewhere, such as a `MappedGraph`.
+///
+/// The functions in this module run over any `GraphView`, so the same traversal answers
+/// queries on a `Graph` and on a file too large to load. Methods return a `Result` because
+/// a view backed by a file can find it damaged.
+pub trait GraphView {
+    /// Returns whether a vertex with the given key exists.
+    fn has_vertex(&self, key: &str) -> Result<bool, GraphError>;
+
+    /// Returns the vertices reached from `key` in one step, with the cost of each step, once
+    /// per edge, or an error if the vertex does not exist.
+    fn neighbors(&self, key: &str) -> Result<Vec<(String, f32)>, GraphError>;
+
+    /// Returns the number of steps `neighbors` lists for a vertex, its out-degree in a
+    /// directed graph, or an error if the vertex does not exist.
+    fn degree(&self, key: &str) -> Result<usize, GraphError>;
+}
+
+impl GraphView for Graph {
+    fn has_vertex(&self, key: &str) -> Result<bool, GraphError> {
+        Ok(Graph::has_vertex(self, key))
+    }
+
+    fn neighbors(&self, key: &str) -> Result<Vec<(String, f32)>, GraphError> {
+        let vertex = self.vertices.get(key).ok_or_else(|| GraphError::VertexNotFound(key.to_string()))?;
+        Ok(vertex.edges.iter()
+            .map(|edge| {
+                let neighbor: &String = if edge.vertex1 == key { &edge.vertex2 } else { &edge.vertex1 };
+                (neighbor.clone(), edge.weight_from(key))
+            })
+            .collect())
+    }
+
+    fn degree(&self, key: &str) -> Result<usize, GraphError> {
+        self.out_degree(key)
+    }
+}
+
+/// Traverses a graph breadth-first from `start`, respecting edge direction.
+///
+/// # Arguments
+///
+/// * `graph` - The graph to traverse.
+/// * `start` - The key of the vertex to start from.
+///
+/// # Returns
+///
+/// * `Result<Vec<String>, GraphError>` - The keys of the reachable vertices, nearest first,
+///   or an error if `start` does not exist or the view fails.
+pub fn bfs<G: GraphView + ?Sized>(graph: &G, start: &str) -> Result<Vec<String>, GraphError> {
+    if !graph.has_vertex(start)? {
+        return Err(GraphError::VertexNotFound(start.to_string()));
+    }
+    let mut visited: HashSet<String> = HashSet::from([start.to_string()]);
+    let mut queue: VecDeque<String> = VecDeque::from([start.to_string()]);
+    let mut order: Vec<String> = Vec::new();
+    while let Some(key) = queue.pop_front() {
+        for (next, _) in graph.neighbors(&key)? {
+            if visited.insert(next.clone()) {
+                queue.push_back(next);
+            }
+        }
+        order.push(key);
+    }
+    Ok(order)
+}
+
+/// Finds a shortest path with Dijkstra's algorithm, as `Graph::shortest_path` does.
+///
+/// Vertices are numbered as they are reached, so memory grows with the part of the graph
+/// the search explores rather than with the whole graph.
+///
+/// # Arguments
+///
+/// * `graph` - The graph to search.
+/// * `from` - The key of the start vertex.
+/// * `to` - The key of the target vertex.
+///
+/// # Returns
+///
+/// * `Result<Option<Path>, GraphError>` - The path, `None` if `to` is unreachable, or an
+///   error if a vertex does not exist, a weight is negative or the view fails.
+pub fn shortest_path<G: GraphView + ?Sized>(graph: &G, from: &str, to: &str) -> Result<Option<Path>, GraphError> {
+    for key in [from, to] {
+        if !graph.has_vertex(key)? {
+            return Err(GraphError::VertexNotFound(key.to_string()));
+        }
+    }
+    let mut keys: Vec<String> = vec![from.to_string()];
+    let mut ids: HashMap<String, usize> = HashMap::from([(from.to_string(), 0)]);
+    let mut distances: Vec<f32> = vec![0.0];
+    let mut predecessors: Vec<Option<(usize, f32)>> = vec![None];
+    let mut heap: BinaryHeap<State> = BinaryHeap::from([State { cost: 0.0, vertex: 0 }]);
+    let mut target: Option<usize> = None;
+    while let Some(State { cost, vertex }) = heap.pop() {
+        if keys[vertex] == to {
+            target = Some(vertex);
+            break;
+        }
+        if cost > distances[vertex] {
+            continue;
+        }
+        for (next, weight) in graph.neighbors(&keys[vertex])? {
+            if weight < 0.0 {
+                return Err(GraphError::NegativeWeight(keys[vertex].clone(), next));
+            }
+            let id: usize = match ids.get(&next) {
+                Some(&id) => id,
+                None => {
+                    ids.insert(next.clone(), keys.len());
+                    keys.push(next);
+                    distances.push(f32::INFINITY);
+                    predecessors.push(None);
+                    keys.len() - 1
+                }
+            };
+            if cost + weight < distances[id] {
+                distances[id] = cost + weight;
+                predecessors[id] = Some((vertex, weight));
+                heap.push(State { cost: cost + weight, vertex: id });
+            }
+        }
+    }
+    let Some(target) = target else {
+        return Ok(None);
+    };
+    let mut steps: Vec<(usize, f32)> = Vec::new();
+    let mut current: usize = target;
+    while let Some((previous, weight)) = predecessors[current] {
+        steps.push((current, weight));
+        current = previous;
+    }
+    let mut path: Path = Path::new(from);
+    for (v, weight) in steps.into_iter().rev() {
+        path.push(&keys[v], weight);
+    }
+    Ok(Some(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{bfs, shortest_path, GraphView};
+    use crate::graph::testing::graph_from;
+    use crate::graph::{Graph, GraphError};
+
+    #[test]
+    fn traversals_match_the_graph_methods() {
+        let graph: Graph = graph_from(true, &[("a", "b", 4.0), ("a", "c", 1.0), ("c", "b", 1.0), ("b", "d", 2.0), ("e", "a", 1.0)]);
+        assert_eq!(bfs(&graph, "a").unwrap(), graph.bfs("a").unwrap().collect::<Vec<&str>>());
+        let path = shortest_path(&graph, "a", "d").unwrap().unwrap();
+        assert_eq!(path.vertices, graph.shortest_path("a", "d").unwrap().unwrap().vertices);
+        assert_eq!(path.cost, 4.0);
+        assert!(shortest_path(&graph, "d", "a").unwrap().is_none());
+    }
+
+    #[test]
+    fn undirected_neighbors_and_degree() {
+        let graph: Graph = graph_from(false, &[("a", "b", 1.0), ("b", "c", 2.0), ("a", "b", 3.0)]);
+        assert_eq!(GraphView::neighbors(&graph, "b").unwrap(), vec![("a".to_string(), 1.0), ("c".to_string(), 2.0), ("a".to_string(), 3.0)]);
+        assert_eq!(GraphView::degree(&graph, "b").unwrap(), 3);
+        assert!(matches!(bfs(&graph, "z"), Err(GraphError::VertexNotFound(_))));
+    }
+
+    #[test]
+    fn negative_weights_are_rejected() {
+        let graph: Graph = graph_from(true, &[("a", "b", -1.0)]);
+        assert!(matches!(shortest_path(&graph, "a", "b"), Err(GraphError::NegativeWeight(_, _))));
+    }
+}
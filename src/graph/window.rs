@@ -0,0 +1,293 @@
+use crate::graph::events::GraphEvent;
+use crate::graph::{Edge, Graph, GraphError, Vertex};
+use std::collections::{HashMap, VecDeque};
+
+/// What a live event keeps in the window.
+#[derive(Clone, Debug)]
+enum Holds {
+    Vertex(String),
+    Edge(String, String),
+}
+
+/// An event still inside the window, in arrival order.
+#[derive(Clone, Debug)]
+struct Live {
+    time: u64,
+    holds: Holds,
+    /// Cleared when an explicit removal takes the vertex or edge out early.
+    alive: bool,
+}
+
+/// The graph of the events in a moving time window, for watching a stream of changes as it
+/// happens, such as the last five minutes of traffic between hosts.
+///
+/// With a window of `w`, the graph at time `t` holds every edge added after `t - w`, and
+/// every vertex that was added after `t - w` or is an endpoint of such an edge; adding an
+/// edge creates missing endpoints. Explicit removals take effect at once. Events expire
+/// oldest first, so each one costs one removal from the graph when it leaves the window.
+#[derive(Clone, Debug)]
+pub struct WindowedGraph {
+    graph: Graph,
+    window: u64,
+    now: u64,
+    live: VecDeque<Live>,
+    /// The number of live events holding every vertex, as a vertex or an endpoint.
+    holders: HashMap<String, usize>,
+}
+
+impl WindowedGraph {
+    /// Creates an empty window.
+    ///
+    /// # Arguments
+    ///
+    /// * `directed` - Whether the edges of the stream are directed.
+    /// * `window` - The length of the window, in the unit of the event times.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<WindowedGraph, GraphError>` - The window, or an error if its length is zero.
+    pub fn new(directed: bool, window: u64) -> Result<WindowedGraph, GraphError> {
+        if window == 0 {
+            return Err(GraphError::InvalidArgument("The window length must be positive".to_string()));
+        }
+        Ok(WindowedGraph { graph: Graph::new(directed), window, now: 0, live: VecDeque::new(), holders: HashMap::new() })
+    }
+
+    /// Returns the graph of the events in the window.
+    pub fn graph(&self) -> &Graph {
+        &self.graph
+    }
+
+    /// Returns the length of the window.
+    pub fn window(&self) -> u64 {
+        self.window
+    }
+
+    /// Returns the time of the latest event, or of the latest `advance_to`.
+    pub fn now(&self) -> u64 {
+        self.now
+    }
+
+    /// Moves the window forward without an event, expiring everything added at or before
+    /// `time - window`.
+    ///
+    /// # Arguments
+    ///
+    /// * `time` - The new current time.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), GraphError>` - `Ok(())`, or an error if `time` is before the current time.
+    pub fn advance_to(&mut self, time: u64) -> Result<(), GraphError> {
+        if time < self.now {
+            return Err(GraphError::InvalidArgument(format!("Time {} is before the current time {}", time, self.now)));
+        }
+        self.now = time;
+        while let Some(oldest) = self.live.front() {
+            if oldest.time.saturating_add(self.window) > time {
+                break;
+            }
+            let oldest: Live = self.live.pop_front().unwrap();
+            if oldest.alive {
+                self.expire(oldest.holds);
+            }
+        }
+        Ok(())
+    }
+
+    /// Applies events in order, as `apply` does.
+    ///
+    /// # Arguments
+    ///
+    /// * `events` - The events to apply, in stream order.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), GraphError>` - `Ok(())`, or an error from the first event that fails; the
+    ///   events before it stay applied.
+    pub fn apply_events(&mut self, events: &[GraphEvent]) -> Result<(), GraphError> {
+        events.iter().try_for_each(|event| self.apply(event))
+    }
+
+    /// Moves the window to the time of an event and applies it.
+    ///
+    /// Removals drop the vertex or the oldest matching edge straight away, which takes time
+    /// proportional to the number of events in the window. A weight update changes the
+    /// weight but not when the edge expires.
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - The event to apply.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), GraphError>` - `Ok(())`, or an error if the event is older than the current
+    ///   time, removes or updates something missing, or breaks the rules of the graph's mode.
+    pub fn apply(&mut self, event: &GraphEvent) -> Result<(), GraphError> {
+        self.advance_to(event.time())?;
+        match event {
+            GraphEvent::AddVertex { time, key, .. } => {
+                self.graph.apply_events(std::slice::from_ref(event))?;
+                self.hold(key);
+                self.live.push_back(Live { time: *time, holds: Holds::Vertex(key.clone()), alive: true });
+            }
+            GraphEvent::AddEdge { time, from, to, weight, reverse_weight, attributes } => {
+                let mut created: Vec<&String> = [from, to].into_iter().filter(|key| !self.graph.has_vertex(key.as_str())).collect();
+                created.dedup();
+                for &key in &created {
+                    self.graph.add_vertex(Vertex::new(key.clone()));
+                }
+                let mut edge: Edge = Edge::new(from.clone(), to.clone(), *weight);
+                edge.reverse_weight = *reverse_weight;
+                edge.attributes = attributes.clone();
+                if let Err(e) = self.graph.add_edge(edge) {
+                    for key in created {
+                        self.graph.remove_vertex(key.as_str()).unwrap();
+                    }
+                    return Err(e);
+                }
+                self.hold(from);
+                self.hold(to);
+                self.live.push_back(Live { time: *time, holds: Holds::Edge(from.clone(), to.clone()), alive: true });
+            }
+            GraphEvent::RemoveVertex { key, .. } => {
+                self.graph.remove_vertex(key.as_str())?;
+                self.holders.remove(key);
+                let mut released: Vec<String> = Vec::new();
+                for entry in self.live.iter_mut().filter(|entry| entry.alive) {
+                    match &entry.holds {
+                        Holds::Vertex(held) if held == key => entry.alive = false,
+                        Holds::Edge(first, second) if first == key || second == key => {
+                            entry.alive = false;
+                            released.extend([first, second].into_iter().filter(|other| *other != key).cloned());
+                        }
+                        _ => {}
+                    }
+                }
+                for other in released {
+                    self.release(&other);
+                }
+            }
+            GraphEvent::RemoveEdge { from, to, .. } => {
+                self.graph.remove_edge(from.as_str(), to.as_str())?;
+                let directed: bool = self.graph.directed;
+                // `remove_edge` takes the edge added first, which is the oldest live one.
+                let entry: &mut Live = self.live.iter_mut()
+                    .find(|entry| entry.alive && match &entry.holds {
+                        Holds::Edge(first, second) => (first == from && second == to) || (!directed && first == to && second == from),
+                        Holds::Vertex(_) => false,
+                    })
+                    .unwrap();
+                entry.alive = false;
+                self.release(from);
+                self.release(to);
+            }
+            GraphEvent::UpdateWeight { .. } => {
+                self.graph.apply_events(std::slice::from_ref(event))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Records one more live event holding a vertex.
+    fn hold(&mut self, key: &str) {
+        *self.holders.entry(key.to_string()).or_insert(0) += 1;
+    }
+
+    /// Records that a live event holding a vertex is gone, dropping the vertex once nothing
+    /// holds it.
+    fn release(&mut self, key: &str) {
+        let count: &mut usize = self.holders.get_mut(key).unwrap();
+        *count -= 1;
+        if *count == 0 {
+            self.holders.remove(key);
+            self.graph.remove_vertex(key).unwrap();
+        }
+    }
+
+    /// Takes an event that left the window out of the graph.
+    fn expire(&mut self, holds: Holds) {
+        match holds {
+            Holds::Vertex(key) => self.release(&key),
+            Holds::Edge(from, to) => {
+                // Edges leave in the order they came, so this is the edge being expired.
+                self.graph.remove_edge(from.as_str(), to.as_str()).unwrap();
+                self.release(&from);
+                self.release(&to);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::WindowedGraph;
+    use crate::graph::events::GraphEvent;
+    use crate::graph::GraphError;
+    use std::collections::HashMap;
+
+    fn add_edge(time: u64, from: &str, to: &str) -> GraphEvent {
+        GraphEvent::AddEdge { time, from: from.to_string(), to: to.to_string(), weight: 1.0, reverse_weight: None, attributes: HashMap::new() }
+    }
+
+    fn keys(window: &WindowedGraph) -> Vec<String> {
+        window.graph().vertex_keys()
+    }
+
+    #[test]
+    fn edges_and_their_endpoints_expire() {
+        let mut window: WindowedGraph = WindowedGraph::new(false, 10).unwrap();
+        window.apply_events(&[add_edge(0, "a", "b"), add_edge(5, "b", "c")]).unwrap();
+        assert_eq!(window.graph().edge_count, 2);
+        window.advance_to(9).unwrap();
+        assert_eq!(window.graph().edge_count, 2);
+        window.advance_to(10).unwrap();
+        assert_eq!(keys(&window), vec!["b", "c"]);
+        assert!(window.graph().has_edge("c", "b"));
+        window.advance_to(15).unwrap();
+        assert!(keys(&window).is_empty());
+        assert_eq!(window.now(), 15);
+    }
+
+    #[test]
+    fn added_vertices_outlive_older_edges() {
+        let mut window: WindowedGraph = WindowedGraph::new(true, 10).unwrap();
+        window.apply(&add_edge(0, "a", "b")).unwrap();
+        window.apply(&GraphEvent::AddVertex { time: 4, key: "a".to_string(), attributes: HashMap::new() }).unwrap();
+        window.advance_to(12).unwrap();
+        assert_eq!(keys(&window), vec!["a"]);
+        assert_eq!(window.graph().edge_count, 0);
+        window.advance_to(14).unwrap();
+        assert!(keys(&window).is_empty());
+    }
+
+    #[test]
+    fn removals_take_effect_at_once_and_are_not_expired_again() {
+        let mut window: WindowedGraph = WindowedGraph::new(false, 10).unwrap();
+        window.apply_events(&[
+            add_edge(0, "a", "b"),
+            add_edge(1, "a", "b"),
+            GraphEvent::RemoveEdge { time: 2, from: "b".to_string(), to: "a".to_string() },
+            add_edge(3, "c", "d"),
+            GraphEvent::RemoveVertex { time: 4, key: "d".to_string() },
+        ]).unwrap();
+        // Removing `d` takes its edge, and with it `c`, which nothing else holds.
+        assert_eq!(keys(&window), vec!["a", "b"]);
+        assert_eq!(window.graph().edge_count, 1);
+        // The edge from time 1 is the one left, so it outlives time 10.
+        window.advance_to(10).unwrap();
+        assert_eq!(window.graph().edge_count, 1);
+        window.advance_to(11).unwrap();
+        assert!(keys(&window).is_empty());
+    }
+
+    #[test]
+    fn invalid_windows_and_times_are_rejected() {
+        assert!(matches!(WindowedGraph::new(true, 0), Err(GraphError::InvalidArgument(_))));
+        let mut window: WindowedGraph = WindowedGraph::new(true, 5).unwrap();
+        window.apply(&add_edge(3, "a", "b")).unwrap();
+        assert!(matches!(window.apply(&add_edge(2, "b", "c")), Err(GraphError::InvalidArgument(_))));
+        assert!(matches!(window.apply(&GraphEvent::RemoveEdge { time: 4, from: "b".to_string(), to: "a".to_string() }), Err(GraphError::EdgeNotFound(_, _))));
+        assert_eq!(keys(&window), vec!["a", "b"]);
+    }
+}
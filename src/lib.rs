@@ -0,0 +1,6 @@
+//! Graph structures supporting directed and undirected graphs, with adjacency matrix,
+//! incidence matrix and CSR representations and a collection of graph algorithms.
+
+pub mod graph;
+
+pub use graph::{AttrValue, Edge, Graph, GraphError, Snapshot, Vertex};
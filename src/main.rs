@@ -1,6 +1,4 @@
-mod graph;
-
-use graph::{Graph, Vertex, Edge};
+use graphs::{Graph, GraphError, Vertex, Edge};
 
 fn main() {
     // Create a new graph (directed or undirected)
@@ -21,42 +19,22 @@ fn main() {
     graph.add_vertex(vertex5);
 
     // Create edges between vertices
-    let edge1: Edge = Edge::new(
-        graph.vertices.get("A").unwrap().clone(),
-        graph.vertices.get("C").unwrap().clone(),
-        1.0,
-    );
+    let edge1: Edge = Edge::new("A".to_string(), "C".to_string(), 1.0);
 
-    let edge2: Edge = Edge::new(
-        graph.vertices.get("C").unwrap().clone(),
-        graph.vertices.get("E").unwrap().clone(),
-        1.0,
-    );
+    let edge2: Edge = Edge::new("C".to_string(), "E".to_string(), 1.0);
 
-    let edge3: Edge = Edge::new(
-        graph.vertices.get("E").unwrap().clone(),
-        graph.vertices.get("B").unwrap().clone(),
-        1.0,
-    );
+    let edge3: Edge = Edge::new("E".to_string(), "B".to_string(), 1.0);
 
-    let edge4: Edge = Edge::new(
-        graph.vertices.get("B").unwrap().clone(),
-        graph.vertices.get("D").unwrap().clone(),
-        1.0,
-    );
+    let edge4: Edge = Edge::new("B".to_string(), "D".to_string(), 1.0);
 
-    let edge5: Edge = Edge::new(
-        graph.vertices.get("D").unwrap().clone(),
-        graph.vertices.get("A").unwrap().clone(),
-        1.0,
-    );
+    let edge5: Edge = Edge::new("D".to_string(), "A".to_string(), 1.0);
 
     // Add the edges to the graph
-    graph.add_edge(edge1).unwrap_or_else(|e: String| println!("Error: {}", e));
-    graph.add_edge(edge2).unwrap_or_else(|e: String| println!("Error: {}", e));
-    graph.add_edge(edge3).unwrap_or_else(|e: String| println!("Error: {}", e));
-    graph.add_edge(edge4).unwrap_or_else(|e: String| println!("Error: {}", e));
-    graph.add_edge(edge5).unwrap_or_else(|e: String| println!("Error: {}", e));
+    graph.add_edge(edge1).unwrap_or_else(|e: GraphError| println!("Error: {}", e));
+    graph.add_edge(edge2).unwrap_or_else(|e: GraphError| println!("Error: {}", e));
+    graph.add_edge(edge3).unwrap_or_else(|e: GraphError| println!("Error: {}", e));
+    graph.add_edge(edge4).unwrap_or_else(|e: GraphError| println!("Error: {}", e));
+    graph.add_edge(edge5).unwrap_or_else(|e: GraphError| println!("Error: {}", e));
 
     // Display the graph
     graph.display();